@@ -0,0 +1,151 @@
+// ============================================================================
+// AEGIS - リクエスト処理経路（ホットパス）のベンチマーク
+// tools/call のパース、判定プロンプトの生成、dispatchString による往復（キャッシュあり・なし）を計測する
+// LLMはMockLLMProviderに差し替えるため、AI呼び出し以外のオーバーヘッドだけを測る
+// 実行: npm run bench（BENCH_ITERATIONS で反復回数を変更）
+// ============================================================================
+
+import { performance } from 'perf_hooks';
+import { CallToolRequestSchema } from '@modelcontextprotocol/sdk/types.js';
+import { AIJudgmentEngine } from '../src/ai/judgment-engine.js';
+import { MockLLMProvider } from '../src/ai/llm-factory.js';
+import { MCPStdioPolicyProxy } from '../src/mcp/stdio-proxy.js';
+import { checkJsonRpcShape } from '../src/mcp/jsonrpc-shape.js';
+import { Config } from '../src/utils/config.js';
+import { Logger } from '../src/utils/logger.js';
+import type { DecisionContext, LLMConfig } from '../src/types/index.js';
+
+const ITERATIONS = parseInt(process.env.BENCH_ITERATIONS || '2000');
+const WARMUP = Math.max(1, Math.floor(ITERATIONS / 10));
+
+const BENCH_LLM: LLMConfig = { provider: 'openai', apiKey: 'bench', model: 'bench' };
+
+const POLICY = '営業時間内（9時〜18時）のファイル読み取りは許可する。個人情報を含むファイルへのアクセスは拒否する';
+
+interface BenchResult {
+  name: string;
+  iterations: number;
+  meanMicros: number;
+  p99Micros: number;
+  opsPerSecond: number;
+}
+
+async function bench(name: string, fn: (i: number) => unknown): Promise<BenchResult> {
+  for (let i = 0; i < WARMUP; i++) {
+    await fn(i);
+  }
+
+  const samples: number[] = [];
+  for (let i = 0; i < ITERATIONS; i++) {
+    const start = performance.now();
+    await fn(WARMUP + i);
+    samples.push(performance.now() - start);
+  }
+
+  samples.sort((a, b) => a - b);
+  const total = samples.reduce((sum, sample) => sum + sample, 0);
+  return {
+    name,
+    iterations: ITERATIONS,
+    meanMicros: (total / ITERATIONS) * 1000,
+    p99Micros: samples[Math.min(samples.length - 1, Math.floor(samples.length * 0.99))] * 1000,
+    opsPerSecond: ITERATIONS / (total / 1000)
+  };
+}
+
+function toolsCallRequest(id: number, resource: string): string {
+  return JSON.stringify({
+    jsonrpc: '2.0',
+    id,
+    method: 'tools/call',
+    params: {
+      name: 'check_policies',
+      arguments: {
+        agent: 'claude',
+        action: 'read',
+        resource,
+        policies: [POLICY],
+        combining_algorithm: 'deny_overrides'
+      }
+    }
+  });
+}
+
+/**
+ * 上流サーバーを起動せず、dispatchString で使えるところまで初期化したプロキシを作る
+ */
+async function createProxy(cacheEnabled: boolean): Promise<MCPStdioPolicyProxy> {
+  const config = new Config({
+    llm: BENCH_LLM,
+    cache: { enabled: cacheEnabled, ttl: 300, maxSize: ITERATIONS + WARMUP }
+  });
+  const judgmentEngine = new AIJudgmentEngine(config.llm, new MockLLMProvider());
+  const proxy = new MCPStdioPolicyProxy(config, new Logger('error'), judgmentEngine);
+
+  await proxy.dispatchString(JSON.stringify({
+    jsonrpc: '2.0',
+    id: 0,
+    method: 'initialize',
+    params: { protocolVersion: '2024-11-05', capabilities: {}, clientInfo: { name: 'bench', version: '1.0.0' } }
+  }));
+  await proxy.dispatchString('{"jsonrpc":"2.0","method":"notifications/initialized"}');
+
+  // エラー応答の経路を計測しないよう、判定まで通ることを先に確認する
+  const response = JSON.parse((await proxy.dispatchString(toolsCallRequest(0, 'file://docs/check.txt')))!);
+  if (!response.result || response.result.isError) {
+    throw new Error(`check_policies did not succeed: ${JSON.stringify(response)}`);
+  }
+  return proxy;
+}
+
+async function main(): Promise<void> {
+  const results: BenchResult[] = [];
+
+  // 1. tools/call リクエストのパース（形の検査 + SDKのスキーマ検証）
+  const request = toolsCallRequest(1, 'file://docs/report.txt');
+  results.push(await bench('parse tools/call request', () => {
+    const message = JSON.parse(request);
+    checkJsonRpcShape(message);
+    return CallToolRequestSchema.parse(message);
+  }));
+
+  // 2. 判定プロンプトの生成
+  const promptEngine = new AIJudgmentEngine(new Config({ llm: BENCH_LLM }).llm, new MockLLMProvider());
+  const context: DecisionContext = {
+    agent: 'claude',
+    action: 'read',
+    resource: 'file://docs/report.txt',
+    purpose: 'documentation',
+    time: new Date(),
+    environment: { transport: 'stdio', toolName: 'filesystem__read_file' }
+  };
+  results.push(await bench('build check_policy prompt', () => promptEngine.renderDecisionPrompt(POLICY, context)));
+
+  // 3. dispatchString の往復（キャッシュあり: 同じリクエストを繰り返す）
+  const cachedProxy = await createProxy(true);
+  results.push(await bench('dispatchString round-trip (cached)', i =>
+    cachedProxy.dispatchString(toolsCallRequest(i + 1, 'file://docs/report.txt'))
+  ));
+
+  // 4. dispatchString の往復（キャッシュなし: 毎回異なるリソースで判定する）
+  const uncachedProxy = await createProxy(false);
+  results.push(await bench('dispatchString round-trip (uncached)', i =>
+    uncachedProxy.dispatchString(toolsCallRequest(i + 1, `file://docs/report-${i}.txt`))
+  ));
+
+  console.table(results.map(result => ({
+    benchmark: result.name,
+    iterations: result.iterations,
+    'mean (µs)': result.meanMicros.toFixed(1),
+    'p99 (µs)': result.p99Micros.toFixed(1),
+    'ops/s': Math.round(result.opsPerSecond)
+  })));
+}
+
+main().then(
+  () => process.exit(0),
+  (error) => {
+    console.error('Benchmark failed:', error);
+    process.exit(1);
+  }
+);
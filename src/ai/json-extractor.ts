@@ -0,0 +1,69 @@
+// ============================================================================
+// AEGIS - LLM応答からのJSON抽出
+// モデルの返答はMarkdownのコードフェンスや前後の説明文を含むことがあるため、
+// パース前にJSON部分だけを取り出す
+// ============================================================================
+
+const FENCED_BLOCK_PATTERN = /```[ \t]*(?:json|JSON)?[ \t]*\r?\n?([\s\S]*?)\r?\n?[ \t]*```/;
+
+/**
+ * 先頭の開き括弧に対応する閉じ括弧までを切り出す（文字列リテラル内の括弧は無視）
+ */
+function sliceBalanced(text: string, start: number): string | null {
+  const open = text[start];
+  const close = open === '{' ? '}' : ']';
+  let depth = 0;
+  let inString = false;
+  let escaped = false;
+
+  for (let i = start; i < text.length; i++) {
+    const char = text[i];
+
+    if (inString) {
+      if (escaped) {
+        escaped = false;
+      } else if (char === '\\') {
+        escaped = true;
+      } else if (char === '"') {
+        inString = false;
+      }
+      continue;
+    }
+
+    if (char === '"') {
+      inString = true;
+    } else if (char === open) {
+      depth++;
+    } else if (char === close) {
+      depth--;
+      if (depth === 0) {
+        return text.slice(start, i + 1);
+      }
+    }
+  }
+
+  return null;
+}
+
+/**
+ * LLM応答からJSON文字列を抽出
+ * 1. ```json / ``` フェンス内のブロック
+ * 2. 本文中の最初のJSONオブジェクト・配列
+ * 3. 見つからなければトリムした全文（呼び出し側のJSON.parseでエラーにする）
+ */
+export function extractJsonBlock(text: string): string {
+  const fenced = text.match(FENCED_BLOCK_PATTERN);
+  if (fenced && fenced[1].trim()) {
+    return fenced[1].trim();
+  }
+
+  const start = text.search(/[{[]/);
+  if (start >= 0) {
+    const balanced = sliceBalanced(text, start);
+    if (balanced) {
+      return balanced;
+    }
+  }
+
+  return text.trim();
+}
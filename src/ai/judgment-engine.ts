@@ -2,14 +2,27 @@
 // AEGIS - AI判定エンジン（実装版）
 // ============================================================================
 
+import { createHash } from 'crypto';
 import type { 
   DecisionContext, 
   PolicyDecision, 
+  PolicyExplanation,
+  PolicyClauseExplanation,
   LLMConfig 
 } from '../types/index.js';
 import { OpenAILLM } from './openai-llm.js';
 import { AnthropicLLM } from './anthropic-llm.js';
-import { PromptTemplateEngine } from './prompt-templates.js';
+import type { LLMProvider } from './llm-factory.js';
+import {
+  PromptTemplateEngine,
+  POLICY_ANALYSIS_PLACEHOLDERS,
+  reasonLocaleInstruction,
+  validateTemplatePlaceholders
+} from './prompt-templates.js';
+import { extractJsonBlock } from './json-extractor.js';
+import { redactContext } from './prompt-redaction.js';
+import { TokenUsageSnapshot, TokenUsageTracker, estimateTokens } from './token-estimate.js';
+import { policyDecisionSchema } from '../schemas/policy.schema.js';
 
 interface LRUCache<K, V> {
   get(key: K): V | undefined;
@@ -48,28 +61,39 @@ class SimpleLRUCache<K, V> implements LRUCache<K, V> {
 }
 
 export class AIJudgmentEngine {
-  private llm: OpenAILLM | AnthropicLLM;
+  private llm: LLMProvider;
   private decisionCache: LRUCache<string, PolicyDecision>;
   private promptTemplateEngine: PromptTemplateEngine;
   private cacheCapacity: number;
+  private tokenUsage = new TokenUsageTracker();
+  // 判定結果に送信したプロンプトを付けるか（--log-prompts）と、伏せ字にする項目（--prompt-redact）
+  private promptLogging?: { redactFields: string[] };
 
-  constructor(llmConfig: LLMConfig) {
+  /**
+   * @param llmProvider 判定に使うLLMを差し替える場合に指定（テスト用のMockLLMProvider、
+   *                    独自のOpenAI互換エンドポイントなど）。省略時はllmConfig.providerから選択
+   */
+  constructor(llmConfig: LLMConfig, llmProvider?: LLMProvider) {
     // Select LLM provider based on configuration
     // Only log in non-stdio mode to avoid corrupting JSON-RPC output
     if (process.env.MCP_TRANSPORT !== 'stdio' && process.env.LOG_SILENT !== 'true') {
-      console.error('[AI Judgment] Initializing with provider:', llmConfig.provider);
+      console.error('[AI Judgment] Initializing with provider:', llmProvider ? 'custom' : llmConfig.provider);
     }
-    switch (llmConfig.provider) {
-      case 'anthropic':
-        if (process.env.MCP_TRANSPORT !== 'stdio' && process.env.LOG_SILENT !== 'true') {
-          console.error('[AI Judgment] Using Anthropic Claude API for real AI judgment');
-        }
-        this.llm = new AnthropicLLM(llmConfig);
-        break;
-      case 'openai':
-      default:
-        this.llm = new OpenAILLM(llmConfig);
-        break;
+    if (llmProvider) {
+      this.llm = llmProvider;
+    } else {
+      switch (llmConfig.provider) {
+        case 'anthropic':
+          if (process.env.MCP_TRANSPORT !== 'stdio' && process.env.LOG_SILENT !== 'true') {
+            console.error('[AI Judgment] Using Anthropic Claude API for real AI judgment');
+          }
+          this.llm = new AnthropicLLM(llmConfig);
+          break;
+        case 'openai':
+        default:
+          this.llm = new OpenAILLM(llmConfig);
+          break;
+      }
     }
     
     this.cacheCapacity = 1000;
@@ -92,7 +116,8 @@ export class AIJudgmentEngine {
         if (process.env.MCP_TRANSPORT !== 'stdio' && process.env.LOG_SILENT !== 'true') {
           console.error('[AI Judgment] Using cached decision');
         }
-        return cachedDecision;
+        // プロンプトは送っていないので、監査ログに最初の要求のプロンプト・推定トークン数を残さない
+        return { ...cachedDecision, metadata: { ...cachedDecision.metadata, cached: true } };
       }
 
       // 2. ポリシー分析プロンプト生成
@@ -113,10 +138,19 @@ export class AIJudgmentEngine {
       if (process.env.MCP_TRANSPORT !== 'stdio' && process.env.LOG_SILENT !== 'true') {
         console.error('[AI Judgment] Executing AI decision...');
       }
-      const rawResponse = await this.llm.complete(analysisPrompt);
+      const { response: rawResponse, promptTokens, completionTokens } = await this.completeWithUsage(analysisPrompt);
       
-      // 4. 結果パース・検証
-      const decision = this.parseAndValidateDecision(rawResponse);
+      // 4. 結果パース・検証（その判定を得るのにかかった推定トークン数を付ける）
+      const parsed = this.parseAndValidateDecision(rawResponse);
+      const decision: PolicyDecision = {
+        ...parsed,
+        metadata: {
+          ...parsed.metadata,
+          estimatedPromptTokens: promptTokens,
+          estimatedCompletionTokens: completionTokens,
+          ...(this.promptLogging ? { renderedPrompt: this.loggedPrompt(analysisPrompt, naturalLanguagePolicy, context) } : {})
+        }
+      };
       
       // デバッグ: AI判定結果をログ出力
       if (process.env.MCP_TRANSPORT !== 'stdio' && process.env.LOG_SILENT !== 'true') {
@@ -148,11 +182,63 @@ export class AIJudgmentEngine {
         constraints: ["手動確認が必要"],
         obligations: ["システム管理者に報告"],
         monitoringRequirements: ["AI判定エラーとして記録"],
-        metadata: { aiError: true }
+        metadata: { aiError: true, indeterminateSource: 'ai-error' }
       };
     }
   }
 
+  /**
+   * AI呼び出しの推定トークン数の累計（statsツール用）
+   */
+  getTokenUsage(): TokenUsageSnapshot {
+    return this.tokenUsage.snapshot();
+  }
+
+  /**
+   * LLMを呼び出し、推定トークン数を累計に加える（判定以外の説明・Rego変換・解析の呼び出しも含める）
+   */
+  private async completeWithUsage(prompt: string): Promise<{ response: string; promptTokens: number; completionTokens: number }> {
+    const response = await this.llm.complete(prompt);
+    const promptTokens = estimateTokens(prompt);
+    const completionTokens = estimateTokens(response);
+    this.tokenUsage.record(promptTokens, completionTokens);
+    return { response, promptTokens, completionTokens };
+  }
+
+  private async complete(prompt: string): Promise<string> {
+    return (await this.completeWithUsage(prompt)).response;
+  }
+
+  /**
+   * 判定に使うプロンプトを生成（AI呼び出しは行わない）
+   * MCPのprompts/getでクライアントに同じプロンプトを提供するために使用
+   */
+  renderDecisionPrompt(policy: string, context: DecisionContext): string {
+    return this.buildAnalysisPrompt(policy, context);
+  }
+
+  /**
+   * 判定結果のmetadata.renderedPromptに送信したプロンプトを付ける（--log-prompts、監査ログに記録される）
+   * redactFieldsの項目は伏せ字にしたコンテキストでプロンプトを組み立て直す
+   */
+  enablePromptLogging(redactFields: string[] = []): void {
+    this.promptLogging = { redactFields };
+  }
+
+  private loggedPrompt(prompt: string, policy: string, context: DecisionContext): string {
+    const { redactFields } = this.promptLogging!;
+    return redactFields.length > 0 ? this.buildAnalysisPrompt(policy, redactContext(context, redactFields)) : prompt;
+  }
+
+  /**
+   * 判定プロンプトのテンプレートを差し替える（--prompt-template）
+   * 未知のプレースホルダーを含む場合は例外
+   */
+  setDecisionPromptTemplate(template: string): void {
+    validateTemplatePlaceholders(template, POLICY_ANALYSIS_PLACEHOLDERS);
+    this.promptTemplateEngine.addTemplate('POLICY_ANALYSIS', template);
+  }
+
   // ポリシー分析プロンプト構築
   private buildAnalysisPrompt(policy: string, context: DecisionContext): string {
     // timeをDateオブジェクトに変換
@@ -167,7 +253,9 @@ export class AIJudgmentEngine {
       purpose: context.purpose || '未指定'
     };
     
-    return this.promptTemplateEngine.render('POLICY_ANALYSIS', templateContext);
+    const prompt = this.promptTemplateEngine.render('POLICY_ANALYSIS', templateContext);
+    // 判定理由の言語指定（--prompt-templateで差し替えたテンプレートにも付ける）
+    return context.locale ? prompt + reasonLocaleInstruction(context.locale) : prompt;
   }
   
   // コンテキスト情報のフォーマット
@@ -175,7 +263,7 @@ export class AIJudgmentEngine {
     return `
 - **エージェント**: ${context.agent} (タイプ: ${context.agentType || '不明'})
 - **要求アクション**: ${context.action}
-- **対象リソース**: ${context.resource}
+- **対象リソース**: ${context.resource}${context.originalResource !== undefined ? ` (正規化前の表記: ${context.originalResource})` : ''}
 - **業務目的**: ${context.purpose || '未指定'}
 - **時刻**: ${timeObj.toLocaleString('ja-JP')} (${this.getTimeContext(timeObj)})
 - **場所**: ${context.location || '不明'}
@@ -203,22 +291,13 @@ ${JSON.stringify(context.environment, null, 2)}
   // 結果パース・検証
   private parseAndValidateDecision(rawResponse: string): PolicyDecision {
     try {
-      const jsonMatch = rawResponse.match(/```json\n([\s\S]*?)\n```/);
-      const jsonStr = jsonMatch ? jsonMatch[1] : rawResponse;
-      
-      const parsed = JSON.parse(jsonStr);
+      const parsed = JSON.parse(extractJsonBlock(rawResponse));
       
-      // 必須フィールド検証
-      if (!["PERMIT", "DENY", "INDETERMINATE"].includes(parsed.decision)) {
-        throw new Error("Invalid decision value");
-      }
-      
-      if (!parsed.reason || typeof parsed.reason !== 'string') {
-        throw new Error("Reason is required");
-      }
-      
-      if (typeof parsed.confidence !== 'number' || parsed.confidence < 0 || parsed.confidence > 1) {
-        throw new Error("Invalid confidence score");
+      // 出力スキーマ検証（decisionの値・confidenceの範囲など、不正な項目をすべて列挙する）
+      const validation = policyDecisionSchema.safeParse(parsed);
+      if (!validation.success) {
+        const issues = validation.error.issues.map(issue => `${issue.path.join('.') || '(root)'}: ${issue.message}`);
+        throw new Error(`Invalid decision output: ${issues.join('; ')}`);
       }
       
       return {
@@ -230,6 +309,7 @@ ${JSON.stringify(context.environment, null, 2)}
         obligations: parsed.obligations || [],
         monitoringRequirements: parsed.monitoringRequirements || [],
         validityPeriod: parsed.validityPeriod,
+        ...(parsed.needs_clarification?.length ? { needsClarification: parsed.needs_clarification } : {}),
         metadata: parsed.metadata || {}
       };
       
@@ -245,7 +325,7 @@ ${JSON.stringify(context.environment, null, 2)}
         constraints: ["手動確認が必要"],
         obligations: ["システム管理者に報告"],
         monitoringRequirements: ["異常判定として記録"],
-        metadata: { parseError: true }
+        metadata: { parseError: true, indeterminateSource: 'parse-error' }
       };
     }
   }
@@ -291,9 +371,8 @@ ${contexts.map((ctx, i) => {
 \`\`\`
 `;
 
-    const response = await this.llm.complete(batchPrompt);
-    const jsonMatch = response.match(/```json\n([\s\S]*?)\n```/);
-    const results = JSON.parse(jsonMatch ? jsonMatch[1] : response);
+    const response = await this.complete(batchPrompt);
+    const results = JSON.parse(extractJsonBlock(response));
     
     return results.map((result: any, index: number) => 
       this.parseAndValidateDecision(JSON.stringify(result))
@@ -310,20 +389,16 @@ ${contexts.map((ctx, i) => {
       action: context.action,
       resource: context.resource,
       purpose: context.purpose,
+      locale: context.locale,
       timeHour: timeObj.getHours() // 時間は時単位でキャッシュ
     }));
     
     return `${policyHash}-${contextHash}`;
   }
 
+  // 内容のハッシュ（32bitのハッシュでは "Aa" と "BB" のように衝突し、編集前のポリシーの判定を返しうる）
   private hashString(str: string): string {
-    let hash = 0;
-    for (let i = 0; i < str.length; i++) {
-      const char = str.charCodeAt(i);
-      hash = ((hash << 5) - hash) + char;
-      hash = hash & hash; // 32bit整数に変換
-    }
-    return Math.abs(hash).toString(36);
+    return createHash('sha256').update(str).digest('hex');
   }
 
   private logDecision(context: DecisionContext, decision: PolicyDecision, policy: string): void {
@@ -398,9 +473,8 @@ ${policy}
 `;
 
     try {
-      const response = await this.llm.complete(prompt);
-      const jsonMatch = response.match(/```json\n([\s\S]*?)\n```/);
-      return JSON.parse(jsonMatch ? jsonMatch[1] : response);
+      const response = await this.complete(prompt);
+      return JSON.parse(extractJsonBlock(response));
     } catch (error) {
       if (process.env.MCP_TRANSPORT !== 'stdio' && process.env.LOG_SILENT !== 'true') {
         console.error('[AI Judgment] ポリシー解析エラー', error);
@@ -416,10 +490,107 @@ ${policy}
     }
   }
 
+  /**
+   * 判定根拠の説明（条項ごとの適用有無と判定への影響）
+   */
+  async explainDecision(policy: string, context: DecisionContext): Promise<PolicyExplanation> {
+    const prompt = `${this.buildAnalysisPrompt(policy, context)}
+
+## 追加指示: 判定根拠の説明
+判定に加えて、ポリシーを条項（文・箇条書きの単位）に分割し、各条項について
+今回の要求に適用されたか、判定にどう影響したかを列挙してください。
+以下のJSON形式のみで回答してください:
+{
+  "decision": "PERMIT" | "DENY" | "INDETERMINATE",
+  "reason": "判定理由",
+  "confidence": 0.0〜1.0,
+  "clauses": [
+    {
+      "text": "条項の原文",
+      "relevance": "HIGH" | "MEDIUM" | "LOW" | "NONE（適用されない場合）",
+      "effect": "PERMIT" | "DENY" | "NEUTRAL"
+    }
+  ]
+}
+`;
+
+    const response = await this.complete(prompt);
+    return this.parseExplanation(response);
+  }
+
+  /**
+   * 自然言語ポリシーからOPA Regoのひな形を生成する（移行の出発点。生成結果は要レビュー）
+   */
+  async exportRego(policy: string, packageName: string): Promise<string> {
+    const prompt = `
+あなたは自然言語のアクセス制御ポリシーをOpen Policy AgentのRegoに移植するアシスタントです。
+以下のポリシーを、input.agent / input.action / input.resource / input.purpose / input.time を参照する
+Regoのひな形に変換してください。
+
+要件:
+- パッケージ名は ${packageName}
+- 既定は拒否（default allow := false）とし、許可条件を allow ルールとして記述する
+- 明示的な禁止は deny ルールとして記述する
+- 自然言語から機械的に判断できない条件は TODO コメントとして残す
+
+ポリシー:
+${policy}
+
+Regoのコードのみを回答してください。
+`;
+
+    const response = await this.complete(prompt);
+    const fenced = response.match(/```[ \t]*(?:rego)?[ \t]*\r?\n([\s\S]*?)```/);
+    const rego = (fenced ? fenced[1] : response).trim();
+    if (!/^package\s+\S+/m.test(rego)) {
+      throw new Error('Response does not contain a Rego package declaration');
+    }
+    return rego;
+  }
+
+  private parseExplanation(rawResponse: string): PolicyExplanation {
+    try {
+      const parsed = JSON.parse(extractJsonBlock(rawResponse));
+
+      if (!["PERMIT", "DENY", "INDETERMINATE"].includes(parsed.decision)) {
+        throw new Error("Invalid decision value");
+      }
+
+      if (!Array.isArray(parsed.clauses)) {
+        throw new Error("Clauses are required");
+      }
+
+      const clauses: PolicyClauseExplanation[] = parsed.clauses
+        .filter((clause: any) => clause && typeof clause.text === 'string')
+        .map((clause: any) => ({
+          text: clause.text,
+          relevance: ["HIGH", "MEDIUM", "LOW", "NONE"].includes(clause.relevance) ? clause.relevance : "NONE",
+          effect: ["PERMIT", "DENY", "NEUTRAL"].includes(clause.effect) ? clause.effect : "NEUTRAL"
+        }));
+
+      return {
+        decision: parsed.decision,
+        reason: typeof parsed.reason === 'string' ? parsed.reason : '',
+        confidence: typeof parsed.confidence === 'number' ? Math.min(Math.max(parsed.confidence, 0), 1) : 0,
+        clauses
+      };
+    } catch (error) {
+      if (process.env.MCP_TRANSPORT !== 'stdio' && process.env.LOG_SILENT !== 'true') {
+        console.error('[AI Judgment] Explanation parse error:', error);
+      }
+      return {
+        decision: "INDETERMINATE",
+        reason: `説明生成エラー: ${error instanceof Error ? error.message : 'Unknown error'}`,
+        confidence: 0.0,
+        clauses: []
+      };
+    }
+  }
+
   // 汎用分析メソッド
   async analyze(prompt: string, options: any = {}): Promise<any> {
     try {
-      const response = await this.llm.complete(prompt);
+      const response = await this.complete(prompt);
       
       if (options.responseFormat === 'json') {
         return this.parseJSONResponse(response);
@@ -437,7 +608,7 @@ ${policy}
   // 汎用生成メソッド
   async generate(prompt: string, options: any = {}): Promise<any> {
     try {
-      const response = await this.llm.complete(prompt);
+      const response = await this.complete(prompt);
       
       if (options.responseFormat === 'json') {
         return this.parseJSONResponse(response);
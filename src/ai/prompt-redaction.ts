@@ -0,0 +1,39 @@
+// ============================================================================
+// AEGIS - 記録するプロンプトの秘匿（--log-prompts / --prompt-redact）
+// 指定したコンテキストの項目を伏せ字にしてからプロンプトを組み立て直す
+// 項目はコンテキストのキー（purpose、location など）か、ドット区切りのパス（environment.apiToken など）
+// ============================================================================
+
+import type { DecisionContext } from '../types/index.js';
+
+export const REDACTED_VALUE = '[REDACTED]';
+
+/**
+ * 指定した項目の値を伏せ字にしたコンテキストのコピー（存在しない項目は無視する）
+ */
+export function redactContext(context: DecisionContext, fields: readonly string[]): DecisionContext {
+  const redacted = { ...context, environment: { ...context.environment } } as Record<string, unknown>;
+
+  for (const field of fields) {
+    const keys = field.split('.');
+    let target: Record<string, unknown> | undefined = redacted;
+    for (const [index, key] of keys.entries()) {
+      if (!target || !Object.prototype.hasOwnProperty.call(target, key)) {
+        break;
+      }
+      if (index === keys.length - 1) {
+        target[key] = REDACTED_VALUE;
+        break;
+      }
+      const child = target[key];
+      if (typeof child !== 'object' || child === null || child instanceof Date) {
+        break;
+      }
+      // 元のコンテキストを変更しないよう、たどったオブジェクトは複製する
+      target[key] = Array.isArray(child) ? [...child] : { ...(child as Record<string, unknown>) };
+      target = target[key] as Record<string, unknown>;
+    }
+  }
+
+  return redacted as unknown as DecisionContext;
+}
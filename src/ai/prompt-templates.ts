@@ -38,6 +38,7 @@ export const PROMPT_TEMPLATES = {
   "confidence": 0.0-1.0の信頼度スコア,
   "constraints": ["適用すべき制約のリスト"],
   "obligations": ["実行すべき義務のリスト"],
+  "needs_clarification": ["判定に追加情報が必要な場合のみ、要求者への質問のリスト"],
   "metadata": {
     "risk_level": "LOW" | "MEDIUM" | "HIGH",
     "policy_violations": ["違反したポリシー項目"],
@@ -133,6 +134,68 @@ export const PROMPT_TEMPLATES = {
 }`
 };
 
+// 判定理由の言語指定の既定値（不正なロケールもこれに置き換える）
+export const DEFAULT_REASON_LOCALE = 'en';
+
+// BCP 47 の言語タグ（言語 + 任意のスクリプト・地域・バリアント）
+const BCP47_PATTERN = /^[A-Za-z]{2,3}(-[A-Za-z]{4})?(-(?:[A-Za-z]{2}|\d{3}))?(-(?:[A-Za-z0-9]{5,8}|\d[A-Za-z0-9]{3}))*$/;
+
+/**
+ * ロケールをBCP 47の表記（言語は小文字・スクリプトは先頭大文字・地域は大文字）にそろえる
+ * パターンに合わない値は DEFAULT_REASON_LOCALE にする
+ */
+export function normalizeLocale(locale: string): string {
+  if (!BCP47_PATTERN.test(locale)) {
+    return DEFAULT_REASON_LOCALE;
+  }
+  return locale.split('-').map((part, index) => {
+    if (index === 0) return part.toLowerCase();
+    if (/^[A-Za-z]{4}$/.test(part)) return part[0].toUpperCase() + part.slice(1).toLowerCase();
+    if (/^[A-Za-z]{2}$/.test(part)) return part.toUpperCase();
+    return part.toLowerCase();
+  }).join('-');
+}
+
+/**
+ * 判定プロンプトの末尾に付ける、reasonの言語の指示（decisionの値は翻訳させない）
+ */
+export function reasonLocaleInstruction(locale: string): string {
+  return `
+
+## 回答言語
+"reason" は言語タグ ${locale} の言語で記述してください。
+"decision" の値（PERMIT / DENY / INDETERMINATE）とJSONのキーは翻訳せず、そのままにしてください。`;
+}
+
+/**
+ * POLICY_ANALYSISテンプレートで使えるプレースホルダー
+ */
+export const POLICY_ANALYSIS_PLACEHOLDERS = ['policy', 'agent', 'action', 'resource', 'context', 'purpose'];
+
+/**
+ * テンプレート中の {name} 形式のプレースホルダー名を列挙（重複なし）
+ */
+export function findTemplatePlaceholders(template: string): string[] {
+  const names = new Set<string>();
+  for (const match of template.matchAll(/{(\w+)}/g)) {
+    names.add(match[1]);
+  }
+  return Array.from(names);
+}
+
+/**
+ * 未知のプレースホルダーを含むテンプレートを拒否する
+ */
+export function validateTemplatePlaceholders(template: string, allowed: string[]): void {
+  const unknown = findTemplatePlaceholders(template).filter(name => !allowed.includes(name));
+  if (unknown.length > 0) {
+    throw new Error(
+      `Unknown prompt template placeholder(s): ${unknown.map(name => `{${name}}`).join(', ')} ` +
+      `(allowed: ${allowed.map(name => `{${name}}`).join(', ')})`
+    );
+  }
+}
+
 /**
  * プロンプトテンプレートエンジン
  */
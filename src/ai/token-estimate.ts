@@ -0,0 +1,52 @@
+// ============================================================================
+// AEGIS - 判定呼び出しのトークン数の概算
+// 正確なトークナイザーを組み込むまでの目安として、文字数から推定する
+//   日本語・中国語などのCJK文字は1文字1トークン、それ以外は空白で区切った語ごとに4文字1トークン
+// 実際の課金トークン数とはずれるため、予算の見積もり用に使う
+// ============================================================================
+
+const CJK = /[぀-ヿ㐀-䶿一-鿿가-힯＀-￯]/g;
+
+export function estimateTokens(text: string): number {
+  let tokens = 0;
+  for (const word of text.split(/\s+/)) {
+    if (word === '') {
+      continue;
+    }
+    const cjk = word.match(CJK)?.length ?? 0;
+    const rest = word.length - cjk;
+    tokens += cjk + Math.ceil(rest / 4);
+  }
+  return tokens;
+}
+
+export interface TokenUsageSnapshot {
+  evaluations: number;
+  estimatedPromptTokens: number;
+  estimatedCompletionTokens: number;
+  estimatedTotalTokens: number;
+}
+
+/**
+ * AI呼び出し（判定・説明・Rego変換など）ごとの推定トークン数を累計する（statsツール用）
+ */
+export class TokenUsageTracker {
+  private evaluations = 0;
+  private promptTokens = 0;
+  private completionTokens = 0;
+
+  record(promptTokens: number, completionTokens: number): void {
+    this.evaluations++;
+    this.promptTokens += promptTokens;
+    this.completionTokens += completionTokens;
+  }
+
+  snapshot(): TokenUsageSnapshot {
+    return {
+      evaluations: this.evaluations,
+      estimatedPromptTokens: this.promptTokens,
+      estimatedCompletionTokens: this.completionTokens,
+      estimatedTotalTokens: this.promptTokens + this.completionTokens
+    };
+  }
+}
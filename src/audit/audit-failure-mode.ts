@@ -0,0 +1,106 @@
+// ============================================================================
+// AEGIS - 監査ログに書き込めないときの扱い（--audit-failure-mode）
+//   fail-closed: リクエストを拒否する（既定・監査証跡の欠落を許さない）
+//   fail-open:   判定はそのまま通し、監査されなかった判定として記録・警告する
+//                （ディスクフルなどで全リクエストが拒否される自己DoSを避ける代わりに、証跡が欠ける）
+//   buffer:      上限付きのメモリ内キューに保持して再試行する（上限を超えたらfail-closed）
+//                （一時的な障害なら証跡は欠けないが、プロセスが落ちるとキュー内のレコードは失われる）
+// ============================================================================
+
+import type { DecisionAuditRecord } from './decision-audit-log.js';
+
+export const AUDIT_FAILURE_MODES = ['fail-closed', 'fail-open', 'buffer'] as const;
+export type AuditFailureMode = typeof AUDIT_FAILURE_MODES[number];
+
+export const DEFAULT_AUDIT_FAILURE_MODE: AuditFailureMode = 'fail-closed';
+
+export function isAuditFailureMode(value: unknown): value is AuditFailureMode {
+  return typeof value === 'string' && (AUDIT_FAILURE_MODES as readonly string[]).includes(value);
+}
+
+/**
+ * 書き込めなかった監査レコードを順番どおりに保持し、一定間隔で書き込みを再試行する
+ * キューが空になるまでは、新しいレコードもキューの後ろに並べる（ハッシュチェーンの順序を保つ）
+ */
+export class AuditRetryBuffer {
+  private write: (record: DecisionAuditRecord) => Promise<void>;
+  private capacity: number;
+  private retryIntervalMs: number;
+  private queue: DecisionAuditRecord[] = [];
+  private timer?: NodeJS.Timeout;
+  private draining?: Promise<void>;
+  private stopped = false;
+
+  /**
+   * @param write レコードを1件書き込む（失敗時はreject）
+   * @param capacity 保持するレコードの上限
+   * @param retryIntervalMs 再試行の間隔
+   */
+  constructor(write: (record: DecisionAuditRecord) => Promise<void>, capacity: number, retryIntervalMs: number) {
+    this.write = write;
+    this.capacity = capacity;
+    this.retryIntervalMs = retryIntervalMs;
+  }
+
+  size(): number {
+    return this.queue.length;
+  }
+
+  /**
+   * キューに追加する（上限に達している場合は追加せずfalse）
+   */
+  push(record: DecisionAuditRecord): boolean {
+    if (this.queue.length >= this.capacity) {
+      return false;
+    }
+    this.queue.push(record);
+    this.scheduleRetry();
+    return true;
+  }
+
+  /**
+   * キューの先頭から書き込む（失敗したレコードで止め、次の再試行に回す）
+   */
+  drain(): Promise<void> {
+    if (!this.draining) {
+      this.draining = this.drainQueue().finally(() => {
+        this.draining = undefined;
+      });
+    }
+    return this.draining;
+  }
+
+  /**
+   * 定期的な再試行を止める（停止時、残ったレコードは呼び出し側で報告する）
+   */
+  stop(): void {
+    this.stopped = true;
+    if (this.timer) {
+      clearTimeout(this.timer);
+      this.timer = undefined;
+    }
+  }
+
+  private async drainQueue(): Promise<void> {
+    while (this.queue.length > 0) {
+      try {
+        await this.write(this.queue[0]);
+      } catch {
+        this.scheduleRetry();
+        return;
+      }
+      this.queue.shift();
+    }
+  }
+
+  private scheduleRetry(): void {
+    if (this.timer || this.stopped) {
+      return;
+    }
+    this.timer = setTimeout(() => {
+      this.timer = undefined;
+      void this.drain();
+    }, this.retryIntervalMs);
+    this.timer.unref?.();
+  }
+}
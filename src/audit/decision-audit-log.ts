@@ -0,0 +1,272 @@
+// ============================================================================
+// AEGIS - 判定監査ログ（JSON Lines）
+// ポリシー判定ごとに1行を追記する改ざん検知しやすい監査証跡
+// 各行は直前の行のハッシュ（prevHash）と自身のハッシュ（hash）を持つハッシュチェーン
+// 鍵を指定した場合はSHA-256の代わりにHMAC-SHA256で認証する
+// 既存ファイルの末尾が壊れている・チェーンを持たない場合は、chainBreakを記録した行から新しいチェーンを始める
+// ============================================================================
+
+import * as fs from 'fs/promises';
+import { constants as fsConstants } from 'fs';
+import * as path from 'path';
+import { createHash, createHmac } from 'crypto';
+import type { PolicyProvenance } from '../policy/policy-versions.js';
+
+export interface DecisionAuditRecord {
+  timestamp: string;
+  requestId: string | number | null;
+  agent: string;
+  action: string;
+  resource: string;
+  decision: string;
+  confidence: number;
+  // 同義語を正規化する前のaction（actionは正規化後の表記）
+  originalAction?: string;
+  // 正規化する前のresource（--canonicalize-resources、resourceは正規化後の表記）
+  originalResource?: string;
+  // エージェントごとの上書き（--agent-overrides）を適用した場合のモード
+  override?: string;
+  // 許可リスト・拒否リスト（--allowlist / --denylist）で判定した場合のリスト
+  fastPath?: 'allowlist' | 'denylist';
+  // AI判定を呼び出した場合の推定トークン数（token-estimate.ts）
+  estimatedTokens?: { prompt: number; completion: number };
+  // AI判定に送信したプロンプト（--log-prompts、--prompt-redact の項目は伏せ字）
+  prompt?: string;
+  // HTTPトランスポートで受けたリクエストのX-Request-Id
+  httpRequestId?: string;
+  // check_policiesで評価したポリシーの版と、結合アルゴリズムで最終判定を決めたポリシー
+  provenance?: DecisionProvenance;
+  // 既存のチェーンを継続できずに新しいチェーンを始めた行の理由（prevHashはGENESIS_HASH）
+  chainBreak?: string;
+}
+
+export interface DecisionProvenance {
+  combiningAlgorithm: string;
+  evaluated: PolicyProvenance[];
+  controlling: PolicyProvenance | null;
+}
+
+// チェーン先頭の行のprevHash
+export const GENESIS_HASH = '0'.repeat(64);
+
+// 続きのハッシュを探すために末尾から読む大きさ（行がこれより長ければ倍にして読み直す）
+const TAIL_READ_BYTES = 64 * 1024;
+
+export type AuditVerification =
+  | { valid: true; entries: number }
+  | { valid: false; entries: number; line: number; reason: string };
+
+/**
+ * 直前のハッシュとレコード本体（hash・prevHashを除く）から行のハッシュを計算
+ */
+export function computeEntryHash(prevHash: string, entry: string, key?: string): string {
+  const digest = key ? createHmac('sha256', key) : createHash('sha256');
+  return digest.update(prevHash).update(entry).digest('hex');
+}
+
+/**
+ * 監査ログのハッシュチェーンを先頭から検証し、最初に壊れている行を報告する
+ */
+export async function verifyAuditLog(filePath: string, key?: string): Promise<AuditVerification> {
+  const lines = (await fs.readFile(filePath, 'utf-8')).split('\n');
+  let prevHash = GENESIS_HASH;
+  let entries = 0;
+
+  for (const [index, line] of lines.entries()) {
+    if (line === '') {
+      continue;
+    }
+    const failure = (reason: string): AuditVerification => ({ valid: false, entries, line: index + 1, reason });
+
+    let parsed: Record<string, unknown>;
+    try {
+      parsed = JSON.parse(line);
+    } catch {
+      return failure('Line is not valid JSON');
+    }
+
+    const { hash, prevHash: recordedPrevHash, ...entry } = parsed;
+    if (recordedPrevHash !== prevHash) {
+      return failure('prevHash does not match the previous entry');
+    }
+    if (hash !== computeEntryHash(prevHash, JSON.stringify(entry), key)) {
+      return failure(key ? 'HMAC does not match the entry' : 'Hash does not match the entry');
+    }
+
+    prevHash = hash as string;
+    entries++;
+  }
+
+  return { valid: true, entries };
+}
+
+export class DecisionAuditLog {
+  private filePath: string;
+  private key?: string;
+  // 追記順序を保つための書き込みキュー
+  private writeChain: Promise<void> = Promise.resolve();
+  private directoryReady = false;
+  // 最後に書き込んだ行のハッシュ（初回書き込み時に既存ファイルの末尾から読み込む）
+  private lastHash?: string;
+  // 既存のチェーンを継続できない理由（次に書き込む行にchainBreakとして記録する）
+  private pendingChainBreak?: string;
+  // 既存ファイルが改行で終わっていない（書き込み途中で止まった）場合、次の行の前に改行を補う
+  private needsLeadingNewline = false;
+  private onChainBreak?: (reason: string) => void;
+
+  constructor(filePath: string, key?: string, onChainBreak?: (reason: string) => void) {
+    this.filePath = path.isAbsolute(filePath) ? filePath : path.resolve(process.cwd(), filePath);
+    this.key = key;
+    this.onChainBreak = onChainBreak;
+  }
+
+  getFilePath(): string {
+    return this.filePath;
+  }
+
+  /**
+   * 1レコードを追記し、ディスクへの同期完了まで待つ
+   * 書き込みに失敗した場合はrejectする（呼び出し側でフェイルクローズする）
+   */
+  append(record: DecisionAuditRecord): Promise<void> {
+    const write = this.writeChain.then(() => this.writeRecord(record));
+    // 失敗した書き込みで後続の書き込みが止まらないようにする
+    this.writeChain = write.catch(() => undefined);
+    return write;
+  }
+
+  /**
+   * キュー内の書き込みがすべて完了するまで待つ
+   */
+  async flush(): Promise<void> {
+    await this.writeChain;
+  }
+
+  /**
+   * 監査ログを検証する（--audit-keyと同じ鍵で）
+   */
+  verify(): Promise<AuditVerification> {
+    return verifyAuditLog(this.filePath, this.key);
+  }
+
+  /**
+   * 監査ログに追記できる状態か（readinessプローブ用、ファイルは作成しない）
+   */
+  async checkWritable(): Promise<boolean> {
+    try {
+      await fs.mkdir(path.dirname(this.filePath), { recursive: true });
+      try {
+        const stat = await fs.stat(this.filePath);
+        if (!stat.isFile()) {
+          return false;
+        }
+        await fs.access(this.filePath, fsConstants.W_OK);
+      } catch (error) {
+        if ((error as NodeJS.ErrnoException).code !== 'ENOENT') {
+          throw error;
+        }
+        await fs.access(path.dirname(this.filePath), fsConstants.W_OK);
+      }
+      return true;
+    } catch {
+      return false;
+    }
+  }
+
+  private async writeRecord(record: DecisionAuditRecord): Promise<void> {
+    if (!this.directoryReady) {
+      await fs.mkdir(path.dirname(this.filePath), { recursive: true });
+      this.directoryReady = true;
+    }
+    if (this.lastHash === undefined) {
+      this.lastHash = await this.readLastHash();
+    }
+
+    const prevHash = this.lastHash;
+    const entry = this.pendingChainBreak ? { ...record, chainBreak: this.pendingChainBreak } : record;
+    const hash = computeEntryHash(prevHash, JSON.stringify(entry), this.key);
+    const prefix = this.needsLeadingNewline ? '\n' : '';
+    await this.writeLine(prefix + JSON.stringify({ ...entry, prevHash, hash }) + '\n');
+    // 書き込みに成功した行だけをチェーンに繋ぐ
+    this.lastHash = hash;
+    this.pendingChainBreak = undefined;
+    this.needsLeadingNewline = false;
+  }
+
+  /**
+   * 既存ファイルの最後の行のハッシュを返す（ファイル全体は読まない）
+   * 最後の行が壊れている・ハッシュを持たない場合は、チェーンの断絶を通知して新しいチェーンを始める
+   */
+  private async readLastHash(): Promise<string> {
+    const tail = await this.readLastLine();
+    if (!tail) {
+      return GENESIS_HASH;
+    }
+    this.needsLeadingNewline = !tail.endsWithNewline;
+
+    let hash: unknown;
+    try {
+      hash = JSON.parse(tail.line)?.hash;
+    } catch {
+      return this.startNewSegment('last line is not valid JSON');
+    }
+    if (typeof hash !== 'string' || !/^[0-9a-f]{64}$/.test(hash)) {
+      return this.startNewSegment('last line has no hash to continue');
+    }
+    return hash;
+  }
+
+  private startNewSegment(reason: string): string {
+    this.pendingChainBreak = reason;
+    this.onChainBreak?.(`${reason}: ${this.filePath}`);
+    return GENESIS_HASH;
+  }
+
+  /**
+   * 末尾から読んで最後の空でない行を返す（空・存在しないファイルはnull）
+   */
+  private async readLastLine(): Promise<{ line: string; endsWithNewline: boolean } | null> {
+    let handle: fs.FileHandle;
+    try {
+      handle = await fs.open(this.filePath, 'r');
+    } catch (error) {
+      if ((error as NodeJS.ErrnoException).code === 'ENOENT') {
+        return null;
+      }
+      throw error;
+    }
+
+    try {
+      const { size } = await handle.stat();
+      let length = Math.min(size, TAIL_READ_BYTES);
+      while (length > 0) {
+        const buffer = Buffer.alloc(length);
+        await handle.read(buffer, 0, length, size - length);
+        const lines = buffer.toString('utf-8').split('\n');
+        // ファイルの途中から読んだ場合、先頭の要素は行の途中なので使わない
+        const complete = length === size ? lines : lines.slice(1);
+        const line = complete.filter(candidate => candidate !== '').pop();
+        if (line !== undefined) {
+          return { line, endsWithNewline: buffer[length - 1] === 0x0a };
+        }
+        if (length === size) {
+          break;
+        }
+        length = Math.min(size, length * 2);
+      }
+      return null;
+    } finally {
+      await handle.close();
+    }
+  }
+
+  private async writeLine(line: string): Promise<void> {
+    const handle = await fs.open(this.filePath, 'a');
+    try {
+      await handle.appendFile(line, 'utf-8');
+      await handle.sync();
+    } finally {
+      await handle.close();
+    }
+  }
+}
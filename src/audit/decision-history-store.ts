@@ -0,0 +1,159 @@
+// ============================================================================
+// AEGIS - 判定履歴DB（SQLite）
+// 追記専用の監査ログとは別に、過去の判定を条件で検索できるように保存する
+// Node.js組み込みの node:sqlite を使うため追加の依存関係は不要（Node.js 22.13以降）
+// ============================================================================
+
+import * as fs from 'fs';
+import * as path from 'path';
+import type { DecisionAuditRecord } from './decision-audit-log.js';
+
+// node:sqlite のうち使用する部分のみの型
+interface SqliteStatement {
+  run(...params: unknown[]): unknown;
+  all(...params: unknown[]): unknown[];
+}
+
+interface SqliteDatabase {
+  exec(sql: string): void;
+  prepare(sql: string): SqliteStatement;
+  close(): void;
+}
+
+export interface DecisionHistoryRow extends DecisionAuditRecord {
+  id: number;
+}
+
+export interface DecisionHistoryFilter {
+  agent?: string;
+  action?: string;
+  resource?: string;
+  decision?: string;
+  since?: string;  // ISO 8601（この時刻以降）
+  until?: string;  // ISO 8601（この時刻より前）
+  limit?: number;
+}
+
+export const DEFAULT_HISTORY_QUERY_LIMIT = 100;
+export const MAX_HISTORY_QUERY_LIMIT = 1000;
+
+const SCHEMA = `
+CREATE TABLE IF NOT EXISTS decisions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  timestamp TEXT NOT NULL,
+  request_id TEXT,
+  agent TEXT NOT NULL,
+  action TEXT NOT NULL,
+  resource TEXT NOT NULL,
+  decision TEXT NOT NULL,
+  confidence REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_decisions_agent ON decisions (agent);
+CREATE INDEX IF NOT EXISTS idx_decisions_action ON decisions (action);
+CREATE INDEX IF NOT EXISTS idx_decisions_resource ON decisions (resource);
+CREATE INDEX IF NOT EXISTS idx_decisions_decision ON decisions (decision);
+CREATE INDEX IF NOT EXISTS idx_decisions_timestamp ON decisions (timestamp);
+`;
+
+// node:sqlite は型定義のないNode.jsバージョンがあるため、指定子を変数経由で読み込む
+const SQLITE_MODULE = 'node:sqlite';
+
+export class DecisionHistoryStore {
+  private db: SqliteDatabase;
+  private filePath: string;
+  private insertStatement: SqliteStatement;
+
+  private constructor(db: SqliteDatabase, filePath: string) {
+    this.db = db;
+    this.filePath = filePath;
+    this.db.exec(SCHEMA);
+    this.insertStatement = this.db.prepare(
+      `INSERT INTO decisions (timestamp, request_id, agent, action, resource, decision, confidence)
+       VALUES (?, ?, ?, ?, ?, ?, ?)`
+    );
+  }
+
+  /**
+   * DBを開く（初回はスキーマを作成）
+   */
+  static async open(filePath: string): Promise<DecisionHistoryStore> {
+    const resolved = path.isAbsolute(filePath) ? filePath : path.resolve(process.cwd(), filePath);
+
+    let sqlite: { DatabaseSync: new (location: string) => SqliteDatabase };
+    try {
+      sqlite = await import(SQLITE_MODULE);
+    } catch (error) {
+      throw new Error(`Decision history requires node:sqlite (Node.js 22.13 or later): ${error instanceof Error ? error.message : error}`);
+    }
+
+    fs.mkdirSync(path.dirname(resolved), { recursive: true });
+    return new DecisionHistoryStore(new sqlite.DatabaseSync(resolved), resolved);
+  }
+
+  getFilePath(): string {
+    return this.filePath;
+  }
+
+  insert(record: DecisionAuditRecord): void {
+    this.insertStatement.run(
+      record.timestamp,
+      record.requestId === null ? null : String(record.requestId),
+      record.agent,
+      record.action,
+      record.resource,
+      record.decision,
+      record.confidence
+    );
+  }
+
+  /**
+   * 条件に一致する判定を新しい順に返す（条件は完全一致、期間はISO 8601の範囲）
+   */
+  query(filter: DecisionHistoryFilter = {}): DecisionHistoryRow[] {
+    const conditions: string[] = [];
+    const params: unknown[] = [];
+
+    const exact: Array<[keyof DecisionHistoryFilter, string]> = [
+      ['agent', 'agent'],
+      ['action', 'action'],
+      ['resource', 'resource'],
+      ['decision', 'decision']
+    ];
+    for (const [key, column] of exact) {
+      if (filter[key] !== undefined) {
+        conditions.push(`${column} = ?`);
+        params.push(filter[key]);
+      }
+    }
+    if (filter.since !== undefined) {
+      conditions.push('timestamp >= ?');
+      params.push(filter.since);
+    }
+    if (filter.until !== undefined) {
+      conditions.push('timestamp < ?');
+      params.push(filter.until);
+    }
+
+    const limit = Math.min(Math.max(filter.limit ?? DEFAULT_HISTORY_QUERY_LIMIT, 1), MAX_HISTORY_QUERY_LIMIT);
+    const where = conditions.length > 0 ? `WHERE ${conditions.join(' AND ')}` : '';
+    const rows = this.db.prepare(
+      `SELECT id, timestamp, request_id, agent, action, resource, decision, confidence
+       FROM decisions ${where} ORDER BY timestamp DESC, id DESC LIMIT ?`
+    ).all(...params, limit) as Array<Record<string, any>>;
+
+    return rows.map(row => ({
+      id: Number(row.id),
+      timestamp: row.timestamp,
+      requestId: row.request_id,
+      agent: row.agent,
+      action: row.action,
+      resource: row.resource,
+      decision: row.decision,
+      confidence: row.confidence
+    }));
+  }
+
+  close(): void {
+    this.db.close();
+  }
+}
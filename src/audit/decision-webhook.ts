@@ -0,0 +1,150 @@
+// ============================================================================
+// AEGIS - 判定のWebhook通知（--webhook-url）
+// フィルターに一致する判定（既定はDENYのみ）をJSONでPOSTし、セキュリティチームへ即時に知らせる
+// 送信は応答を待たずに非同期で行い、再送しても届かなかった通知はデッドレターログ（JSON Lines）に残す
+// 送信中の通知が上限に達している間の通知は送らずにデッドレターへ回す（Webhook停止中にメモリを使い切らないため）
+// ============================================================================
+
+import * as fs from 'fs/promises';
+import * as path from 'path';
+import { AUDIT } from '../constants/index.js';
+
+export interface DecisionWebhookPayload {
+  agent: string;
+  action: string;
+  resource: string;
+  decision: string;
+  reason: string;
+  timestamp: string;
+}
+
+export interface DecisionWebhookOptions {
+  // 通知する判定（大文字、例: ['DENY']）
+  decisions?: string[];
+  maxRetries?: number;
+  retryBackoffMs?: number;
+  timeoutMs?: number;
+  // 同時に送信中にできる通知の数
+  maxPending?: number;
+  // 届かなかった通知の出力先（未設定ならログに警告するだけ）
+  deadLetterPath?: string;
+  fetch?: typeof fetch;
+  onError?: (message: string, error?: unknown) => void;
+}
+
+export const WEBHOOK_DECISIONS = ['PERMIT', 'DENY', 'INDETERMINATE'] as const;
+
+/**
+ * --webhook-decisions のうち判定として読めない値を返す（大文字小文字は区別しない）
+ */
+export function invalidWebhookDecisions(decisions: string[]): string[] {
+  return decisions.filter(decision => !(WEBHOOK_DECISIONS as readonly string[]).includes(decision.toUpperCase()));
+}
+
+/**
+ * http(s)のURLとして読めるか（--webhook-urlの検証用）
+ */
+export function isWebhookUrl(value: string): boolean {
+  try {
+    const url = new URL(value);
+    return url.protocol === 'http:' || url.protocol === 'https:';
+  } catch {
+    return false;
+  }
+}
+
+export class DecisionWebhookNotifier {
+  private url: string;
+  private decisions: Set<string>;
+  private maxRetries: number;
+  private retryBackoffMs: number;
+  private timeoutMs: number;
+  private maxPending: number;
+  private deadLetterPath?: string;
+  private fetchImpl: typeof fetch;
+  private onError: (message: string, error?: unknown) => void;
+  // 送信中の通知（停止時に待ち合わせる）
+  private pending = new Set<Promise<void>>();
+  // pending のうちWebhookへの送信（再送待ちを含む）の数
+  private activeDeliveries = 0;
+
+  constructor(url: string, options: DecisionWebhookOptions = {}) {
+    this.url = url;
+    this.decisions = new Set((options.decisions ?? AUDIT.WEBHOOK.DEFAULT_DECISIONS).map(decision => decision.toUpperCase()));
+    this.maxRetries = options.maxRetries ?? AUDIT.WEBHOOK.MAX_RETRIES;
+    this.retryBackoffMs = options.retryBackoffMs ?? AUDIT.WEBHOOK.RETRY_BACKOFF_MS;
+    this.timeoutMs = options.timeoutMs ?? AUDIT.WEBHOOK.TIMEOUT_MS;
+    this.maxPending = options.maxPending ?? AUDIT.WEBHOOK.MAX_PENDING;
+    this.deadLetterPath = options.deadLetterPath && path.resolve(options.deadLetterPath);
+    this.fetchImpl = options.fetch ?? fetch;
+    this.onError = options.onError ?? (() => undefined);
+  }
+
+  /**
+   * フィルターに一致する判定なら送信を始める（完了を待たない）
+   * 送信中の通知が上限に達していれば送らずにデッドレターへ書き込む
+   */
+  notify(payload: DecisionWebhookPayload): void {
+    if (!this.decisions.has(payload.decision.toUpperCase())) {
+      return;
+    }
+    if (this.activeDeliveries >= this.maxPending) {
+      this.onError(`Decision webhook queue is full (${this.maxPending} pending), notification not sent`);
+      this.track(this.writeDeadLetter(payload, 'queue full', 0));
+      return;
+    }
+    this.activeDeliveries++;
+    this.track(this.deliver(payload).finally(() => { this.activeDeliveries--; }));
+  }
+
+  /**
+   * 送信中の通知（再送・デッドレターへの書き込みを含む）がすべて終わるまで待つ
+   */
+  async flush(): Promise<void> {
+    await Promise.all([...this.pending]);
+  }
+
+  private track(task: Promise<void>): void {
+    const tracked = task.finally(() => this.pending.delete(tracked));
+    this.pending.add(tracked);
+  }
+
+  private async deliver(payload: DecisionWebhookPayload): Promise<void> {
+    let lastError = '';
+    for (let attempt = 0; attempt <= this.maxRetries; attempt++) {
+      if (attempt > 0) {
+        await new Promise(resolve => setTimeout(resolve, this.retryBackoffMs * 2 ** (attempt - 1)));
+      }
+      try {
+        const response = await this.fetchImpl(this.url, {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json' },
+          body: JSON.stringify(payload),
+          signal: AbortSignal.timeout(this.timeoutMs)
+        });
+        if (response.ok) {
+          return;
+        }
+        lastError = `HTTP ${response.status}`;
+      } catch (error) {
+        lastError = error instanceof Error ? error.message : String(error);
+      }
+    }
+
+    this.onError(`Decision webhook failed after ${this.maxRetries + 1} attempts: ${lastError}`);
+    await this.writeDeadLetter(payload, lastError, this.maxRetries + 1);
+  }
+
+  private async writeDeadLetter(payload: DecisionWebhookPayload, error: string, attempts: number): Promise<void> {
+    if (!this.deadLetterPath) {
+      return;
+    }
+    const entry = { failedAt: new Date().toISOString(), url: this.url, attempts, error, payload };
+    try {
+      await fs.mkdir(path.dirname(this.deadLetterPath), { recursive: true });
+      await fs.appendFile(this.deadLetterPath, `${JSON.stringify(entry)}\n`, 'utf-8');
+    } catch (writeError) {
+      this.onError(`Failed to write decision webhook dead letter: ${this.deadLetterPath}`, writeError);
+    }
+  }
+}
@@ -3,11 +3,16 @@
 // 全システムで使用される定数定義
 // ============================================================================
 
+import packageJson from '../../package.json';
+
 // タイムアウト設定
 export const TIMEOUTS = {
   // ポリシー判定
   POLICY_DECISION: 30000,        // 30秒
   POLICY_DECISION_BATCH: 60000,  // 60秒（バッチ処理）
+  POLICY_EVALUATION: 20000,      // 20秒（tools/call内の判定1回、--eval-timeout-ms。超えたらDENY）
+  EVAL_RETRY_BACKOFF: 200,       // 0.2秒（--eval-retry-backoff-ms: AI判定の1回目の再試行までの待ち時間）
+  EVAL_BREAKER_COOLDOWN: 30000,  // 30秒（--eval-breaker-cooldown-ms: サーキットブレーカーが開いている時間）
   
   // 上流サーバー
   UPSTREAM_REQUEST: 60000,       // 60秒（テスト用に延長）
@@ -18,10 +23,12 @@ export const TIMEOUTS = {
   OBLIGATION_EXECUTION: 30000,   // 30秒
   
   // その他
+  TOOL_CALL: 30000,              // 30秒（tools/call 1件あたり）
   CONTEXT_ENRICHMENT: 5000,      // 5秒
   CACHE_OPERATION: 1000,         // 1秒
   AUDIT_WRITE: 5000,            // 5秒
   STARTUP_DELAY: 2000,          // 2秒（起動時の待機）
+  POLICY_WATCH_DEBOUNCE: 300,   // 0.3秒（--watch: 連続した書き込みをまとめる）
 } as const;
 
 // サーキットブレーカー設定
@@ -61,6 +68,8 @@ export const BATCH = {
   },
   TIMEOUT: 2000,                // 2秒
   MAX_QUEUE_SIZE: 100,
+  MAX_SIMULATE_SIZE: 50,        // policy_simulateで一度に判定できるリクエスト数
+  POLICY_LOAD_CONCURRENCY: 8,   // ポリシーディレクトリの読み込みで同時に読むファイル数
 } as const;
 
 // サーバー設定
@@ -70,6 +79,17 @@ export const SERVER = {
     API: 8080,
   },
   GRACEFUL_SHUTDOWN_TIMEOUT: 10000, // 10秒
+  // initializeで通知するserverInfoの既定値（バージョンはpackage.jsonから読む）
+  DEFAULT_NAME: 'aegis-proxy',
+  DEFAULT_VERSION: packageJson.version,
+  // 1リクエスト（stdioの1行 / HTTPボディ）の最大サイズ
+  DEFAULT_MAX_REQUEST_BYTES: 1024 * 1024, // 1MiB
+  // HTTPレスポンスをgzip圧縮する最小サイズ（これ未満は圧縮しない）
+  DEFAULT_GZIP_MIN_BYTES: 1024, // 1KiB
+  // 1応答の最大サイズ（超えたらテキストを切り詰める、0で無制限）
+  DEFAULT_MAX_RESPONSE_BYTES: 0,
+  // tools/callの重複排除で応答を覚えておくidの数（0で無効）
+  DEFAULT_DEDUP_WINDOW: 100,
 } as const;
 
 // 監査設定
@@ -81,6 +101,17 @@ export const AUDIT = {
     MAX_FILES: 10,
     DATE_PATTERN: 'YYYY-MM-DD',
   },
+  FAILURE_BUFFER: {
+    MAX_RECORDS: 1000,            // --audit-failure-mode buffer で保持するレコードの上限
+    RETRY_INTERVAL_MS: 5000,      // 書き込みの再試行間隔
+  },
+  WEBHOOK: {
+    DEFAULT_DECISIONS: ['DENY'],  // 通知する判定（--webhook-decisions 未指定時）
+    MAX_RETRIES: 3,               // 初回の送信に失敗した後の再送回数
+    RETRY_BACKOFF_MS: 1000,       // 再送間隔（試行ごとに倍にする）
+    TIMEOUT_MS: 5000,             // 1回の送信のタイムアウト
+    MAX_PENDING: 100,             // 同時に送信中にできる通知の数（超えた分はすぐデッドレターへ）
+  },
 } as const;
 
 // モニタリング設定
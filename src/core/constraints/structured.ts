@@ -0,0 +1,244 @@
+// ============================================================================
+// AEGIS - 構造化制約
+// 判定結果のconstraintsのうち、機械的に評価できる小さな文法の制約を解釈する
+//   time_window:09:00-17:00   指定時間帯のみ許可（日跨ぎ可: 22:00-06:00）
+//   rate_limit:100/min        エージェントごとの回数制限（単位: sec/min/hour/day）
+//   require_approval[:role]   承認済み（environment.approvedBy）であることを要求
+// それ以外の文字列は自然言語の制約として従来どおり制約プロセッサに任せる
+// ============================================================================
+
+import type { DecisionContext, PolicyDecision } from '../../types/index.js';
+
+export type StructuredConstraint =
+  | { type: 'time_window'; raw: string; startMinutes: number; endMinutes: number }
+  | { type: 'rate_limit'; raw: string; maxRequests: number; windowMs: number }
+  | { type: 'require_approval'; raw: string; approver?: string };
+
+export interface ConstraintCheck {
+  constraint: string;
+  satisfied: boolean;
+  reason: string;
+}
+
+export interface ConstraintEvaluation {
+  satisfied: boolean;
+  checks: ConstraintCheck[];
+}
+
+const STRUCTURED_TYPES = ['time_window', 'rate_limit', 'require_approval'];
+
+const RATE_UNITS_MS: Record<string, number> = {
+  s: 1000,
+  sec: 1000,
+  m: 60 * 1000,
+  min: 60 * 1000,
+  h: 60 * 60 * 1000,
+  hour: 60 * 60 * 1000,
+  d: 24 * 60 * 60 * 1000,
+  day: 24 * 60 * 60 * 1000
+};
+
+function parseClock(value: string): number | null {
+  const match = value.trim().match(/^(\d{1,2}):(\d{2})$/);
+  if (!match) {
+    return null;
+  }
+  const hours = Number(match[1]);
+  const minutes = Number(match[2]);
+  if (hours > 24 || minutes > 59 || (hours === 24 && minutes !== 0)) {
+    return null;
+  }
+  return hours * 60 + minutes;
+}
+
+/**
+ * 構造化制約として解釈できるか（型名のプレフィックスで判定）
+ */
+export function isStructuredConstraint(text: string): boolean {
+  const type = text.trim().split(':')[0];
+  return STRUCTURED_TYPES.includes(type);
+}
+
+/**
+ * 制約文字列を解釈する
+ * 構造化制約でない文字列はnull、型名は正しいが引数が不正な場合は例外
+ */
+export function parseConstraint(text: string): StructuredConstraint | null {
+  const raw = text.trim();
+  if (!isStructuredConstraint(raw)) {
+    return null;
+  }
+
+  const separator = raw.indexOf(':');
+  const type = separator === -1 ? raw : raw.substring(0, separator);
+  const argument = separator === -1 ? '' : raw.substring(separator + 1).trim();
+
+  switch (type) {
+    case 'time_window': {
+      const [start, end, ...rest] = argument.split('-');
+      const startMinutes = start !== undefined ? parseClock(start) : null;
+      const endMinutes = end !== undefined ? parseClock(end) : null;
+      if (rest.length > 0 || startMinutes === null || endMinutes === null) {
+        throw new Error(`Invalid time_window constraint: ${raw} (expected time_window:HH:MM-HH:MM)`);
+      }
+      return { type: 'time_window', raw, startMinutes, endMinutes };
+    }
+
+    case 'rate_limit': {
+      const match = argument.match(/^(\d+)\s*\/\s*([a-z]+)$/);
+      const windowMs = match ? RATE_UNITS_MS[match[2]] : undefined;
+      if (!match || !windowMs || Number(match[1]) <= 0) {
+        throw new Error(`Invalid rate_limit constraint: ${raw} (expected rate_limit:N/sec|min|hour|day)`);
+      }
+      return { type: 'rate_limit', raw, maxRequests: Number(match[1]), windowMs };
+    }
+
+    default:
+      return { type: 'require_approval', raw, approver: argument || undefined };
+  }
+}
+
+/**
+ * 構造化制約の評価器
+ * rate_limitの計数を保持するため、プロキシごとに1インスタンスを使う
+ */
+export class ConstraintEvaluator {
+  // `${agent}|${制約}` ごとの要求時刻（スライディングウィンドウ）とウィンドウの長さ
+  private requestLog = new Map<string, { windowMs: number; times: number[] }>();
+
+  /**
+   * 制約を評価し、それぞれ満たされているかを返す
+   * 評価したrate_limitには今回の要求が1回として計上される
+   */
+  evaluate(constraints: StructuredConstraint[], context: DecisionContext, now: number = Date.now()): ConstraintEvaluation {
+    this.evictExpiredWindows(now);
+    const checks = constraints.map(constraint => this.check(constraint, context, now));
+    return {
+      satisfied: checks.every(check => check.satisfied),
+      checks
+    };
+  }
+
+  /**
+   * 判定結果のconstraintsから構造化制約を取り出して評価する
+   * 引数が不正な構造化制約は満たされないものとして扱う（フェイルクローズ）
+   */
+  evaluateDecision(decision: Pick<PolicyDecision, 'constraints'>, context: DecisionContext, now: number = Date.now()): ConstraintEvaluation {
+    const parsed: StructuredConstraint[] = [];
+    const invalid: ConstraintCheck[] = [];
+
+    for (const text of decision.constraints ?? []) {
+      try {
+        const constraint = parseConstraint(text);
+        if (constraint) {
+          parsed.push(constraint);
+        }
+      } catch (error) {
+        invalid.push({
+          constraint: text,
+          satisfied: false,
+          reason: error instanceof Error ? error.message : 'Invalid constraint'
+        });
+      }
+    }
+
+    const evaluation = this.evaluate(parsed, context, now);
+    return {
+      satisfied: evaluation.satisfied && invalid.length === 0,
+      checks: [...evaluation.checks, ...invalid]
+    };
+  }
+
+  /**
+   * 計数中のrate_limitのウィンドウ数（エージェントと制約の組み合わせごと）
+   */
+  get trackedWindows(): number {
+    return this.requestLog.size;
+  }
+
+  /**
+   * 最後の要求からウィンドウの長さ以上経ったエージェントの記録を捨てる
+   * （要求をやめたエージェントの記録が残り続けないようにする）
+   */
+  private evictExpiredWindows(now: number): void {
+    for (const [key, { windowMs, times }] of this.requestLog) {
+      if (now - times[times.length - 1] >= windowMs) {
+        this.requestLog.delete(key);
+      }
+    }
+  }
+
+  private check(constraint: StructuredConstraint, context: DecisionContext, now: number): ConstraintCheck {
+    switch (constraint.type) {
+      case 'time_window': {
+        const time = context.time instanceof Date ? context.time : new Date(context.time);
+        const minutes = time.getHours() * 60 + time.getMinutes();
+        const { startMinutes, endMinutes } = constraint;
+        const inWindow = startMinutes <= endMinutes
+          ? minutes >= startMinutes && minutes < endMinutes
+          : minutes >= startMinutes || minutes < endMinutes;
+        return {
+          constraint: constraint.raw,
+          satisfied: inWindow,
+          reason: inWindow ? 'Within the allowed time window' : 'Outside the allowed time window'
+        };
+      }
+
+      case 'rate_limit': {
+        const key = `${context.agent}|${constraint.raw}`;
+        const recent = (this.requestLog.get(key)?.times ?? []).filter(at => now - at < constraint.windowMs);
+        const allowed = recent.length < constraint.maxRequests;
+        if (allowed) {
+          recent.push(now);
+        }
+        if (recent.length > 0) {
+          this.requestLog.set(key, { windowMs: constraint.windowMs, times: recent });
+        } else {
+          this.requestLog.delete(key);
+        }
+        return {
+          constraint: constraint.raw,
+          satisfied: allowed,
+          reason: allowed
+            ? `${recent.length}/${constraint.maxRequests} requests in window`
+            : `Rate limit of ${constraint.maxRequests} requests exceeded`
+        };
+      }
+
+      case 'require_approval': {
+        const approvedBy = context.environment?.approvedBy;
+        const approved = typeof approvedBy === 'string' && approvedBy !== '' &&
+          (!constraint.approver || approvedBy === constraint.approver);
+        return {
+          constraint: constraint.raw,
+          satisfied: approved,
+          reason: approved
+            ? `Approved by ${approvedBy}`
+            : `Approval required${constraint.approver ? ` from ${constraint.approver}` : ''}`
+        };
+      }
+    }
+  }
+}
+
+/**
+ * 構造化制約を満たさないPERMITをINDETERMINATEに格下げする
+ * DENY・INDETERMINATEはそのまま返す
+ */
+export function applyConstraintEvaluation<T extends PolicyDecision>(decision: T, evaluation: ConstraintEvaluation): T {
+  if (decision.decision !== 'PERMIT' || evaluation.satisfied) {
+    return decision;
+  }
+
+  const unmet = evaluation.checks.filter(check => !check.satisfied);
+  return {
+    ...decision,
+    decision: 'INDETERMINATE',
+    reason: `${decision.reason} (unmet constraints: ${unmet.map(check => `${check.constraint} - ${check.reason}`).join('; ')})`,
+    metadata: {
+      ...decision.metadata,
+      unmetConstraints: unmet.map(check => check.constraint).join(', '),
+      indeterminateSource: 'constraint'
+    }
+  };
+}
@@ -9,6 +9,7 @@ import { ContextCollector } from './context/collector.js';
 // Removed old WebSocket proxy import
 import { MCPStdioPolicyProxy } from './mcp/stdio-proxy.js';
 import { MCPHttpPolicyProxy } from './mcp/http-proxy.js';
+import { InMemoryDispatchTransport } from './mcp/in-memory-dispatcher.js';
 import { PolicyAdministrator } from './policies/administrator.js';
 import { Logger } from './utils/logger.js';
 import { Config } from './utils/config.js';
@@ -84,6 +85,7 @@ export {
   // MCPPolicyProxy removed - use MCPStdioPolicyProxy or MCPHttpPolicyProxy
   MCPStdioPolicyProxy,
   MCPHttpPolicyProxy,
+  InMemoryDispatchTransport,
   PolicyAdministrator,
   type DecisionContext,
   type PolicyDecision,
@@ -91,6 +93,14 @@ export {
   type NaturalLanguagePolicyDefinition
 };
 
+// 義務ハンドラーのエクスポート
+export {
+  ObligationHandlerRegistry,
+  LogObligationHandler,
+  type ObligationHandler,
+  type ObligationOutcome
+} from './mcp/obligation-handlers.js';
+
 // エラークラスのエクスポート
 export {
   AEGISError,
@@ -6,11 +6,26 @@
 // ============================================================================
 
 import { Config } from './utils/config.js';
-import { Logger } from './utils/logger.js';
+import { LOG_FORMATS, Logger, isLogFormat } from './utils/logger.js';
 import { AIJudgmentEngine } from './ai/judgment-engine.js';
+import { POLICY_ANALYSIS_PLACEHOLDERS, validateTemplatePlaceholders } from './ai/prompt-templates.js';
 import { MCPStdioPolicyProxy } from './mcp/stdio-proxy.js';
 import { MCPHttpPolicyProxy } from './mcp/http-proxy.js';
 import { policyLoader } from './policies/policy-loader.js';
+import { PolicyDirectoryWatcher } from './policies/policy-directory-watcher.js';
+import { loadLintRules } from './policy/policy-lint.js';
+import { loadAgentOverrides } from './policy/agent-overrides.js';
+import { loadAccessList } from './policy/access-lists.js';
+import { loadActionSynonyms } from './policy/action-synonyms.js';
+import { verifyAuditLog } from './audit/decision-audit-log.js';
+import { WEBHOOK_DECISIONS, invalidWebhookDecisions, isWebhookUrl } from './audit/decision-webhook.js';
+import { AUDIT_FAILURE_MODES, isAuditFailureMode } from './audit/audit-failure-mode.js';
+import { loadConfigFile } from './utils/config-file.js';
+import { formatConfigSummary } from './utils/config-summary.js';
+import { FRAMINGS, isFraming } from './mcp/content-length-transport.js';
+import { runRepl } from './mcp/repl.js';
+import { INDETERMINATE_RESOLUTIONS, isIndeterminateResolution } from './policy/indeterminate-resolution.js';
+import { BATCH, SERVER, TIMEOUTS } from './constants/index.js';
 import * as dotenv from 'dotenv';
 import * as fs from 'fs';
 import * as path from 'path';
@@ -21,10 +36,26 @@ import cors from 'cors';
 // 環境変数読み込み
 dotenv.config();
 
+// --check-config の要約に表示する、指定されたファイル（環境変数と表示名）
+const CHECKED_FILES: ReadonlyArray<[string, string]> = [
+  ['AEGIS_POLICY_DIR', 'Policy directory'],
+  ['AEGIS_PROMPT_TEMPLATE', 'Prompt template'],
+  ['AEGIS_LINT_RULES', 'Lint rules'],
+  ['AEGIS_ACTION_SYNONYMS', 'Action synonyms'],
+  ['AEGIS_AGENT_OVERRIDES', 'Agent overrides'],
+  ['AEGIS_ALLOWLIST', 'Allowlist'],
+  ['AEGIS_DENYLIST', 'Denylist'],
+  ['AEGIS_DEFAULT_CONTEXT', 'Default context'],
+  ['AEGIS_CONTEXT_SCHEMA', 'Context schema'],
+  ['AEGIS_AUDIT_LOG', 'Audit log'],
+  ['AEGIS_HISTORY_DB', 'History database']
+];
+
 /**
  * MCPプロキシサーバーを起動
+ * checkConfig のときは同じ手順で読み込みと検証だけを行い、要約を表示して終了する（--check-config）
  */
-async function startMCPServer(transport: 'stdio' | 'http' | 'api-only' = 'stdio') {
+async function startMCPServer(transport: 'stdio' | 'http' | 'api-only' = 'stdio', repl = false, checkConfig = false) {
   const logLevel = process.env.LOG_LEVEL || 'info';
   const logger = new Logger(logLevel);
   
@@ -34,6 +65,19 @@ async function startMCPServer(transport: 'stdio' | 'http' | 'api-only' = 'stdio'
     // 設定を読み込み（環境変数とdefault値を使用）
     const config = new Config();
 
+    // 判定プロンプトのテンプレート（--prompt-template / AEGIS_PROMPT_TEMPLATE、未指定時は組み込み）
+    let promptTemplate: string | undefined;
+    const promptTemplatePath = process.env.AEGIS_PROMPT_TEMPLATE;
+    if (promptTemplatePath) {
+      try {
+        promptTemplate = fs.readFileSync(path.resolve(promptTemplatePath), 'utf-8');
+        validateTemplatePlaceholders(promptTemplate, POLICY_ANALYSIS_PLACEHOLDERS);
+      } catch (error) {
+        logger.critical(`Invalid prompt template ${promptTemplatePath}: ${error instanceof Error ? error.message : error}`);
+        throw error;
+      }
+    }
+
     // APIキーチェック
     let judgmentEngine: AIJudgmentEngine | null = null;
     let useAI = true;
@@ -46,10 +90,21 @@ async function startMCPServer(transport: 'stdio' | 'http' | 'api-only' = 'stdio'
       // AI判定エンジン初期化
       logger.info('Initializing AI Judgment Engine...');
       judgmentEngine = new AIJudgmentEngine(config.llm);
+      if (promptTemplate) {
+        judgmentEngine.setDecisionPromptTemplate(promptTemplate);
+        logger.info(`  ✓ Loaded prompt template: ${promptTemplatePath}`);
+      }
+      // 送信したプロンプトを監査ログに記録（--log-prompts、プロンプトには機密のコンテキストが含まれうる）
+      if (process.env.AEGIS_LOG_PROMPTS === 'true') {
+        const redactFields = (process.env.AEGIS_PROMPT_REDACT ?? '').split(',').map(field => field.trim()).filter(field => field !== '');
+        judgmentEngine.enablePromptLogging(redactFields);
+        logger.warn(`Logging rendered prompts to the decision audit log (redacted: ${redactFields.length > 0 ? redactFields.join(', ') : 'none'})`);
+      }
     }
 
     // トランスポートに応じてプロキシを初期化
     let mcpProxy: MCPStdioPolicyProxy | MCPHttpPolicyProxy;
+    const upstreamServerNames: string[] = [];
     
     if (transport === 'stdio') {
       logger.info('Using stdio transport (MCP standard)');
@@ -74,14 +129,19 @@ async function startMCPServer(transport: 'stdio' | 'http' | 'api-only' = 'stdio'
           
           if (aegisConfig.mcpServers) {
             logger.info('Loading upstream servers from aegis-mcp-config.json...');
-            mcpProxy.loadDesktopConfig(aegisConfig);
+            // --check-config では上流サーバーを登録するだけで起動しない
+            mcpProxy.loadDesktopConfig(aegisConfig, !checkConfig);
             
             const serverNames = Object.keys(aegisConfig.mcpServers)
               .filter(name => name !== 'aegis-proxy' && name !== 'aegis');
+            upstreamServerNames.push(...serverNames);
             logger.info(`  ✓ Loaded ${serverNames.length} servers: ${serverNames.join(', ')}`);
           
           }
         } catch (error) {
+          if (checkConfig) {
+            throw new Error(`Failed to load ${aegisConfigPath}: ${error instanceof Error ? error.message : error}`);
+          }
           logger.warn('Failed to load aegis-mcp-config.json:', error);
         }
       }
@@ -97,13 +157,17 @@ async function startMCPServer(transport: 'stdio' | 'http' | 'api-only' = 'stdio'
           
           if (desktopConfig.mcpServers) {
             logger.info('Loading upstream servers from Claude Desktop config...');
-            mcpProxy.loadDesktopConfig(desktopConfig);
+            mcpProxy.loadDesktopConfig(desktopConfig, !checkConfig);
             
             const serverNames = Object.keys(desktopConfig.mcpServers)
               .filter(name => name !== 'aegis-proxy' && name !== 'aegis');
+            upstreamServerNames.push(...serverNames);
             logger.info(`  ✓ Loaded ${serverNames.length} servers: ${serverNames.join(', ')}`);
           }
         } catch (error) {
+          if (checkConfig) {
+            throw new Error(`Failed to load ${desktopConfigPath}: ${error instanceof Error ? error.message : error}`);
+          }
           logger.warn('Failed to load Claude Desktop config:', error);
         }
       }
@@ -118,6 +182,7 @@ async function startMCPServer(transport: 'stdio' | 'http' | 'api-only' = 'stdio'
           if (name && commandParts.length > 0) {
             const [command, ...args] = commandParts[0].split(' ');
             mcpProxy.addUpstreamServer(name, command, args);
+            upstreamServerNames.push(name);
             logger.info(`  ✓ Added upstream: ${name} -> ${command} ${args.join(' ')}`);
           }
         });
@@ -141,9 +206,13 @@ async function startMCPServer(transport: 'stdio' | 'http' | 'api-only' = 'stdio'
             
             const serverNames = Object.keys(aegisConfig.mcpServers)
               .filter(name => name !== 'aegis-proxy' && name !== 'aegis');
+            upstreamServerNames.push(...serverNames);
             logger.info(`  ✓ Loaded ${serverNames.length} stdio servers in bridge mode: ${serverNames.join(', ')}`);
           }
         } catch (error) {
+          if (checkConfig) {
+            throw new Error(`Failed to load ${aegisConfigPath}: ${error instanceof Error ? error.message : error}`);
+          }
           logger.warn('Failed to load aegis-mcp-config.json:', error);
         }
       }
@@ -158,6 +227,7 @@ async function startMCPServer(transport: 'stdio' | 'http' | 'api-only' = 'stdio'
           const url = urlParts.join(':');
           if (name && url) {
             mcpProxy.addUpstreamServer(name.trim(), url.trim());
+            upstreamServerNames.push(name.trim());
             logger.info(`  ✓ Added HTTP upstream: ${name} -> ${url}`);
           }
         });
@@ -172,22 +242,130 @@ async function startMCPServer(transport: 'stdio' | 'http' | 'api-only' = 'stdio'
 
     // デフォルトポリシーを追加
     logger.info('Loading default policies...');
+    const loadedPolicies: string[] = [];
     try {
       await policyLoader.loadPolicies();
+      
+      // --policy-dir / AEGIS_POLICY_DIR の .md/.txt ファイルも読み込む
+      const policyDir = process.env.AEGIS_POLICY_DIR;
+      if (policyDir) {
+        policyLoader.setStrictEnv(process.env.AEGIS_STRICT_ENV === 'true');
+        await policyLoader.loadPolicyDirectory(policyDir);
+      }
+      
       const policies = policyLoader.getAllPolicies();
       
       policies.forEach(policy => {
-        const policyText = typeof policy.policy === 'string' 
-          ? policy.policy 
-          : JSON.stringify(policy.policy);
+        const policyText = policyLoader.getPolicyText(policy);
         
-        mcpProxy.addPolicy(policy.id, policyText);
+        mcpProxy.addPolicy(policy.id, policyText, policy.metadata?.tags);
+        loadedPolicies.push(policy.id);
         logger.info(`  ✓ Loaded policy: ${policy.id}`);
       });
+
+      // --watch: ポリシーディレクトリの変更を再起動なしで反映する（読めない間は以前の本文を使い続ける）
+      if (policyDir && process.env.AEGIS_POLICY_WATCH === 'true' && !checkConfig) {
+        const watcher = new PolicyDirectoryWatcher(policyLoader, event => {
+          if (event.type === 'updated') {
+            mcpProxy.addPolicy(event.id, policyLoader.getPolicyText(event.policy), event.policy.metadata?.tags);
+            logger.info(`  ✓ Reloaded policy: ${event.id} (${event.file})`);
+          } else if (event.type === 'removed') {
+            mcpProxy.removePolicy(event.id);
+            logger.info(`  ✓ Removed policy: ${event.id} (${event.file} deleted)`);
+          } else {
+            logger.warn(`Policy reload failed for ${event.file}; keeping the previous version: ${event.error.message}`);
+          }
+        });
+        watcher.start();
+      }
     } catch (error) {
+      // --check-config ではポリシーを読み込めないことを設定の誤りとして扱う
+      if (checkConfig) {
+        throw new Error(`Failed to load policies: ${error instanceof Error ? error.message : error}`);
+      }
       logger.error('Failed to load policies:', error);
     }
 
+    // policy_lintのルール（--lint-rules / AEGIS_LINT_RULES、既定のルールに追加）
+    const lintRulesPath = process.env.AEGIS_LINT_RULES;
+    if (lintRulesPath) {
+      mcpProxy.setLintRules(loadLintRules(path.resolve(lintRulesPath)));
+      logger.info(`  ✓ Loaded lint rules: ${lintRulesPath}`);
+    }
+
+    // actionの同義語（--action-synonyms / AEGIS_ACTION_SYNONYMS、既定の同義語に追加）
+    const actionSynonymsPath = process.env.AEGIS_ACTION_SYNONYMS;
+    if (actionSynonymsPath) {
+      mcpProxy.setActionSynonyms(loadActionSynonyms(path.resolve(actionSynonymsPath)));
+      logger.info(`  ✓ Loaded action synonyms: ${actionSynonymsPath}`);
+    }
+
+    // エージェントごとの判定の上書き（--agent-overrides / AEGIS_AGENT_OVERRIDES）
+    const agentOverridesPath = process.env.AEGIS_AGENT_OVERRIDES;
+    if (agentOverridesPath) {
+      mcpProxy.setAgentOverrides(loadAgentOverrides(path.resolve(agentOverridesPath)));
+      logger.info(`  ✓ Loaded agent overrides: ${agentOverridesPath}`);
+    }
+
+    // 許可リスト・拒否リスト（--allowlist / --denylist、AEGIS_ALLOWLIST / AEGIS_DENYLIST）
+    const allowlistPath = process.env.AEGIS_ALLOWLIST;
+    if (allowlistPath) {
+      mcpProxy.setAccessLists({ allowlist: loadAccessList(path.resolve(allowlistPath)) });
+      logger.info(`  ✓ Loaded allowlist: ${allowlistPath}`);
+    }
+    const denylistPath = process.env.AEGIS_DENYLIST;
+    if (denylistPath) {
+      mcpProxy.setAccessLists({ denylist: loadAccessList(path.resolve(denylistPath)) });
+      logger.info(`  ✓ Loaded denylist: ${denylistPath}`);
+    }
+
+    // 既定コンテキスト（--default-context / AEGIS_DEFAULT_CONTEXT）
+    // リクエストのコンテキストの下に再帰マージされ、競合時はリクエスト側が優先される
+    const defaultContextPath = process.env.AEGIS_DEFAULT_CONTEXT;
+    if (defaultContextPath) {
+      const defaultContext = JSON.parse(fs.readFileSync(path.resolve(defaultContextPath), 'utf-8'));
+      if (defaultContext === null || typeof defaultContext !== 'object' || Array.isArray(defaultContext)) {
+        throw new Error(`Default context must be a JSON object: ${defaultContextPath}`);
+      }
+      mcpProxy.setDefaultContext(defaultContext);
+      logger.info(`  ✓ Loaded default context: ${defaultContextPath}`);
+    }
+
+    // コンテキストのJSON Schema（--context-schema / AEGIS_CONTEXT_SCHEMA）
+    // 既定コンテキストをマージした後のコンテキストに適用される
+    const contextSchemaPath = process.env.AEGIS_CONTEXT_SCHEMA;
+    if (contextSchemaPath) {
+      const contextSchema = JSON.parse(fs.readFileSync(path.resolve(contextSchemaPath), 'utf-8'));
+      if (contextSchema === null || typeof contextSchema !== 'object' || Array.isArray(contextSchema)) {
+        throw new Error(`Context schema must be a JSON object: ${contextSchemaPath}`);
+      }
+      mcpProxy.setContextSchema(contextSchema);
+      logger.info(`  ✓ Loaded context schema: ${contextSchemaPath}`);
+    }
+
+    // --check-config は読み込みと検証が済んだ時点で、起動した場合の内容を表示して終了する
+    if (checkConfig) {
+      await mcpProxy.stop();
+      process.stdout.write(formatConfigSummary({
+        transport: transport === 'stdio' ? 'stdio' : 'http',
+        port: transport === 'stdio' ? undefined : config.mcpProxy.port || 3000,
+        ai: judgmentEngine ? { provider: config.llm.provider, model: config.llm.model } : null,
+        policies: loadedPolicies,
+        upstreamServers: upstreamServerNames,
+        files: CHECKED_FILES
+          .filter(([variable]) => process.env[variable])
+          .map(([variable, label]) => ({ label, path: process.env[variable]! }))
+      }));
+      process.exit(0);
+    }
+
+    // --repl はサーバーを起動せず、入力したチェックをプロセス内で処理して終了する
+    if (repl) {
+      await runRepl(mcpProxy, process.stdin, process.stdout);
+      await mcpProxy.stop();
+      process.exit(0);
+    }
+
     // サーバー起動
     await mcpProxy.start();
 
@@ -209,28 +387,45 @@ async function startMCPServer(transport: 'stdio' | 'http' | 'api-only' = 'stdio'
       logger.info('Press Ctrl+C to stop the server');
     }
 
-    // グレースフルシャットダウン
-    process.on('SIGINT', async () => {
-      if (transport !== 'stdio') {
-        logger.critical('\n🛑 Shutting down AEGIS MCP Proxy Server...');
-      }
-      await mcpProxy.stop();
-      if (transport !== 'stdio') {
-        logger.critical('✅ Server stopped gracefully');
+    // グレースフルシャットダウン（SIGINT/SIGTERM/stdin EOF）
+    let shuttingDown = false;
+    const shutdown = async (reason: string) => {
+      if (shuttingDown) {
+        return;
       }
-      process.exit(0);
-    });
-
-    process.on('SIGTERM', async () => {
+      shuttingDown = true;
+      
+      logger.info(`Shutting down AEGIS MCP Proxy Server (${reason})`);
       if (transport !== 'stdio') {
         logger.critical('\n🛑 Shutting down AEGIS MCP Proxy Server...');
       }
-      await mcpProxy.stop();
+      
+      let exitCode = 0;
+      try {
+        // 上流サーバー停止・監査ログのフラッシュまで待つ
+        await mcpProxy.stop();
+      } catch (error) {
+        logger.error('Error during shutdown:', error);
+        exitCode = 1;
+      }
+      
       if (transport !== 'stdio') {
         logger.critical('✅ Server stopped gracefully');
+      } else {
+        // 直前の応答（shutdownへの応答など）がstdoutに書き出されるまで待つ
+        await new Promise<void>(resolve => process.stdout.write('', () => resolve()));
       }
-      process.exit(0);
-    });
+      process.exit(exitCode);
+    };
+
+    mcpProxy.setShutdownHandler(reason => { void shutdown(reason); });
+    process.on('SIGINT', () => { void shutdown('SIGINT'); });
+    process.on('SIGTERM', () => { void shutdown('SIGTERM'); });
+    
+    // stdioではクライアントがstdinを閉じたら終了する（SDKのトランスポートはEOFを扱わない）
+    if (transport === 'stdio') {
+      process.stdin.once('end', () => { void shutdown('stdin closed'); });
+    }
 
     // エラーハンドリング
     process.on('uncaughtException', (error) => {
@@ -244,7 +439,9 @@ async function startMCPServer(transport: 'stdio' | 'http' | 'api-only' = 'stdio'
     });
 
   } catch (error) {
-    if (transport !== 'stdio') {
+    if (checkConfig) {
+      console.error(`Configuration check failed: ${error instanceof Error ? error.message : error}`);
+    } else if (transport !== 'stdio') {
       logger.error('Failed to start MCP Proxy Server:', error);
     }
     process.exit(1);
@@ -272,32 +469,119 @@ function parseArgs() {
   return options;
 }
 
+// --config の設定ファイルで指定できるオプション（--help・--version・--audit-verify・--check-config・--config 以外のすべて）
+const CONFIG_FILE_OPTIONS: ReadonlySet<string> = new Set([
+  'transport', 'port', 'provider', 'model', 'repl', 'debug',
+  'policy-dir', 'watch', 'strict-env', 'lint-rules', 'prompt-template', 'log-format',
+  'log-prompts', 'prompt-redact',
+  'cache-size', 'cache-ttl',
+  'audit-log', 'audit-key', 'audit-failure-mode', 'history-db', 'webhook-url', 'webhook-decisions', 'webhook-dead-letter',
+  'framing', 'strict-protocol', 'max-request-bytes', 'max-response-bytes', 'gzip-min-bytes', 'max-simulate-batch',
+  'default-context', 'context-schema', 'agent-overrides', 'action-synonyms', 'allowlist', 'denylist',
+  'allow-http-force-permit', 'default-agent', 'require-agent', 'canonicalize-resources', 'strict-policy-refs',
+  'min-confidence', 'fail-closed', 'indeterminate-resolution', 'rate-limit', 'dedup-window',
+  'slow-request-ms', 'tool-timeout-ms', 'eval-timeout-ms', 'eval-retries', 'eval-retry-backoff-ms',
+  'eval-breaker-threshold', 'eval-breaker-cooldown-ms',
+  'enable-tool', 'disable-tool'
+]);
+
+// 設定ファイルに書いた相対パスを設定ファイルのディレクトリから解決するオプション
+const CONFIG_FILE_PATH_OPTIONS: ReadonlySet<string> = new Set([
+  'policy-dir', 'lint-rules', 'prompt-template', 'audit-log', 'history-db', 'webhook-dead-letter',
+  'default-context', 'context-schema', 'agent-overrides', 'action-synonyms', 'allowlist', 'denylist'
+]);
+
+// バージョン表示
+function showVersion() {
+  // サーバーを起動しないため、stdioモードでもstdoutに出力してよい
+  process.stdout.write(`${SERVER.DEFAULT_NAME} ${SERVER.DEFAULT_VERSION}\n`);
+}
+
 // ヘルプ表示
 function showHelp() {
-  // Use the logger with critical level to ensure it goes to stderr in stdio mode
-  const logLevel = process.env.LOG_LEVEL || 'info';
-  const logger = new Logger(logLevel);
-  
-  // In stdio mode, we must not output anything to stdout
-  const transport = process.argv.includes('--transport') && process.argv[process.argv.indexOf('--transport') + 1] === 'stdio' ? 'stdio' : 'http';
-  if (transport === 'stdio') {
-    // In stdio mode, exit immediately without outputting help
-    process.exit(0);
-  }
-  
-  // For non-stdio mode, output help to stderr
+  // ヘルプはstderrに出力する（stdioモードでもJSON-RPCのstdoutを汚さない）
   console.error(`
 AEGIS MCP Proxy Server (MCP Standard Compliant)
 
 Usage: node mcp-server.js [options]
 
 Options:
-  --help                Show this help message
+  --help                Show this help message and exit
+  --version             Show the server name and version and exit
+  --config <path>       TOML/YAML file setting any option below by its flag name (CLI flags take precedence)
+                        Tables / mappings join with a hyphen: [cache] size = 100 sets --cache-size
+                        Relative paths in the file resolve against the file's directory
+  --check-config        Load and validate the config file, policies, templates and schemas, print a summary
+                        and exit (0 if valid, 1 otherwise) without starting the server
   --transport <type>    Transport type: stdio or http (default: http)
-  --port <port>         Server port for HTTP transport (default: 8080)
-  --provider <provider> LLM provider: openai or anthropic (default: openai)
-  --model <model>       LLM model name (default: gpt-4)
-  --debug               Enable debug logging
+  --repl                Interactive prompt for local testing: check <agent> <action> <resource>
+  --port <port>         Server port for HTTP transport (default: ${SERVER.DEFAULT_PORT.HTTP})
+  --provider <provider> LLM provider: openai or anthropic (default: anthropic)
+  --model <model>       LLM model name (default: claude-opus-4-20250514)
+  --policy-dir <dir>    Load .md/.txt policies from a directory (id = file name, default: none)
+                        \${VAR} in policy text is replaced from the environment (\$\${VAR} keeps it literal)
+  --strict-env          Fail policy directory loading when a referenced variable is undefined (default: off)
+  --watch               Reload changed --policy-dir files without restarting (default: off)
+  --cache-size <n>      Max cached policy decisions (0 disables, default: 1000)
+  --cache-ttl <sec>     Cached decision lifetime in seconds (default: 300)
+  --audit-log <path>    Append one JSON line per policy decision (default: off)
+  --audit-key <key>     HMAC-SHA256 key authenticating the audit log hash chain (default: plain SHA-256)
+                        Prefer AEGIS_AUDIT_KEY or a --config file: command-line arguments are visible to other users
+  --audit-failure-mode <mode> On audit log write errors: fail-closed (deny), fail-open (allow, logged as UNAUDITED)
+                        or buffer (retry from a bounded in-memory queue) (default: fail-closed)
+  --audit-verify <path> Verify an audit log hash chain (with AEGIS_AUDIT_KEY if set) and exit (1 on the first broken line)
+  --history-db <path>   Store decisions in SQLite and enable the history_query tool (default: off)
+                        Each query is evaluated as read aegis://history and runs only when permitted
+  --webhook-url <url>   POST matching decisions as JSON to a webhook without blocking the response (default: off)
+  --webhook-decisions <list>
+                        Comma-separated decisions to send to the webhook: PERMIT, DENY, INDETERMINATE (default: DENY)
+  --webhook-dead-letter <path>
+                        JSON Lines file for webhook notifications that failed after retries (default: log only)
+  --framing <type>      stdio message framing: newline or lsp (Content-Length headers) (default: newline)
+  --strict-protocol     Reject JSON-RPC messages with unknown top-level fields with -32600
+                        (default: off, unknown fields are dropped so client extensions keep working)
+  --max-request-bytes <n>  Reject JSON-RPC messages larger than n bytes (default: ${SERVER.DEFAULT_MAX_REQUEST_BYTES})
+  --max-response-bytes <n> Truncate text content of responses larger than n bytes with a marker (default: 0 = unlimited)
+  --gzip-min-bytes <n>  Gzip HTTP JSON responses of at least n bytes for Accept-Encoding: gzip (default: ${SERVER.DEFAULT_GZIP_MIN_BYTES})
+                        SSE streams are gzipped regardless of size, flushed after every event
+  --default-context <path> JSON object merged under every request context (request values win, default: none)
+  --context-schema <path> JSON Schema the merged request context must match (-32602 otherwise, default: none)
+  --agent-overrides <path> JSON mapping agent ids to force-permit, force-deny or append-policy (default: none)
+                        force-permit is ignored on the HTTP transport, where X-Agent-ID is chosen by the client
+  --allow-http-force-permit Apply force-permit on the HTTP transport too. Any client can claim an overridden
+                        agent id, so only use this behind a proxy that authenticates and sets X-Agent-ID (default: off)
+  --action-synonyms <path> JSON mapping canonical actions to extra synonyms (default: built-in CRUD verbs)
+  --default-agent <id>  Agent for requests without one (stdio: the connected client; default: mcp-client / http-client)
+  --require-agent       Reject requests without an explicit agent with -32602 (stdio: requires --default-agent)
+  --canonicalize-resources Collapse . and .. and normalize separators in path-like resources before evaluation
+                        (the original resource is kept in the prompt and audit log, default: off)
+  --strict-policy-refs  Reject with -32602 a tool policy that looks like an id but is not registered, and a policy
+                        that differs from policy_id (default: off; policy is inline text unless registered and wins)
+  --allowlist <path>    File of "action resource-glob" lines permitted without AI evaluation (default: none)
+  --denylist <path>     File of "action resource-glob" lines denied without AI evaluation (default: none)
+  --max-simulate-batch <n> Max requests per policy_simulate call (default: ${BATCH.MAX_SIMULATE_SIZE})
+  --min-confidence <x>  Treat AI decisions below this confidence (0-1) as INDETERMINATE (default: 0 = off)
+  --fail-closed         With --min-confidence, turn low-confidence decisions into DENY instead (default: off)
+  --indeterminate-resolution <mode> Turn INDETERMINATE decisions into permit or deny, or passthrough unchanged (default: deny)
+  --rate-limit <n>      Max tools/call requests per agent per minute (default: 0 = unlimited)
+  --slow-request-ms <n> Warn with the method and id when a request takes longer than n milliseconds (default: 0 = off)
+  --tool-timeout-ms <n> Fail a tools/call with -32603 after n milliseconds (default: ${TIMEOUTS.TOOL_CALL})
+  --eval-timeout-ms <n> DENY a tools/call whose policy evaluation takes longer than n milliseconds (default: ${TIMEOUTS.POLICY_EVALUATION})
+  --eval-retries <n>    Retry a failed AI policy evaluation up to n times before failing closed (default: 0)
+  --eval-retry-backoff-ms <n> Wait before the first retry, doubling with jitter after each failure (default: ${TIMEOUTS.EVAL_RETRY_BACKOFF})
+  --eval-breaker-threshold <n> DENY without calling the AI backend after n consecutive evaluation failures (default: 0 = off)
+  --eval-breaker-cooldown-ms <n> Keep the breaker open this long before probing the backend again (default: ${TIMEOUTS.EVAL_BREAKER_COOLDOWN})
+  --dedup-window <n>    Replay the response for a repeated tools/call id among the last n ids (default: ${SERVER.DEFAULT_DEDUP_WINDOW}, 0 = off)
+  --enable-tool <names> Only expose these tools (comma-separated, default: all tools)
+  --disable-tool <names> Hide these tools; calls fail with -32601 (comma-separated, default: none)
+  --lint-rules <path>   JSON with extra policy_lint ambiguityMarkers/roles (default: built-in list)
+  --prompt-template <path> Decision prompt template with {policy} {agent} {action} {resource} {context} {purpose}
+                           (default: built-in template)
+  --log-prompts         Record the rendered decision prompt in each --audit-log record (may contain sensitive context, default: off)
+  --prompt-redact <fields> Context fields replaced with [REDACTED] in logged prompts, e.g. purpose,environment.apiToken
+                           (comma-separated, default: none)
+  --log-format <fmt>    Console log format: pretty, compact or json (NDJSON) (default: pretty)
+  --debug               Enable debug logging (HTTP transport only, default: off)
 
 Environment Variables:
   OPENAI_API_KEY        OpenAI API key
@@ -306,6 +590,35 @@ Environment Variables:
   LLM_MODEL             LLM model name
   MCP_PROXY_PORT        Server port for HTTP transport
   LOG_LEVEL             Log level (debug/info/warn/error)
+  LOG_FORMAT            Console log format (same as --log-format)
+  AEGIS_POLICY_DIR      Directory of .md/.txt policies (same as --policy-dir)
+  AEGIS_STRICT_ENV      Set to true to fail on undefined policy variables (same as --strict-env)
+  AEGIS_POLICY_WATCH    Set to true to reload changed policy files (same as --watch)
+  AEGIS_DEFAULT_CONTEXT Default context JSON file (same as --default-context)
+  AEGIS_CONTEXT_SCHEMA  Context JSON Schema file (same as --context-schema)
+  AEGIS_AGENT_OVERRIDES Agent overrides JSON file (same as --agent-overrides)
+  AEGIS_ALLOW_HTTP_FORCE_PERMIT
+                        Set to true to apply force-permit over HTTP (same as --allow-http-force-permit)
+  AEGIS_ACTION_SYNONYMS Action synonyms JSON file (same as --action-synonyms)
+  AEGIS_DEFAULT_AGENT   Agent for requests without one (same as --default-agent)
+  AEGIS_REQUIRE_AGENT   Set to true to reject requests without an agent (same as --require-agent)
+  AEGIS_STRICT_PROTOCOL Set to true to reject unknown JSON-RPC fields (same as --strict-protocol)
+  AEGIS_CANONICALIZE_RESOURCES
+                        Set to true to canonicalize path-like resources (same as --canonicalize-resources)
+  AEGIS_STRICT_POLICY_REFS
+                        Set to true to reject unregistered policy ids (same as --strict-policy-refs)
+  AEGIS_ALLOWLIST       Allowlist file (same as --allowlist)
+  AEGIS_DENYLIST        Denylist file (same as --denylist)
+  AEGIS_AUDIT_KEY       Audit log HMAC key (same as --audit-key, kept out of the process list)
+  AEGIS_AUDIT_FAILURE_MODE
+                        Audit log write failure handling (same as --audit-failure-mode)
+  AEGIS_LOG_PROMPTS     Set to true to record rendered prompts in the audit log (same as --log-prompts)
+  AEGIS_PROMPT_REDACT   Context fields redacted in logged prompts (same as --prompt-redact)
+  AEGIS_WEBHOOK_URL     Decision webhook URL (same as --webhook-url)
+  AEGIS_WEBHOOK_DECISIONS
+                        Decisions sent to the webhook (same as --webhook-decisions)
+  AEGIS_WEBHOOK_DEAD_LETTER
+                        Webhook dead-letter file (same as --webhook-dead-letter)
   
   For stdio transport:
   CLAUDE_DESKTOP_CONFIG Path to claude_desktop_config.json (auto-detected by default)
@@ -329,6 +642,15 @@ Examples:
   # Start with stdio transport
   node mcp-server.js --transport stdio
   
+  # Load options from a config file, overriding the port on the command line
+  node mcp-server.js --config aegis.toml --port 9000
+
+  # Validate a config change in CI without starting the server
+  node mcp-server.js --config aegis.toml --check-config
+
+  # Try policy decisions interactively (no MCP client needed)
+  node mcp-server.js --policy-dir ./policies --repl
+
 
   # Start with Anthropic Claude (default: Opus 4)
   node mcp-server.js --provider anthropic --model claude-opus-4-20250514
@@ -345,28 +667,154 @@ Examples:
 
 // メイン関数
 async function main() {
-  const options = parseArgs();
+  const cliOptions = parseArgs();
+
+  // --config の設定ファイルを読み込む（同じオプションはCLIの指定が優先）
+  let options = cliOptions;
+  if (cliOptions.config) {
+    try {
+      options = { ...loadConfigFile(path.resolve(cliOptions.config), CONFIG_FILE_OPTIONS, CONFIG_FILE_PATH_OPTIONS), ...cliOptions };
+    } catch (error) {
+      console.error(`Invalid config file: ${error instanceof Error ? error.message : error}`);
+      process.exit(1);
+    }
+  }
   
   // Determine transport mode FIRST, before any logging
   const transport = (options.transport as 'stdio' | 'http') || 'http';
   
-  // Set LOG_SILENT immediately for stdio mode
-  if (transport === 'stdio') {
+  // Set LOG_SILENT immediately for stdio mode (the REPL also owns stdout)
+  if (transport === 'stdio' || options.repl) {
     process.env.LOG_SILENT = 'true';
     process.env.MCP_TRANSPORT = 'stdio';
   }
 
+  // --help / --version はサーバーを起動せずに終了する（stdinの待ち受けに入らない）
   if (options.help) {
     showHelp();
     process.exit(0);
   }
 
+  if (options.version) {
+    showVersion();
+    process.exit(0);
+  }
+
+  // --audit-verify は監査ログを検証して終了する（サーバーは起動しない）
+  if (options['audit-verify']) {
+    const result = await verifyAuditLog(path.resolve(options['audit-verify']), options['audit-key'] ?? process.env.AEGIS_AUDIT_KEY);
+    if (result.valid) {
+      process.stdout.write(`Audit log OK: ${result.entries} entries\n`);
+      process.exit(0);
+    }
+    console.error(`Audit log verification failed at line ${result.line}: ${result.reason}`);
+    process.exit(1);
+  }
+
   // CLIオプションを環境変数に反映
   if (options.port) process.env.MCP_PROXY_PORT = options.port;
   if (options.provider) process.env.LLM_PROVIDER = options.provider;
   if (options.model) process.env.LLM_MODEL = options.model;
+  if (options['policy-dir']) process.env.AEGIS_POLICY_DIR = options['policy-dir'];
+  if (options.watch) process.env.AEGIS_POLICY_WATCH = 'true';
+  if (options['strict-env']) process.env.AEGIS_STRICT_ENV = 'true';
+  if (options['cache-size']) process.env.AEGIS_CACHE_MAX_SIZE = options['cache-size'];
+  if (options['cache-ttl']) process.env.AEGIS_CACHE_TTL = options['cache-ttl'];
+  if (options['audit-log']) process.env.AEGIS_AUDIT_LOG = options['audit-log'];
+  if (options['audit-key']) process.env.AEGIS_AUDIT_KEY = options['audit-key'];
+  if (options['audit-failure-mode']) process.env.AEGIS_AUDIT_FAILURE_MODE = options['audit-failure-mode'];
+  if (options['history-db']) process.env.AEGIS_HISTORY_DB = options['history-db'];
+  if (options['webhook-url']) process.env.AEGIS_WEBHOOK_URL = options['webhook-url'];
+  if (options['webhook-decisions']) process.env.AEGIS_WEBHOOK_DECISIONS = options['webhook-decisions'];
+  if (options['webhook-dead-letter']) process.env.AEGIS_WEBHOOK_DEAD_LETTER = options['webhook-dead-letter'];
+  if (options.framing) process.env.AEGIS_FRAMING = options.framing;
+  if (options['strict-protocol']) process.env.AEGIS_STRICT_PROTOCOL = 'true';
+  if (options['max-request-bytes']) process.env.AEGIS_MAX_REQUEST_BYTES = options['max-request-bytes'];
+  if (options['max-response-bytes']) process.env.AEGIS_MAX_RESPONSE_BYTES = options['max-response-bytes'];
+  if (options['gzip-min-bytes']) process.env.AEGIS_GZIP_MIN_BYTES = options['gzip-min-bytes'];
+  if (options['default-context']) process.env.AEGIS_DEFAULT_CONTEXT = options['default-context'];
+  if (options['context-schema']) process.env.AEGIS_CONTEXT_SCHEMA = options['context-schema'];
+  if (options['agent-overrides']) process.env.AEGIS_AGENT_OVERRIDES = options['agent-overrides'];
+  if (options['allow-http-force-permit']) process.env.AEGIS_ALLOW_HTTP_FORCE_PERMIT = 'true';
+  if (options['action-synonyms']) process.env.AEGIS_ACTION_SYNONYMS = options['action-synonyms'];
+  if (options['default-agent']) process.env.AEGIS_DEFAULT_AGENT = options['default-agent'];
+  if (options['require-agent']) process.env.AEGIS_REQUIRE_AGENT = 'true';
+  if (options['canonicalize-resources']) process.env.AEGIS_CANONICALIZE_RESOURCES = 'true';
+  if (options['strict-policy-refs']) process.env.AEGIS_STRICT_POLICY_REFS = 'true';
+  if (options['allowlist']) process.env.AEGIS_ALLOWLIST = options['allowlist'];
+  if (options['denylist']) process.env.AEGIS_DENYLIST = options['denylist'];
+  if (options['max-simulate-batch']) process.env.AEGIS_MAX_SIMULATE_BATCH = options['max-simulate-batch'];
+  if (options['min-confidence']) process.env.AEGIS_MIN_CONFIDENCE = options['min-confidence'];
+  if (options['fail-closed']) process.env.AEGIS_FAIL_CLOSED = 'true';
+  if (options['indeterminate-resolution']) process.env.AEGIS_INDETERMINATE_RESOLUTION = options['indeterminate-resolution'];
+  if (options['rate-limit']) process.env.AEGIS_RATE_LIMIT = options['rate-limit'];
+  if (options['slow-request-ms']) process.env.AEGIS_SLOW_REQUEST_MS = options['slow-request-ms'];
+  if (options['tool-timeout-ms']) process.env.AEGIS_TOOL_TIMEOUT_MS = options['tool-timeout-ms'];
+  if (options['eval-timeout-ms']) process.env.AEGIS_EVAL_TIMEOUT_MS = options['eval-timeout-ms'];
+  if (options['eval-retries']) process.env.AEGIS_EVAL_RETRIES = options['eval-retries'];
+  if (options['eval-retry-backoff-ms']) process.env.AEGIS_EVAL_RETRY_BACKOFF_MS = options['eval-retry-backoff-ms'];
+  if (options['eval-breaker-threshold']) process.env.AEGIS_EVAL_BREAKER_THRESHOLD = options['eval-breaker-threshold'];
+  if (options['eval-breaker-cooldown-ms']) process.env.AEGIS_EVAL_BREAKER_COOLDOWN_MS = options['eval-breaker-cooldown-ms'];
+  if (options['dedup-window']) process.env.AEGIS_DEDUP_WINDOW = options['dedup-window'];
+  if (options['enable-tool']) process.env.AEGIS_ENABLED_TOOLS = options['enable-tool'];
+  if (options['disable-tool']) process.env.AEGIS_DISABLED_TOOLS = options['disable-tool'];
+  if (options['prompt-template']) process.env.AEGIS_PROMPT_TEMPLATE = options['prompt-template'];
+  if (options['log-prompts']) process.env.AEGIS_LOG_PROMPTS = 'true';
+  if (options['prompt-redact']) process.env.AEGIS_PROMPT_REDACT = options['prompt-redact'];
+  if (options['lint-rules']) process.env.AEGIS_LINT_RULES = options['lint-rules'];
+  if (options['log-format']) process.env.LOG_FORMAT = options['log-format'];
   if (options.debug && transport !== 'stdio') process.env.LOG_LEVEL = 'debug';
 
+  if (process.env.AEGIS_FRAMING && !isFraming(process.env.AEGIS_FRAMING)) {
+    console.error(`Invalid framing: ${process.env.AEGIS_FRAMING}. Use one of: ${FRAMINGS.join(', ')}`);
+    process.exit(1);
+  }
+
+  if (process.env.AEGIS_INDETERMINATE_RESOLUTION && !isIndeterminateResolution(process.env.AEGIS_INDETERMINATE_RESOLUTION)) {
+    console.error(`Invalid indeterminate resolution: ${process.env.AEGIS_INDETERMINATE_RESOLUTION}. Use one of: ${INDETERMINATE_RESOLUTIONS.join(', ')}`);
+    process.exit(1);
+  }
+
+  // stdioはリクエストごとにエージェントを指定できないため、接続元を --default-agent で明示する
+  if (transport === 'stdio' && process.env.AEGIS_REQUIRE_AGENT === 'true' && !process.env.AEGIS_DEFAULT_AGENT) {
+    console.error('--require-agent with the stdio transport needs --default-agent to identify the connected agent');
+    process.exit(1);
+  }
+
+  if (process.env.AEGIS_AUDIT_FAILURE_MODE && !isAuditFailureMode(process.env.AEGIS_AUDIT_FAILURE_MODE)) {
+    console.error(`Invalid audit failure mode: ${process.env.AEGIS_AUDIT_FAILURE_MODE}. Use one of: ${AUDIT_FAILURE_MODES.join(', ')}`);
+    process.exit(1);
+  }
+
+  // プロンプトは監査レコードに記録する
+  if (process.env.AEGIS_LOG_PROMPTS === 'true' && !process.env.AEGIS_AUDIT_LOG) {
+    console.error('--log-prompts records prompts in the decision audit log and needs --audit-log');
+    process.exit(1);
+  }
+
+  if (process.env.AEGIS_PROMPT_REDACT && process.env.AEGIS_LOG_PROMPTS !== 'true') {
+    console.error('--prompt-redact only applies to logged prompts and needs --log-prompts');
+    process.exit(1);
+  }
+
+  if (process.env.AEGIS_WEBHOOK_URL && !isWebhookUrl(process.env.AEGIS_WEBHOOK_URL)) {
+    console.error(`Invalid webhook URL: ${process.env.AEGIS_WEBHOOK_URL}. Use an http:// or https:// URL`);
+    process.exit(1);
+  }
+
+  if (process.env.AEGIS_WEBHOOK_DECISIONS) {
+    const invalid = invalidWebhookDecisions(process.env.AEGIS_WEBHOOK_DECISIONS.split(',').map(value => value.trim()).filter(Boolean));
+    if (invalid.length > 0) {
+      console.error(`Invalid webhook decisions: ${invalid.join(', ')}. Use any of: ${WEBHOOK_DECISIONS.join(', ')}`);
+      process.exit(1);
+    }
+  }
+
+  if (process.env.LOG_FORMAT && !isLogFormat(process.env.LOG_FORMAT)) {
+    console.error(`Invalid log format: ${process.env.LOG_FORMAT}. Use one of: ${LOG_FORMATS.join(', ')}`);
+    process.exit(1);
+  }
+
   // トランスポートタイプを検証
   if (!['stdio', 'http'].includes(transport)) {
     // In stdio mode, we must not output anything to stdout
@@ -378,7 +826,8 @@ async function main() {
     }
   }
 
-  await startMCPServer(transport);
+  // REPLはstdioと同じくプロセス内で処理する（HTTPサーバーは起動しない）
+  await startMCPServer(options.repl ? 'stdio' : transport, Boolean(options.repl), Boolean(options['check-config']));
 }
 
 // 実行
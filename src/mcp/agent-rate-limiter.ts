@@ -0,0 +1,86 @@
+// ============================================================================
+// AEGIS - エージェントごとのレート制限（トークンバケット）
+// 1分あたりN回を上限に、エージェントごとのバケットから1トークンずつ消費する
+// 満タンまで補充されたバケットは捨てる（HTTPではエージェントIDをクライアントが名乗るため、記録が増え続けないようにする）
+// ============================================================================
+
+import { SystemTimeProvider, TimeProvider } from '../utils/time-provider.js';
+
+const WINDOW_MS = 60 * 1000;
+
+interface Bucket {
+  tokens: number;
+  updatedAt: number;
+}
+
+export type RateLimitResult =
+  | { allowed: true }
+  | { allowed: false; retryAfterMs: number };
+
+export class AgentRateLimiter {
+  private requestsPerMinute: number;
+  private clock: TimeProvider;
+  private buckets = new Map<string, Bucket>();
+  // 最後に満タンのバケットを捨てた時刻（捨てる処理は1ウィンドウに1回）
+  private sweptAt: number | null = null;
+
+  /**
+   * @param requestsPerMinute 1分あたりの上限（0以下は無制限）
+   * @param clock トークンの補充に使う時刻（テストでは固定の時刻を渡す）
+   */
+  constructor(requestsPerMinute: number, clock: TimeProvider = new SystemTimeProvider()) {
+    this.requestsPerMinute = requestsPerMinute;
+    this.clock = clock;
+  }
+
+  isEnabled(): boolean {
+    return this.requestsPerMinute > 0;
+  }
+
+  /**
+   * エージェントのバケットから1トークン消費する
+   * 不足している場合は消費せず、次のトークンが補充されるまでの時間を返す
+   */
+  take(agent: string, now: number = this.clock.now()): RateLimitResult {
+    if (!this.isEnabled()) {
+      return { allowed: true };
+    }
+
+    this.evictRefilledBuckets(now);
+    const capacity = this.requestsPerMinute;
+    const refillPerMs = capacity / WINDOW_MS;
+    const bucket = this.buckets.get(agent) ?? { tokens: capacity, updatedAt: now };
+    bucket.tokens = Math.min(capacity, bucket.tokens + (now - bucket.updatedAt) * refillPerMs);
+    bucket.updatedAt = now;
+    this.buckets.set(agent, bucket);
+
+    if (bucket.tokens >= 1) {
+      bucket.tokens -= 1;
+      return { allowed: true };
+    }
+
+    return { allowed: false, retryAfterMs: Math.ceil((1 - bucket.tokens) / refillPerMs) };
+  }
+
+  /**
+   * 記録しているエージェントの数（満タンのバケットを捨てたことの確認用）
+   */
+  get trackedAgents(): number {
+    return this.buckets.size;
+  }
+
+  /**
+   * 最後の要求から1ウィンドウ以上経ったバケットは満タンになっており、新しいバケットと区別できないので捨てる
+   */
+  private evictRefilledBuckets(now: number): void {
+    if (this.sweptAt !== null && now - this.sweptAt < WINDOW_MS) {
+      return;
+    }
+    this.sweptAt = now;
+    for (const [agent, bucket] of this.buckets) {
+      if (now - bucket.updatedAt >= WINDOW_MS) {
+        this.buckets.delete(agent);
+      }
+    }
+  }
+}
@@ -8,7 +8,8 @@ import type {
   DecisionContext, 
   AccessControlResult,
   AEGISConfig,
-  PolicyDecision 
+  PolicyDecision,
+  PolicyExplanation
 } from '../types/index.js';
 import { AIJudgmentEngine } from '../ai/judgment-engine.js';
 import { Logger } from '../utils/logger.js';
@@ -22,7 +23,394 @@ import {
 import { EnforcementSystem } from '../core/enforcement.js';
 import { AdvancedAuditSystem } from '../audit/advanced-audit-system.js';
 import { AuditDashboardDataProvider } from '../audit/audit-dashboard-data.js';
+import { DecisionAuditLog, DecisionAuditRecord, DecisionProvenance } from '../audit/decision-audit-log.js';
+import { DecisionWebhookNotifier } from '../audit/decision-webhook.js';
+import { AuditFailureMode, AuditRetryBuffer, DEFAULT_AUDIT_FAILURE_MODE } from '../audit/audit-failure-mode.js';
+import { DecisionHistoryStore, DecisionHistoryFilter, MAX_HISTORY_QUERY_LIMIT } from '../audit/decision-history-store.js';
 import { AIPolicyEngine } from '../policy/ai-policy-engine.js';
+import type { ResourceReadResult } from '../types/mcp-types.js';
+import type {
+  CallToolResult,
+  ClientCapabilities,
+  CompleteResult,
+  GetPromptResult,
+  Prompt,
+  ResourceTemplate,
+  Tool
+} from '@modelcontextprotocol/sdk/types.js';
+import { AUDIT, BATCH, SERVER, TIMEOUTS } from '../constants/index.js';
+import { deepMerge } from '../utils/deep-merge.js';
+import { createRequestTrace, getRequestTrace, runWithRequestTrace } from '../utils/request-trace.js';
+import { RuntimeSources, systemRuntimeSources } from '../utils/rng.js';
+import { ServerStats } from './server-stats.js';
+import { AgentRateLimiter } from './agent-rate-limiter.js';
+import { RequestDeduplicator } from './request-deduplicator.js';
+import { limitResponseSize } from './response-size-limiter.js';
+import { validateAgainstSchema } from './tool-argument-validator.js';
+import { DEFAULT_LINT_RULES, LintRules, lintPolicy } from '../policy/policy-lint.js';
+import type { AgentOverride } from '../policy/agent-overrides.js';
+import { AccessListEntry, findAccessListMatch } from '../policy/access-lists.js';
+import { ActionSynonyms, DEFAULT_ACTION_SYNONYMS, buildActionLookup, canonicalizeAction } from '../policy/action-synonyms.js';
+import { canonicalizeResource } from '../policy/resource-canonicalization.js';
+import { DEFAULT_REASON_LOCALE, normalizeLocale } from '../ai/prompt-templates.js';
+import { COMBINING_ALGORITHMS, CombinedDecision, combineDecisions, isCombiningAlgorithm } from '../policy/combining-algorithms.js';
+import { DEFAULT_INDETERMINATE_RESOLUTION, resolveIndeterminate } from '../policy/indeterminate-resolution.js';
+import {
+  availablePolicyFormats,
+  DEFAULT_POLICY_FORMAT,
+  evaluateCedarPolicy,
+  evaluateJsonPolicy,
+  isPolicyFormat,
+  parseJsonPolicy,
+  POLICY_FORMATS,
+  validateCedarPolicy
+} from '../policy/structured-policy.js';
+import { PolicyProvenance, PolicyVersion, PolicyVersionStore, hashPolicyContent, unifiedDiff } from '../policy/policy-versions.js';
+import { selectPolicySections } from '../policies/policy-sections.js';
+import { ConstraintEvaluator, applyConstraintEvaluation } from '../core/constraints/structured.js';
+import type { Transport } from '@modelcontextprotocol/sdk/shared/transport.js';
+import { InMemoryDispatchTransport } from './in-memory-dispatcher.js';
+import {
+  Clarification,
+  ELICITATION_METHOD,
+  ElicitResult,
+  ElicitResultSchema,
+  buildElicitationParams,
+  clarificationRequired,
+  clarificationsFrom
+} from './clarification.js';
+import { LogObligationHandler, ObligationHandler, ObligationHandlerRegistry, ObligationOutcome } from './obligation-handlers.js';
+
+// 長時間の組み込みツールが途中経過を通知するためのコールバック
+// （リクエストの params._meta.progressToken がある場合のみ notifications/progress を送る）
+export type ProgressReporter = (progress: number, total: number, message: string) => Promise<void>;
+
+// AEGIS自身が公開するポリシーリソースのURIプレフィックス
+export const POLICY_RESOURCE_URI_PREFIX = 'aegis://policy/';
+// 読み取るとその場で判定する判定リソースのURIプレフィックス
+export const DECISION_RESOURCE_URI_PREFIX = 'aegis://decision/';
+const AEGIS_RESOURCE_URI_SCHEME = 'aegis://';
+// 判定リソースの読み取りとして評価するアクション
+const DECISION_RESOURCE_ACTION = 'read';
+// history_query の実行前に判定するリソース（判定履歴の読み取りとして評価する）
+export const HISTORY_RESOURCE_URI = 'aegis://history';
+
+// resources/templates/list: AEGIS自身が公開するリソースのURIテンプレート
+export const RESOURCE_TEMPLATES: ResourceTemplate[] = [
+  {
+    uriTemplate: `${POLICY_RESOURCE_URI_PREFIX}{name}`,
+    name: 'policy',
+    mimeType: 'text/plain',
+    description: '登録済みポリシーの本文'
+  },
+  {
+    uriTemplate: `${DECISION_RESOURCE_URI_PREFIX}{agent}/{resource}`,
+    name: 'decision',
+    mimeType: 'application/json',
+    description: '既定ポリシーでagentがresourceを読み取れるかをその場で判定した結果（各パラメーターはパーセントエンコード）'
+  }
+];
+
+// completion/complete で返す候補の上限（MCPの仕様で1回の応答は100件まで）
+const MAX_COMPLETION_VALUES = 100;
+
+// MCPのログレベル（RFC 5424）からwinstonのレベルへの対応
+const MCP_LOG_LEVEL_MAP: Record<string, string> = {
+  debug: 'debug',
+  info: 'info',
+  notice: 'info',
+  warning: 'warn',
+  error: 'error',
+  critical: 'error',
+  alert: 'error',
+  emergency: 'error'
+};
+
+// 組み込みプロンプト: ポリシー判定と同じプロンプトをクライアントに提供する
+export const EVALUATE_ACCESS_PROMPT: Prompt = {
+  name: 'evaluate_access',
+  description: 'AEGISのポリシー判定プロンプト（check時と同じ構造）を生成します',
+  arguments: [
+    { name: 'agent', description: 'アクセスするエージェント', required: true },
+    { name: 'action', description: '要求アクション', required: true },
+    { name: 'resource', description: '対象リソース', required: true },
+    { name: 'policy', description: 'ポリシー本文、または登録済みポリシーID', required: true }
+  ]
+};
+
+// 組み込みツール: ポリシー条項ごとの判定根拠を返す
+// policy か policy_id のどちらかも必須（resolvePolicyArgument で検査する）
+const POLICY_EXPLAIN_REQUIRED_ARGUMENTS = ['agent', 'action', 'resource'];
+
+// policy（本文または登録済みID）と並べて指定できる、登録済みポリシーのID・版の指定
+const POLICY_ID_PROPERTY = {
+  type: 'string',
+  description: '登録済みポリシーID、または過去の版（ID@版番号）。未登録なら -32602。policy と両方指定した場合は policy を優先する'
+};
+
+export const POLICY_EXPLAIN_TOOL: Tool = {
+  name: 'policy_explain',
+  description: 'ポリシーの各条項が要求に適用されたか、判定にどう影響したかを説明します',
+  inputSchema: {
+    type: 'object',
+    properties: {
+      agent: { type: 'string', description: 'アクセスするエージェント' },
+      action: { type: 'string', description: '要求アクション' },
+      resource: { type: 'string', description: '対象リソース' },
+      policy: { type: 'string', description: 'ポリシー本文、または登録済みポリシーID' },
+      policy_id: POLICY_ID_PROPERTY,
+      purpose: { type: 'string', description: '業務目的（任意）' }
+    },
+    required: POLICY_EXPLAIN_REQUIRED_ARGUMENTS
+  }
+};
+
+// 組み込みツール: 過去のリクエスト群に対するポリシーのドライラン（監査ログ・義務には影響しない）
+export const POLICY_SIMULATE_TOOL: Tool = {
+  name: 'policy_simulate',
+  description: 'ポリシーを複数のリクエストに対して試行し、判定結果と判定の分布を返します（副作用なし）',
+  inputSchema: {
+    type: 'object',
+    properties: {
+      policy: { type: 'string', description: 'ポリシー本文、登録済みポリシーID、または過去の版（ID@版番号）' },
+      policy_id: POLICY_ID_PROPERTY,
+      requests: {
+        type: 'array',
+        description: '判定を試すリクエストの一覧',
+        items: {
+          type: 'object',
+          properties: {
+            agent: { type: 'string' },
+            action: { type: 'string' },
+            resource: { type: 'string' },
+            purpose: { type: 'string' },
+            time: { type: 'string', description: 'ISO 8601形式の要求時刻（省略時は現在時刻）' },
+            environment: { type: 'object' }
+          },
+          required: ['agent', 'action', 'resource']
+        }
+      }
+    },
+    required: ['requests']
+  }
+};
+
+// check_policies の応答形式
+export const CHECK_POLICIES_FORMATS = ['full', 'minimal'] as const;
+
+// 判定に使ったポリシーの版（check_policies の provenance）
+const POLICY_PROVENANCE_SCHEMA = {
+  type: 'object',
+  properties: {
+    policy: { type: 'string' },
+    version: { type: ['integer', 'null'] },
+    hash: { type: 'string', description: 'ポリシー本文のSHA-256' }
+  },
+  required: ['policy', 'version', 'hash']
+};
+
+// 組み込みツール: 複数ポリシーを結合アルゴリズムでまとめて判定
+export const CHECK_POLICIES_TOOL: Tool = {
+  name: 'check_policies',
+  description: '複数のポリシーでリクエストを判定し、結合アルゴリズム（XACML方式）で最終判定にまとめます。ポリシーごとの判定も返します',
+  inputSchema: {
+    type: 'object',
+    properties: {
+      agent: { type: 'string' },
+      action: { type: 'string' },
+      resource: { type: 'string' },
+      purpose: { type: 'string' },
+      policies: {
+        type: 'array',
+        description: 'ポリシー本文、または登録済みポリシーIDの一覧（評価順）',
+        items: { type: 'string' }
+      },
+      tags: {
+        type: 'array',
+        description: 'いずれかのタグが付いた登録済みポリシーをすべて評価する（policiesの後に登録順で追加）',
+        items: { type: 'string' }
+      },
+      scope: {
+        type: 'array',
+        description: '判定に含めるセクション名（Markdownの見出し）。省略時はポリシー全体。存在しないセクションは -32602',
+        items: { type: 'string' }
+      },
+      combining_algorithm: { type: 'string', enum: [...COMBINING_ALGORITHMS] },
+      policy_format: {
+        type: 'string',
+        enum: [...POLICY_FORMATS],
+        description: 'ポリシーの形式。natural（既定）はAIで判定、json は組み込みのルールインタープリタ、' +
+          'cedar はCedarで評価する（@cedar-policy/cedar-wasm がインストールされている場合のみ。使えない形式は -32602）'
+      },
+      format: {
+        type: 'string',
+        enum: [...CHECK_POLICIES_FORMATS],
+        description: 'full（既定）は理由とポリシーごとの判定を含む。minimal は { "decision": ... } のみを返す'
+      },
+      locale: {
+        type: 'string',
+        description: '判定理由（reason）を返す言語のBCP 47タグ（例: en, ja, pt-BR）。不正な値は en として扱う'
+      }
+    },
+    required: ['agent', 'action', 'resource', 'combining_algorithm']
+  },
+  // structuredContent の形（テキストの content と同じ内容をJSONのまま返す、format: minimal は decision のみ）
+  outputSchema: {
+    type: 'object',
+    properties: {
+      decision: { type: 'string', enum: ['PERMIT', 'DENY', 'INDETERMINATE'] },
+      reason: { type: 'string' },
+      confidence: { type: 'number', minimum: 0, maximum: 1 },
+      decidingIndex: { type: ['integer', 'null'], description: '最終判定を決めたポリシーの位置' },
+      combiningAlgorithm: { type: 'string', enum: [...COMBINING_ALGORITHMS] },
+      provenance: {
+        type: 'object',
+        description: '評価したポリシーの版（登録済みでない本文はversionがnull）と、最終判定を決めたポリシー',
+        properties: {
+          evaluated: { type: 'array', items: POLICY_PROVENANCE_SCHEMA },
+          controlling: { oneOf: [POLICY_PROVENANCE_SCHEMA, { type: 'null' }] }
+        },
+        required: ['evaluated', 'controlling']
+      },
+      decisions: {
+        type: 'array',
+        items: {
+          type: 'object',
+          properties: {
+            policy: { type: 'string' },
+            decision: { type: 'string', enum: ['PERMIT', 'DENY', 'INDETERMINATE'] },
+            reason: { type: 'string' },
+            confidence: { type: 'number' }
+          },
+          required: ['policy', 'decision', 'reason', 'confidence']
+        }
+      }
+    },
+    required: ['decision']
+  }
+};
+
+// 組み込みツール: 自然言語ポリシーからOPA Regoのひな形を生成（AI支援による移行用）
+export const POLICY_EXPORT_REGO_TOOL: Tool = {
+  name: 'policy_export_rego',
+  description: '自然言語ポリシーをOpen Policy AgentのRegoのひな形に変換します（AIによる変換のため要レビュー）',
+  inputSchema: {
+    type: 'object',
+    properties: {
+      policy: { type: 'string', description: '登録済みポリシー名、またはポリシー本文' },
+      package: { type: 'string', description: 'Regoのパッケージ名（既定: aegis.authz）' }
+    },
+    required: ['policy']
+  }
+};
+
+const DEFAULT_REGO_PACKAGE = 'aegis.authz';
+
+// --strict-policy-refs で未登録のポリシーIDとみなす値（空白を含まない英数字・. _ -、@版番号つきも含む）
+const POLICY_ID_PATTERN = /^[A-Za-z0-9._-]+(@\d+)?$/;
+
+// 組み込みツール: 登録済みポリシーの2つの版の差分
+export const POLICY_DIFF_TOOL: Tool = {
+  name: 'policy_diff',
+  description: '登録済みポリシーの2つの版のunified diffを返します（既定は直前の版と最新版）。版の一覧も返します',
+  inputSchema: {
+    type: 'object',
+    properties: {
+      policy: { type: 'string', description: '登録済みポリシーID' },
+      from: { type: 'integer', minimum: 1, description: '比較元の版番号（既定: toの1つ前）' },
+      to: { type: 'integer', minimum: 1, description: '比較先の版番号（既定: 最新版）' }
+    },
+    required: ['policy']
+  }
+};
+
+// 組み込みツール: 実行中サーバーの統計
+export const STATS_TOOL: Tool = {
+  name: 'stats',
+  description: 'リクエスト数・判定結果の内訳・キャッシュヒット率・稼働時間・AI判定のサーキットブレーカーの状態・推定トークン使用量を返します',
+  inputSchema: {
+    type: 'object',
+    properties: {}
+  }
+};
+
+// 組み込みツール: 実行中サーバーの実効設定（秘密情報は含めない）
+export const SERVER_INFO_TOOL: Tool = {
+  name: 'server_info',
+  description: 'サーバー名・バージョン、読み込み済みポリシー数、公開中のツール、キャッシュ・監査の状態など実行中の設定を返します（APIキーや監査鍵は含みません）',
+  inputSchema: {
+    type: 'object',
+    properties: {}
+  }
+};
+
+// 組み込みツール: ポリシー本文の曖昧な表現・未定義ロールの検出
+export const POLICY_LINT_TOOL: Tool = {
+  name: 'policy_lint',
+  description: 'ポリシーの曖昧な表現（「適宜」「sometimes」など）と未定義のロールを検出し、行・列ごとの指摘を返します',
+  inputSchema: {
+    type: 'object',
+    properties: {
+      policy: { type: 'string', description: '登録済みポリシー名、またはポリシー本文' }
+    },
+    required: ['policy']
+  }
+};
+
+// 組み込みツール: ポリシーを登録する前の妥当性検査（起動時の読み込みと同じ検査をツールとして提供）
+export const POLICY_VALIDATE_TOOL: Tool = {
+  name: 'policy_validate',
+  description: 'ポリシーが空でないこと、構造化形式（json・cedar）なら解釈できることを検査し、{ valid, errors } を返します。lint: true でリントの指摘も返します',
+  inputSchema: {
+    type: 'object',
+    properties: {
+      policy: { type: 'string', description: '登録済みポリシー名、またはポリシー本文' },
+      policy_format: {
+        type: 'string',
+        enum: [...POLICY_FORMATS],
+        description: 'ポリシーの形式（既定: natural）。使えない形式は -32602'
+      },
+      lint: { type: 'boolean', description: 'policy_lintと同じ検査も行う（severity: error の指摘は errors に含める、既定: false）' }
+    },
+    required: ['policy']
+  }
+};
+
+// 組み込みツール: 判定履歴DBの検索（--history-db指定時のみ公開）
+export const HISTORY_QUERY_TOOL: Tool = {
+  name: 'history_query',
+  description: '過去の判定を条件（完全一致・期間）で検索し、新しい順に返します',
+  inputSchema: {
+    type: 'object',
+    properties: {
+      agent: { type: 'string' },
+      action: { type: 'string' },
+      resource: { type: 'string' },
+      decision: { type: 'string', enum: ['PERMIT', 'DENY', 'INDETERMINATE'] },
+      since: { type: 'string', description: 'ISO 8601形式（この時刻以降）' },
+      until: { type: 'string', description: 'ISO 8601形式（この時刻より前）' },
+      limit: { type: 'number', description: `最大件数（最大${MAX_HISTORY_QUERY_LIMIT}）` }
+    }
+  }
+};
+
+// 組み込みツール: 判定監査ログのハッシュチェーン検証（--audit-log指定時のみ公開）
+export const AUDIT_VERIFY_TOOL: Tool = {
+  name: 'audit_verify',
+  description: '判定監査ログのハッシュチェーン（--audit-key指定時はHMAC）を先頭から検証し、最初に壊れている行番号を返します',
+  inputSchema: {
+    type: 'object',
+    properties: {}
+  }
+};
+
+export interface PolicyResource {
+  uri: string;
+  name: string;
+  mimeType: string;
+  description: string;
+  _meta?: { tags: string[] };
+}
 
 export abstract class MCPPolicyProxyBase {
   protected server: Server;
@@ -33,17 +421,95 @@ export abstract class MCPPolicyProxyBase {
   protected contextCollector: ContextCollector;
   protected enforcementSystem: EnforcementSystem;
   
+  // initializeで通知するサーバー情報
+  protected serverInfo: { name: string; version: string };
+  
   // 高度な監査システム
   protected advancedAuditSystem: AdvancedAuditSystem;
   protected auditDashboardProvider: AuditDashboardDataProvider;
+  protected decisionAuditLog?: DecisionAuditLog;
+  // 監査ログに書き込めないときの扱い（--audit-failure-mode）
+  protected auditFailureMode: AuditFailureMode = DEFAULT_AUDIT_FAILURE_MODE;
+  // buffer: 書き込めなかった監査レコードの再試行キュー
+  protected auditRetryBuffer?: AuditRetryBuffer;
+  // fail-open: 監査ログに残らなかった判定の数（statsで返す）
+  protected unauditedDecisions = 0;
+  protected sources: RuntimeSources;
+  protected agentRateLimiter: AgentRateLimiter;
+  protected toolCallDeduplicator: RequestDeduplicator<unknown>;
+  protected lintRules: LintRules = DEFAULT_LINT_RULES;
+  // initializeでクライアントが宣言した機能（sampling・rootsなど）
+  protected clientCapabilities: ClientCapabilities = {};
+  // 初期化ハンドシェイクの状態（installHandshakeGuardで更新）
+  protected handshakeState: 'awaiting_initialize' | 'initializing' | 'initialized' = 'awaiting_initialize';
+  // 判定履歴DB（開くのは非同期のため、初回使用時に待つ）
+  protected decisionHistory?: Promise<DecisionHistoryStore>;
+  // 判定のWebhook通知（--webhook-url指定時のみ）
+  protected decisionWebhook?: DecisionWebhookNotifier;
+  // shutdownリクエストへの応答を送り終えた後に呼ぶ終了処理（mcp-serverが設定）
+  private shutdownHandler?: (reason: string) => void;
+  private pendingShutdownId?: string | number;
+  // dispatchString用のプロセス内トランスポート（初回呼び出し時に接続）
+  private dispatchTransport?: Promise<InMemoryDispatchTransport>;
+  // tools/listで取得したツールごとのinputSchema（引数検証用）
+  private toolInputSchemas = new Map<string, Record<string, any>>();
+  // tools/listで取得済みのツール名（未取得の間はnull）
+  private knownToolNames: Set<string> | null = null;
+  
+  // statsツール用の累積カウンター
+  protected stats = new ServerStats();
+  
+  // 構造化制約（time_window / rate_limit / require_approval）の評価器
+  protected constraintEvaluator = new ConstraintEvaluator();
+
+  // 転送前に実行する名前付き義務ハンドラー（組み込みは log）
+  protected obligationHandlers = new ObligationHandlerRegistry();
   
   // ポリシー管理
   protected policies = new Map<string, string>();
+  // ポリシーごとのタグ（check_policiesのtagsでの選択、resources/listの_meta）
+  protected policyTags = new Map<string, string[]>();
+  // 登録されたポリシーの版（内容のハッシュと登録時刻）
+  protected policyVersions = new PolicyVersionStore();
+  // 接続（セッションID）ごとに購読中のポリシーリソースURI（resources/subscribe）
+  protected resourceSubscriptions = new Map<string, Set<string>>();
+  
+  // 全リクエストに共通する既定コンテキスト（--default-context）
+  protected defaultContext: Record<string, unknown> = {};
 
-  constructor(config: AEGISConfig, logger: Logger, judgmentEngine: AIJudgmentEngine | null) {
+  // マージ後のコンテキストに適用するJSON Schema（--context-schema、未設定なら検証しない）
+  protected contextSchema?: Record<string, unknown>;
+
+  // エージェントごとの判定の上書き（--agent-overrides）
+  protected agentOverrides = new Map<string, AgentOverride>();
+
+  // AI判定を通さずに許可・拒否するリクエスト（--allowlist / --denylist）
+  protected allowlist: AccessListEntry[] = [];
+  protected denylist: AccessListEntry[] = [];
+
+  // actionの同義語 → 正規の表記（--action-synonyms で既定の同義語に追加）
+  protected actionLookup = buildActionLookup(DEFAULT_ACTION_SYNONYMS);
+
+  /**
+   * @param sources 乱数（合成ID・再試行のジッター）と時刻（レート制限・キャッシュ・サーキットブレーカー）
+   *                テストでは SeededRng・FixedTimeProvider を渡して決定的にする
+   */
+  constructor(
+    config: AEGISConfig,
+    logger: Logger,
+    judgmentEngine: AIJudgmentEngine | null,
+    sources: RuntimeSources = systemRuntimeSources()
+  ) {
     this.config = config;
     this.logger = logger;
     this.judgmentEngine = judgmentEngine;
+    this.sources = sources;
+    this.serverInfo = {
+      name: config.mcpProxy?.serverName ?? SERVER.DEFAULT_NAME,
+      version: config.mcpProxy?.serverVersion ?? SERVER.DEFAULT_VERSION
+    };
+    this.agentRateLimiter = new AgentRateLimiter(config.mcpProxy?.agentRateLimitPerMinute ?? 0, sources.clock);
+    this.toolCallDeduplicator = new RequestDeduplicator(config.mcpProxy?.dedupWindow ?? SERVER.DEFAULT_DEDUP_WINDOW);
     
     // AIポリシーエンジン初期化
     if (!judgmentEngine) {
@@ -51,9 +517,16 @@ export abstract class MCPPolicyProxyBase {
     }
     this.aiPolicyEngine = new AIPolicyEngine(judgmentEngine, {
       aiThreshold: parseFloat(process.env.AEGIS_AI_THRESHOLD || '0.7'),
-      cacheEnabled: true,
-      cacheTTL: 300000 // 5分
-    });
+      cacheEnabled: config.cache?.enabled ?? true,
+      cacheTTL: (config.cache?.ttl ?? 300) * 1000, // 設定は秒単位（デフォルト5分）
+      maxCacheSize: config.cache?.maxSize,
+      minConfidence: config.minConfidence,
+      failClosed: config.failClosed,
+      evalRetries: config.mcpProxy?.evalRetries,
+      evalRetryBackoffMs: config.mcpProxy?.evalRetryBackoffMs,
+      breakerThreshold: config.mcpProxy?.evalBreakerThreshold,
+      breakerCooldownMs: config.mcpProxy?.evalBreakerCooldownMs
+    }, sources);
     
     // コンテキストコレクター初期化
     this.contextCollector = new ContextCollector();
@@ -61,6 +534,7 @@ export abstract class MCPPolicyProxyBase {
     
     // 制約・義務実施システム初期化
     this.enforcementSystem = new EnforcementSystem();
+    this.obligationHandlers.register(new LogObligationHandler(logger));
     
     // 高度な監査システム初期化
     this.advancedAuditSystem = new AdvancedAuditSystem();
@@ -69,19 +543,49 @@ export abstract class MCPPolicyProxyBase {
       this.advancedAuditSystem
     );
     
+    // 判定監査ログ（--audit-log指定時のみ）
+    if (config.monitoring?.decisionAuditLogPath) {
+      this.decisionAuditLog = new DecisionAuditLog(
+        config.monitoring.decisionAuditLogPath,
+        config.monitoring.decisionAuditLogKey,
+        reason => this.logger.error(`Decision audit log chain break (${reason}); starting a new hash chain segment`)
+      );
+      this.logger.info(`Decision audit log: ${this.decisionAuditLog.getFilePath()}`);
+      this.configureAuditFailureMode(config.monitoring.decisionAuditFailureMode ?? DEFAULT_AUDIT_FAILURE_MODE);
+    }
+    
+    // 判定のWebhook通知（--webhook-url指定時のみ）
+    if (config.monitoring?.decisionWebhookUrl) {
+      this.decisionWebhook = new DecisionWebhookNotifier(config.monitoring.decisionWebhookUrl, {
+        decisions: config.monitoring.decisionWebhookDecisions,
+        deadLetterPath: config.monitoring.decisionWebhookDeadLetterPath,
+        onError: (message, error) => this.logger.warn(message, error)
+      });
+      this.logger.info(`Decision webhook: ${new URL(config.monitoring.decisionWebhookUrl).host}`);
+    }
+
+    // 判定履歴DB（--history-db指定時のみ）
+    if (config.monitoring?.decisionHistoryDbPath) {
+      this.decisionHistory = DecisionHistoryStore.open(config.monitoring.decisionHistoryDbPath);
+      this.decisionHistory.then(
+        store => this.logger.info(`Decision history database: ${store.getFilePath()}`),
+        error => this.logger.error('Failed to open decision history database', error)
+      );
+    }
+    
     // MCPサーバー作成
     this.server = new Server(
-      {
-        name: 'aegis-proxy',
-        version: '1.0.0'
-      },
+      this.serverInfo,
       {
         capabilities: {
           resources: {
+            subscribe: true,   // ポリシーリソースの変更通知（notifications/resources/updated）
             listChanged: true  // resources/listChanged通知をサポート
           },
           tools: {},
-          prompts: {}
+          prompts: {},
+          completions: {},  // completion/complete（evaluate_accessとポリシーリソースの引数の補完）
+          logging: {}
         }
       }
     );
@@ -140,6 +644,21 @@ export abstract class MCPPolicyProxyBase {
     return 'default-policy';
   }
 
+  /**
+   * 構造化制約の評価（共通ロジック）
+   * 満たされない構造化制約があるPERMITはINDETERMINATEに格下げする
+   */
+  protected enforceStructuredConstraints<T extends PolicyDecision>(decision: T, context: DecisionContext): T {
+    const evaluation = this.constraintEvaluator.evaluateDecision(decision, context);
+    const result = applyConstraintEvaluation(decision, evaluation);
+    if (result !== decision) {
+      this.logger.warn('PERMIT downgraded to INDETERMINATE by unmet constraints', {
+        unmet: evaluation.checks.filter(check => !check.satisfied)
+      });
+    }
+    return result;
+  }
+
   /**
    * 制約の適用（共通ロジック）
    */
@@ -205,11 +724,87 @@ export abstract class MCPPolicyProxyBase {
     }
   }
 
+  /**
+   * INDETERMINATE判定を --indeterminate-resolution に従ってPERMIT/DENYに置き換える（passthroughならそのまま）
+   */
+  protected resolveIndeterminate<T extends Pick<PolicyDecision, 'decision' | 'reason'>>(decision: T): T {
+    const resolved = resolveIndeterminate(decision, this.config.indeterminateResolution ?? DEFAULT_INDETERMINATE_RESOLUTION);
+    if (resolved !== decision) {
+      this.logger.info(`INDETERMINATE decision resolved to ${resolved.decision}`);
+    }
+    return resolved;
+  }
+
+  /**
+   * 名前付き義務ハンドラーを登録（同じ名前の組み込みハンドラーも置き換えられる）
+   */
+  registerObligationHandler(handler: ObligationHandler): void {
+    this.obligationHandlers.register(handler);
+    this.logger.info(`Obligation handler registered: ${handler.name}${handler.mandatory ? ' (mandatory)' : ''}`);
+  }
+
+  /**
+   * PERMIT判定の義務のうち、登録済みハンドラーに一致するものを転送前に実行する
+   * 必須の義務が失敗した場合は判定をDENYに格下げする
+   * 返す判定のobligationsには、ハンドラーに一致しなかった義務（転送後にEnforcementSystemで実行）だけを残す
+   */
+  protected async runObligationHandlers(
+    result: AccessControlResult
+  ): Promise<{ result: AccessControlResult; outcomes: ObligationOutcome[] }> {
+    if (result.decision !== 'PERMIT' || !result.obligations || result.obligations.length === 0) {
+      return { result, outcomes: [] };
+    }
+
+    const context: DecisionContext = result.context ?? {
+      agent: 'unknown',
+      action: 'unknown',
+      resource: 'unknown',
+      time: new Date()
+    };
+    const { outcomes, unhandled } = await this.obligationHandlers.run(result.obligations, context, result);
+
+    for (const outcome of outcomes.filter(candidate => !candidate.success)) {
+      this.logger.error(`Obligation failed: ${outcome.obligation}`, { error: outcome.error, mandatory: outcome.mandatory });
+    }
+
+    const failed = outcomes.find(outcome => outcome.mandatory && !outcome.success);
+    if (failed) {
+      return {
+        result: {
+          ...result,
+          decision: 'DENY',
+          reason: `Mandatory obligation failed: ${failed.obligation} (${failed.error})`,
+          obligations: unhandled
+        },
+        outcomes
+      };
+    }
+
+    return { result: { ...result, obligations: unhandled }, outcomes };
+  }
+
+  /**
+   * 義務ハンドラーの実行結果をツール結果の_meta.obligationsとして返す
+   */
+  protected attachObligationOutcomes<T extends Record<string, any>>(toolResult: T, outcomes: ObligationOutcome[]): T {
+    if (outcomes.length === 0) {
+      return toolResult;
+    }
+    return { ...toolResult, _meta: { ...toolResult._meta, obligations: outcomes } };
+  }
+
   /**
    * ポリシーの追加
    */
-  addPolicy(name: string, policy: string): void {
+  addPolicy(name: string, policy: string, tags: string[] = []): void {
+    const changed = this.policies.has(name) && this.policies.get(name) !== policy;
     this.policies.set(name, policy);
+    this.policyTags.set(name, [...new Set(tags.map(tag => tag.toLowerCase()))]);
+    const version = this.policyVersions.record(name, policy);
+    this.logger.debug(`Policy ${name} version ${version.version} (${version.hash.slice(0, 12)})`);
+    if (changed) {
+      this.notifyPolicyResourceUpdated(name);
+    }
     
     // キャッシュをクリア
     try {
@@ -220,6 +815,2017 @@ export abstract class MCPPolicyProxyBase {
     }
   }
 
+  /**
+   * ポリシーの削除（版の履歴は残す）
+   */
+  removePolicy(name: string): boolean {
+    if (!this.policies.delete(name)) {
+      return false;
+    }
+    this.policyTags.delete(name);
+    try {
+      this.aiPolicyEngine.clearCache();
+    } catch (error) {
+      this.logger.error(`Failed to clear cache after removing policy ${name}:`, error);
+    }
+    this.logger.info(`Policy removed: ${name}`);
+    this.notifyPolicyResourceUpdated(name);
+    return true;
+  }
+
+  /**
+   * 既定コンテキストの設定
+   * 判定時にリクエストのコンテキストの下に再帰マージされる（競合時はリクエスト側が優先）
+   */
+  setDefaultContext(context: Record<string, unknown>): void {
+    this.defaultContext = context;
+    this.logger.info(`Default context set: ${Object.keys(context).join(', ')}`);
+  }
+
+  /**
+   * actionの同義語を設定（--action-synonyms、既定の同義語に追加したもの）
+   */
+  setActionSynonyms(synonyms: ActionSynonyms): void {
+    this.actionLookup = buildActionLookup(synonyms);
+  }
+
+  /**
+   * 判定に使うエージェントを決める（--default-agent / --require-agent）
+   * 明示されたエージェントがなければ既定のエージェント、それもなければトランスポートの代替値を使う
+   * --require-agent 指定時は代替値で判定せず -32602 (Invalid params)
+   */
+  protected resolveAgent(explicit: string | undefined, fallback: string): string {
+    if (explicit) {
+      return explicit;
+    }
+    if (this.config.mcpProxy?.requireAgent) {
+      throw this.invalidParams('Request has no explicit agent and --require-agent is set', { fallback });
+    }
+    return this.config.mcpProxy?.defaultAgent || fallback;
+  }
+
+  /**
+   * リクエストのコンテキストを既定コンテキストの上に重ね、actionを正規の表記にする
+   * --canonicalize-resources 指定時はパス形式のresourceも正規化する
+   * 表記を変えた場合は元の値を originalAction / originalResource に残す（AIへの入力と監査ログに含まれる）
+   */
+  protected applyDefaultContext(context: DecisionContext): DecisionContext {
+    let merged = deepMerge(this.defaultContext, context);
+    const action = canonicalizeAction(merged.action, this.actionLookup);
+    if (action !== merged.action) {
+      merged = { ...merged, action, originalAction: merged.action };
+    }
+    if (this.config.mcpProxy?.canonicalizeResources) {
+      const resource = canonicalizeResource(merged.resource);
+      if (resource !== merged.resource) {
+        merged = { ...merged, resource, originalResource: merged.resource };
+      }
+    }
+    return merged;
+  }
+
+  /**
+   * エージェントごとの判定の上書きを設定（--agent-overrides）
+   */
+  setAgentOverrides(overrides: Map<string, AgentOverride>): void {
+    this.agentOverrides = overrides;
+    for (const [agent, override] of overrides) {
+      if (override.mode === 'force-permit' && !this.forcePermitAllowed()) {
+        this.logger.warn(`Agent override ${agent} -> force-permit is ignored: agent ids are client-controlled on this transport (see --allow-http-force-permit)`);
+        continue;
+      }
+      this.logger.warn(`Agent override configured: ${agent} -> ${override.mode}`);
+    }
+  }
+
+  /**
+   * force-permit を適用してよいか
+   * エージェントIDをクライアントが自由に名乗れるトランスポート（HTTPのX-Agent-ID）では、
+   * 明示的に許可されない限り偽装でアクセスを広げられないよう無効にする
+   */
+  protected forcePermitAllowed(): boolean {
+    return true;
+  }
+
+  /**
+   * force-permit / force-deny の上書きがあるエージェントなら、AI判定の代わりの判定を返す（なければnull）
+   * force-permit が許可されていないトランスポートでは通常の判定に任せる
+   * 監査ログには recordDecisionAudit で override として記録される
+   */
+  protected forcedAgentDecision(context: DecisionContext): PolicyDecision | null {
+    const override = this.agentOverrides.get(context.agent);
+    if (!override || override.mode === 'append-policy') {
+      return null;
+    }
+    if (override.mode === 'force-permit' && !this.forcePermitAllowed()) {
+      this.logger.warn(`Agent override force-permit not applied for ${context.agent}: agent ids are client-controlled on this transport`);
+      return null;
+    }
+    // 強制PERMITは監査ログの有無にかかわらず毎回警告する（黙ってアクセスを広げない）
+    const log = override.mode === 'force-permit' ? this.logger.warn : this.logger.info;
+    log.call(this.logger, `Agent override ${override.mode} applied for ${context.agent}: ${context.action} ${context.resource}`);
+    return {
+      decision: override.mode === 'force-permit' ? 'PERMIT' : 'DENY',
+      reason: `Agent override ${override.mode} applied for ${context.agent}`,
+      confidence: 1.0,
+      metadata: { agentOverride: override.mode }
+    };
+  }
+
+  /**
+   * 許可リスト・拒否リストを設定（--allowlist / --denylist）
+   */
+  setAccessLists(lists: { allowlist?: AccessListEntry[]; denylist?: AccessListEntry[] }): void {
+    this.allowlist = lists.allowlist ?? this.allowlist;
+    this.denylist = lists.denylist ?? this.denylist;
+  }
+
+  /**
+   * 拒否リスト・許可リストに一致すれば、AI判定の代わりの判定を返す（なければnull）
+   * 両方に一致する場合は拒否リストを優先する。監査ログには fastPath として記録される
+   */
+  protected fastPathDecision(context: DecisionContext): PolicyDecision | null {
+    const lists = [['denylist', this.denylist], ['allowlist', this.allowlist]] as const;
+    for (const [list, entries] of lists) {
+      const entry = findAccessListMatch(entries, context);
+      if (entry) {
+        this.logger.info(`Fast path ${list} matched line ${entry.line}: ${context.action} ${context.resource}`);
+        return {
+          decision: list === 'denylist' ? 'DENY' : 'PERMIT',
+          reason: `Fast-path decision: ${list} entry at line ${entry.line} matched (${entry.action} ${entry.resource})`,
+          confidence: 1.0,
+          metadata: { fastPath: list }
+        };
+      }
+    }
+    return null;
+  }
+
+  /**
+   * append-policy の上書きがあるエージェントなら、評価するポリシーに追加のポリシーを付け加える
+   */
+  protected withAgentPolicy(context: DecisionContext, policy: string | null): string | null {
+    const override = this.agentOverrides.get(context.agent);
+    if (override?.mode !== 'append-policy') {
+      return policy;
+    }
+    return policy ? `${policy}\n\n${override.policy}` : override.policy!;
+  }
+
+  /**
+   * append-policy で評価した判定の理由に、上書きを適用したことを記録する
+   */
+  protected markAgentOverride(context: DecisionContext, decision: PolicyDecision): PolicyDecision {
+    const override = this.agentOverrides.get(context.agent);
+    if (override?.mode !== 'append-policy' || decision.metadata?.agentOverride) {
+      return decision;
+    }
+    return {
+      ...decision,
+      reason: `${decision.reason} (agent override append-policy applied for ${context.agent})`,
+      metadata: { ...decision.metadata, agentOverride: override.mode }
+    };
+  }
+
+  /**
+   * コンテキストのJSON Schemaを設定
+   */
+  setContextSchema(schema: Record<string, unknown>): void {
+    this.contextSchema = schema;
+    this.logger.info(`Context schema set: required ${JSON.stringify((schema.required as unknown[]) ?? [])}`);
+  }
+
+  /**
+   * 既定コンテキストをマージした後のコンテキストをJSON Schemaで検証する
+   * 適合しなければ -32602 (Invalid params) で拒否（日時はJSONと同じくISO 8601文字列として検証）
+   */
+  protected assertContextMatchesSchema(context: DecisionContext, path: string = 'context'): void {
+    if (!this.contextSchema) {
+      return;
+    }
+
+    const issues = validateAgainstSchema(JSON.parse(JSON.stringify(context)), this.contextSchema, path);
+    if (issues.length > 0) {
+      throw this.invalidParams(
+        `Context does not match schema: ${issues.map(issue => `${issue.path} ${issue.message}`).join('; ')}`,
+        { issues }
+      );
+    }
+  }
+
+  /**
+   * ポリシーをMCPリソースとして列挙
+   */
+  protected listPolicyResources(): PolicyResource[] {
+    return Array.from(this.policies.keys()).map(id => {
+      const tags = this.policyTags.get(id) ?? [];
+      return {
+        uri: `${POLICY_RESOURCE_URI_PREFIX}${encodeURIComponent(id)}`,
+        name: id,
+        mimeType: 'text/plain',
+        description: `AEGIS policy "${id}"`,
+        ...(tags.length > 0 ? { _meta: { tags } } : {})
+      };
+    });
+  }
+
+  /**
+   * いずれかのタグが付いた登録済みポリシー名（登録順）
+   */
+  protected policiesTagged(tags: string[]): string[] {
+    const wanted = new Set(tags.map(tag => tag.toLowerCase()));
+    return Array.from(this.policies.keys()).filter(name =>
+      (this.policyTags.get(name) ?? []).some(tag => wanted.has(tag))
+    );
+  }
+
+  /**
+   * ポリシーリソースURIかどうかを判定
+   */
+  protected isPolicyResourceUri(uri: string): boolean {
+    return typeof uri === 'string' && uri.startsWith(POLICY_RESOURCE_URI_PREFIX);
+  }
+
+  /**
+   * ポリシーリソースの読み取り（存在しない場合はnull）
+   */
+  protected readPolicyResource(uri: string): ResourceReadResult | null {
+    if (!this.isPolicyResourceUri(uri)) {
+      return null;
+    }
+
+    const id = decodeURIComponent(uri.substring(POLICY_RESOURCE_URI_PREFIX.length));
+    const policy = this.policies.get(id);
+    if (policy === undefined) {
+      return null;
+    }
+
+    return {
+      contents: [{
+        uri,
+        mimeType: 'text/plain',
+        text: policy
+      }]
+    };
+  }
+
+  /**
+   * resources/subscribe: ポリシーリソースの変更通知を購読する（接続ごと、stdioは 'default'）
+   * 購読できるのはポリシーリソースのみ（それ以外のURIは -32602）
+   */
+  protected subscribeResource(uri: string, sessionId: string = 'default'): Record<string, never> {
+    if (!this.isPolicyResourceUri(uri)) {
+      throw this.invalidParams(`Only policy resources can be subscribed: ${uri}`);
+    }
+    const uris = this.resourceSubscriptions.get(sessionId) ?? new Set<string>();
+    uris.add(uri);
+    this.resourceSubscriptions.set(sessionId, uris);
+    this.logger.debug(`Resource subscribed: ${uri} (session ${sessionId})`);
+    return {};
+  }
+
+  /**
+   * resources/unsubscribe: 購読を解除する（購読していないURIでも成功を返す）
+   */
+  protected unsubscribeResource(uri: string, sessionId: string = 'default'): Record<string, never> {
+    const uris = this.resourceSubscriptions.get(sessionId);
+    uris?.delete(uri);
+    if (uris?.size === 0) {
+      this.resourceSubscriptions.delete(sessionId);
+    }
+    return {};
+  }
+
+  /**
+   * 切断した接続の購読をすべて破棄する
+   */
+  protected clearResourceSubscriptions(sessionId: string = 'default'): void {
+    if (this.resourceSubscriptions.delete(sessionId)) {
+      this.logger.debug(`Resource subscriptions cleared for session ${sessionId}`);
+    }
+  }
+
+  /**
+   * 購読されているポリシーの内容が変わったら notifications/resources/updated を送る
+   */
+  protected notifyPolicyResourceUpdated(name: string): void {
+    const uri = `${POLICY_RESOURCE_URI_PREFIX}${encodeURIComponent(name)}`;
+    const subscribed = Array.from(this.resourceSubscriptions.values()).some(uris => uris.has(uri));
+    if (!subscribed) {
+      return;
+    }
+    this.server.sendResourceUpdated({ uri }).catch(error => {
+      this.logger.warn(`Failed to send resource update notification for ${uri}`, error);
+    });
+  }
+
+  /**
+   * 接続が閉じたら（HTTPのセッション終了など）その接続の購読を破棄する
+   */
+  protected installSubscriptionCleanup(transport: Transport): void {
+    const protocolOnClose = transport.onclose;
+    transport.onclose = () => {
+      this.clearResourceSubscriptions(transport.sessionId ?? 'default');
+      protocolOnClose?.();
+    };
+  }
+
+  /**
+   * AEGIS自身のリソースURI（aegis://）かどうか（上流には転送しない）
+   */
+  protected isAegisResourceUri(uri: string): boolean {
+    return typeof uri === 'string' && uri.startsWith(AEGIS_RESOURCE_URI_SCHEME);
+  }
+
+  /**
+   * 判定リソースURIからagentとresourceを取り出す（テンプレートに一致しなければnull）
+   */
+  protected parseDecisionResourceUri(uri: string): { agent: string; resource: string } | null {
+    if (typeof uri !== 'string' || !uri.startsWith(DECISION_RESOURCE_URI_PREFIX)) {
+      return null;
+    }
+    const segments = uri.substring(DECISION_RESOURCE_URI_PREFIX.length).split('/');
+    if (segments.length !== 2) {
+      return null;
+    }
+    try {
+      const [agent, resource] = segments.map(segment => decodeURIComponent(segment));
+      return agent !== '' && resource !== '' ? { agent, resource } : null;
+    } catch {
+      return null;
+    }
+  }
+
+  /**
+   * 判定リソースの読み取り: 既定ポリシー（policies.defaultPolicy、未設定なら default-policy）でその場で判定する
+   * tools/callと同じく、要求元エージェントのレート制限と enforceContextDecision の保護を適用する
+   * どのテンプレートにも一致しないaegis:// のURIは -32602 (Invalid params)、レート制限の超過は -32000
+   */
+  protected async readDecisionResource(uri: string, requester: string, requestId?: string | number): Promise<ResourceReadResult> {
+    const parsed = this.parseDecisionResourceUri(uri);
+    if (!parsed) {
+      throw this.invalidParams(`Resource URI does not match any template: ${uri}`, {
+        templates: RESOURCE_TEMPLATES.map(template => template.uriTemplate)
+      });
+    }
+
+    const rateLimited = this.agentRateLimiter.take(requester);
+    if (!rateLimited.allowed) {
+      this.logger.warn(`Rate limit exceeded for agent ${requester}`, { retryAfterMs: rateLimited.retryAfterMs });
+      const error = new Error(`Rate limit exceeded for agent ${requester}`) as Error & { code: number; data: Record<string, unknown> };
+      error.code = -32000;
+      error.data = { agent: requester, retry_after_ms: rateLimited.retryAfterMs };
+      throw error;
+    }
+
+    const policy = this.config.policies?.defaultPolicy ?? 'default-policy';
+    const policyText = this.policies.get(policy);
+    if (policyText === undefined) {
+      throw this.invalidParams(`Default policy is not loaded: ${policy}`);
+    }
+
+    const context = this.applyDefaultContext({
+      agent: parsed.agent,
+      action: DECISION_RESOURCE_ACTION,
+      resource: parsed.resource,
+      time: new Date(),
+      environment: {}
+    });
+    this.assertContextMatchesSchema(context);
+
+    const decision = this.resolveIndeterminate(await this.enforceContextDecision(context, policyText, requestId));
+    this.logger.info(`Decision resource evaluated: ${parsed.agent} → ${parsed.resource}: ${decision.decision}`);
+    return {
+      contents: [{
+        uri,
+        mimeType: 'application/json',
+        text: JSON.stringify({
+          ...parsed,
+          action: DECISION_RESOURCE_ACTION,
+          policy,
+          decision: decision.decision,
+          reason: decision.reason,
+          confidence: decision.confidence,
+          constraints: decision.constraints ?? [],
+          obligations: decision.obligations ?? []
+        }, null, 2)
+      }]
+    };
+  }
+
+  /**
+   * prompts/list: 組み込みプロンプト一覧
+   */
+  protected listBuiltinPrompts(): Prompt[] {
+    return [EVALUATE_ACCESS_PROMPT];
+  }
+
+  /**
+   * prompts/get: 組み込みプロンプトを引数でレンダリング
+   * 未知のプロンプト名・必須引数の欠落は -32602 (Invalid params)
+   */
+  protected getBuiltinPrompt(name: string, args: Record<string, string> = {}): GetPromptResult {
+    if (name !== EVALUATE_ACCESS_PROMPT.name) {
+      throw this.invalidParams(`Unknown prompt: ${name}`);
+    }
+
+    const required = (EVALUATE_ACCESS_PROMPT.arguments || [])
+      .filter(argument => argument.required)
+      .map(argument => argument.name);
+    this.assertRequiredArguments('prompt', required, args);
+
+    if (!this.judgmentEngine) {
+      throw new Error('AI judgment engine is not available');
+    }
+
+    // 登録済みポリシーIDが指定された場合は本文に置き換える
+    const policyText = this.policies.get(args.policy) ?? args.policy;
+    const text = this.judgmentEngine.renderDecisionPrompt(policyText, {
+      agent: args.agent,
+      action: args.action,
+      resource: args.resource,
+      time: new Date(),
+      environment: {}
+    });
+
+    return {
+      description: `Access evaluation for ${args.agent} → ${args.action} ${args.resource}`,
+      messages: [{
+        role: 'user',
+        content: { type: 'text', text }
+      }]
+    };
+  }
+
+  /**
+   * completion/complete: 引数の補完候補（入力中の値に前方一致、大文字小文字は区別しない）
+   * evaluate_access の action は同義語表の正規のアクション（同義語に一致した場合も正規のアクションを返す）、
+   * policy とポリシーリソースの {name} は登録済みポリシー名。補完を提供しない引数は空の一覧
+   */
+  protected completeArgument(
+    ref: { type: string; name?: string; uri?: string },
+    argument: { name: string; value: string }
+  ): CompleteResult {
+    const prefix = argument.value.toLowerCase();
+    let candidates: string[] = [];
+    if (ref.type === 'ref/prompt' && ref.name === EVALUATE_ACCESS_PROMPT.name && argument.name === 'action') {
+      const canonical = new Set<string>();
+      for (const [word, action] of this.actionLookup) {
+        if (word.startsWith(prefix)) {
+          canonical.add(action);
+        }
+      }
+      candidates = [...canonical].sort();
+    } else if (
+      (ref.type === 'ref/prompt' && ref.name === EVALUATE_ACCESS_PROMPT.name && argument.name === 'policy') ||
+      (ref.type === 'ref/resource' && ref.uri === RESOURCE_TEMPLATES[0].uriTemplate && argument.name === 'name')
+    ) {
+      candidates = [...this.policies.keys()].filter(name => name.toLowerCase().startsWith(prefix)).sort();
+    }
+
+    return {
+      completion: {
+        values: candidates.slice(0, MAX_COMPLETION_VALUES),
+        total: candidates.length,
+        hasMore: candidates.length > MAX_COMPLETION_VALUES
+      }
+    };
+  }
+
+  /**
+   * tools/list: AEGIS自身が提供する組み込みツール一覧
+   */
+  protected listBuiltinTools(): Tool[] {
+    const tools = [
+      POLICY_EXPLAIN_TOOL,
+      POLICY_SIMULATE_TOOL,
+      STATS_TOOL,
+      SERVER_INFO_TOOL,
+      POLICY_LINT_TOOL,
+      POLICY_VALIDATE_TOOL,
+      CHECK_POLICIES_TOOL,
+      POLICY_EXPORT_REGO_TOOL,
+      POLICY_DIFF_TOOL
+    ];
+    if (this.decisionHistory) {
+      tools.push(HISTORY_QUERY_TOOL);
+    }
+    if (this.decisionAuditLog) {
+      tools.push(AUDIT_VERIFY_TOOL);
+    }
+    return tools;
+  }
+
+  protected isBuiltinTool(name: string): boolean {
+    return this.listBuiltinTools().some(tool => tool.name === name);
+  }
+
+  /**
+   * tools/list: 上流のツールに組み込みツールを加える
+   * 組み込みツールと同じ名前の上流ツールは tools/call で組み込みツールが実行され呼び出せないため、一覧から除いて警告する
+   */
+  protected withBuiltinTools<T extends { name: string }>(upstreamTools: T[]): Array<T | Tool> {
+    const builtins = this.listBuiltinTools();
+    const builtinNames = new Set(builtins.map(tool => tool.name));
+    const shadowed = upstreamTools.filter(tool => builtinNames.has(tool.name)).map(tool => tool.name);
+    if (shadowed.length > 0) {
+      this.logger.warn(`Hiding upstream tools that collide with AEGIS builtin tools: ${shadowed.join(', ')}`);
+    }
+    return [...upstreamTools.filter(tool => !builtinNames.has(tool.name)), ...builtins];
+  }
+
+  /**
+   * tools/call の arguments が省略された場合は空のオブジェクトにそろえる
+   * 組み込みツール・引数の検証・ポリシー判定・上流への転送が同じ形の引数を受け取るようにする
+   */
+  protected normalizeToolCallParams<T extends { arguments?: Record<string, unknown> | null }>(
+    params: T
+  ): T & { arguments: Record<string, unknown> } {
+    return { ...params, arguments: params.arguments ?? {} };
+  }
+
+  /**
+   * 組み込みツールの実行前にポリシー判定するアクションとリソース
+   * history_query は他のエージェントを含む過去の判定を読み出すため、判定履歴の読み取りとして判定する
+   * それ以外は上流のツールと同じく tool:<name> の実行として判定する
+   */
+  protected builtinToolPolicyTarget(name: string): { action: string; resource: string } {
+    return name === HISTORY_QUERY_TOOL.name
+      ? { action: 'read', resource: HISTORY_RESOURCE_URI }
+      : { action: 'execute', resource: `tool:${name}` };
+  }
+
+  /**
+   * tools/call: 組み込みツールの実行（上流には転送しない）
+   * 未知のツール・引数不足・判定処理の失敗はツール実行エラー（isError）として返す
+   */
+  protected async callBuiltinTool(
+    name: string,
+    args: Record<string, unknown> = {},
+    progress?: ProgressReporter,
+    signal?: AbortSignal
+  ): Promise<CallToolResult> {
+    switch (name) {
+      case POLICY_EXPLAIN_TOOL.name:
+        return this.explainPolicy(args, progress);
+      case POLICY_SIMULATE_TOOL.name:
+        return this.simulatePolicy(args, progress, signal);
+      case STATS_TOOL.name:
+        return {
+          content: [{
+            type: 'text',
+            text: JSON.stringify({
+              ...this.stats.snapshot(),
+              circuitBreaker: this.aiPolicyEngine.getCircuitBreakerState(),
+              requestLatency: this.stats.requestLatency(),
+              tokenUsage: this.judgmentEngine?.getTokenUsage(),
+              auditLog: this.decisionAuditLog ? {
+                failureMode: this.auditFailureMode,
+                unauditedDecisions: this.unauditedDecisions,
+                bufferedRecords: this.auditRetryBuffer?.size() ?? 0
+              } : undefined
+            }, null, 2)
+          }]
+        };
+      case SERVER_INFO_TOOL.name:
+        return {
+          content: [{ type: 'text', text: JSON.stringify(this.describeServer(), null, 2) }]
+        };
+      case POLICY_LINT_TOOL.name:
+        return this.lintPolicyTool(args);
+      case POLICY_VALIDATE_TOOL.name:
+        return this.validatePolicyTool(args);
+      case CHECK_POLICIES_TOOL.name:
+        return this.checkPolicies(args, signal);
+      case POLICY_EXPORT_REGO_TOOL.name:
+        return this.exportRego(args);
+      case POLICY_DIFF_TOOL.name:
+        return this.diffPolicyVersions(args);
+      case HISTORY_QUERY_TOOL.name:
+        if (this.decisionHistory) {
+          return this.queryDecisionHistory(args);
+        }
+        return this.toolErrorResult(`Unknown tool: ${name}`);
+      case AUDIT_VERIFY_TOOL.name:
+        if (this.decisionAuditLog) {
+          return this.verifyDecisionAuditLog();
+        }
+        return this.toolErrorResult(`Unknown tool: ${name}`);
+      default:
+        return this.toolErrorResult(`Unknown tool: ${name}`);
+    }
+  }
+
+  /**
+   * server_info: 実行中の設定と有効な機能の要約
+   * APIキー・監査ログのHMAC鍵・secretKeyなどの秘密情報の値は出さない（監査ログは署名の有無のみ返す）
+   */
+  protected describeServer(): Record<string, unknown> {
+    const { mcpProxy, cache, monitoring, llm } = this.config;
+    return {
+      server: { ...this.serverInfo, handshake: this.handshakeState },
+      policies: { count: this.policies.size, names: [...this.policies.keys()] },
+      tools: {
+        builtin: this.filterEnabledTools(this.listBuiltinTools()).map(tool => tool.name),
+        enabled: mcpProxy?.enabledTools ?? [],
+        disabled: mcpProxy?.disabledTools ?? []
+      },
+      decisions: {
+        defaultPolicyStrictness: this.config.defaultPolicyStrictness ?? 'medium',
+        minConfidence: this.config.minConfidence ?? 0,
+        failClosed: this.config.failClosed ?? false,
+        indeterminateResolution: this.config.indeterminateResolution ?? DEFAULT_INDETERMINATE_RESOLUTION,
+        obligationHandlers: this.obligationHandlers.names(),
+        defaultContext: Object.keys(this.defaultContext).length > 0,
+        contextSchema: this.contextSchema !== undefined,
+        agentOverrides: this.agentOverrides.size
+      },
+      llm: llm ? { provider: llm.provider, model: llm.model } : null,
+      cache: cache ? { enabled: cache.enabled, ttl: cache.ttl, maxSize: cache.maxSize } : null,
+      audit: {
+        decisionAuditLog: this.decisionAuditLog !== undefined,
+        signed: this.decisionAuditLog !== undefined && Boolean(monitoring?.decisionAuditLogKey),
+        decisionHistory: this.decisionHistory !== undefined
+      },
+      limits: {
+        agentRateLimitPerMinute: mcpProxy?.agentRateLimitPerMinute ?? 0,
+        toolTimeoutMs: mcpProxy?.toolTimeoutMs ?? TIMEOUTS.TOOL_CALL,
+        dedupWindow: mcpProxy?.dedupWindow ?? SERVER.DEFAULT_DEDUP_WINDOW,
+        maxSimulateBatch: mcpProxy?.maxSimulateBatch ?? BATCH.MAX_SIMULATE_SIZE,
+        maxRequestBytes: mcpProxy?.maxRequestBytes ?? SERVER.DEFAULT_MAX_REQUEST_BYTES
+      }
+    };
+  }
+
+  /**
+   * progressTokenに紐づく進捗通知コールバックを作成（トークンがなければundefined）
+   * 通知の送信失敗はログのみとし、ツールの実行は続ける
+   */
+  protected createProgressReporter(
+    request: { params?: { _meta?: { progressToken?: string | number } } },
+    extra?: { sendNotification?: (notification: any) => Promise<void> }
+  ): ProgressReporter | undefined {
+    const progressToken = request.params?._meta?.progressToken;
+    const sendNotification = extra?.sendNotification;
+    if (progressToken === undefined || !sendNotification) {
+      return undefined;
+    }
+
+    return async (progress, total, message) => {
+      try {
+        await sendNotification({
+          method: 'notifications/progress',
+          params: { progressToken, progress, total, message }
+        });
+      } catch (error) {
+        this.logger.warn('Failed to send progress notification', error);
+      }
+    };
+  }
+
+  private async explainPolicy(args: Record<string, unknown>, progress?: ProgressReporter): Promise<CallToolResult> {
+    this.assertRequiredArguments(
+      'tool',
+      args.policy_id === undefined ? [...POLICY_EXPLAIN_REQUIRED_ARGUMENTS, 'policy'] : POLICY_EXPLAIN_REQUIRED_ARGUMENTS,
+      args
+    );
+
+    if (!this.judgmentEngine) {
+      return this.toolErrorResult('AI judgment engine is not available');
+    }
+
+    await progress?.(0, 2, 'parsing policy');
+    const policyText = this.resolvePolicyArgument(args);
+    // check_policy・tools/callと同じコンテキスト（既定のコンテキスト・resourceの正規化・スキーマ検証）で説明する
+    const context = this.applyDefaultContext({
+      agent: args.agent as string,
+      action: args.action as string,
+      resource: args.resource as string,
+      purpose: typeof args.purpose === 'string' ? args.purpose : undefined,
+      time: new Date(),
+      environment: {}
+    });
+    this.assertContextMatchesSchema(context);
+    try {
+      await progress?.(1, 2, 'evaluating clauses');
+      // エージェントの上書き・許可/拒否リストに一致すれば、tools/callと同じくポリシーの条項は判定に使われない
+      const shortcut = this.forcedAgentDecision(context) ?? this.fastPathDecision(context);
+      const explanation: PolicyExplanation = shortcut
+        ? { decision: shortcut.decision, reason: shortcut.reason, confidence: shortcut.confidence, clauses: [] }
+        : await this.judgmentEngine.explainDecision(this.withAgentPolicy(context, policyText) ?? policyText, context);
+
+      await progress?.(2, 2, 'done');
+      this.logger.info(`Policy explanation generated: ${explanation.decision} (${explanation.clauses.length} clauses)`);
+      return {
+        content: [{ type: 'text', text: JSON.stringify(explanation, null, 2) }]
+      };
+    } catch (error) {
+      this.logger.error('Policy explanation failed', error);
+      return this.toolErrorResult(`Policy explanation failed: ${error instanceof Error ? error.message : 'Unknown error'}`);
+    }
+  }
+
+  /**
+   * policy_simulate: 通常の判定経路（AIPolicyEngine）で複数リクエストを順に判定する
+   * 監査ログへの記録・義務の実行は行わない
+   */
+  private async simulatePolicy(
+    args: Record<string, unknown>,
+    progress?: ProgressReporter,
+    signal?: AbortSignal
+  ): Promise<CallToolResult> {
+    const policyText = this.resolvePolicyArgument(args);
+    // 空の配列も省略と同じく必須引数の欠落として扱う
+    if (args.requests === undefined || (Array.isArray(args.requests) && args.requests.length === 0)) {
+      throw this.missingArgumentsError('tool', ['requests']);
+    }
+    if (!Array.isArray(args.requests)) {
+      return this.toolErrorResult('requests must be an array');
+    }
+
+    const maxBatch = this.config.mcpProxy?.maxSimulateBatch ?? BATCH.MAX_SIMULATE_SIZE;
+    if (args.requests.length > maxBatch) {
+      return this.toolErrorResult(`Too many requests to simulate: ${args.requests.length} (max ${maxBatch})`);
+    }
+
+    args.requests.forEach((request, index) =>
+      this.assertRequiredArguments('tool', ['agent', 'action', 'resource'], (request ?? {}) as Record<string, unknown>, `requests[${index}].`));
+
+    const summary: Record<PolicyDecision['decision'], number> = { PERMIT: 0, DENY: 0, INDETERMINATE: 0 };
+    const decisions = [];
+
+    const total = args.requests.length;
+    for (const [index, request] of (args.requests as Array<Record<string, any>>).entries()) {
+      this.throwIfCancelled(signal);
+      await progress?.(index, total, `evaluating request ${index + 1}/${total}`);
+      const context = this.applyDefaultContext({
+        agent: request.agent,
+        action: request.action,
+        resource: request.resource,
+        purpose: typeof request.purpose === 'string' ? request.purpose : undefined,
+        time: request.time ? new Date(request.time) : new Date(),
+        environment: { ...(request.environment ?? {}), simulation: true }
+      });
+      this.assertContextMatchesSchema(context, `requests[${index}]`);
+
+      try {
+        // tools/callと同じく、エージェントの上書き・許可/拒否リストをAI判定より先に適用する
+        const decision = this.forcedAgentDecision(context) ?? this.fastPathDecision(context) ??
+          await this.aiPolicyEngine.decide(context, this.withAgentPolicy(context, policyText) ?? policyText, signal);
+        summary[decision.decision]++;
+        decisions.push({
+          index,
+          agent: context.agent,
+          action: context.action,
+          resource: context.resource,
+          decision: decision.decision,
+          reason: decision.reason,
+          confidence: decision.confidence
+        });
+      } catch (error) {
+        summary.INDETERMINATE++;
+        decisions.push({
+          index,
+          agent: context.agent,
+          action: context.action,
+          resource: context.resource,
+          decision: 'INDETERMINATE',
+          reason: `Evaluation failed: ${error instanceof Error ? error.message : 'Unknown error'}`,
+          confidence: 0
+        });
+      }
+    }
+
+    await progress?.(total, total, 'done');
+    this.logger.info(`Policy simulation completed: ${decisions.length} requests`, summary);
+    return {
+      content: [{ type: 'text', text: JSON.stringify({ summary, decisions }, null, 2) }]
+    };
+  }
+
+  /**
+   * check_policies: 各ポリシーを通常の判定経路で順に評価し、結合アルゴリズムで最終判定を決める
+   * 未知の結合アルゴリズム・format・policy_format、scope に存在しないセクション、読み込めないJSONポリシーは -32602 (Invalid params)
+   */
+  private async checkPolicies(args: Record<string, unknown>, signal?: AbortSignal): Promise<CallToolResult> {
+    this.assertRequiredArguments('tool', ['agent', 'action', 'resource', 'combining_algorithm'], args);
+    if (!isCombiningAlgorithm(args.combining_algorithm)) {
+      throw this.invalidParams(`Unknown combining_algorithm: ${String(args.combining_algorithm)}`, {
+        supported: [...COMBINING_ALGORITHMS]
+      });
+    }
+    const format = args.format ?? 'full';
+    if (!(CHECK_POLICIES_FORMATS as readonly unknown[]).includes(format)) {
+      throw this.invalidParams(`Unknown format: ${String(format)}`, { supported: [...CHECK_POLICIES_FORMATS] });
+    }
+    const policyFormat = args.policy_format ?? DEFAULT_POLICY_FORMAT;
+    const supportedPolicyFormats = await availablePolicyFormats();
+    if (!isPolicyFormat(policyFormat) || !supportedPolicyFormats.includes(policyFormat)) {
+      throw this.invalidParams(`Unsupported policy_format: ${String(policyFormat)}`, { supported: supportedPolicyFormats });
+    }
+    const isStringList = (value: unknown): value is string[] =>
+      Array.isArray(value) && value.every(item => typeof item === 'string' && item !== '');
+    if (args.tags !== undefined && !isStringList(args.tags)) {
+      return this.toolErrorResult('tags must be an array of strings');
+    }
+    if (args.scope !== undefined && !isStringList(args.scope)) {
+      return this.toolErrorResult('scope must be an array of strings');
+    }
+    if (args.policies !== undefined && !isStringList(args.policies)) {
+      return this.toolErrorResult('policies must be an array of strings');
+    }
+    // tags指定時はpoliciesを省略できる（省略・空の配列は必須引数の欠落）
+    if (args.tags === undefined && ((args.policies as string[] | undefined) ?? []).length === 0) {
+      throw this.missingArgumentsError('tool', ['policies']);
+    }
+    const tags = (args.tags as string[] | undefined) ?? [];
+    const explicit = (args.policies as string[] | undefined) ?? [];
+    const selected = [...new Set([...explicit, ...this.policiesTagged(tags)])];
+
+    // scope指定時は各ポリシーから指定セクションだけを評価する（評価を始める前にすべて検査する）
+    const policyTexts = new Map<string, string>();
+    for (const policy of selected) {
+      const policyText = this.resolvePolicyReference(policy);
+      if (args.scope === undefined) {
+        policyTexts.set(policy, policyText);
+        continue;
+      }
+      if (policyFormat !== 'natural') {
+        throw this.invalidParams('scope is only supported with policy_format natural', { policyFormat });
+      }
+      const selection = selectPolicySections(policyText, args.scope as string[]);
+      if (selection.missing.length > 0) {
+        throw this.invalidParams(`Unknown policy section in scope: ${selection.missing.join(', ')}`, {
+          policy,
+          missing: selection.missing,
+          available: selection.available
+        });
+      }
+      policyTexts.set(policy, selection.text);
+    }
+
+    // JSONポリシーは評価を始める前にすべて読み込む（形式の誤りは判定ではなく引数の誤り）
+    const jsonRules = new Map<string, ReturnType<typeof parseJsonPolicy>>();
+    if (policyFormat === 'json') {
+      for (const [policy, policyText] of policyTexts) {
+        try {
+          jsonRules.set(policy, parseJsonPolicy(policyText));
+        } catch (error) {
+          throw this.invalidParams(error instanceof Error ? error.message : String(error), { policy });
+        }
+      }
+    }
+
+    const context = this.applyDefaultContext({
+      agent: args.agent as string,
+      action: args.action as string,
+      resource: args.resource as string,
+      purpose: typeof args.purpose === 'string' ? args.purpose : undefined,
+      locale: typeof args.locale === 'string' ? this.reasonLocale(args.locale) : undefined,
+      time: new Date(),
+      environment: {}
+    });
+    this.assertContextMatchesSchema(context);
+
+    // tools/callと同じく、エージェントの上書き・許可/拒否リストに一致すればポリシーごとの評価はしない
+    const shortcut = this.forcedAgentDecision(context) ?? this.fastPathDecision(context);
+    const decisions = [];
+    for (const policy of shortcut ? [] : selected) {
+      this.throwIfCancelled(signal);
+      const policyText = policyTexts.get(policy)!;
+      try {
+        let decision: PolicyDecision;
+        if (policyFormat === 'json') {
+          decision = evaluateJsonPolicy(jsonRules.get(policy)!, context);
+        } else if (policyFormat === 'cedar') {
+          decision = await evaluateCedarPolicy(policyText, context);
+        } else {
+          decision = this.markAgentOverride(context, await this.decideWithEvalTimeout(
+            context,
+            this.withAgentPolicy(context, policyText) ?? policyText,
+            signal
+          ));
+        }
+        decision = this.enforceStructuredConstraints(decision, context);
+        decisions.push({
+          policy,
+          decision: decision.decision,
+          reason: decision.reason,
+          confidence: decision.confidence,
+          ...(decision.metadata ? { metadata: decision.metadata } : {})
+        });
+      } catch (error) {
+        decisions.push({
+          policy,
+          decision: 'INDETERMINATE' as const,
+          reason: `Evaluation failed: ${error instanceof Error ? error.message : 'Unknown error'}`,
+          confidence: 0,
+          metadata: { indeterminateSource: 'evaluation-error' }
+        });
+      }
+    }
+
+    // タグに一致するポリシーがなければ、判定なしの結果（decisionsは空）を返す
+    const noPolicies: CombinedDecision = {
+      decision: 'INDETERMINATE',
+      reason: `No registered policies are tagged: ${tags.join(', ')}`,
+      confidence: 0,
+      decidingIndex: null,
+      metadata: { indeterminateSource: 'no-match' }
+    };
+    const combined: CombinedDecision = shortcut ? { ...shortcut, decidingIndex: null } : this.resolveIndeterminate(
+      decisions.length > 0 ? combineDecisions(args.combining_algorithm, decisions) : noPolicies
+    );
+    this.logger.info(`Combined ${decisions.length} policy decisions with ${args.combining_algorithm}: ${combined.decision}`);
+    const evaluated = selected.map(policy => this.policyProvenance(policy));
+    const provenance: DecisionProvenance = {
+      combiningAlgorithm: args.combining_algorithm,
+      evaluated,
+      controlling: combined.decidingIndex === null ? null : evaluated[combined.decidingIndex]
+    };
+    await this.recordDecisionAudit(context, combined, getRequestTrace()?.requestId, provenance);
+    if (format === 'minimal') {
+      // 判定だけが必要な呼び出し元向けに、理由・ポリシーごとの判定を省いて応答を小さくする
+      return {
+        content: [{ type: 'text', text: JSON.stringify({ decision: combined.decision }) }],
+        structuredContent: { decision: combined.decision }
+      };
+    }
+    const structuredContent = {
+      ...combined,
+      combiningAlgorithm: args.combining_algorithm,
+      provenance: { evaluated: provenance.evaluated, controlling: provenance.controlling },
+      decisions
+    };
+    return {
+      content: [{ type: 'text', text: JSON.stringify(structuredContent, null, 2) }],
+      structuredContent
+    };
+  }
+
+  /**
+   * 登録済みポリシーは最新版の番号とハッシュ、ポリシー本文の直接指定はハッシュのみ
+   */
+  private policyProvenance(policy: string): PolicyProvenance {
+    const latest = this.policies.has(policy) ? this.policyVersions.latest(policy) : undefined;
+    if (latest) {
+      return { policy, version: latest.version, hash: latest.hash };
+    }
+    return { policy, version: null, hash: hashPolicyContent(this.resolvePolicyReference(policy)) };
+  }
+
+  /**
+   * 判定理由の言語。BCP 47として読めない値は警告して en にする
+   */
+  private reasonLocale(locale: string): string {
+    const normalized = normalizeLocale(locale);
+    if (normalized === DEFAULT_REASON_LOCALE && locale.toLowerCase() !== DEFAULT_REASON_LOCALE) {
+      this.logger.warn(`Unsupported locale "${locale}"; returning reasons in ${DEFAULT_REASON_LOCALE}`);
+    }
+    return normalized;
+  }
+
+  /**
+   * policy_export_rego: AIでRegoのひな形を生成し、language付きで返す
+   */
+  private async exportRego(args: Record<string, unknown>): Promise<CallToolResult> {
+    this.assertRequiredArguments('tool', ['policy'], args);
+    const packageName = typeof args.package === 'string' && args.package !== '' ? args.package : DEFAULT_REGO_PACKAGE;
+    if (!/^[A-Za-z_][\w]*(\.[A-Za-z_][\w]*)*$/.test(packageName)) {
+      return this.toolErrorResult(`Invalid Rego package name: ${packageName}`);
+    }
+
+    if (!this.judgmentEngine) {
+      return this.toolErrorResult('AI judgment engine is not available');
+    }
+
+    const policyText = this.policies.get(args.policy) ?? args.policy;
+    try {
+      const source = await this.judgmentEngine.exportRego(policyText, packageName);
+      this.logger.info(`Rego stub exported for package ${packageName}`);
+      return {
+        content: [{ type: 'text', text: JSON.stringify({ language: 'rego', package: packageName, source }, null, 2) }]
+      };
+    } catch (error) {
+      this.logger.error('Rego export failed', error);
+      return this.toolErrorResult(`Rego export failed: ${error instanceof Error ? error.message : 'Unknown error'}`);
+    }
+  }
+
+  /**
+   * 登録済みポリシーID・過去の版（ID@版番号）を本文に置き換える（どちらでもなければ本文として扱う）
+   * --strict-policy-refs では、IDに見える（空白を含まない英数字・. _ -）未登録の値を -32602 で拒否する
+   */
+  protected resolvePolicyReference(reference: string): string {
+    const resolved = this.findPolicyReference(reference);
+    if (resolved !== undefined) {
+      return resolved;
+    }
+    if (this.config.mcpProxy?.strictPolicyRefs && POLICY_ID_PATTERN.test(reference)) {
+      throw this.invalidParams(`Unknown policy: ${reference}`, { policy: reference, registered: [...this.policies.keys()] });
+    }
+    return reference;
+  }
+
+  private findPolicyReference(reference: string): string | undefined {
+    const registered = this.policies.get(reference);
+    if (registered !== undefined) {
+      return registered;
+    }
+    const match = reference.match(/^(.+)@(\d+)$/);
+    return match ? this.policyVersions.get(match[1], Number(match[2]))?.content : undefined;
+  }
+
+  /**
+   * ツール引数の policy（本文または登録済みID）と policy_id（登録済みIDのみ）から評価する本文を決める
+   * 両方指定された場合は policy を優先する。--strict-policy-refs では両者の本文が異なれば -32602
+   * どちらもなければ必須引数の欠落、policy_id が未登録なら常に -32602
+   */
+  protected resolvePolicyArgument(args: Record<string, unknown>): string {
+    if (args.policy_id === undefined) {
+      this.assertRequiredArguments('tool', ['policy'], args);
+      return this.resolvePolicyReference(args.policy);
+    }
+    const policyId = args.policy_id;
+    const byId = typeof policyId === 'string' ? this.findPolicyReference(policyId) : undefined;
+    if (byId === undefined) {
+      throw this.invalidParams(`Unknown policy_id: ${String(policyId)}`, { policy_id: policyId, registered: [...this.policies.keys()] });
+    }
+    if (args.policy === undefined) {
+      return byId;
+    }
+    this.assertRequiredArguments('tool', ['policy'], args);
+    const inline = this.resolvePolicyReference(args.policy);
+    if (inline !== byId) {
+      if (this.config.mcpProxy?.strictPolicyRefs) {
+        throw this.invalidParams('policy and policy_id refer to different policies', { policy_id: policyId });
+      }
+      this.logger.warn(`Both policy and policy_id (${policyId}) were given; evaluating policy`);
+    }
+    return inline;
+  }
+
+  /**
+   * policy_diff: 登録済みポリシーの2つの版のunified diffを返す
+   */
+  private diffPolicyVersions(args: Record<string, unknown>): CallToolResult {
+    this.assertRequiredArguments('tool', ['policy'], args);
+    const versions = this.policyVersions.list(args.policy);
+    if (versions.length === 0) {
+      return this.toolErrorResult(`Unknown policy: ${args.policy}`);
+    }
+    for (const key of ['from', 'to'] as const) {
+      if (args[key] !== undefined && (!Number.isInteger(args[key]) || (args[key] as number) < 1)) {
+        return this.toolErrorResult(`${key} must be a positive integer`);
+      }
+    }
+
+    const toVersion = (args.to as number | undefined) ?? versions[versions.length - 1].version;
+    const fromVersion = (args.from as number | undefined) ?? toVersion - 1;
+    const to = this.policyVersions.get(args.policy, toVersion);
+    const from = this.policyVersions.get(args.policy, fromVersion);
+    if (!to || !from) {
+      const available = versions.map(version => version.version).join(', ');
+      return this.toolErrorResult(`Version ${!to ? toVersion : fromVersion} of ${args.policy} not found (available: ${available})`);
+    }
+
+    const describe = ({ version, hash, loadedAt }: PolicyVersion) => ({ version, hash, loadedAt });
+    const diff = unifiedDiff(from.content, to.content, `${args.policy}@${from.version}`, `${args.policy}@${to.version}`);
+    return {
+      content: [{
+        type: 'text',
+        text: JSON.stringify({
+          policy: args.policy,
+          from: describe(from),
+          to: describe(to),
+          diff,
+          versions: versions.map(describe)
+        }, null, 2)
+      }]
+    };
+  }
+
+  /**
+   * policy_lint: 登録済みポリシー名が指定されればその本文を、そうでなければ引数をそのまま検査する
+   */
+  private lintPolicyTool(args: Record<string, unknown>): CallToolResult {
+    this.assertRequiredArguments('tool', ['policy'], args);
+
+    const policyText = this.policies.get(args.policy) ?? args.policy;
+    const findings = lintPolicy(policyText, this.lintRules);
+    return {
+      content: [{ type: 'text', text: JSON.stringify({ findings }, null, 2) }]
+    };
+  }
+
+  /**
+   * policy_validate: 空のポリシー、構造化形式で解釈できないポリシーを errors に列挙する
+   * 検査に失敗したポリシーはツール実行エラーではなく valid: false として返す
+   */
+  private async validatePolicyTool(args: Record<string, unknown>): Promise<CallToolResult> {
+    this.assertRequiredArguments('tool', ['policy'], args);
+    const policyFormat = args.policy_format ?? DEFAULT_POLICY_FORMAT;
+    const supportedPolicyFormats = await availablePolicyFormats();
+    if (!isPolicyFormat(policyFormat) || !supportedPolicyFormats.includes(policyFormat)) {
+      throw this.invalidParams(`Unsupported policy_format: ${String(policyFormat)}`, { supported: supportedPolicyFormats });
+    }
+
+    const policyText = this.policies.get(args.policy) ?? args.policy;
+    const errors: Array<{ message: string; line?: number; column?: number }> = [];
+    if (policyText.trim() === '') {
+      errors.push({ message: 'Policy is empty' });
+    } else if (policyFormat === 'json') {
+      try {
+        parseJsonPolicy(policyText);
+      } catch (error) {
+        errors.push({ message: error instanceof Error ? error.message : String(error) });
+      }
+    } else if (policyFormat === 'cedar') {
+      errors.push(...(await validateCedarPolicy(policyText)).map(message => ({ message })));
+    }
+
+    const findings = args.lint === true ? lintPolicy(policyText, this.lintRules) : undefined;
+    for (const finding of findings ?? []) {
+      if (finding.severity === 'error') {
+        errors.push({ message: finding.message, line: finding.line, column: finding.column });
+      }
+    }
+
+    const result = { valid: errors.length === 0, errors, ...(findings ? { findings } : {}) };
+    return {
+      content: [{ type: 'text', text: JSON.stringify(result, null, 2) }]
+    };
+  }
+
+  /**
+   * policy_lintで使うルール（--lint-rulesで既定のルールに追加したもの）を設定
+   */
+  setLintRules(rules: LintRules): void {
+    this.lintRules = rules;
+  }
+
+  /**
+   * audit_verify: 書き込み待ちのレコードを反映してから監査ログを検証する
+   * チェーンが壊れている場合は行番号と理由をツール実行エラーとして返す
+   */
+  private async verifyDecisionAuditLog(): Promise<CallToolResult> {
+    const log = this.decisionAuditLog!;
+    try {
+      await log.flush();
+      const result = await log.verify();
+      if (!result.valid) {
+        this.logger.warn(`Decision audit log verification failed at line ${result.line}: ${result.reason}`);
+        return {
+          content: [{ type: 'text', text: JSON.stringify(result, null, 2) }],
+          isError: true
+        };
+      }
+      return {
+        content: [{ type: 'text', text: JSON.stringify(result, null, 2) }]
+      };
+    } catch (error) {
+      if ((error as NodeJS.ErrnoException).code === 'ENOENT') {
+        return { content: [{ type: 'text', text: JSON.stringify({ valid: true, entries: 0 }, null, 2) }] };
+      }
+      this.logger.error('Decision audit log verification failed', error);
+      return this.toolErrorResult(`Audit verification failed: ${error instanceof Error ? error.message : 'Unknown error'}`);
+    }
+  }
+
+  /**
+   * history_query: 判定履歴DBを検索
+   */
+  private async queryDecisionHistory(args: Record<string, unknown>): Promise<CallToolResult> {
+    const filter: DecisionHistoryFilter = {};
+    for (const key of ['agent', 'action', 'resource', 'decision', 'since', 'until'] as const) {
+      const value = args[key];
+      if (value !== undefined) {
+        if (typeof value !== 'string') {
+          return this.toolErrorResult(`${key} must be a string`);
+        }
+        filter[key] = value;
+      }
+    }
+    if (args.limit !== undefined) {
+      if (typeof args.limit !== 'number' || !Number.isInteger(args.limit) || args.limit < 1) {
+        return this.toolErrorResult('limit must be a positive integer');
+      }
+      filter.limit = args.limit;
+    }
+
+    try {
+      const store = await this.decisionHistory!;
+      const rows = store.query(filter);
+      return {
+        content: [{ type: 'text', text: JSON.stringify({ count: rows.length, decisions: rows }, null, 2) }]
+      };
+    } catch (error) {
+      this.logger.error('Decision history query failed', error);
+      return this.toolErrorResult(`Decision history query failed: ${error instanceof Error ? error.message : 'Unknown error'}`);
+    }
+  }
+
+  /**
+   * tools/callハンドラーにタイムアウトを付ける（リクエストごとに計時）
+   * 期限を過ぎたら -32603 で応答し、signalを中断状態にする
+   * クライアントの notifications/cancelled（SDKがextra.signalを中断する）でも同じsignalを中断して処理を打ち切る
+   * （キャンセルされたリクエストにはSDKが応答を送らない）
+   * ハンドラーはsignalが中断されていれば判定結果をキャッシュに書き込まず、上流にも転送しない
+   * （判定・義務の実行後に確認し、転送中の要求にもsignalを渡す）
+   */
+  protected withToolTimeout<E, R>(
+    handler: (request: any, extra: E, signal: AbortSignal) => Promise<R>
+  ): (request: any, extra: E) => Promise<R> {
+    const timeoutMs = this.config.mcpProxy?.toolTimeoutMs ?? TIMEOUTS.TOOL_CALL;
+
+    return async (request, extra) => {
+      const controller = new AbortController();
+      const cancelSignal = (extra as { signal?: AbortSignal } | undefined)?.signal;
+      let onCancel: (() => void) | undefined;
+      const cancelled = new Promise<never>((_, reject) => {
+        onCancel = () => {
+          controller.abort();
+          const toolName = request?.params?.name ?? 'unknown';
+          this.logger.info(`Tool call cancelled by client: ${toolName}`);
+          const error = new Error(`Request cancelled: ${toolName}`) as Error & { code?: number };
+          error.code = -32800;
+          reject(error);
+        };
+        if (cancelSignal?.aborted) {
+          onCancel();
+        } else {
+          cancelSignal?.addEventListener('abort', onCancel, { once: true });
+        }
+      });
+      let timer: NodeJS.Timeout | undefined;
+      const timeout = new Promise<never>((_, reject) => {
+        timer = setTimeout(() => {
+          controller.abort();
+          const toolName = request?.params?.name ?? 'unknown';
+          this.logger.warn(`Tool call timed out: ${toolName} (${timeoutMs}ms)`);
+          const error = new Error(`Tool timed out: ${toolName} (after ${timeoutMs}ms)`) as Error & { code?: number; data?: unknown };
+          error.code = -32603;
+          error.data = { tool: toolName, timeoutMs };
+          reject(error);
+        }, timeoutMs);
+      });
+
+      try {
+        return await Promise.race([handler(request, extra, controller.signal), timeout, cancelled]);
+      } finally {
+        clearTimeout(timer);
+        if (onCancel) {
+          cancelSignal?.removeEventListener('abort', onCancel);
+        }
+      }
+    };
+  }
+
+  /**
+   * tools/callハンドラーに重複排除を付ける（セッション・エージェントが同じで、同じidと引数なら前回の応答を返す）
+   * 別のエージェントの同じidの要求に、他のエージェント向けの判定・応答を返さない
+   */
+  protected withDeduplication<E extends { requestId?: string | number; sessionId?: string } | undefined, R>(
+    handler: (request: any, extra: E) => Promise<R>
+  ): (request: any, extra: E) => Promise<R> {
+    return (request, extra) => {
+      const key = extra?.requestId === undefined
+        ? undefined
+        : JSON.stringify([extra.sessionId ?? 'default', this.deduplicationAgent(extra), typeof extra.requestId, extra.requestId]);
+      return this.toolCallDeduplicator.run(key, request?.params, () => handler(request, extra)) as Promise<R>;
+    };
+  }
+
+  /**
+   * 重複排除のキーに含める要求元エージェント（リクエストごとにエージェントを名乗れないトランスポートではnull）
+   */
+  protected deduplicationAgent(_extra: { requestId?: string | number; sessionId?: string } | undefined): string | null {
+    return null;
+  }
+
+  /**
+   * tools/call内のポリシー判定にタイムアウトを付ける（ツール全体のタイムアウトとは別に計時）
+   * 期限を過ぎたら判定を中断し、フェイルクローズでDENYを返す（metadata.evaluationTimedOut）
+   * 判定にかかった時間は /metrics の判定時間ヒストグラムに記録する
+   */
+  protected async decideWithEvalTimeout(
+    context: DecisionContext,
+    policyText: string,
+    signal?: AbortSignal
+  ): Promise<PolicyDecision> {
+    const timeoutMs = this.config.mcpProxy?.evalTimeoutMs ?? TIMEOUTS.POLICY_EVALUATION;
+    const startedAt = Date.now();
+    const controller = new AbortController();
+    const abort = () => controller.abort();
+    signal?.addEventListener('abort', abort, { once: true });
+
+    let timer: NodeJS.Timeout | undefined;
+    const timeout = new Promise<PolicyDecision>(resolve => {
+      timer = setTimeout(() => {
+        controller.abort();
+        this.logger.warn(`Policy evaluation timed out after ${timeoutMs}ms; defaulting to DENY`, {
+          agent: context.agent,
+          action: context.action,
+          resource: context.resource
+        });
+        resolve({
+          decision: 'DENY',
+          reason: `Policy evaluation timed out after ${timeoutMs}ms; defaulting to DENY`,
+          confidence: 0,
+          metadata: { evaluationTimedOut: true, evalTimeoutMs: timeoutMs }
+        });
+      }, timeoutMs);
+    });
+
+    try {
+      return await Promise.race([this.aiPolicyEngine.decide(context, policyText, controller.signal), timeout]);
+    } finally {
+      clearTimeout(timer);
+      signal?.removeEventListener('abort', abort);
+      this.stats.recordEvaluation(Date.now() - startedAt);
+    }
+  }
+
+  /**
+   * トランスポートの enforcePolicy を通らない判定（判定リソース・組み込みツール）を1件行う
+   * tools/callと同じく、エージェントの上書き・許可/拒否リストを先に適用し、
+   * AI判定には評価のタイムアウトと構造化制約を適用して、判定監査ログに記録する
+   */
+  protected async enforceContextDecision(
+    context: DecisionContext,
+    policyText: string,
+    requestId?: string | number,
+    signal?: AbortSignal
+  ): Promise<PolicyDecision> {
+    const shortcut = this.forcedAgentDecision(context) ?? this.fastPathDecision(context);
+    const decision = shortcut ?? this.markAgentOverride(context, this.enforceStructuredConstraints(
+      await this.decideWithEvalTimeout(context, this.withAgentPolicy(context, policyText) ?? policyText, signal),
+      context
+    ));
+    await this.recordDecisionAudit(context, decision, requestId);
+    return decision;
+  }
+
+  /**
+   * 判定結果をキャッシュしてよいか
+   * 中断された呼び出し、評価のタイムアウト・サーキットブレーカー・AI呼び出しの失敗による判定は
+   * 一時的な障害の結果なのでキャッシュせず、次の要求で改めて判定する
+   */
+  protected isCacheableDecision(decision: PolicyDecision, signal?: AbortSignal): boolean {
+    const metadata = decision.metadata ?? {};
+    return !signal?.aborted && !metadata.evaluationTimedOut && !metadata.circuitOpen && !metadata.aiError;
+  }
+
+  /**
+   * 判定監査ログに書き込めるか（監査ログ未設定なら常にtrue）
+   */
+  protected async isAuditLogWritable(): Promise<boolean> {
+    return this.decisionAuditLog ? this.decisionAuditLog.checkWritable() : true;
+  }
+
+  /**
+   * 運用者が公開を許可したツールか（許可リスト未設定なら拒否リスト以外すべて）
+   */
+  protected isToolEnabled(name: string): boolean {
+    const { enabledTools, disabledTools } = this.config.mcpProxy ?? {};
+    if (enabledTools && enabledTools.length > 0 && !enabledTools.includes(name)) {
+      return false;
+    }
+    return !disabledTools?.includes(name);
+  }
+
+  /**
+   * tools/list: 無効化されたツールを一覧から除く
+   */
+  protected filterEnabledTools<T extends { name: string }>(tools: T[]): T[] {
+    return tools.filter(tool => this.isToolEnabled(tool.name));
+  }
+
+  /**
+   * tools/call: 無効化されたツールは -32601 (Method not found) で拒否する
+   */
+  protected assertToolEnabled(name: string): void {
+    if (this.isToolEnabled(name)) {
+      return;
+    }
+    const error = new Error(`Tool not found: ${name}`) as Error & { code: number; data?: Record<string, unknown> };
+    error.code = -32601;
+    error.data = { tool: name };
+    throw error;
+  }
+
+  /**
+   * tools/call: エージェントごとのレート制限（判定の前に確認する）
+   * 上限を超えた場合はツール実行エラーとして retry_after_ms を返し、超えていなければnull
+   */
+  protected checkAgentRateLimit(agent: string): CallToolResult | null {
+    const result = this.agentRateLimiter.take(agent);
+    if (result.allowed) {
+      return null;
+    }
+
+    this.logger.warn(`Rate limit exceeded for agent ${agent}`, { retryAfterMs: result.retryAfterMs });
+    return {
+      content: [{
+        type: 'text',
+        text: JSON.stringify({ error: 'Rate limit exceeded', agent, retry_after_ms: result.retryAfterMs })
+      }],
+      isError: true
+    };
+  }
+
+  /**
+   * tools/listの結果からinputSchemaを記録（stdio・HTTPの tools/list で呼ぶ）
+   */
+  protected rememberToolInputSchemas(tools: Array<{ name: string; inputSchema?: Record<string, any> }>): void {
+    this.toolInputSchemas.clear();
+    this.knownToolNames = new Set(tools.map(tool => tool.name));
+    tools.forEach(tool => {
+      if (tool.name && tool.inputSchema) {
+        this.toolInputSchemas.set(tool.name, tool.inputSchema);
+      }
+    });
+  }
+
+  /**
+   * ツール名と引数（inputSchema）を検証し、問題があればツール実行エラー（isError）の結果を返す
+   * ツール一覧・スキーマ未取得のツールは上流に判断を委ねる
+   */
+  protected validateToolArguments(toolName: string, args: Record<string, any> | undefined): CallToolResult | null {
+    if (this.knownToolNames && !this.knownToolNames.has(toolName)) {
+      return this.toolErrorResult(`Unknown tool: ${toolName}`);
+    }
+    
+    const schema = this.toolInputSchemas.get(toolName);
+    if (!schema) {
+      return null;
+    }
+    
+    const issues = validateAgainstSchema(args ?? {}, schema);
+    if (issues.length > 0) {
+      return this.toolErrorResult(
+        `Invalid arguments for tool ${toolName}: ${issues.map(issue => `${issue.path} ${issue.message}`).join('; ')}`
+      );
+    }
+    return null;
+  }
+
+  /**
+   * 上流のツール一覧が変わった（notifications/tools/list_changed）ため、記録したツール名・inputSchemaを捨てる
+   * 次に tools/list を取得するまでは上流に判断を委ねる
+   */
+  protected forgetToolInputSchemas(): void {
+    this.toolInputSchemas.clear();
+    this.knownToolNames = null;
+  }
+
+  /**
+   * ツール実行エラーの結果（MCPのisError規約）
+   * JSON-RPCエラーはプロトコル上の異常に限り、ツール側の失敗は通常の結果として返す
+   */
+  protected toolErrorResult(message: string): CallToolResult {
+    return {
+      content: [{ type: 'text', text: message }],
+      isError: true
+    };
+  }
+
+  private missingArguments(required: string[], args: Record<string, unknown>): string[] {
+    return required.filter(name => typeof args[name] !== 'string' || args[name] === '');
+  }
+
+  /**
+   * 必須引数の欠落を示す -32602 (Invalid params)
+   * error.data の field（最初に欠けた引数）と reason: 'required' で、クライアントが欠けた引数を判別できる
+   */
+  private missingArgumentsError(kind: 'tool' | 'prompt', missing: string[]): Error {
+    return this.invalidParams(`Missing required ${kind} arguments: ${missing.join(', ')}`, {
+      field: missing[0],
+      reason: 'required',
+      missing
+    });
+  }
+
+  /**
+   * 必須引数（空でない文字列）が欠けていれば missingArgumentsError を投げる
+   * prefix は配列要素の引数などでフィールド名の前に付ける位置（例: requests[0].）
+   */
+  private assertRequiredArguments<K extends string>(
+    kind: 'tool' | 'prompt',
+    required: readonly K[],
+    args: Record<string, unknown>,
+    prefix: string = ''
+  ): asserts args is Record<string, unknown> & Record<K, string> {
+    const missing = this.missingArguments(required, args).map(name => `${prefix}${name}`);
+    if (missing.length > 0) {
+      throw this.missingArgumentsError(kind, missing);
+    }
+  }
+
+  /**
+   * キャンセル・タイムアウト後の処理を打ち切る
+   * （複数の判定を順に行う組み込みツールの判定、tools/callの上流への転送の前に確認する）
+   */
+  protected throwIfCancelled(signal?: AbortSignal): void {
+    if (signal?.aborted) {
+      const error = new Error('Request cancelled') as Error & { code: number };
+      error.code = -32800;
+      throw error;
+    }
+  }
+
+  private invalidParams(message: string, data?: Record<string, unknown>): Error {
+    const error = new Error(message) as Error & { code: number; data?: Record<string, unknown> };
+    error.code = -32602;
+    error.data = data;
+    return error;
+  }
+
+  /**
+   * logging/setLevel: クライアント指定のログレベルを適用
+   */
+  protected setLogLevel(level: string): Record<string, never> {
+    const winstonLevel = MCP_LOG_LEVEL_MAP[level];
+    if (!winstonLevel) {
+      throw this.invalidParams(`Unsupported log level: ${level}`);
+    }
+
+    this.logger.setLevel(winstonLevel);
+    this.logger.info(`Log level changed to ${level} (${winstonLevel})`);
+    return {};
+  }
+
+  /**
+   * initializeのparams.capabilitiesを保存し、合意した機能をログに出す
+   * initializeが再送された場合は最新の宣言で置き換える
+   */
+  protected recordClientCapabilities(capabilities: ClientCapabilities | undefined): void {
+    this.clientCapabilities = capabilities ?? {};
+    const features = Object.keys(this.clientCapabilities).filter(feature => this.clientCapabilities[feature] !== undefined);
+    this.logger.info(`Client capabilities: ${features.length > 0 ? features.join(', ') : 'none'}`);
+  }
+
+  /**
+   * クライアントがelicitation/create（要求者への追加情報の確認）に対応しているか
+   */
+  clientSupportsElicitation(): boolean {
+    return (this.clientCapabilities as Record<string, unknown>).elicitation !== undefined;
+  }
+
+  /**
+   * 判定がneeds_clarificationを含む場合、elicitationで回答を集めて再判定する
+   * 非対応のクライアント・回答が得られなかった場合は質問を理由に列挙したINDETERMINATEを返す
+   * 再判定でも追加情報を求められた場合は繰り返さない
+   */
+  protected async resolveClarification(
+    decision: AccessControlResult,
+    reevaluate: (clarifications: Clarification[]) => Promise<AccessControlResult>,
+    requestId?: string | number,
+    signal?: AbortSignal
+  ): Promise<AccessControlResult> {
+    const questions = decision.needsClarification ?? [];
+    if (questions.length === 0) {
+      return decision;
+    }
+    if (!this.clientSupportsElicitation()) {
+      return clarificationRequired(decision, questions, 'needs clarification');
+    }
+
+    let result: ElicitResult;
+    try {
+      result = await this.server.request(
+        { method: ELICITATION_METHOD, params: buildElicitationParams(questions, decision.reason) } as unknown as Parameters<Server['request']>[0],
+        ElicitResultSchema,
+        { relatedRequestId: requestId, signal }
+      );
+    } catch (error) {
+      this.logger.warn('Clarification request failed', error);
+      return clarificationRequired(decision, questions, 'clarification failed');
+    }
+
+    const clarifications = clarificationsFrom(questions, result);
+    if (clarifications.length < questions.length) {
+      const note = { accept: 'clarification incomplete', decline: 'clarification declined', cancel: 'clarification cancelled' }[result.action];
+      return clarificationRequired(decision, questions, note);
+    }
+
+    this.logger.info(`Re-evaluating with ${clarifications.length} clarification answers`);
+    const reevaluated = await reevaluate(clarifications);
+    const remaining = reevaluated.needsClarification ?? [];
+    return remaining.length > 0 ? clarificationRequired(reevaluated, remaining, 'needs clarification') : reevaluated;
+  }
+
+  /**
+   * 初期化ハンドシェイク（initialize → notifications/initialized）の完了前に届いた
+   * initialize・ping以外のリクエストを -32002 (Server not initialized) で拒否する
+   * initializeが再送された場合は notifications/initialized を待つ状態に戻す
+   */
+  protected installHandshakeGuard(transport: Transport): void {
+    const protocolOnMessage = transport.onmessage;
+    if (!protocolOnMessage) {
+      return;
+    }
+
+    transport.onmessage = (...args) => {
+      const message = args[0] as { id?: string | number; method?: string };
+
+      switch (message.method) {
+        case 'initialize':
+          if (this.handshakeState === 'initialized') {
+            this.logger.warn('initialize received again; waiting for notifications/initialized');
+          }
+          this.handshakeState = 'initializing';
+          break;
+        case 'notifications/initialized':
+          if (this.handshakeState === 'initializing') {
+            this.handshakeState = 'initialized';
+          }
+          break;
+        case 'ping':
+        case undefined:
+          break;
+        default:
+          if (this.handshakeState !== 'initialized' && message.id !== undefined) {
+            this.logger.warn(`Rejecting ${message.method} before initialization completed`);
+            transport.send({
+              jsonrpc: '2.0',
+              id: message.id,
+              error: { code: -32002, message: 'Server not initialized' }
+            }).catch(error => {
+              this.logger.error('Failed to send error response', error);
+            });
+            return;
+          }
+      }
+
+      protocolOnMessage(...args);
+    };
+  }
+
+  /**
+   * shutdownリクエストを受けたときの終了処理を設定
+   */
+  setShutdownHandler(handler: (reason: string) => void): void {
+    this.shutdownHandler = handler;
+  }
+
+  /**
+   * shutdown: 書き込み待ちの監査ログを反映してから空の結果を返す
+   * 実際の終了は応答の送信完了後（installShutdownAcknowledgement）
+   */
+  protected async handleShutdownRequest(requestId: string | number | undefined): Promise<Record<string, never>> {
+    this.logger.info('Shutdown requested by client');
+    if (this.decisionAuditLog) {
+      try {
+        await this.decisionAuditLog.flush();
+      } catch (error) {
+        this.logger.error('Failed to flush decision audit log', error);
+      }
+    }
+    this.pendingShutdownId = requestId;
+    return {};
+  }
+
+  /**
+   * shutdownへの応答をトランスポートが書き終えてから終了処理を呼ぶ
+   */
+  protected installShutdownAcknowledgement(transport: Transport): void {
+    const send = transport.send.bind(transport);
+    transport.send = async (message, ...rest) => {
+      await send(message, ...rest);
+      const response = message as { id?: string | number; method?: string };
+      if (!response.method && response.id !== undefined && response.id === this.pendingShutdownId) {
+        this.pendingShutdownId = undefined;
+        this.shutdownHandler?.('shutdown request');
+      }
+    };
+  }
+
+  /**
+   * JSON-RPCメッセージ（文字列）をプロセス内で処理し、応答を文字列で返す（通知の場合はnull）
+   * stdio・HTTPと同じハンドラー・ハンドシェイク検査を通るため、テストや組み込み利用から直接呼び出せる
+   * serverは1つのトランスポートにしか接続できないため、start() 済みのプロキシでは使わないこと
+   */
+  async dispatchString(input: string): Promise<string | null> {
+    if (!this.dispatchTransport) {
+      this.dispatchTransport = (async () => {
+        const transport = new InMemoryDispatchTransport(this.config.mcpProxy?.strictProtocol ?? false);
+        await this.server.connect(transport);
+        this.installHandshakeGuard(transport);
+        this.installMetaEcho(transport);
+        this.installShutdownAcknowledgement(transport);
+        this.installResponseSizeLimit(transport);
+        this.installRequestTracing(transport);
+        this.installRequestTiming(transport);
+        return transport;
+      })();
+    }
+    const transport = await this.dispatchTransport;
+    return transport.dispatch(input);
+  }
+
+  /**
+   * リクエストのparams._metaを応答のresult._metaとして返す（クライアント側の相関用）
+   * 応答自身が_metaを持つ場合はその値を優先してマージする
+   */
+  protected installMetaEcho(transport: Transport): void {
+    const protocolOnMessage = transport.onmessage;
+    if (!protocolOnMessage) {
+      return;
+    }
+
+    const pendingMeta = new Map<string | number, Record<string, unknown>>();
+
+    transport.onmessage = (...args) => {
+      const message = args[0] as { id?: string | number; method?: string; params?: { _meta?: unknown } };
+      const meta = message.params?._meta;
+      if (message.method && message.id !== undefined && meta && typeof meta === 'object') {
+        pendingMeta.set(message.id, meta as Record<string, unknown>);
+      }
+      protocolOnMessage(...args);
+    };
+
+    const send = transport.send.bind(transport);
+    transport.send = (message, ...rest) => {
+      const response = message as { id?: string | number; method?: string; result?: Record<string, any> };
+      if (!response.method && response.id !== undefined && pendingMeta.has(response.id)) {
+        const meta = pendingMeta.get(response.id)!;
+        pendingMeta.delete(response.id);
+        if (response.result) {
+          message = {
+            ...response,
+            result: { ...response.result, _meta: { ...meta, ...response.result._meta } }
+          } as typeof message;
+        }
+      }
+      return send(message, ...rest);
+    };
+  }
+
+  /**
+   * 送信する応答のサイズを --max-response-bytes 以内に収める（テキストだけを切り詰める）
+   * 他のsendの差し替え（_metaのエコーなど）の後に入れ、最終的に送る応答で計測する
+   */
+  protected installResponseSizeLimit(transport: Transport): void {
+    const maxBytes = this.config.mcpProxy?.maxResponseBytes ?? SERVER.DEFAULT_MAX_RESPONSE_BYTES;
+    if (maxBytes <= 0) {
+      return;
+    }
+
+    const send = transport.send.bind(transport);
+    transport.send = (message, ...rest) => {
+      const limited = limitResponseSize(message, maxBytes);
+      if (limited !== message) {
+        this.logger.warn(`Response truncated to ${maxBytes} bytes`, { id: (message as { id?: string | number }).id ?? null });
+      }
+      return send(limited, ...rest);
+    };
+  }
+
+  /**
+   * リクエストの受信から応答の送信までの時間をメソッドごとに記録する（statsツール・/metrics）
+   * --slow-request-ms を超えたリクエストはメソッドとidを付けて警告する
+   * 応答を返さないまま取り消されたリクエスト（notifications/cancelled）は記録しない
+   * ハンドラーのないメソッドは other にまとめる（任意のメソッド名で集計の種類を増やされないため）
+   */
+  protected installRequestTiming(transport: Transport): void {
+    const protocolOnMessage = transport.onmessage;
+    if (!protocolOnMessage) {
+      return;
+    }
+
+    const slowRequestMs = this.config.mcpProxy?.slowRequestMs ?? 0;
+    const pending = new Map<string | number, { method: string; startedAt: number }>();
+    transport.onmessage = (...args) => {
+      const message = args[0] as { id?: string | number; method?: string; params?: { requestId?: string | number } };
+      if (message.method && message.id !== undefined) {
+        const method = this.hasRequestHandler(message.method) ? message.method : 'other';
+        pending.set(message.id, { method, startedAt: this.sources.clock.now() });
+      } else if (message.method === 'notifications/cancelled' && message.params?.requestId !== undefined) {
+        pending.delete(message.params.requestId);
+      }
+      protocolOnMessage(...args);
+    };
+
+    const send = transport.send.bind(transport);
+    transport.send = (message, ...rest) => {
+      const response = message as { id?: string | number; method?: string };
+      const started = !response.method && response.id !== undefined ? pending.get(response.id) : undefined;
+      if (started && response.id !== undefined) {
+        pending.delete(response.id);
+        const durationMs = this.sources.clock.now() - started.startedAt;
+        this.stats.recordRequestDuration(started.method, durationMs);
+        if (slowRequestMs > 0 && durationMs > slowRequestMs) {
+          this.logger.warn(`Slow request: ${started.method} took ${durationMs}ms`, {
+            method: started.method,
+            id: response.id,
+            durationMs,
+            slowRequestMs
+          });
+        }
+      }
+      return send(message, ...rest);
+    };
+  }
+
+  /**
+   * ハンドラーを登録したメソッドか（SDKの初期化・pingの既定ハンドラーを含む）
+   */
+  protected hasRequestHandler(method: string): boolean {
+    const handlers = (this.server as unknown as { _requestHandlers?: Map<string, unknown> })._requestHandlers;
+    return handlers?.has(method) ?? false;
+  }
+
+  /**
+   * 受信メッセージごとにトレース情報を設定し、処理中のログにidとメソッドを付与する
+   * server.connect()の後に呼ぶ（Protocolが設定したonmessageを包む）
+   */
+  protected installRequestTracing(transport: Transport): void {
+    const protocolOnMessage = transport.onmessage;
+    if (!protocolOnMessage) {
+      return;
+    }
+
+    transport.onmessage = (...args) => {
+      // HTTPではExpressのハンドラーが設定したX-Request-Idを引き継ぐ
+      const trace = createRequestTrace(
+        args[0] as { id?: string | number; method?: string },
+        getRequestTrace()?.httpRequestId,
+        () => this.sources.rng.uuid()
+      );
+      if (trace.method) {
+        this.stats.recordRequest(trace.method);
+      }
+      runWithRequestTrace(trace, () => {
+        this.logger.debug('Incoming JSON-RPC message');
+        protocolOnMessage(...args);
+      });
+    };
+  }
+
+  /**
+   * 判定結果を監査ログに追記（statsの判定カウンター・判定履歴DBもここで更新する）
+   * 監査ログの書き込みに失敗した場合は例外を投げ、リクエストを続行させない（フェイルクローズ）
+   * 判定履歴DBは検索用のため、書き込みに失敗してもリクエストは続行する
+   * Webhook通知は応答を待たずに送る
+   */
+  protected async recordDecisionAudit(
+    context: DecisionContext,
+    decision: Pick<PolicyDecision, 'decision' | 'confidence' | 'metadata'> & { reason?: string },
+    requestId?: string | number,
+    provenance?: DecisionProvenance
+  ): Promise<void> {
+    this.stats.recordDecision(decision.decision);
+    
+    const override = decision.metadata?.agentOverride;
+    const fastPath = decision.metadata?.fastPath;
+    // キャッシュした判定はAIを呼び出していないため推定トークン数を記録しない
+    const promptTokens = decision.metadata?.cached === true ? undefined : decision.metadata?.estimatedPromptTokens;
+    const completionTokens = decision.metadata?.estimatedCompletionTokens;
+    const prompt = decision.metadata?.cached === true ? undefined : decision.metadata?.renderedPrompt;
+    const httpRequestId = getRequestTrace()?.httpRequestId;
+    const record: DecisionAuditRecord = {
+      timestamp: new Date().toISOString(),
+      requestId: requestId ?? null,
+      agent: context.agent,
+      action: context.action,
+      resource: context.resource,
+      decision: decision.decision,
+      confidence: decision.confidence,
+      ...(context.originalAction !== undefined ? { originalAction: context.originalAction } : {}),
+      ...(context.originalResource !== undefined ? { originalResource: context.originalResource } : {}),
+      ...(typeof override === 'string' ? { override } : {}),
+      ...(fastPath === 'allowlist' || fastPath === 'denylist' ? { fastPath } : {}),
+      ...(typeof promptTokens === 'number' && typeof completionTokens === 'number'
+        ? { estimatedTokens: { prompt: promptTokens, completion: completionTokens } }
+        : {}),
+      ...(typeof prompt === 'string' ? { prompt } : {}),
+      ...(httpRequestId !== undefined ? { httpRequestId } : {}),
+      ...(provenance ? { provenance } : {})
+    };
+    
+    await this.recordDecisionHistory(record);
+    this.decisionWebhook?.notify({
+      agent: record.agent,
+      action: record.action,
+      resource: record.resource,
+      decision: record.decision,
+      reason: decision.reason ?? '',
+      timestamp: record.timestamp
+    });
+    
+    if (!this.decisionAuditLog) {
+      return;
+    }
+
+    // 再試行待ちのレコードがある間は、チェーンの順序を保つため新しいレコードも後ろに並べる
+    if (this.auditRetryBuffer && this.auditRetryBuffer.size() > 0) {
+      this.bufferAuditRecord(record, 'earlier records are waiting for a retry');
+      return;
+    }
+
+    try {
+      await this.decisionAuditLog.append(record);
+    } catch (error) {
+      this.handleAuditWriteFailure(record, error);
+    }
+  }
+
+  /**
+   * 監査ログ書き込み失敗時の扱いを設定し、fail-closed以外ではその代償をログに残す
+   */
+  private configureAuditFailureMode(mode: AuditFailureMode): void {
+    this.auditFailureMode = mode;
+    if (mode === 'fail-open') {
+      this.logger.warn('Audit failure mode fail-open: decisions are allowed even when the audit log cannot be written (they are logged as UNAUDITED)');
+    } else if (mode === 'buffer') {
+      const log = this.decisionAuditLog!;
+      this.auditRetryBuffer = new AuditRetryBuffer(
+        record => log.append(record),
+        AUDIT.FAILURE_BUFFER.MAX_RECORDS,
+        AUDIT.FAILURE_BUFFER.RETRY_INTERVAL_MS
+      );
+      this.logger.warn(`Audit failure mode buffer: up to ${AUDIT.FAILURE_BUFFER.MAX_RECORDS} unwritten records are kept in memory and retried (lost if the process exits)`);
+    } else {
+      this.logger.info('Audit failure mode fail-closed: requests are denied when the audit log cannot be written');
+    }
+  }
+
+  private handleAuditWriteFailure(record: DecisionAuditRecord, error: unknown): void {
+    const message = error instanceof Error ? error.message : 'Unknown error';
+    if (this.auditFailureMode === 'fail-open') {
+      this.unauditedDecisions++;
+      this.logger.critical(`Decision audit log write failed - allowing the decision UNAUDITED (fail-open): ${message}`, record);
+      return;
+    }
+    if (this.auditFailureMode === 'buffer') {
+      this.bufferAuditRecord(record, message);
+      return;
+    }
+    this.logger.error('Failed to write decision audit log - denying request', error);
+    throw new Error(`Audit log write failed: ${message}`);
+  }
+
+  /**
+   * 監査レコードを再試行キューに入れる（キューが上限に達していればfail-closedにする）
+   */
+  private bufferAuditRecord(record: DecisionAuditRecord, reason: string): void {
+    const buffer = this.auditRetryBuffer!;
+    if (buffer.push(record)) {
+      this.logger.critical(`Decision audit log write failed - buffered for retry (${buffer.size()}/${AUDIT.FAILURE_BUFFER.MAX_RECORDS}): ${reason}`);
+      return;
+    }
+    this.logger.critical(`Decision audit retry buffer is full (${AUDIT.FAILURE_BUFFER.MAX_RECORDS} records) - denying request`);
+    throw new Error(`Audit log write failed and the retry buffer is full: ${reason}`);
+  }
+
+  private async recordDecisionHistory(record: DecisionAuditRecord): Promise<void> {
+    if (!this.decisionHistory) {
+      return;
+    }
+
+    try {
+      (await this.decisionHistory).insert(record);
+    } catch (error) {
+      this.logger.error('Failed to write decision history', error);
+    }
+  }
+
+  /**
+   * 書き込み待ちの監査レコードをすべてディスクに反映し、判定履歴DBを閉じる（停止時）
+   */
+  protected async flushAuditState(): Promise<void> {
+    if (this.decisionWebhook) {
+      await this.decisionWebhook.flush();
+    }
+
+    if (this.auditRetryBuffer) {
+      this.auditRetryBuffer.stop();
+      await this.auditRetryBuffer.drain();
+      if (this.auditRetryBuffer.size() > 0) {
+        this.logger.critical(`${this.auditRetryBuffer.size()} buffered decision audit records could not be written before shutdown`);
+      }
+    }
+
+    if (this.decisionAuditLog) {
+      try {
+        await this.decisionAuditLog.flush();
+        this.logger.info('Decision audit log flushed');
+      } catch (error) {
+        this.logger.error('Failed to flush decision audit log', error);
+      }
+    }
+
+    if (this.decisionHistory) {
+      try {
+        (await this.decisionHistory).close();
+        this.logger.info('Decision history database closed');
+      } catch (error) {
+        this.logger.error('Failed to close decision history database', error);
+      }
+    }
+  }
+
   /**
    * パフォーマンス統計の取得（共通）
    */
@@ -0,0 +1,73 @@
+// ============================================================================
+// AEGIS - 判定に必要な追加情報の確認（elicitation/create）
+// AIの判定が needs_clarification（要求者への質問）を含む場合、
+// elicitationに対応したクライアントには質問を送り、回答をコンテキストに加えて再判定する
+// 非対応のクライアントには質問を理由に列挙したINDETERMINATEを返す
+// ============================================================================
+
+import { z } from 'zod';
+import type { PolicyDecision } from '../types/index.js';
+import { markIndeterminateSource } from '../policy/indeterminate-resolution.js';
+
+export const ELICITATION_METHOD = 'elicitation/create';
+
+export const ElicitResultSchema = z.object({
+  action: z.enum(['accept', 'decline', 'cancel']),
+  content: z.record(z.unknown()).optional()
+}).passthrough();
+
+export type ElicitResult = z.infer<typeof ElicitResultSchema>;
+
+export interface Clarification {
+  question: string;
+  answer: string;
+}
+
+// 質問ごとの回答フィールド名（answer_1, answer_2, ...）
+function answerField(index: number): string {
+  return `answer_${index + 1}`;
+}
+
+/**
+ * 質問の一覧からelicitation/createのparams（文字列フィールドのみのフラットなスキーマ）を作る
+ */
+export function buildElicitationParams(questions: string[], reason: string): {
+  message: string;
+  requestedSchema: { type: 'object'; properties: Record<string, { type: 'string'; title: string }>; required: string[] };
+} {
+  const properties: Record<string, { type: 'string'; title: string }> = {};
+  questions.forEach((question, index) => {
+    properties[answerField(index)] = { type: 'string', title: question };
+  });
+  return {
+    message: `AEGIS needs more information to evaluate this request: ${reason}`,
+    requestedSchema: { type: 'object', properties, required: Object.keys(properties) }
+  };
+}
+
+/**
+ * 受け入れられた回答を質問と対応付ける（未回答・空の回答は含めない）
+ */
+export function clarificationsFrom(questions: string[], result: ElicitResult): Clarification[] {
+  if (result.action !== 'accept' || !result.content) {
+    return [];
+  }
+  const content = result.content;
+  return questions.flatMap((question, index) => {
+    const answer = content[answerField(index)];
+    return answer === undefined || answer === null || String(answer).trim() === ''
+      ? []
+      : [{ question, answer: String(answer) }];
+  });
+}
+
+/**
+ * 質問を理由に列挙したINDETERMINATEにする（由来はclarification）
+ */
+export function clarificationRequired<T extends PolicyDecision>(decision: T, questions: string[], note: string): T {
+  return markIndeterminateSource({
+    ...decision,
+    decision: 'INDETERMINATE',
+    reason: `${decision.reason} [${note}: ${questions.join(' / ')}]`
+  }, 'clarification');
+}
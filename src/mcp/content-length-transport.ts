@@ -0,0 +1,185 @@
+// ============================================================================
+// AEGIS - Content-Lengthフレーミングのstdioトランスポート（--framing lsp）
+// LSPと同じ「Content-Length: N\r\n\r\n<本文>」形式でメッセージを読み書きする
+// SDKのStdioServerTransport（改行区切り）と同じTransportを実装するため、
+// Serverへの接続以降の処理（ハンドラー・エラー応答）は改行区切りと共通
+// ============================================================================
+
+import type { Readable, Writable } from 'stream';
+import type { Transport } from '@modelcontextprotocol/sdk/shared/transport.js';
+import { JSONRPCMessageSchema } from '@modelcontextprotocol/sdk/types.js';
+import type { JSONRPCMessage } from '@modelcontextprotocol/sdk/types.js';
+
+export const FRAMINGS = ['newline', 'lsp'] as const;
+export type Framing = typeof FRAMINGS[number];
+
+export function isFraming(value: unknown): value is Framing {
+  return typeof value === 'string' && (FRAMINGS as readonly string[]).includes(value);
+}
+
+const HEADER_DELIMITER = Buffer.from('\r\n\r\n');
+// ヘッダー部の最大サイズ（区切りが来ないまま溜め込まない）
+const MAX_HEADER_BYTES = 8 * 1024;
+
+export type Frame =
+  | { kind: 'message'; body: string }
+  | { kind: 'oversize'; length: number }
+  | { kind: 'invalid'; reason: string };
+
+/**
+ * 受信データからContent-Lengthで区切られたフレームを順に取り出す
+ * 上限を超えるフレームは本文を読み飛ばして oversize として1回だけ返す
+ */
+export class ContentLengthReader {
+  private buffer = Buffer.alloc(0);
+  private skipBytes = 0;
+  private maxMessageBytes: number;
+
+  constructor(maxMessageBytes: number) {
+    this.maxMessageBytes = maxMessageBytes;
+  }
+
+  append(chunk: Buffer): void {
+    this.buffer = this.buffer.length === 0 ? chunk : Buffer.concat([this.buffer, chunk]);
+  }
+
+  /**
+   * 次のフレーム（まだ揃っていなければnull）
+   */
+  read(): Frame | null {
+    this.discardSkipped();
+    if (this.skipBytes > 0) {
+      return null;
+    }
+
+    const headerEnd = this.buffer.indexOf(HEADER_DELIMITER);
+    if (headerEnd === -1) {
+      if (this.buffer.length > MAX_HEADER_BYTES) {
+        this.buffer = Buffer.alloc(0);
+        return { kind: 'invalid', reason: 'Message header too large' };
+      }
+      return null;
+    }
+
+    const bodyStart = headerEnd + HEADER_DELIMITER.length;
+    const match = this.buffer.subarray(0, headerEnd).toString('ascii').split('\r\n')
+      .map(header => header.match(/^content-length\s*:\s*(\d+)\s*$/i))
+      .find(Boolean);
+    if (!match) {
+      this.buffer = this.buffer.subarray(bodyStart);
+      return { kind: 'invalid', reason: 'Missing Content-Length header' };
+    }
+
+    const length = Number(match[1]);
+    if (length > this.maxMessageBytes) {
+      this.buffer = this.buffer.subarray(bodyStart);
+      this.skipBytes = length;
+      this.discardSkipped();
+      return { kind: 'oversize', length };
+    }
+
+    if (this.buffer.length < bodyStart + length) {
+      return null;
+    }
+    const body = this.buffer.subarray(bodyStart, bodyStart + length).toString('utf8');
+    this.buffer = this.buffer.subarray(bodyStart + length);
+    return { kind: 'message', body };
+  }
+
+  private discardSkipped(): void {
+    const count = Math.min(this.skipBytes, this.buffer.length);
+    this.buffer = this.buffer.subarray(count);
+    this.skipBytes -= count;
+  }
+}
+
+export interface ContentLengthTransportOptions {
+  maxMessageBytes: number;
+  // 上限を超えるメッセージを読み飛ばしたときに1件につき1回呼ばれる
+  onOversize?: (limit: number) => void;
+  // 上限内のメッセージ本文ごとに呼ばれ、falseを返したメッセージは渡さない（文字列を返した場合はその内容に置き換える）
+  acceptMessage?: (body: string) => boolean | string;
+}
+
+export class ContentLengthStdioTransport implements Transport {
+  onclose?: () => void;
+  onerror?: (error: Error) => void;
+  onmessage?: (message: JSONRPCMessage) => void;
+
+  private stdin: Readable;
+  private stdout: Writable;
+  private options: ContentLengthTransportOptions;
+  private reader: ContentLengthReader;
+  private started = false;
+
+  constructor(stdin: Readable, stdout: Writable, options: ContentLengthTransportOptions) {
+    this.stdin = stdin;
+    this.stdout = stdout;
+    this.options = options;
+    this.reader = new ContentLengthReader(options.maxMessageBytes);
+  }
+
+  async start(): Promise<void> {
+    if (this.started) {
+      throw new Error('ContentLengthStdioTransport already started');
+    }
+    this.started = true;
+    this.stdin.on('data', this.handleData);
+    this.stdin.on('error', this.handleError);
+  }
+
+  async close(): Promise<void> {
+    this.stdin.off('data', this.handleData);
+    this.stdin.off('error', this.handleError);
+    if (this.stdin.listenerCount('data') === 0) {
+      this.stdin.pause();
+    }
+    this.onclose?.();
+  }
+
+  send(message: JSONRPCMessage): Promise<void> {
+    return new Promise(resolve => {
+      const json = JSON.stringify(message);
+      const frame = `Content-Length: ${Buffer.byteLength(json, 'utf8')}\r\n\r\n${json}`;
+      if (this.stdout.write(frame)) {
+        resolve();
+      } else {
+        this.stdout.once('drain', resolve);
+      }
+    });
+  }
+
+  private handleData = (chunk: Buffer | string): void => {
+    this.reader.append(typeof chunk === 'string' ? Buffer.from(chunk) : chunk);
+
+    for (let frame = this.reader.read(); frame; frame = this.reader.read()) {
+      if (frame.kind === 'oversize') {
+        this.options.onOversize?.(this.options.maxMessageBytes);
+        continue;
+      }
+      if (frame.kind === 'invalid') {
+        // 改行区切りでJSONとして読めない行と同じく -32700 の対象にする
+        this.onerror?.(new SyntaxError(frame.reason));
+        continue;
+      }
+      const accepted = this.options.acceptMessage ? this.options.acceptMessage(frame.body) : true;
+      if (accepted === false) {
+        continue;
+      }
+      const body = typeof accepted === 'string' ? accepted : frame.body;
+
+      let message: JSONRPCMessage;
+      try {
+        message = JSONRPCMessageSchema.parse(JSON.parse(body));
+      } catch (error) {
+        this.onerror?.(error as Error);
+        continue;
+      }
+      this.onmessage?.(message);
+    }
+  };
+
+  private handleError = (error: Error): void => {
+    this.onerror?.(error);
+  };
+}
@@ -0,0 +1,180 @@
+// ============================================================================
+// AEGIS - HTTPレスポンスのgzip圧縮
+// Accept-Encoding: gzip のクライアントにはJSONレスポンスとSSEストリームを圧縮して返す
+// JSONは本文をまとめて非同期に圧縮し（イベントループを止めない）、SSEはイベントごとにフラッシュして
+// ストリーミングを妨げないようにする。それ以外のレスポンスはそのまま流す
+// ============================================================================
+
+import { constants, createGzip, gzip, type Gzip } from 'zlib';
+import type { Request, Response, NextFunction, RequestHandler } from 'express';
+
+type HeaderArgs = [number, ...unknown[]];
+
+function acceptsGzip(header: string | string[] | undefined): boolean {
+  const value = Array.isArray(header) ? header.join(',') : header ?? '';
+  return value.split(',').some(entry => {
+    const [coding, ...params] = entry.trim().toLowerCase().split(';');
+    const q = params.map(param => param.trim()).find(param => param.startsWith('q='));
+    return (coding === 'gzip' || coding === '*') && (q === undefined || Number(q.slice(2)) > 0);
+  });
+}
+
+// writeHead(status, headers) / writeHead(status, message, headers) のheadersを取り出す
+function headersOf(args: HeaderArgs): Record<string, unknown> | undefined {
+  const last = args[args.length - 1];
+  return last && typeof last === 'object' && !Array.isArray(last) ? last as Record<string, unknown> : undefined;
+}
+
+function headerFrom(res: Response, args: HeaderArgs | null, name: string): unknown {
+  const headers = (args && headersOf(args)) ?? {};
+  const key = Object.keys(headers).find(candidate => candidate.toLowerCase() === name);
+  return key !== undefined ? headers[key] : res.getHeader(name);
+}
+
+// writeHeadのheadersはsetHeaderより優先されるため、圧縮前の長さをどちらからも取り除く
+function removeContentLength(res: Response, args: HeaderArgs | null): void {
+  const headers = (args && headersOf(args)) ?? {};
+  for (const key of Object.keys(headers)) {
+    if (key.toLowerCase() === 'content-length') {
+      delete headers[key];
+    }
+  }
+  res.removeHeader('Content-Length');
+}
+
+/**
+ * JSONレスポンスとSSEストリームをgzip圧縮するミドルウェア
+ * JSONの本文が minBytes 未満のときは圧縮のオーバーヘッドを避けてそのまま返す
+ * SSEは長さが事前に分からないため、minBytes に関係なく圧縮する
+ */
+export function gzipResponses(minBytes: number): RequestHandler {
+  return (req: Request, res: Response, next: NextFunction) => {
+    if (!acceptsGzip(req.headers['accept-encoding'])) {
+      return next();
+    }
+
+    const writeHead = res.writeHead.bind(res) as (...args: HeaderArgs) => Response;
+    const write = res.write.bind(res) as (...args: unknown[]) => boolean;
+    const end = res.end.bind(res) as (...args: unknown[]) => Response;
+
+    let headArgs: HeaderArgs | null = null;
+    let mode: 'undecided' | 'buffer' | 'stream' | 'passthrough' = 'undecided';
+    const chunks: Buffer[] = [];
+    // SSEの圧縮器（streamモードのときのみ作る）
+    let stream: Gzip | null = null;
+
+    const toBuffer = (chunk: unknown, encoding?: unknown): Buffer | null => {
+      if (chunk === undefined || chunk === null || typeof chunk === 'function') {
+        return null;
+      }
+      return Buffer.isBuffer(chunk) ? chunk : Buffer.from(chunk as string, encoding as BufferEncoding);
+    };
+
+    const decide = () => {
+      if (mode === 'undecided') {
+        const contentType = String(headerFrom(res, headArgs, 'content-type') ?? '');
+        const compressible = headerFrom(res, headArgs, 'content-encoding') === undefined && req.method !== 'HEAD';
+        if (compressible && contentType.includes('application/json')) {
+          mode = 'buffer';
+        } else if (compressible && contentType.includes('text/event-stream')) {
+          mode = 'stream';
+          res.setHeader('Vary', 'Accept-Encoding');
+          res.setHeader('Content-Encoding', 'gzip');
+          removeContentLength(res, headArgs);
+          stream = createGzip();
+          stream.on('data', (data: Buffer) => write(data));
+          stream.on('end', () => end());
+          stream.on('error', error => res.destroy(error));
+        } else {
+          mode = 'passthrough';
+        }
+      }
+      if ((mode === 'passthrough' || mode === 'stream') && headArgs) {
+        writeHead(...headArgs);
+        headArgs = null;
+      }
+    };
+
+    // 圧縮済み（または圧縮しない）本文をまとめて送る
+    const sendBody = (body: Buffer, callback: unknown) => {
+      res.setHeader('Content-Length', body.length);
+      if (headArgs) {
+        writeHead(...headArgs);
+      }
+      end(body, callback);
+    };
+
+    res.writeHead = ((...args: HeaderArgs) => {
+      headArgs = args;
+      return res;
+    }) as Response['writeHead'];
+
+    // SSEは writeHead().flushHeaders() でストリームを開始するため、ここで判定してヘッダーを送る
+    const flushHeaders = res.flushHeaders.bind(res);
+    res.flushHeaders = () => {
+      decide();
+      if (mode === 'passthrough' || mode === 'stream') {
+        flushHeaders();
+      }
+    };
+
+    res.write = ((chunk: unknown, ...rest: unknown[]) => {
+      decide();
+      if (mode === 'passthrough') {
+        return write(chunk, ...rest);
+      }
+      const buffer = toBuffer(chunk, typeof rest[0] === 'string' ? rest[0] : undefined);
+      if (stream) {
+        // イベントを圧縮器に溜めたままにしないよう、書き込みごとにフラッシュする
+        if (buffer) {
+          stream.write(buffer);
+          stream.flush(constants.Z_SYNC_FLUSH);
+        }
+        return true;
+      }
+      if (buffer) {
+        chunks.push(buffer);
+      }
+      return true;
+    }) as Response['write'];
+
+    res.end = ((chunk?: unknown, ...rest: unknown[]) => {
+      decide();
+      if (mode === 'passthrough') {
+        return end(chunk, ...rest);
+      }
+
+      const buffer = toBuffer(chunk, typeof rest[0] === 'string' ? rest[0] : undefined);
+      const callback = [chunk, ...rest].find(arg => typeof arg === 'function');
+      if (stream) {
+        if (typeof callback === 'function') {
+          res.once('finish', callback as () => void);
+        }
+        stream.end(buffer ?? undefined);
+        return res;
+      }
+
+      if (buffer) {
+        chunks.push(buffer);
+      }
+      const body = Buffer.concat(chunks);
+
+      res.setHeader('Vary', 'Accept-Encoding');
+      if (body.length < minBytes) {
+        sendBody(body, callback);
+        return res;
+      }
+      gzip(body, (error, compressed) => {
+        // 圧縮に失敗した場合は元の本文をそのまま返す
+        if (!error) {
+          res.setHeader('Content-Encoding', 'gzip');
+          removeContentLength(res, headArgs);
+        }
+        sendBody(error ? body : compressed, callback);
+      });
+      return res;
+    }) as Response['end'];
+
+    next();
+  };
+}
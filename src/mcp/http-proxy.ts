@@ -11,7 +11,14 @@ import {
   CallToolRequestSchema, 
   ListResourcesRequestSchema,
   ListToolsRequestSchema,
-  ReadResourceRequestSchema 
+  ReadResourceRequestSchema,
+  SetLevelRequestSchema,
+  ListPromptsRequestSchema,
+  GetPromptRequestSchema,
+  CompleteRequestSchema,
+  ListResourceTemplatesRequestSchema,
+  SubscribeRequestSchema,
+  UnsubscribeRequestSchema
 } from '@modelcontextprotocol/sdk/types.js';
 import express from 'express';
 import type { 
@@ -26,7 +33,13 @@ import { AIJudgmentEngine } from '../ai/judgment-engine.js';
 import { Logger } from '../utils/logger.js';
 import { createAuditEndpoints } from '../api/audit-endpoints.js';
 import { StdioRouter, MCPServerConfig } from './stdio-router.js';
-import { MCPPolicyProxyBase } from './base-proxy.js';
+import { MCPPolicyProxyBase, RESOURCE_TEMPLATES } from './base-proxy.js';
+import { gzipResponses } from './gzip-response.js';
+import { PROMETHEUS_CONTENT_TYPE, formatPrometheusMetrics } from './prometheus-metrics.js';
+import { findUnknownJsonRpcField, removeUnknownJsonRpcFields } from './jsonrpc-shape.js';
+import type { Clarification } from './clarification.js';
+import { getRequestTrace, resolveHttpRequestId, runWithRequestTrace } from '../utils/request-trace.js';
+import type { RuntimeSources } from '../utils/rng.js';
 import { 
   TimeBasedEnricher,
   AgentInfoEnricher,
@@ -49,9 +62,12 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
   // stdio上流サーバー管理（ブリッジモード）
   private stdioRouter?: StdioRouter;
   private bridgeMode: boolean = false;
+
+  // MCPサーバーの接続（initializeを受け付けられる状態）まで完了したか
+  private ready = false;
   
-  constructor(config: AEGISConfig, logger: Logger, judgmentEngine: AIJudgmentEngine | null) {
-    super(config, logger, judgmentEngine);
+  constructor(config: AEGISConfig, logger: Logger, judgmentEngine: AIJudgmentEngine | null, sources?: RuntimeSources) {
+    super(config, logger, judgmentEngine, sources);
     
     // Express アプリ作成
     this.app = express();
@@ -62,15 +78,40 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
   }
 
   private setupMiddleware(): void {
-    this.app.use(express.json());
+    const maxRequestBytes = this.config.mcpProxy?.maxRequestBytes ?? SERVER.DEFAULT_MAX_REQUEST_BYTES;
+    // Accept-Encoding: gzip のクライアントには一定サイズ以上のJSONレスポンスとSSEストリームを圧縮して返す
+    this.app.use(gzipResponses(this.config.mcpProxy?.gzipMinBytes ?? SERVER.DEFAULT_GZIP_MIN_BYTES));
+    this.app.use(express.json({ limit: maxRequestBytes }));
+    // 上限超過のボディはJSON-RPCの -32600 (Invalid Request) で応答
+    this.app.use((err: any, req: express.Request, res: express.Response, next: express.NextFunction) => {
+      if (err?.type === 'entity.too.large') {
+        this.logger.warn(`Rejected request body larger than ${maxRequestBytes} bytes`, { path: req.path });
+        return res.status(413).json({
+          jsonrpc: '2.0',
+          id: null,
+          error: {
+            code: -32600,
+            message: 'Request too large',
+            data: { maxRequestBytes }
+          }
+        });
+      }
+      next(err);
+    });
     this.app.use((req, res, next) => {
       // CORS 設定
       res.header('Access-Control-Allow-Origin', '*');
       res.header('Access-Control-Allow-Methods', 'GET, POST, PUT, DELETE, OPTIONS');
-      res.header('Access-Control-Allow-Headers', 'Origin, X-Requested-With, Content-Type, Accept, Authorization, X-Agent-ID, X-Agent-Type, X-Agent-Metadata, mcp-session-id');
+      res.header('Access-Control-Allow-Headers', 'Origin, X-Requested-With, Content-Type, Accept, Authorization, X-Agent-ID, X-Agent-Type, X-Agent-Metadata, X-Request-Id, mcp-session-id');
+      res.header('Access-Control-Expose-Headers', 'X-Request-Id');
+
+      // リバースプロキシ・ゲートウェイのX-Request-Idを引き継ぎ（なければ生成し）、応答ヘッダーで返す
+      const httpRequestId = resolveHttpRequestId(req.headers['x-request-id'], () => this.sources.rng.uuid());
+      req.headers['x-request-id'] = httpRequestId;
+      res.header('X-Request-Id', httpRequestId);
       
       // リクエストコンテキストを保存
-      const sessionId = (Array.isArray(req.headers['mcp-session-id']) ? req.headers['mcp-session-id'][0] : req.headers['mcp-session-id']) || uuidv4();
+      const sessionId = (Array.isArray(req.headers['mcp-session-id']) ? req.headers['mcp-session-id'][0] : req.headers['mcp-session-id']) || this.sources.rng.uuid();
       this.requestContext.set(sessionId, {
         headers: req.headers,
         sessionId,
@@ -112,6 +153,22 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
     this.logger.info('Context enrichers registered successfully');
   }
 
+  /**
+   * 重複排除はセッションのX-Agent-IDごとに行う（判定は要求元エージェントによって変わるため）
+   */
+  protected deduplicationAgent(extra: { sessionId?: string } | undefined): string | null {
+    const headers = this.requestContext.get(extra?.sessionId || 'http-client')?.headers as Record<string, unknown> | undefined;
+    const agentId = headers?.['x-agent-id'] ?? headers?.['X-Agent-ID'];
+    return typeof agentId === 'string' ? agentId : null;
+  }
+
+  /**
+   * X-Agent-IDはクライアントが自由に指定できるため、force-permit は --allow-http-force-permit を指定した場合のみ適用する
+   */
+  protected forcePermitAllowed(): boolean {
+    return this.config.mcpProxy?.allowHttpForcePermit === true;
+  }
+
   protected setupHandlers(): void {
     // リソース読み取りハンドラー
     this.server.setRequestHandler(ReadResourceRequestSchema, async (request: any, extra: any) => {
@@ -124,13 +181,26 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
         agentId: (context.headers as any)['x-agent-id'] || (context.headers as any)['X-Agent-ID'] 
       });
       
+      // AEGIS自身のポリシーリソースは上流に転送せず直接返す
+      if (this.isPolicyResourceUri(request.params.uri)) {
+        const policyResource = this.readPolicyResource(request.params.uri);
+        if (!policyResource) {
+          throw new Error(`Policy resource not found: ${request.params.uri}`);
+        }
+        return policyResource;
+      }
+      if (this.isAegisResourceUri(request.params.uri)) {
+        const agentId = (context.headers as any)['x-agent-id'] || (context.headers as any)['X-Agent-ID'] || sessionId;
+        return this.readDecisionResource(request.params.uri, agentId, extra?.requestId);
+      }
+      
       try {
         // ポリシー判定実行
-        const decision = await this.enforcePolicy('read', request.params.uri, { 
+        const decision = this.resolveIndeterminate(await this.enforcePolicy('read', request.params.uri, { 
           request,
           clientId: sessionId,
           headers: context.headers 
-        });
+        }, extra?.requestId));
         
         if (decision.decision === 'DENY') {
           throw new Error(`Access denied: ${decision.reason}`);
@@ -169,11 +239,11 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
       
       try {
         // ポリシー判定実行
-        const decision = await this.enforcePolicy('list', 'resource-listing', { 
+        const decision = this.resolveIndeterminate(await this.enforcePolicy('list', 'resource-listing', { 
           request,
           clientId: sessionId,
           headers: context.headers 
-        });
+        }, extra?.requestId));
         
         if (decision.decision === 'DENY') {
           throw new Error(`Access denied: ${decision.reason}`);
@@ -183,11 +253,13 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
         const result = await this.forwardToUpstream('resources/list', request.params || {});
         
         // ブリッジモードの場合、resultはすでに正しい形式
-        if (this.bridgeMode && result && result.result) {
-          return result.result;
-        }
+        const listResult = this.bridgeMode && result && result.result ? result.result : result;
         
-        return result;
+        // AEGISのポリシーリソースを追加
+        return {
+          ...listResult,
+          resources: [...(listResult?.resources || []), ...this.listPolicyResources()]
+        };
       } catch (error) {
         this.logger.error('List resources error', error);
         throw error;
@@ -195,7 +267,9 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
     });
 
     // ツール実行ハンドラー
-    this.server.setRequestHandler(CallToolRequestSchema, async (request: any, extra: any) => {
+    this.server.setRequestHandler(CallToolRequestSchema, this.withDeduplication(this.withToolTimeout(async (request: any, extra: any, signal: AbortSignal) => {
+      // argumentsを省略した呼び出しも空の引数として扱う
+      request = { ...request, params: this.normalizeToolCallParams(request.params) };
       const sessionId = extra?.sessionId || 'http-client';
       const context = this.requestContext.get(sessionId) || { headers: {} };
       
@@ -206,12 +280,60 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
       });
       
       try {
+        // 無効化されたツールは存在しないものとして扱う
+        this.assertToolEnabled(request.params.name);
+
+        // エージェントごとのレート制限（判定・組み込みツールの実行より前）
+        const agentId = (context.headers as any)['x-agent-id'] || (context.headers as any)['X-Agent-ID'] || sessionId;
+        const rateLimited = this.checkAgentRateLimit(agentId);
+        if (rateLimited) {
+          return rateLimited;
+        }
+        
+        // AEGIS組み込みツールは上流に転送せずに処理（ポリシー判定で許可された場合のみ）
+        if (this.isBuiltinTool(request.params.name)) {
+          const target = this.builtinToolPolicyTarget(request.params.name);
+          const builtinDecision = this.resolveIndeterminate(await this.enforcePolicy(target.action, target.resource, {
+            request,
+            clientId: sessionId,
+            headers: context.headers
+          }, extra?.requestId, signal));
+          if (builtinDecision.decision !== 'PERMIT') {
+            throw new Error(`Access denied: ${builtinDecision.reason}`);
+          }
+          return await this.callBuiltinTool(
+            request.params.name,
+            request.params.arguments,
+            this.createProgressReporter(request, extra),
+            signal
+          );
+        }
+        
+        // 宣言されたinputSchemaで引数を検証（ポリシー判定・転送の前に弾く）
+        const argumentError = this.validateToolArguments(request.params.name, request.params.arguments);
+        if (argumentError) {
+          this.logger.warn(`Rejected tool call: ${(argumentError.content[0] as { text: string }).text}`);
+          return argumentError;
+        }
+        
         // ポリシー判定実行
-        const decision = await this.enforcePolicy('execute', `tool:${request.params.name}`, { 
-          request,
-          clientId: sessionId,
-          headers: context.headers 
-        });
+        // 追加情報が必要な判定は、elicitationで集めた回答をコンテキストに加えて再判定する
+        const evaluate = (clarifications?: Clarification[]) =>
+          this.enforcePolicy('execute', `tool:${request.params.name}`, { 
+            request,
+            clientId: sessionId,
+            headers: context.headers,
+            clarifications
+          }, extra?.requestId, signal);
+        const clarified = await this.resolveClarification(await evaluate(), evaluate, extra?.requestId, signal);
+        // タイムアウト・キャンセル済みの呼び出しは義務の実行・転送に進まない
+        this.throwIfCancelled(signal);
+        
+        // 登録済みの義務ハンドラーは転送前に実行する（必須の義務が失敗すればDENY）
+        const { result: decision, outcomes: obligationOutcomes } = await this.runObligationHandlers(
+          this.resolveIndeterminate(clarified)
+        );
+        this.throwIfCancelled(signal);
         
         if (decision.decision === 'DENY') {
           throw new Error(`Access denied: ${decision.reason}`);
@@ -229,7 +351,7 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
         }
         
         // 上流サーバーに転送
-        const result = await this.forwardToUpstream('tools/call', forwardParams);
+        const result = await this.forwardToUpstream('tools/call', forwardParams, signal);
         
         // 義務実行
         if (decision.obligations) {
@@ -238,15 +360,15 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
         
         // ブリッジモードの場合、resultはすでに正しい形式
         if (this.bridgeMode && result && result.result) {
-          return result.result;
+          return this.attachObligationOutcomes(result.result, obligationOutcomes);
         }
         
-        return result;
+        return this.attachObligationOutcomes(result, obligationOutcomes);
       } catch (error) {
         this.logger.error('Tool call error', error);
         throw error;
       }
-    });
+    })));
 
     // ツール一覧ハンドラー
     this.server.setRequestHandler(ListToolsRequestSchema, async (request: any, extra: any) => {
@@ -266,7 +388,7 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
           request,
           clientId: sessionId,
           headers: context.headers 
-        });
+        }, extra?.requestId);
         
         if (decision.decision === 'DENY') {
           throw new Error(`Access denied: ${decision.reason}`);
@@ -277,23 +399,62 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
         const result = await this.forwardToUpstream('tools/list', request.params || {});
         
         // ブリッジモードの場合、resultはすでに正しい形式
-        if (this.bridgeMode && result && result.result) {
-          return result.result;
-        }
+        const listResult = this.bridgeMode && result && result.result ? result.result : result;
+        this.rememberToolInputSchemas(listResult?.tools || []);
         
-        return result;
+        // AEGIS組み込みツールを追加
+        return {
+          ...listResult,
+          tools: this.filterEnabledTools(this.withBuiltinTools(listResult?.tools || []))
+        };
       } catch (error) {
         this.logger.error('List tools error', error);
         throw error;
       }
     });
+
+    // ログレベル変更ハンドラー
+    this.server.setRequestHandler(SetLevelRequestSchema, async (request: any) => {
+      return this.setLogLevel(request.params.level);
+    });
+
+    // 組み込みプロンプト（ポリシー判定プロンプト）
+    this.server.setRequestHandler(ListPromptsRequestSchema, async () => {
+      return { prompts: this.listBuiltinPrompts() };
+    });
+
+    this.server.setRequestHandler(GetPromptRequestSchema, async (request: any) => {
+      return this.getBuiltinPrompt(request.params.name, request.params.arguments);
+    });
+
+    // 引数の補完（evaluate_accessのaction・policy、ポリシーリソースのname）
+    this.server.setRequestHandler(CompleteRequestSchema, async (request: any) => {
+      return this.completeArgument(request.params.ref, request.params.argument);
+    });
+
+    // AEGIS自身のリソースのURIテンプレート（判定リソースなど）
+    this.server.setRequestHandler(ListResourceTemplatesRequestSchema, async () => {
+      return { resourceTemplates: RESOURCE_TEMPLATES };
+    });
+
+    // ポリシーリソースの変更通知の購読（セッションごと、セッション終了時に破棄）
+    this.server.setRequestHandler(SubscribeRequestSchema, async (request: any, extra: any) => {
+      return this.subscribeResource(request.params.uri, extra?.sessionId);
+    });
+
+    this.server.setRequestHandler(UnsubscribeRequestSchema, async (request: any, extra: any) => {
+      return this.unsubscribeResource(request.params.uri, extra?.sessionId);
+    });
   }
 
-  private async enforcePolicy(action: string, resource: string, context: any): Promise<AccessControlResult> {
+  private async enforcePolicy(action: string, resource: string, context: any, requestId?: string | number, signal?: AbortSignal): Promise<AccessControlResult> {
     const startTime = Date.now();
     
-    // ヘッダーからエージェント情報を取得
-    const agentId = context.headers?.['X-Agent-ID'] || context.headers?.['x-agent-id'] || context.clientId || 'http-client';
+    // ヘッダーからエージェント情報を取得（なければ --default-agent、セッションID、'http-client' の順）
+    const agentId = this.resolveAgent(
+      context.headers?.['X-Agent-ID'] || context.headers?.['x-agent-id'],
+      context.clientId || 'http-client'
+    );
     const agentType = context.headers?.['X-Agent-Type'] || context.headers?.['x-agent-type'] || 'http-client';
     const agentMetadata = context.headers?.['X-Agent-Metadata'] || context.headers?.['x-agent-metadata'];
     
@@ -313,15 +474,42 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
       }
     };
     
-    // コンテキスト拡張
-    const enrichedContext = await this.contextCollector.enrichContext(baseContext);
+    // コンテキスト拡張（既定コンテキストの上にリクエストのコンテキストを重ねる）
+    const mergedContext = this.applyDefaultContext(baseContext);
+    this.assertContextMatchesSchema(mergedContext);
+    const enrichedContext = await this.contextCollector.enrichContext(mergedContext);
+
+    // エージェントごとの強制判定（--agent-overrides）はAI判定より先に適用する
+    const forced = this.forcedAgentDecision(enrichedContext);
+    if (forced) {
+      await this.recordDecisionAudit(enrichedContext, forced, requestId);
+      return {
+        ...forced,
+        processingTime: Date.now() - startTime,
+        policyUsed: 'agent-override',
+        context: enrichedContext
+      };
+    }
+
+    // 許可リスト・拒否リスト（--allowlist / --denylist）に一致すればAI判定を行わない
+    const fastPath = this.fastPathDecision(enrichedContext);
+    if (fastPath) {
+      await this.recordDecisionAudit(enrichedContext, fastPath, requestId);
+      return {
+        ...fastPath,
+        processingTime: Date.now() - startTime,
+        policyUsed: 'fast-path',
+        context: enrichedContext
+      };
+    }
     
     // 適用ポリシー選択
     const policyName = await this.selectApplicablePolicy(enrichedContext);
-    const policy = this.policies.get(policyName || 'default-policy');
+    const policy = this.withAgentPolicy(enrichedContext, this.policies.get(policyName || 'default-policy') ?? null);
     
     if (!policy) {
       this.logger.warn(`No policy found for resource: ${resource}`);
+      await this.recordDecisionAudit(enrichedContext, { decision: 'PERMIT', confidence: 1.0 }, requestId);
       // ポリシーがない場合はデフォルトで許可
       return {
         decision: 'PERMIT',
@@ -332,8 +520,9 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
       };
     }
     
-    // ハイブリッドポリシーエンジンで判定実行
-    const decision = await this.aiPolicyEngine.decide(enrichedContext, policy);
+    // ハイブリッドポリシーエンジンで判定実行（タイムアウトした場合はDENY）
+    const aiDecision = await this.decideWithEvalTimeout(enrichedContext, policy, signal);
+    const decision = this.markAgentOverride(enrichedContext, this.enforceStructuredConstraints(aiDecision, enrichedContext));
     
     const result = {
       ...decision,
@@ -342,6 +531,11 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
       context: enrichedContext
     };
     
+    this.stats.recordCacheLookup(decision.metadata?.cached === true);
+    
+    // 判定監査ログ（失敗時はフェイルクローズ）
+    await this.recordDecisionAudit(enrichedContext, decision, requestId);
+    
     // 監査ログ記録
     try {
       const outcome = decision.decision === 'PERMIT' ? 'SUCCESS' : 
@@ -367,7 +561,12 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
   }
 
 
-  private async forwardToUpstream(method: string, params: any): Promise<any> {
+  /**
+   * 上流サーバーに転送する（signalが中断されていれば転送せず、HTTPの転送中なら打ち切る）
+   */
+  private async forwardToUpstream(method: string, params: any, signal?: AbortSignal): Promise<any> {
+    this.throwIfCancelled(signal);
+
     // ブリッジモードの場合はstdioルーターを使用
     if (this.bridgeMode && this.stdioRouter) {
       try {
@@ -412,7 +611,8 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
           method,
           params,
           id: Date.now()
-        })
+        }),
+        signal
       });
       
       if (!response.ok) {
@@ -588,7 +788,7 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
       res.json({
         status: 'healthy',
         uptime: process.uptime(),
-        version: '1.0.0',
+        version: this.serverInfo.version,
         upstream: Array.from(this.upstreamServers.entries()).reduce((acc, [name, server]) => {
           acc[name] = {
             url: server.url,
@@ -599,6 +799,24 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
       });
     });
 
+    // readinessプローブ（JSON-RPCのPOSTルートとは別）
+    // 起動処理（ポリシー読み込み後のMCPサーバー接続）の完了前と、監査ログに書き込めない間は503
+    this.app.get('/healthz', async (req, res) => {
+      const auditLogWritable = await this.isAuditLogWritable();
+      const ready = this.ready && auditLogWritable;
+      res.status(ready ? 200 : 503).json({
+        status: ready ? 'ready' : 'not_ready',
+        initialized: this.ready,
+        policies: this.policies.size,
+        auditLogWritable
+      });
+    });
+
+    // Prometheusメトリクス（statsツールのカウンターと判定時間のヒストグラム）
+    this.app.get('/metrics', (req, res) => {
+      res.type(PROMETHEUS_CONTENT_TYPE).send(formatPrometheusMetrics(this.stats));
+    });
+
     // ポリシー管理API（完全CRUD対応）
     this.app.get('/policies', async (req, res) => {
       try {
@@ -726,27 +944,64 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
     const transport = new StreamableHTTPServerTransport({
       sessionIdGenerator: () => {
         // HTTPモードでは各リクエストが独立しているため、新規生成
-        return uuidv4();
+        return this.sources.rng.uuid();
       },
       enableJsonResponse: false // SSEストリーミングを有効化
     });
     
+    // X-Request-Idのもとでトランスポートに渡す（トレース・監査ログ・応答の_metaに引き継がれる）
+    const withHttpRequestId = (req: express.Request, handle: () => Promise<void>) =>
+      runWithRequestTrace({ httpRequestId: req.headers['x-request-id'] as string }, handle);
+
     // POST: JSON-RPCリクエストの処理
+    // 想定外のトップレベルのフィールドは取り除いて渡す（--strict-protocol では -32600 で応答する）
+    const strictProtocol = this.config.mcpProxy?.strictProtocol ?? false;
     this.app.post('/mcp/messages', async (req, res) => {
-      await transport.handleRequest(req, res, req.body);
+      if (strictProtocol) {
+        const messages: unknown[] = Array.isArray(req.body) ? req.body : [req.body];
+        for (const message of messages) {
+          const field = typeof message === 'object' && message !== null && !Array.isArray(message)
+            ? findUnknownJsonRpcField(message as Record<string, unknown>)
+            : undefined;
+          if (field !== undefined) {
+            const { id } = message as { id?: unknown };
+            this.logger.warn('Rejecting malformed JSON-RPC message', { field });
+            return res.status(400).json({
+              jsonrpc: '2.0',
+              id: typeof id === 'string' || typeof id === 'number' ? id : null,
+              error: { code: -32600, message: `unexpected field \`${field}\``, data: { field } }
+            });
+          }
+        }
+      } else {
+        req.body = removeUnknownJsonRpcFields(req.body);
+      }
+      await withHttpRequestId(req, () => transport.handleRequest(req, res, req.body));
     });
     
     // GET: SSEストリームの確立
     this.app.get('/mcp/messages', async (req, res) => {
-      await transport.handleRequest(req, res);
+      await withHttpRequestId(req, () => transport.handleRequest(req, res));
     });
     
     // DELETE: セッションの終了
     this.app.delete('/mcp/messages', async (req, res) => {
-      await transport.handleRequest(req, res);
+      await withHttpRequestId(req, () => transport.handleRequest(req, res));
     });
     
+    // SDKのinitializeハンドラーが保存したクライアントの機能を取り込む
+    this.server.oninitialized = () => {
+      this.recordClientCapabilities(this.server.getClientCapabilities());
+    };
     await this.server.connect(transport);
+    this.installHandshakeGuard(transport);
+    this.installMetaEcho(transport);
+    this.installHttpRequestIdMeta(transport);
+    this.installResponseSizeLimit(transport);
+    this.installRequestTracing(transport);
+    this.installRequestTiming(transport);
+    this.installSubscriptionCleanup(transport);
+    this.ready = true;
     
     // Expressサーバー起動（Promiseでラップ）
     await new Promise<void>((resolve, reject) => {
@@ -756,6 +1011,7 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
         this.logger.info(`📡 MCP endpoint: http://localhost:${port}/mcp/messages`);
         this.logger.info(`🌐 Web UI: http://localhost:${port}/`);
         this.logger.info(`🔗 Health check: http://localhost:${port}/health`);
+        this.logger.info(`🔗 Readiness probe: http://localhost:${port}/healthz`);
         this.logger.info(`📋 Policy Management API: http://localhost:${port}/policies`);
         this.logger.info(`📊 Audit API: http://localhost:${port}/audit`);
         
@@ -768,7 +1024,28 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
     });
   }
 
+  /**
+   * 応答のresult._meta.httpRequestIdに、リクエストを運んだHTTPリクエストのX-Request-Idを入れる
+   * 応答は受信したリクエストの非同期コンテキストで送られるため、トレースから引ける
+   */
+  private installHttpRequestIdMeta(transport: StreamableHTTPServerTransport): void {
+    const send = transport.send.bind(transport);
+    transport.send = (message, ...rest) => {
+      const httpRequestId = getRequestTrace()?.httpRequestId;
+      const response = message as { id?: string | number; method?: string; result?: Record<string, any> };
+      if (httpRequestId !== undefined && !response.method && response.id !== undefined && response.result) {
+        message = {
+          ...response,
+          result: { ...response.result, _meta: { ...response.result._meta, httpRequestId } }
+        } as typeof message;
+      }
+      return send(message, ...rest);
+    };
+  }
+
   async stop(): Promise<void> {
+    this.ready = false;
+
     // stdio上流サーバーを停止
     if (this.bridgeMode && this.stdioRouter) {
       await this.stdioRouter.stopServers();
@@ -784,6 +1061,9 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
     }
     
     await this.server.close();
+    
+    // 監査ログを書き切ってから停止
+    await this.flushAuditState();
     this.logger.info('🛑 AEGIS MCP Proxy (HTTP) stopped');
   }
 
@@ -835,12 +1115,4 @@ export class MCPHttpPolicyProxy extends MCPPolicyProxyBase {
     
     return warnings;
   }
-}
-
-function uuidv4(): string {
-  return 'xxxxxxxx-xxxx-4xxx-yxxx-xxxxxxxxxxxx'.replace(/[xy]/g, function(c) {
-    const r = Math.random() * 16 | 0;
-    const v = c === 'x' ? r : (r & 0x3 | 0x8);
-    return v.toString(16);
-  });
 }
\ No newline at end of file
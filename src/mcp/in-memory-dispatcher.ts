@@ -0,0 +1,113 @@
+// ============================================================================
+// AEGIS - プロセス内でJSON-RPCメッセージを処理するトランスポート
+// stdioやHTTPを介さずに1メッセージ（文字列）を渡し、対応する応答（文字列）を受け取る
+// テストや組み込み利用で、サーバーを起動せずにリクエスト処理経路を直接呼び出すために使う
+// ============================================================================
+
+import type { Transport } from '@modelcontextprotocol/sdk/shared/transport.js';
+import type { JSONRPCMessage } from '@modelcontextprotocol/sdk/types.js';
+import { JSONRPC_VERSION, findInvalidJsonRpcVersion } from './jsonrpc-version.js';
+import { checkJsonRpcShape, removeUnknownJsonRpcFields } from './jsonrpc-shape.js';
+
+type MessageId = string | number;
+
+function invalidRequest(id: MessageId | null, message: string, data: Record<string, unknown>): string {
+  return JSON.stringify({ jsonrpc: '2.0', id, error: { code: -32600, message, data } });
+}
+
+/**
+ * dispatch() に渡したリクエストの応答を、送信されたメッセージのidで待ち合わせる
+ * 通知（idなし）は処理を依頼するだけで応答を待たない
+ * 応答待ちのリクエストと同じidのリクエストは、先の応答を取り違えないよう -32600 で拒否する
+ */
+export class InMemoryDispatchTransport implements Transport {
+  onclose?: () => void;
+  onerror?: (error: Error) => void;
+  onmessage?: (message: JSONRPCMessage) => void;
+
+  private pending = new Map<MessageId, (response: string) => void>();
+  private started = false;
+  private strictProtocol: boolean;
+
+  /**
+   * @param strictProtocol 想定外のトップレベルのフィールドを -32600 で拒否する（既定では取り除いて処理する）
+   */
+  constructor(strictProtocol = false) {
+    this.strictProtocol = strictProtocol;
+  }
+
+  async start(): Promise<void> {
+    if (this.started) {
+      throw new Error('InMemoryDispatchTransport already started');
+    }
+    this.started = true;
+  }
+
+  async close(): Promise<void> {
+    this.pending.clear();
+    this.onclose?.();
+  }
+
+  async send(message: JSONRPCMessage): Promise<void> {
+    const response = message as { id?: MessageId; method?: string };
+    if (response.method || response.id === undefined) {
+      // サーバーからの通知・リクエストは待ち合わせの対象外
+      return;
+    }
+    const resolve = this.pending.get(response.id);
+    if (resolve) {
+      this.pending.delete(response.id);
+      resolve(JSON.stringify(message));
+    }
+  }
+
+  /**
+   * JSON-RPCメッセージ（文字列）を1件処理し、応答を文字列で返す（通知の場合はnull）
+   * JSONとして読めない入力には、stdioと同じく id: null の -32700 を返す
+   * jsonrpcが"2.0"でない・フィールドの型が不正な入力は、SDKが応答しないため -32600 を返す
+   */
+  async dispatch(input: string): Promise<string | null> {
+    if (!this.started || !this.onmessage) {
+      throw new Error('InMemoryDispatchTransport is not connected');
+    }
+
+    let message: JSONRPCMessage;
+    try {
+      message = JSON.parse(input);
+    } catch (error) {
+      return JSON.stringify({
+        jsonrpc: '2.0',
+        id: null,
+        error: { code: -32700, message: 'Parse error', data: { message: (error as Error).message } }
+      });
+    }
+
+    const invalidVersion = findInvalidJsonRpcVersion(input);
+    if (invalidVersion) {
+      return invalidRequest(invalidVersion.id, 'Invalid Request', {
+        expected: JSONRPC_VERSION,
+        received: invalidVersion.version ?? null
+      });
+    }
+    const malformed = checkJsonRpcShape(message, this.strictProtocol);
+    if (malformed) {
+      return invalidRequest(malformed.id, malformed.message, { field: malformed.field });
+    }
+    message = removeUnknownJsonRpcFields(message) as JSONRPCMessage;
+
+    const request = message as { id?: MessageId; method?: string };
+    if (!request.method || request.id === undefined) {
+      this.onmessage(message);
+      return null;
+    }
+
+    if (this.pending.has(request.id)) {
+      return invalidRequest(request.id, 'Duplicate request id', { field: 'id' });
+    }
+    const response = new Promise<string>(resolve => {
+      this.pending.set(request.id!, resolve);
+    });
+    this.onmessage(message);
+    return response;
+  }
+}
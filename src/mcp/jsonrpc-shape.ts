@@ -0,0 +1,113 @@
+// ============================================================================
+// AEGIS - JSON-RPCメッセージの形（フィールドの型）の検証
+// JSONとしては読めても method が数値・params が文字列などのメッセージは、SDKのスキーマ検証で
+// 「Unknown message type」としてonerrorに通知されるだけで応答されない
+// トランスポートの手前でフィールドの型を検査し、どのフィールドが不正かを示して -32600 を返す
+// （jsonrpcフィールドの検査は jsonrpc-version.ts が担当する）
+// 想定外のトップレベルのフィールドは、既定では取り除いてから渡し（独自拡張を付けるクライアント向け）、
+// --strict-protocol のときだけ -32600 で拒否する（プロトコル適合性の検証向け）
+// ============================================================================
+
+export interface InvalidJsonRpcShape {
+  id: string | number | null;
+  field: string;
+  message: string;
+}
+
+// SDKのスキーマは余分なフィールドを許さない（strict）ため、既定でも取り除く必要がある
+const MESSAGE_FIELDS = new Set(['jsonrpc', 'id', 'method', 'params', 'result', 'error']);
+
+function isPlainObject(value: unknown): value is Record<string, unknown> {
+  return typeof value === 'object' && value !== null && !Array.isArray(value);
+}
+
+/**
+ * JSON-RPCのメッセージにないトップレベルのフィールド（最初の1つ）
+ */
+export function findUnknownJsonRpcField(message: Record<string, unknown>): string | undefined {
+  return Object.keys(message).find(key => !MESSAGE_FIELDS.has(key));
+}
+
+/**
+ * 想定外のトップレベルのフィールドを取り除いたメッセージ（バッチは要素ごと、取り除くものがなければそのまま）
+ */
+export function removeUnknownJsonRpcFields(message: unknown): unknown {
+  if (Array.isArray(message)) {
+    return message.map(removeUnknownJsonRpcFields);
+  }
+  if (!isPlainObject(message) || findUnknownJsonRpcField(message) === undefined) {
+    return message;
+  }
+  return Object.fromEntries(Object.entries(message).filter(([key]) => MESSAGE_FIELDS.has(key)));
+}
+
+/**
+ * パース済みのメッセージのフィールドの型を検査する（問題がなければnull）
+ * strict のときは想定外のトップレベルのフィールドも不正とする
+ * バッチ（配列）は対象外
+ */
+export function checkJsonRpcShape(message: unknown, strict = false): InvalidJsonRpcShape | null {
+  if (Array.isArray(message)) {
+    return null;
+  }
+  if (!isPlainObject(message)) {
+    return { id: null, field: 'message', message: 'message must be a JSON object' };
+  }
+
+  const { id, method, params } = message;
+  const validId = typeof id === 'string' || typeof id === 'number';
+  const replyId = validId ? id : null;
+  if (id !== undefined && !validId) {
+    return { id: null, field: 'id', message: '`id` must be a string or number' };
+  }
+  if (method !== undefined && typeof method !== 'string') {
+    return { id: replyId, field: 'method', message: '`method` must be a string' };
+  }
+  if (params !== undefined && !isPlainObject(params)) {
+    return { id: replyId, field: 'params', message: '`params` must be an object' };
+  }
+  const isResponse = 'result' in message || 'error' in message;
+  if (method === undefined && !isResponse) {
+    return { id: replyId, field: 'method', message: 'message must have a `method`, `result` or `error`' };
+  }
+  if (method !== undefined && isResponse) {
+    return { id: replyId, field: 'method', message: 'a request must not have `result` or `error`' };
+  }
+  const unexpected = strict ? findUnknownJsonRpcField(message) : undefined;
+  if (unexpected !== undefined) {
+    return { id: replyId, field: unexpected, message: `unexpected field \`${unexpected}\`` };
+  }
+  return null;
+}
+
+/**
+ * 1行分のメッセージを検査する。JSONとして読めない行はパースエラーの処理に任せるためnull
+ */
+export function findInvalidJsonRpcShape(line: string, strict = false): InvalidJsonRpcShape | null {
+  let message: unknown;
+  try {
+    message = JSON.parse(line);
+  } catch {
+    return null;
+  }
+  return checkJsonRpcShape(message, strict);
+}
+
+/**
+ * 1行分のメッセージから想定外のトップレベルのフィールドを取り除く
+ * 取り除くものがない・JSONとして読めない行はnull（元の行をそのまま使う）
+ */
+export function stripUnknownJsonRpcFields(line: string): string | null {
+  let message: unknown;
+  try {
+    message = JSON.parse(line);
+  } catch {
+    return null;
+  }
+  const stripped = removeUnknownJsonRpcFields(message);
+  if (Array.isArray(message)) {
+    const changed = message.some((item, index) => item !== (stripped as unknown[])[index]);
+    return changed ? JSON.stringify(stripped) : null;
+  }
+  return stripped === message ? null : JSON.stringify(stripped);
+}
@@ -0,0 +1,39 @@
+// ============================================================================
+// AEGIS - JSON-RPCバージョンの検証
+// SDKのトランスポートはjsonrpcが"2.0"でないメッセージをスキーマ検証エラーとして
+// onerrorに通知するだけで応答しないため、トランスポートの手前で検出して -32600 を返す
+// ============================================================================
+
+export const JSONRPC_VERSION = '2.0';
+
+export interface InvalidJsonRpcVersion {
+  id: string | number | null;
+  version: unknown;
+}
+
+/**
+ * 1行分のメッセージのjsonrpcフィールドを検査する
+ * "2.0"以外ならidを添えて返す。JSONとして読めない行はパースエラーの処理に任せるためnull
+ */
+export function findInvalidJsonRpcVersion(line: string): InvalidJsonRpcVersion | null {
+  let message: unknown;
+  try {
+    message = JSON.parse(line);
+  } catch {
+    return null;
+  }
+
+  if (typeof message !== 'object' || message === null || Array.isArray(message)) {
+    return null;
+  }
+
+  const { jsonrpc, id } = message as { jsonrpc?: unknown; id?: unknown };
+  if (jsonrpc === JSONRPC_VERSION) {
+    return null;
+  }
+
+  return {
+    id: typeof id === 'string' || typeof id === 'number' ? id : null,
+    version: jsonrpc
+  };
+}
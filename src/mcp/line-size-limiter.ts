@@ -0,0 +1,116 @@
+// ============================================================================
+// AEGIS - stdio入力の行サイズ制限
+// SDKのStdioServerTransportは改行が来るまで入力を無制限にバッファするため、
+// トランスポートの手前で1行（1メッセージ）の最大バイト数を制限する
+// 上限内の行も、必要に応じてacceptLineで検査してから渡す（書き換えた行を返すこともできる）
+// Windowsのシェルから起動したクライアントが送るCRLFはLFに正規化し、空行は渡さない
+// ============================================================================
+
+import { Readable, Transform, TransformCallback } from 'stream';
+
+const NEWLINE = 0x0a;
+const CARRIAGE_RETURN = 0x0d;
+const NEWLINE_BUFFER = Buffer.from([NEWLINE]);
+
+export class LineSizeLimiter extends Transform {
+  private maxLineBytes: number;
+  private onOversize: (limit: number) => void;
+  private acceptLine?: (line: Buffer) => boolean | string;
+  private pending: Buffer[] = [];
+  private pendingBytes = 0;
+  private discarding = false;
+
+  /**
+   * @param maxLineBytes 1行の最大バイト数（改行を除く）
+   * @param onOversize 上限を超えた行を検出したときに1行につき1回呼ばれる
+   * @param acceptLine 上限内の行（改行を除く）ごとに呼ばれ、falseを返した行は渡さない
+   *                   文字列を返した場合は、その内容に置き換えて渡す
+   */
+  constructor(maxLineBytes: number, onOversize: (limit: number) => void, acceptLine?: (line: Buffer) => boolean | string) {
+    super();
+    this.maxLineBytes = maxLineBytes;
+    this.onOversize = onOversize;
+    this.acceptLine = acceptLine;
+  }
+
+  /**
+   * 読み手がdataリスナーを登録した時点で入力元を接続する
+   * （読み手がいないうちから入力元をflowingモードにしない）
+   */
+  connectWhenRead(source: Readable): this {
+    let connected = false;
+    this.on('newListener', (event: string) => {
+      if (event === 'data' && !connected) {
+        connected = true;
+        source.pipe(this);
+      }
+    });
+    return this;
+  }
+
+  _transform(chunk: Buffer | string, _encoding: BufferEncoding, callback: TransformCallback): void {
+    const data = typeof chunk === 'string' ? Buffer.from(chunk) : chunk;
+    let start = 0;
+
+    while (start < data.length) {
+      const newline = data.indexOf(NEWLINE, start);
+      const end = newline === -1 ? data.length : newline + 1;
+      const segment = data.subarray(start, end);
+
+      if (!this.discarding) {
+        const lineBytes = this.pendingBytes + segment.length - (newline === -1 ? 0 : 1);
+        if (lineBytes > this.maxLineBytes) {
+          // 上限超過: ここまでの内容を捨て、行末まで読み飛ばす
+          this.discarding = true;
+          this.pending = [];
+          this.pendingBytes = 0;
+          this.onOversize(this.maxLineBytes);
+        } else {
+          this.pending.push(segment);
+          this.pendingBytes += segment.length;
+        }
+      }
+
+      if (newline !== -1) {
+        if (this.discarding) {
+          this.discarding = false;
+        } else {
+          this.pushLine(Buffer.concat(this.pending));
+          this.pending = [];
+          this.pendingBytes = 0;
+        }
+      }
+
+      start = end;
+    }
+
+    callback();
+  }
+
+  _flush(callback: TransformCallback): void {
+    if (!this.discarding && this.pendingBytes > 0) {
+      this.pushLine(Buffer.concat(this.pending));
+    }
+    callback();
+  }
+
+  private pushLine(line: Buffer): void {
+    const terminated = line[line.length - 1] === NEWLINE;
+    let content = terminated ? line.subarray(0, line.length - 1) : line;
+    if (content[content.length - 1] === CARRIAGE_RETURN) {
+      content = content.subarray(0, content.length - 1);
+    }
+
+    if (content.toString('utf8').trim() === '') {
+      return;
+    }
+    const accepted = this.acceptLine ? this.acceptLine(content) : true;
+    if (accepted === false) {
+      return;
+    }
+    if (typeof accepted === 'string') {
+      content = Buffer.from(accepted, 'utf8');
+    }
+    this.push(terminated ? Buffer.concat([content, NEWLINE_BUFFER]) : content);
+  }
+}
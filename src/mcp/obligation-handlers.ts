@@ -0,0 +1,119 @@
+// ============================================================================
+// AEGIS - 名前付き義務ハンドラー
+// PERMIT判定に付いた義務（"log: 機密ファイルへのアクセス" など）を、上流へ転送する前に実行する
+// 義務の名前は最初の ":" より前（":" がなければ全体）で、大文字小文字は区別しない
+// 必須（mandatory）のハンドラーが失敗した場合、呼び出し元は判定をDENYに格下げする（フェイルクローズ）
+// どのハンドラーにも一致しない義務は従来どおりEnforcementSystemが転送後に実行する
+// ============================================================================
+
+import type { DecisionContext, PolicyDecision } from '../types/index.js';
+import type { Logger } from '../utils/logger.js';
+
+export interface ObligationHandler {
+  // 義務の名前（"log" など）
+  name: string;
+  // 失敗したときにリクエストを拒否するか
+  mandatory?: boolean;
+  // argumentは名前の後の ":" 以降（前後の空白を除く）
+  execute(argument: string, context: DecisionContext, decision: PolicyDecision): Promise<void>;
+}
+
+export interface ObligationOutcome {
+  obligation: string;
+  handler: string;
+  mandatory: boolean;
+  success: boolean;
+  error?: string;
+}
+
+export interface ObligationRunResult {
+  outcomes: ObligationOutcome[];
+  // どのハンドラーにも一致しなかった義務（元の順序）
+  unhandled: string[];
+}
+
+export function parseObligation(obligation: string): { name: string; argument: string } {
+  const separator = obligation.indexOf(':');
+  if (separator === -1) {
+    return { name: obligation.trim().toLowerCase(), argument: '' };
+  }
+  return {
+    name: obligation.slice(0, separator).trim().toLowerCase(),
+    argument: obligation.slice(separator + 1).trim()
+  };
+}
+
+export class ObligationHandlerRegistry {
+  private handlers = new Map<string, ObligationHandler>();
+
+  /**
+   * ハンドラーを登録（同じ名前のハンドラーは置き換える）
+   */
+  register(handler: ObligationHandler): void {
+    this.handlers.set(handler.name.toLowerCase(), handler);
+  }
+
+  unregister(name: string): boolean {
+    return this.handlers.delete(name.toLowerCase());
+  }
+
+  get(name: string): ObligationHandler | undefined {
+    return this.handlers.get(name.toLowerCase());
+  }
+
+  names(): string[] {
+    return Array.from(this.handlers.keys());
+  }
+
+  /**
+   * 義務を順に実行する（1件が失敗しても残りは実行する）
+   */
+  async run(obligations: string[], context: DecisionContext, decision: PolicyDecision): Promise<ObligationRunResult> {
+    const outcomes: ObligationOutcome[] = [];
+    const unhandled: string[] = [];
+
+    for (const obligation of obligations) {
+      const { name, argument } = parseObligation(obligation);
+      const handler = this.handlers.get(name);
+      if (!handler) {
+        unhandled.push(obligation);
+        continue;
+      }
+
+      const outcome: ObligationOutcome = {
+        obligation,
+        handler: handler.name,
+        mandatory: handler.mandatory ?? false,
+        success: true
+      };
+      try {
+        await handler.execute(argument, context, decision);
+      } catch (error) {
+        outcome.success = false;
+        outcome.error = error instanceof Error ? error.message : String(error);
+      }
+      outcomes.push(outcome);
+    }
+
+    return { outcomes, unhandled };
+  }
+}
+
+/**
+ * 組み込みの log 義務: 判定の要点をロガーに出力する
+ */
+export class LogObligationHandler implements ObligationHandler {
+  readonly name = 'log';
+  readonly mandatory = true;
+
+  constructor(private logger: Logger) {}
+
+  async execute(argument: string, context: DecisionContext, decision: PolicyDecision): Promise<void> {
+    this.logger.info(`Obligation log: ${argument || decision.reason}`, {
+      agent: context.agent,
+      action: context.action,
+      resource: context.resource,
+      decision: decision.decision
+    });
+  }
+}
@@ -0,0 +1,57 @@
+// ============================================================================
+// AEGIS - Prometheusのテキスト形式（exposition format 0.0.4）でのメトリクス出力
+// statsツールと同じカウンターに、ポリシー判定時間・メソッドごとのリクエスト処理時間のヒストグラムを加えて返す
+// ============================================================================
+
+import type { ServerStats } from './server-stats.js';
+
+export const PROMETHEUS_CONTENT_TYPE = 'text/plain; version=0.0.4; charset=utf-8';
+
+function escapeLabel(value: string): string {
+  return value.replace(/\\/g, '\\\\').replace(/"/g, '\\"').replace(/\n/g, '\\n');
+}
+
+function metric(lines: string[], name: string, type: string, help: string, samples: Array<[string, number]>): void {
+  lines.push(`# HELP ${name} ${help}`, `# TYPE ${name} ${type}`);
+  for (const [labels, value] of samples) {
+    lines.push(`${name}${labels} ${value}`);
+  }
+}
+
+/**
+ * 現在の統計をPrometheusのテキスト形式で返す（末尾は改行）
+ */
+export function formatPrometheusMetrics(stats: ServerStats, now: number = Date.now()): string {
+  const snapshot = stats.snapshot(now);
+  const latency = stats.evaluationLatency();
+  const lookups = snapshot.cache.hits + snapshot.cache.misses;
+  const lines: string[] = [];
+
+  metric(lines, 'aegis_uptime_seconds', 'gauge', 'Seconds since the proxy started.', [['', snapshot.uptimeSeconds]]);
+  metric(lines, 'aegis_requests_total', 'counter', 'JSON-RPC requests handled, by method.',
+    Object.entries(snapshot.requestsByMethod).map(([method, count]) => [`{method="${escapeLabel(method)}"}`, count]));
+  metric(lines, 'aegis_decisions_total', 'counter', 'Policy decisions, by decision type.',
+    Object.entries(snapshot.decisions).map(([decision, count]) => [`{decision="${decision}"}`, count]));
+  metric(lines, 'aegis_cache_hits_total', 'counter', 'Decision cache hits.', [['', snapshot.cache.hits]]);
+  metric(lines, 'aegis_cache_misses_total', 'counter', 'Decision cache misses.', [['', snapshot.cache.misses]]);
+  metric(lines, 'aegis_cache_hit_ratio', 'gauge', 'Decision cache hits divided by lookups (0 before the first lookup).',
+    [['', lookups > 0 ? snapshot.cache.hits / lookups : 0]]);
+  metric(lines, 'aegis_policy_evaluation_duration_seconds', 'histogram', 'Time spent evaluating policies for tools/call.', [
+    ...latency.buckets.map(({ le, count }): [string, number] => [`_bucket{le="${le}"}`, count]),
+    ['_bucket{le="+Inf"}', latency.count],
+    ['_sum', latency.sumSeconds],
+    ['_count', latency.count]
+  ]);
+  metric(lines, 'aegis_request_duration_seconds', 'histogram', 'Time from receiving a JSON-RPC request to sending its response, by method.',
+    Object.entries(stats.requestLatency()).flatMap(([method, histogram]): Array<[string, number]> => {
+      const label = `method="${escapeLabel(method)}"`;
+      return [
+        ...histogram.buckets.map(({ le, count }): [string, number] => [`_bucket{${label},le="${le}"}`, count]),
+        [`_bucket{${label},le="+Inf"}`, histogram.count],
+        [`_sum{${label}}`, histogram.sumSeconds],
+        [`_count{${label}}`, histogram.count]
+      ];
+    }));
+
+  return `${lines.join('\n')}\n`;
+}
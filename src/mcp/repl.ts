@@ -0,0 +1,143 @@
+// ============================================================================
+// AEGIS - ローカル確認用の対話モード（--repl）
+// `check <agent> <action> <resource>` と入力すると、check_policies のJSON-RPCリクエストを組み立てて
+// dispatchString で処理し、判定結果を人が読める形で表示する（stdioプロトコルは使わない）
+// ============================================================================
+
+import * as readline from 'readline';
+import { POLICY_RESOURCE_URI_PREFIX } from './base-proxy.js';
+
+export const REPL_PROMPT = 'aegis> ';
+
+export const REPL_HELP = [
+  'Commands:',
+  '  check <agent> <action> <resource>  Evaluate all registered policies (deny_overrides)',
+  '  help                               Show this help',
+  '  exit                               Leave the REPL'
+].join('\n');
+
+export type ReplCommand =
+  | { kind: 'check'; agent: string; action: string; resource: string }
+  | { kind: 'help' }
+  | { kind: 'exit' }
+  | { kind: 'empty' }
+  | { kind: 'invalid'; message: string };
+
+export interface ReplDispatcher {
+  dispatchString(input: string): Promise<string | null>;
+}
+
+/**
+ * 1行分の入力を解釈する（resourceは空白を含んでもよく、残りをすべて使う）
+ */
+export function parseReplCommand(line: string): ReplCommand {
+  const words = line.trim().split(/\s+/).filter(word => word !== '');
+  if (words.length === 0) {
+    return { kind: 'empty' };
+  }
+  const [command, ...rest] = words;
+  switch (command.toLowerCase()) {
+    case 'check':
+      if (rest.length < 3) {
+        return { kind: 'invalid', message: 'Usage: check <agent> <action> <resource>' };
+      }
+      return { kind: 'check', agent: rest[0], action: rest[1], resource: rest.slice(2).join(' ') };
+    case 'help':
+      return { kind: 'help' };
+    case 'exit':
+    case 'quit':
+      return { kind: 'exit' };
+    default:
+      return { kind: 'invalid', message: `Unknown command: ${command} (type "help" for commands)` };
+  }
+}
+
+/**
+ * check_policies の応答（JSON-RPC）を表示用の文字列にする
+ */
+export function formatCheckResponse(response: string | null): string {
+  if (response === null) {
+    return 'Error: no response';
+  }
+  const message = JSON.parse(response);
+  if (message.error) {
+    return `Error ${message.error.code}: ${message.error.message}`;
+  }
+  const result = message.result;
+  if (result?.isError || !result?.structuredContent) {
+    return `Error: ${result?.content?.[0]?.text ?? 'unexpected response'}`;
+  }
+
+  const combined = result.structuredContent;
+  const lines = [
+    `${combined.decision} (confidence ${combined.confidence}, ${combined.combiningAlgorithm})`,
+    `  reason: ${combined.reason}`
+  ];
+  for (const decision of combined.decisions) {
+    lines.push(`  - ${decision.policy}: ${decision.decision} (${decision.confidence}) ${decision.reason}`);
+  }
+  return lines.join('\n');
+}
+
+/**
+ * 対話モードを実行する。入力が終わるか exit で戻る
+ */
+export async function runRepl(
+  dispatcher: ReplDispatcher,
+  input: NodeJS.ReadableStream,
+  output: NodeJS.WritableStream
+): Promise<void> {
+  let nextId = 1;
+  const request = async (method: string, params: Record<string, unknown>) =>
+    dispatcher.dispatchString(JSON.stringify({ jsonrpc: '2.0', id: nextId++, method, params }));
+
+  await request('initialize', {
+    protocolVersion: '2024-11-05',
+    capabilities: {},
+    clientInfo: { name: 'aegis-repl', version: '1.0.0' }
+  });
+  await dispatcher.dispatchString(JSON.stringify({ jsonrpc: '2.0', method: 'notifications/initialized' }));
+
+  // 評価の対象はその時点で登録されているポリシーすべて
+  const policyNames = async (): Promise<string[]> => {
+    const response = await request('resources/list', {});
+    const resources: Array<{ uri: string; name: string }> = JSON.parse(response ?? '{}').result?.resources ?? [];
+    return resources.filter(resource => resource.uri.startsWith(POLICY_RESOURCE_URI_PREFIX)).map(resource => resource.name);
+  };
+
+  const rl = readline.createInterface({ input, output, terminal: false });
+  const write = (text: string) => output.write(`${text}\n`);
+  write('AEGIS REPL. Type "help" for commands.');
+  output.write(REPL_PROMPT);
+
+  for await (const line of rl) {
+    const command = parseReplCommand(line);
+    if (command.kind === 'exit') {
+      break;
+    }
+    if (command.kind === 'help') {
+      write(REPL_HELP);
+    } else if (command.kind === 'invalid') {
+      write(command.message);
+    } else if (command.kind === 'check') {
+      const policies = await policyNames();
+      if (policies.length === 0) {
+        write('No policies are registered');
+      } else {
+        const response = await request('tools/call', {
+          name: 'check_policies',
+          arguments: {
+            agent: command.agent,
+            action: command.action,
+            resource: command.resource,
+            policies,
+            combining_algorithm: 'deny_overrides'
+          }
+        });
+        write(formatCheckResponse(response));
+      }
+    }
+    output.write(REPL_PROMPT);
+  }
+  rl.close();
+}
@@ -0,0 +1,83 @@
+// ============================================================================
+// AEGIS - JSON-RPC idによるtools/callの重複排除
+// クライアントの再送や中継の二重送信で同じidのリクエストが届いた場合に、
+// 副作用のあるツールを二度実行しないよう、直近のidと応答を一定件数だけ保持する
+// 同じidでも引数が異なる場合は別のリクエストとして扱う（idの使い回しを誤って束ねないため）
+// ============================================================================
+
+type Entry<R> =
+  | { fingerprint: string; state: 'pending' }
+  | { fingerprint: string; state: 'done'; result: R }
+  | { fingerprint: string; state: 'failed'; error: unknown };
+
+export class RequestDeduplicator<R> {
+  private windowSize: number;
+  // 挿入順を保つMapを上限付きの窓として使う（古いidから捨てる）
+  private entries = new Map<string, Entry<R>>();
+
+  /**
+   * @param windowSize 保持するidの数（0以下は重複排除しない）
+   */
+  constructor(windowSize: number) {
+    this.windowSize = windowSize;
+  }
+
+  isEnabled(): boolean {
+    return this.windowSize > 0;
+  }
+
+  /**
+   * 初めてのidならexecuteを実行して応答を記録する
+   * 記録済みのidなら前回の応答（失敗ならそのエラー）を返し、実行中なら -32600 で拒否する
+   */
+  async run(key: string | undefined, params: unknown, execute: () => Promise<R>): Promise<R> {
+    if (!this.isEnabled() || key === undefined) {
+      return execute();
+    }
+
+    const fingerprint = JSON.stringify(params ?? null);
+    const seen = this.entries.get(key);
+    if (seen && seen.fingerprint === fingerprint) {
+      if (seen.state === 'pending') {
+        const error = new Error(`Duplicate request id is still in flight: ${key}`) as Error & { code: number };
+        error.code = -32600;
+        throw error;
+      }
+      if (seen.state === 'failed') {
+        throw seen.error;
+      }
+      return seen.result;
+    }
+
+    const entry: Entry<R> = { fingerprint, state: 'pending' };
+    this.remember(key, entry);
+    try {
+      const result = await execute();
+      this.settle(key, entry, { fingerprint, state: 'done', result });
+      return result;
+    } catch (error) {
+      this.settle(key, entry, { fingerprint, state: 'failed', error });
+      throw error;
+    }
+  }
+
+  size(): number {
+    return this.entries.size;
+  }
+
+  private remember(key: string, entry: Entry<R>): void {
+    this.entries.delete(key);
+    this.entries.set(key, entry);
+    while (this.entries.size > this.windowSize) {
+      const oldest = this.entries.keys().next().value as string;
+      this.entries.delete(oldest);
+    }
+  }
+
+  // 実行中に同じidが別の引数で上書きされていれば、その記録は残す
+  private settle(key: string, pending: Entry<R>, settled: Entry<R>): void {
+    if (this.entries.get(key) === pending) {
+      this.entries.set(key, settled);
+    }
+  }
+}
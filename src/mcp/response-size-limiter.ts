@@ -0,0 +1,68 @@
+// ============================================================================
+// AEGIS - 応答サイズの上限（--max-response-bytes）
+// シリアライズした応答が上限を超える場合、テキスト（content[].text / contents[].text）を
+// 大きいものから切り詰めて「... [truncated N bytes]」を付ける
+// JSONとしての妥当性と、テキスト以外のフィールド（structuredContent・_metaなど）はそのまま保つ
+// ============================================================================
+
+import type { JSONRPCMessage } from '@modelcontextprotocol/sdk/types.js';
+
+type TextItem = { text: string };
+
+function byteLength(value: unknown): number {
+  return Buffer.byteLength(JSON.stringify(value), 'utf8');
+}
+
+// UTF-8で maxBytes 以内に収まる先頭部分（マルチバイト文字の途中では切らない）
+function sliceUtf8(text: string, maxBytes: number): string {
+  const sliced = Buffer.from(text, 'utf8').subarray(0, Math.max(0, maxBytes)).toString('utf8');
+  return sliced.endsWith('\uFFFD') && !text.startsWith(sliced) ? sliced.slice(0, -1) : sliced;
+}
+
+function textItems(result: Record<string, unknown>): TextItem[] {
+  return (['content', 'contents'] as const).flatMap(field => {
+    const items = result[field];
+    return Array.isArray(items)
+      ? items.filter((item): item is TextItem => !!item && typeof item === 'object' && typeof item.text === 'string')
+      : [];
+  });
+}
+
+export function truncationMarker(bytes: number): string {
+  return `... [truncated ${bytes} bytes]`;
+}
+
+/**
+ * 応答（resultを持つメッセージ）を上限に収まるよう切り詰めた複製を返す
+ * 上限内の応答・リクエスト・通知・エラー応答はそのまま返す（テキストを切り詰めても収まらない場合も切り詰めた結果を返す）
+ */
+export function limitResponseSize(message: JSONRPCMessage, maxBytes: number): JSONRPCMessage {
+  const response = message as { result?: Record<string, unknown> };
+  if (maxBytes <= 0 || !response.result || byteLength(message) <= maxBytes) {
+    return message;
+  }
+
+  const limited = JSON.parse(JSON.stringify(message)) as { result: Record<string, unknown> };
+  const items = textItems(limited.result).sort((a, b) => b.text.length - a.text.length);
+  for (const item of items) {
+    const excess = byteLength(limited) - maxBytes;
+    if (excess <= 0) {
+      break;
+    }
+
+    const original = item.text;
+    const originalBytes = Buffer.byteLength(original, 'utf8');
+    // JSONエスケープで増える分は元の比率で見込み、収まらなければ段階的に短くする
+    const serializedBytes = byteLength(original);
+    let keepBytes = Math.floor((serializedBytes - excess - truncationMarker(originalBytes).length) * originalBytes / serializedBytes);
+    do {
+      const kept = sliceUtf8(original, keepBytes);
+      item.text = `${kept}${truncationMarker(originalBytes - Buffer.byteLength(kept, 'utf8'))}`;
+      keepBytes -= Math.max(16, byteLength(limited) - maxBytes);
+    } while (byteLength(limited) > maxBytes && keepBytes > 0);
+    if (byteLength(limited) > maxBytes) {
+      item.text = truncationMarker(originalBytes);
+    }
+  }
+  return limited as unknown as JSONRPCMessage;
+}
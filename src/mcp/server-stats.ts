@@ -0,0 +1,128 @@
+// ============================================================================
+// AEGIS - サーバー統計カウンター
+// statsツールで返す実行中サーバーの累積カウンター（HTTPの /metrics でも公開する）
+// （Node.jsのイベントループ上で更新されるためロックは不要）
+// ============================================================================
+
+import type { PolicyDecision } from '../types/index.js';
+
+export interface ServerStatsSnapshot {
+  uptimeSeconds: number;
+  totalRequests: number;
+  requestsByMethod: Record<string, number>;
+  decisions: Record<PolicyDecision['decision'], number>;
+  cache: {
+    hits: number;
+    misses: number;
+  };
+}
+
+// ポリシー判定時間のヒストグラムのバケット上限（秒）
+export const EVALUATION_LATENCY_BUCKETS = [0.05, 0.1, 0.25, 0.5, 1, 2.5, 5, 10, 20];
+
+// リクエスト（受信から応答の送信まで）の処理時間のヒストグラムのバケット上限（秒）
+// pingなどの軽いメソッドも区別できるよう、判定時間より細かい値から始める
+export const REQUEST_LATENCY_BUCKETS = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1, 2.5, 5, 10, 30];
+
+export interface LatencyHistogram {
+  // le以下だった判定の累積数（bucketsと同じ順）
+  buckets: Array<{ le: number; count: number }>;
+  sumSeconds: number;
+  count: number;
+}
+
+interface HistogramState {
+  buckets: number[];
+  sumSeconds: number;
+  count: number;
+}
+
+export class ServerStats {
+  private startedAt: number;
+  private totalRequests = 0;
+  private requestsByMethod = new Map<string, number>();
+  private decisions: Record<PolicyDecision['decision'], number> = { PERMIT: 0, DENY: 0, INDETERMINATE: 0 };
+  private cacheHits = 0;
+  private cacheMisses = 0;
+  private evaluationBuckets = EVALUATION_LATENCY_BUCKETS.map(() => 0);
+  private evaluationSumSeconds = 0;
+  private evaluationCount = 0;
+  private requestLatencyByMethod = new Map<string, HistogramState>();
+
+  constructor(now: number = Date.now()) {
+    this.startedAt = now;
+  }
+
+  recordRequest(method: string): void {
+    this.totalRequests++;
+    this.requestsByMethod.set(method, (this.requestsByMethod.get(method) ?? 0) + 1);
+  }
+
+  recordDecision(decision: PolicyDecision['decision']): void {
+    this.decisions[decision]++;
+  }
+
+  recordCacheLookup(hit: boolean): void {
+    if (hit) {
+      this.cacheHits++;
+    } else {
+      this.cacheMisses++;
+    }
+  }
+
+  recordEvaluation(durationMs: number): void {
+    const seconds = durationMs / 1000;
+    EVALUATION_LATENCY_BUCKETS.forEach((le, index) => {
+      if (seconds <= le) {
+        this.evaluationBuckets[index]++;
+      }
+    });
+    this.evaluationSumSeconds += seconds;
+    this.evaluationCount++;
+  }
+
+  recordRequestDuration(method: string, durationMs: number): void {
+    const seconds = durationMs / 1000;
+    const histogram = this.requestLatencyByMethod.get(method) ?? { buckets: REQUEST_LATENCY_BUCKETS.map(() => 0), sumSeconds: 0, count: 0 };
+    REQUEST_LATENCY_BUCKETS.forEach((le, index) => {
+      if (seconds <= le) {
+        histogram.buckets[index]++;
+      }
+    });
+    histogram.sumSeconds += seconds;
+    histogram.count++;
+    this.requestLatencyByMethod.set(method, histogram);
+  }
+
+  /**
+   * メソッドごとのリクエスト処理時間（応答を返したリクエストのみ）
+   */
+  requestLatency(): Record<string, LatencyHistogram> {
+    return Object.fromEntries([...this.requestLatencyByMethod].map(([method, histogram]) => [method, {
+      buckets: REQUEST_LATENCY_BUCKETS.map((le, index) => ({ le, count: histogram.buckets[index] })),
+      sumSeconds: histogram.sumSeconds,
+      count: histogram.count
+    }]));
+  }
+
+  evaluationLatency(): LatencyHistogram {
+    return {
+      buckets: EVALUATION_LATENCY_BUCKETS.map((le, index) => ({ le, count: this.evaluationBuckets[index] })),
+      sumSeconds: this.evaluationSumSeconds,
+      count: this.evaluationCount
+    };
+  }
+
+  snapshot(now: number = Date.now()): ServerStatsSnapshot {
+    return {
+      uptimeSeconds: Math.floor((now - this.startedAt) / 1000),
+      totalRequests: this.totalRequests,
+      requestsByMethod: Object.fromEntries(this.requestsByMethod),
+      decisions: { ...this.decisions },
+      cache: {
+        hits: this.cacheHits,
+        misses: this.cacheMisses
+      }
+    };
+  }
+}
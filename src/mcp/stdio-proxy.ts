@@ -4,6 +4,7 @@
 // ============================================================================
 
 import { StdioServerTransport } from '@modelcontextprotocol/sdk/server/stdio.js';
+import type { Transport } from '@modelcontextprotocol/sdk/shared/transport.js';
 import { Server } from '@modelcontextprotocol/sdk/server/index.js';
 import { 
   CallToolRequestSchema, 
@@ -12,8 +13,17 @@ import {
   ReadResourceRequestSchema,
   InitializeRequestSchema,
   InitializedNotificationSchema,
+  SetLevelRequestSchema,
+  ListPromptsRequestSchema,
+  GetPromptRequestSchema,
+  CompleteRequestSchema,
+  ListResourceTemplatesRequestSchema,
+  SubscribeRequestSchema,
+  UnsubscribeRequestSchema,
   LATEST_PROTOCOL_VERSION
 } from '@modelcontextprotocol/sdk/types.js';
+import { z } from 'zod';
+import type { CallToolResult, JSONRPCMessage } from '@modelcontextprotocol/sdk/types.js';
 import type { 
   DecisionContext, 
   AccessControlResult,
@@ -60,12 +70,25 @@ import { PolicyLoader } from '../policies/policy-loader.js';
 import { RealTimeAnomalyDetector } from '../audit/real-time-anomaly-detector.js';
 import { IntelligentCacheSystem } from '../performance/intelligent-cache-system.js';
 import { BatchJudgmentSystem } from '../performance/batch-judgment-system.js';
-import { MCPPolicyProxyBase } from './base-proxy.js';
-import { CIRCUIT_BREAKER, CACHE, BATCH, TIMEOUTS, AUDIT, MONITORING } from '../constants/index.js';
+import { MCPPolicyProxyBase, RESOURCE_TEMPLATES } from './base-proxy.js';
+import { AegisError, ErrorHandler } from '../utils/error-handler.js';
+import type { RuntimeSources } from '../utils/rng.js';
+import { LineSizeLimiter } from './line-size-limiter.js';
+import { ContentLengthStdioTransport } from './content-length-transport.js';
+import { JSONRPC_VERSION, findInvalidJsonRpcVersion } from './jsonrpc-version.js';
+import { findInvalidJsonRpcShape, stripUnknownJsonRpcFields } from './jsonrpc-shape.js';
+import type { Clarification } from './clarification.js';
+import { CIRCUIT_BREAKER, CACHE, BATCH, TIMEOUTS, AUDIT, MONITORING, SERVER } from '../constants/index.js';
+
+// クライアントからの終了指示（MCP標準外の拡張メソッド）
+const ShutdownRequestSchema = z.object({
+  method: z.literal('shutdown'),
+  params: z.optional(z.object({}).passthrough())
+});
 
 // Interface for HTTP proxy to avoid circular dependency
 interface IHttpProxy {
-  addPolicy(name: string, policy: string): void;
+  addPolicy(name: string, policy: string, tags?: string[]): void;
   start(): Promise<void>;
   stop(): Promise<void>;
 }
@@ -88,6 +111,10 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
   
   private upstreamStartPromise: Promise<void> | null = null;
   
+  // 上流へ転送するリクエストのID採番
+  // MCP SDKはリクエストを並行処理するため、Date.now()では同一ミリ秒の転送でIDが衝突する
+  private upstreamRequestSeq = 0;
+  
   // サーキットブレーカー状態管理
   private circuitBreakerState: Map<string, CircuitBreakerState> = new Map();
   
@@ -103,8 +130,8 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
   }> = new Map();
   
 
-  constructor(config: AEGISConfig, logger: Logger, judgmentEngine: AIJudgmentEngine | null) {
-    super(config, logger, judgmentEngine);
+  constructor(config: AEGISConfig, logger: Logger, judgmentEngine: AIJudgmentEngine | null, sources?: RuntimeSources) {
+    super(config, logger, judgmentEngine, sources);
     
     this.policyLoader = new PolicyLoader();
     
@@ -197,7 +224,7 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
     this.apiApp.get('/health', (req, res) => {
       res.json({
         status: 'healthy',
-        version: '1.0.0',
+        version: this.serverInfo.version,
         mode: 'stdio',
         policies: this.policyLoader.getAllPolicies().length,
         aiEnabled: !!this.judgmentEngine,
@@ -412,23 +439,29 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
     // ポリシー評価テストAPI
     this.apiApp.post('/api/test/evaluate', async (req, res) => {
       try {
-        const { context, policyId } = req.body;
+        const { context, policyId, policy: inlinePolicy } = req.body;
         
-        if (!context || !policyId) {
-          return res.status(400).json({ error: 'Missing context or policyId' });
+        // ポリシーはpolicyId（登録済み）またはpolicy（本文）で指定する
+        if (!context || (!policyId && typeof inlinePolicy !== 'string')) {
+          return res.status(400).json({ error: 'Missing context, or neither policy nor policyId was provided' });
         }
         
-        // ポリシーローダーからポリシーを取得
-        const { policyLoader } = await import('../policies/policy-loader.js');
-        const policy = policyLoader.getPolicy(policyId);
-        
-        if (!policy) {
-          return res.status(404).json({ error: 'Policy not found' });
+        let policyText: string;
+        if (typeof inlinePolicy === 'string') {
+          policyText = inlinePolicy;
+        } else {
+          // ポリシーローダーからポリシーを取得
+          const { policyLoader } = await import('../policies/policy-loader.js');
+          const policy = policyLoader.getPolicy(policyId);
+          
+          if (!policy) {
+            return res.status(404).json({ error: 'Policy not found' });
+          }
+          policyText = policyLoader.getPolicyText(policy);
         }
         
         // AIPolicyEngineで評価実行
         const startTime = Date.now();
-        const policyText = typeof policy.policy === 'string' ? policy.policy : JSON.stringify(policy.policy);
         const decision = await this.aiPolicyEngine.decide(context, policyText);
         const processingTime = Date.now() - startTime;
         
@@ -438,7 +471,7 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
           processingTime
         };
         
-        this.logger.info(`Policy evaluation completed for ${policyId}: ${decision.decision}`);
+        this.logger.info(`Policy evaluation completed for ${policyId || 'inline policy'}: ${decision.decision}`);
         res.json(response);
         
       } catch (error) {
@@ -466,6 +499,7 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
         protocolVersion: request.params.protocolVersion,
         clientInfo: request.params.clientInfo
       });
+      this.recordClientCapabilities(request.params.capabilities);
       
       // プロトコルバージョンの確認
       const clientProtocolVersion = request.params.protocolVersion || LATEST_PROTOCOL_VERSION;
@@ -493,31 +527,43 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
           },
           resources: {
             // リソース関連の能力
-            subscribe: false, // リソース購読は未実装
+            subscribe: true, // ポリシーリソースの変更通知（notifications/resources/updated）
             listChanged: false // リソースリスト変更通知は未実装
           },
           prompts: {
-            // プロンプト関連の能力（未実装）
+            // 組み込みプロンプト（evaluate_access）を提供
             listChanged: false
           },
+          completions: {
+            // completion/complete（evaluate_accessとポリシーリソースの引数の補完）
+          },
           logging: {
-            // ロギング関連の能力（未実装）
+            // logging/setLevelによる実行時のログレベル変更に対応
           }
         },
-        serverInfo: {
-          name: 'AEGIS Policy Enforcement Proxy',
-          version: '1.0.0'
-        }
+        serverInfo: this.serverInfo
       };
     });
     
     // リソース読み取りハンドラー
-    this.server.setRequestHandler(ReadResourceRequestSchema, async (request: any) => {
+    this.server.setRequestHandler(ReadResourceRequestSchema, async (request: any, extra?: { requestId?: string | number }) => {
       this.logger.info('Resource read request', { uri: request.params.uri });
       
+      // AEGIS自身のポリシーリソースは上流に転送せず直接返す
+      if (this.isPolicyResourceUri(request.params.uri)) {
+        const policyResource = this.readPolicyResource(request.params.uri);
+        if (!policyResource) {
+          this.createErrorResponse(-32602, `Policy resource not found: ${request.params.uri}`);
+        }
+        return policyResource;
+      }
+      if (this.isAegisResourceUri(request.params.uri)) {
+        return this.readDecisionResource(request.params.uri, this.stdioAgent(), extra?.requestId);
+      }
+      
       try {
         // ポリシー判定実行
-        const decision = await this.enforcePolicy('read', request.params.uri, { request });
+        const decision = this.resolveIndeterminate(await this.enforcePolicy('read', request.params.uri, { request }, extra?.requestId));
         
         if (decision.decision === 'DENY') {
           this.createAccessDeniedError(decision.reason, {
@@ -557,14 +603,19 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
         // リソース一覧取得はポリシー判定をスキップ（リソースアクセス時に判定）
         // 上流サーバーに転送
         const result = await this.forwardToUpstream('resources/list', {});
+        const policyResources = this.listPolicyResources();
         
-        // MCPプロトコルに準拠した形式で返す
+        // MCPプロトコルに準拠した形式で返す（AEGISのポリシーリソースを追加）
         if (result && result.result) {
-          return result.result;
+          const upstreamResult = result.result as ResourcesListResult;
+          return {
+            ...upstreamResult,
+            resources: [...(upstreamResult.resources || []), ...policyResources]
+          };
         }
         
-        // フォールバック（空の配列を返す）
-        return { resources: [] };
+        // フォールバック（ポリシーリソースのみ返す）
+        return { resources: policyResources };
       } catch (error) {
         this.logger.error('List resources error', error);
         throw error;
@@ -572,7 +623,12 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
     });
 
     // ツール実行ハンドラー
-    this.server.setRequestHandler(CallToolRequestSchema, async (request: any) => {
+    this.server.setRequestHandler(CallToolRequestSchema, this.withDeduplication(this.withToolTimeout(async (request: any, extra: {
+      requestId?: string | number;
+      sendNotification?: (notification: any) => Promise<void>;
+    } | undefined, signal: AbortSignal) => {
+      // argumentsを省略した呼び出しも空の引数として扱う
+      request = { ...request, params: this.normalizeToolCallParams(request.params) };
       this.logger.info('🔧 Tool call request', { 
         name: request.params.name,
         params: request.params
@@ -588,9 +644,45 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
       }
       
       try {
+        // 無効化されたツールは存在しないものとして扱う
+        this.assertToolEnabled(request.params.name);
+
+        // エージェントごとのレート制限（判定コンテキストと同じエージェント）
+        const rateLimited = this.checkAgentRateLimit(this.stdioAgent());
+        if (rateLimited) {
+          return rateLimited;
+        }
+        
+        // AEGIS組み込みツールは上流に転送せずに処理（ポリシー判定で許可された場合のみ）
+        if (this.isBuiltinTool(request.params.name)) {
+          const target = this.builtinToolPolicyTarget(request.params.name);
+          const builtinDecision = this.resolveIndeterminate(
+            await this.enforcePolicy(target.action, target.resource, { request }, extra?.requestId, signal)
+          );
+          if (builtinDecision.decision !== 'PERMIT') {
+            this.createAccessDeniedError(builtinDecision.reason, {
+              decision: builtinDecision.decision,
+              confidence: builtinDecision.confidence
+            });
+          }
+          return await this.callBuiltinTool(
+            request.params.name,
+            request.params.arguments,
+            this.createProgressReporter(request, extra),
+            signal
+          );
+        }
+        
+        // 宣言されたinputSchemaで引数を検証（ポリシー判定・転送の前に弾く）
+        const toolName = request.params.name;
+        const argumentError = this.validateToolArguments(toolName, request.params.arguments);
+        if (argumentError) {
+          this.logger.warn(`Rejected tool call: ${(argumentError.content[0] as { text: string }).text}`);
+          return argumentError;
+        }
+        
         // ポリシー判定実行
         // ツール名とリソースの両方を適切に記録
-        const toolName = request.params.name;
         let resourceString = `tool:${toolName}`;
         
         // ファイルシステムツールの場合、ツール名とパスの両方を保持
@@ -598,7 +690,18 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
           resourceString = `${toolName}|file:${request.params.arguments.path}`;
         }
         
-        const decision = await this.enforcePolicy(toolName, resourceString, { request });
+        // 追加情報が必要な判定は、elicitationで集めた回答をコンテキストに加えて再判定する
+        const evaluate = (clarifications?: Clarification[]) =>
+          this.enforcePolicy(toolName, resourceString, { request, clarifications }, extra?.requestId, signal);
+        const clarified = await this.resolveClarification(await evaluate(), evaluate, extra?.requestId, signal);
+        // タイムアウト・キャンセル済みの呼び出しは義務の実行・転送に進まない
+        this.throwIfCancelled(signal);
+        
+        // 登録済みの義務ハンドラーは転送前に実行する（必須の義務が失敗すればDENY）
+        const { result: decision, outcomes: obligationOutcomes } = await this.runObligationHandlers(
+          this.resolveIndeterminate(clarified)
+        );
+        this.throwIfCancelled(signal);
         
         if (decision.decision === 'DENY') {
           this.createAccessDeniedError(decision.reason, {
@@ -620,7 +723,7 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
         // 上流サーバーに転送（プレフィックス付きの名前でルーティング）
         // プレフィックス削除はstdio-router内で行われる
         this.logger.debug('Forwarding to upstream with params:', request.params);
-        const result = await this.forwardToUpstream('tools/call', request.params);
+        const result = await this.forwardToUpstream('tools/call', request.params, signal);
         
         // history-mcpの結果の場合は詳細ログ
         if (request.params.name && request.params.name.startsWith('history-mcp__')) {
@@ -638,7 +741,7 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
         }
         
         // result.resultを返す
-        return result && result.result ? result.result : {};
+        return this.attachObligationOutcomes(result && result.result ? result.result : {}, obligationOutcomes);
       } catch (error) {
         this.logger.error('Tool call error', error);
         
@@ -653,7 +756,7 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
         
         throw error;
       }
-    });
+    })));
 
     // ツール一覧ハンドラー
     this.server.setRequestHandler(ListToolsRequestSchema, async (request: any) => {
@@ -683,40 +786,94 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
         // MCPプロトコルに準拠した形式で返す
         if (result && result.result) {
           const tools = (result.result as any).tools || [];
+          this.rememberToolInputSchemas(tools);
           this.logger.info(`📋 Returning ${tools.length} tools to client`);
           // ツール名をログ出力
           if (tools.length > 0) {
             this.logger.info('📋 Available tools:', tools.map((t: any) => t.name).join(', '));
           }
-          return result.result;
+          return { ...result.result, tools: this.filterEnabledTools(this.withBuiltinTools(tools)) };
         } else if (result && (result as any).tools) {
           // 直接toolsが含まれている場合
           const tools = (result as any).tools || [];
+          this.rememberToolInputSchemas(tools);
           this.logger.info(`📋 Returning ${tools.length} tools to client (direct format)`);
           // ツール名をログ出力
           if (tools.length > 0) {
             this.logger.info('📋 Available tools:', tools.map((t: any) => t.name).join(', '));
           }
-          return { tools };
+          return { tools: this.filterEnabledTools(this.withBuiltinTools(tools)) };
         }
         
-        // フォールバック（空の配列を返す）
-        this.logger.warn('No valid result from upstream, returning empty tools array');
+        // フォールバック（組み込みツールのみ返す）
+        this.logger.warn('No valid result from upstream, returning builtin tools only');
         this.logger.debug('Full result object:', JSON.stringify(result));
-        return { tools: [] };
+        return { tools: this.filterEnabledTools(this.listBuiltinTools()) };
       } catch (error) {
         this.logger.error('List tools error', error);
         throw error;
       }
     });
+
+    // ログレベル変更ハンドラー
+    this.server.setRequestHandler(SetLevelRequestSchema, async (request: any) => {
+      return this.setLogLevel(request.params.level);
+    });
+
+    // 組み込みプロンプト（ポリシー判定プロンプト）
+    this.server.setRequestHandler(ListPromptsRequestSchema, async () => {
+      return { prompts: this.listBuiltinPrompts() };
+    });
+
+    this.server.setRequestHandler(GetPromptRequestSchema, async (request: any) => {
+      return this.getBuiltinPrompt(request.params.name, request.params.arguments);
+    });
+
+    // 引数の補完（evaluate_accessのaction・policy、ポリシーリソースのname）
+    this.server.setRequestHandler(CompleteRequestSchema, async (request: any) => {
+      return this.completeArgument(request.params.ref, request.params.argument);
+    });
+
+    // AEGIS自身のリソースのURIテンプレート（判定リソースなど）
+    this.server.setRequestHandler(ListResourceTemplatesRequestSchema, async () => {
+      return { resourceTemplates: RESOURCE_TEMPLATES };
+    });
+
+    // shutdown: 状態を書き切って空の結果を返し、応答の送信後にプロセスを終了する
+    this.server.setRequestHandler(ShutdownRequestSchema, async (_request: any, extra?: { requestId?: string | number }) => {
+      return this.handleShutdownRequest(extra?.requestId);
+    });
+
+    // ポリシーリソースの変更通知の購読（stdioは接続が1つのため 'default' で管理）
+    this.server.setRequestHandler(SubscribeRequestSchema, async (request: any) => {
+      return this.subscribeResource(request.params.uri);
+    });
+
+    this.server.setRequestHandler(UnsubscribeRequestSchema, async (request: any) => {
+      return this.unsubscribeResource(request.params.uri);
+    });
+  }
+
+  /**
+   * stdioの接続元エージェント。リクエストごとの識別子がないため、--default-agent を明示的な指定として扱う
+   * 未設定なら 'mcp-client'（--require-agent 指定時は -32602）
+   */
+  private stdioAgent(): string {
+    return this.resolveAgent(this.config.mcpProxy?.defaultAgent, 'mcp-client');
   }
 
-  private async enforcePolicy(action: string, resource: string, context: { request?: MCPRequest }): Promise<AccessControlResult> {
+  private async enforcePolicy(
+    action: string,
+    resource: string,
+    context: { request?: MCPRequest; clarifications?: Clarification[] },
+    requestId?: string | number,
+    signal?: AbortSignal
+  ): Promise<AccessControlResult> {
     const startTime = Date.now();
     
     // 基本コンテキスト構築
     const baseContext: DecisionContext = {
-      agent: 'mcp-client', // stdioでは識別子が限定的
+      agent: this.stdioAgent(),
       action,
       resource,
       purpose: (context.request?.params as any)?.purpose || 'general-operation',
@@ -727,8 +884,34 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
       }
     };
     
-    // コンテキスト拡張
-    const enrichedContext = await this.contextCollector.enrichContext(baseContext);
+    // コンテキスト拡張（既定コンテキストの上にリクエストのコンテキストを重ねる）
+    const mergedContext = this.applyDefaultContext(baseContext);
+    this.assertContextMatchesSchema(mergedContext);
+    const enrichedContext = await this.contextCollector.enrichContext(mergedContext);
+
+    // エージェントごとの強制判定（--agent-overrides）はキャッシュ・AI判定より先に適用する
+    const forced = this.forcedAgentDecision(enrichedContext);
+    if (forced) {
+      await this.recordDecisionAudit(enrichedContext, forced, requestId);
+      return {
+        ...forced,
+        processingTime: Date.now() - startTime,
+        policyUsed: 'agent-override',
+        context: enrichedContext
+      };
+    }
+
+    // 許可リスト・拒否リスト（--allowlist / --denylist）に一致すればAI判定を行わない
+    const fastPath = this.fastPathDecision(enrichedContext);
+    if (fastPath) {
+      await this.recordDecisionAudit(enrichedContext, fastPath, requestId);
+      return {
+        ...fastPath,
+        processingTime: Date.now() - startTime,
+        policyUsed: 'fast-path',
+        context: enrichedContext
+      };
+    }
 
     // 適用ポリシー選択（設定ファイルから）
     const activePolicies = this.policyLoader.getActivePolicies();
@@ -737,13 +920,20 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
     if (activePolicies.length > 0) {
       // 優先度順（priority降順）で最初のアクティブポリシーを使用
       const selectedPolicy = activePolicies[0];
-      policy = this.policyLoader.formatPolicyForAI(selectedPolicy);
+      policy = this.withAgentPolicy(enrichedContext, this.policyLoader.formatPolicyForAI(selectedPolicy));
       this.logger.info(`Using policy: ${selectedPolicy.name} (priority: ${selectedPolicy.metadata.priority})`);
     }
 
     // キャッシュから判定結果を確認
-    const cachedResult = await this.intelligentCacheSystem.get(enrichedContext, policy || '', enrichedContext.environment);
-    if (cachedResult) {
+    const cached = await this.intelligentCacheSystem.get(enrichedContext, policy || '', enrichedContext.environment);
+    if (cached) {
+      // 時間帯・回数の制約はキャッシュした判定でも要求ごとに評価する
+      // cached: true にして、最初の要求のプロンプト・推定トークン数を監査ログに残さない
+      const cachedResult = this.markAgentOverride(enrichedContext, this.enforceStructuredConstraints(
+        { ...cached, metadata: { ...cached.metadata, cached: true } },
+        enrichedContext
+      ));
+      this.stats.recordCacheLookup(true);
       this.logger.debug('Using cached decision result', {
         action,
         resource,
@@ -772,6 +962,7 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
       } catch (auditError) {
         this.logger.warn('Failed to record cached result audit entry', auditError);
       }
+      await this.recordDecisionAudit(enrichedContext, cachedResult, requestId);
 
       return {
         ...cachedResult,
@@ -784,11 +975,12 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
     if (!policy) {
       // フォールバック: 従来のポリシーマップから選択
       const policyName = await this.selectApplicablePolicy(baseContext);
-      policy = this.policies.get(policyName || 'default-policy') || null;
+      policy = this.withAgentPolicy(enrichedContext, this.policies.get(policyName || 'default-policy') || null);
     }
     
     if (!policy) {
       this.logger.warn(`No policy found for resource: ${resource}`);
+      await this.recordDecisionAudit(enrichedContext, { decision: 'INDETERMINATE', confidence: 0.0 }, requestId);
       // ポリシーがない場合はセキュアなデフォルトでINDETERMINATEを返す
       return {
         decision: 'INDETERMINATE',
@@ -797,17 +989,16 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
         processingTime: Date.now() - startTime,
         policyUsed: 'no-policy-found',
         constraints: ['手動承認が必要'],
-        obligations: ['ポリシー管理者に通知']
+        obligations: ['ポリシー管理者に通知'],
+        metadata: { indeterminateSource: 'no-match' }
       };
     }
     
-    // AI判定実行にタイムアウトを設定
-    const decision = await Promise.race([
-      this.aiPolicyEngine.decide(enrichedContext, policy),
-      new Promise<never>((_, reject) => {
-        setTimeout(() => reject(new Error('AI policy judgment timeout')), TIMEOUTS.POLICY_DECISION);
-      })
-    ]);
+    // AI判定実行にタイムアウトを設定（超えた場合はDENY）
+    const aiDecision = await this.decideWithEvalTimeout(enrichedContext, policy, signal);
+    const decision = this.markAgentOverride(enrichedContext, this.enforceStructuredConstraints(aiDecision, enrichedContext));
+    
+    this.stats.recordCacheLookup(decision.metadata?.cached === true);
     
     const result = {
       ...decision,
@@ -816,6 +1007,9 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
       context: enrichedContext
     };
 
+    // 判定監査ログ（失敗時はフェイルクローズ）
+    await this.recordDecisionAudit(enrichedContext, decision, requestId);
+
     // 高度な監査システムに判定結果を記録
     try {
       const outcome = decision.decision === 'PERMIT' ? 'SUCCESS' : 
@@ -851,16 +1045,19 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
         });
       }
 
-      // 新しい判定結果をキャッシュに保存
-      try {
-        await this.intelligentCacheSystem.set(
-          enrichedContext,
-          policy || '',
-          enrichedContext.environment,
-          result
-        );
-      } catch (cacheError) {
-        this.logger.warn('Failed to cache decision result', cacheError);
+      // 新しい判定結果をキャッシュに保存（構造化制約の評価前の判定を保存する）
+      // タイムアウト・判定の打ち切り・AI判定の障害によるDENY/INDETERMINATEは保存しない
+      if (this.isCacheableDecision(aiDecision, signal)) {
+        try {
+          await this.intelligentCacheSystem.set(
+            enrichedContext,
+            policy || '',
+            enrichedContext.environment,
+            { ...result, ...aiDecision }
+          );
+        } catch (cacheError) {
+          this.logger.warn('Failed to cache decision result', cacheError);
+        }
       }
     } catch (auditError) {
       // 監査記録の失敗も重大なセキュリティ問題として扱う
@@ -877,28 +1074,27 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
 
 
   /**
-   * キャンセルリクエストを上流サーバーに転送
+   * キャンセルを上流サーバーに転送（転送済みのリクエストを送った上流にnotifications/cancelledを送る）
    */
-  private async forwardCancelToUpstream(requestId: string | number): Promise<void> {
+  private forwardCancelToUpstream(requestId: string | number): void {
     try {
-      // stdioルーター経由でキャンセル通知を送信
-      const cancelNotification = {
-        jsonrpc: '2.0',
-        method: '$/cancelRequest',
-        params: { id: requestId }
-      };
-      
-      // stdioRouterのrouteRequestメソッドを使用してキャンセル通知を送信
-      // 通知なのでレスポンスは期待しない
-      await this.stdioRouter.routeRequest(cancelNotification).catch(() => {
-        // キャンセル通知のエラーは無視
-      });
+      this.stdioRouter.cancelRequest(requestId, 'Cancelled by client or tool timeout');
     } catch (error) {
       this.logger.error('Failed to forward cancel notification:', error);
     }
   }
 
-  private async forwardToUpstream(method: string, params: Record<string, any> | undefined): Promise<UpstreamResponse> {
+  /**
+   * 上流サーバーに転送する
+   * signalが中断されたら応答を待たずに打ち切り、上流にもキャンセルを通知する
+   */
+  private async forwardToUpstream(
+    method: string,
+    params: Record<string, any> | undefined,
+    signal?: AbortSignal
+  ): Promise<UpstreamResponse> {
+    this.throwIfCancelled(signal);
+
     // サーキットブレーカーチェック
     if (this.isCircuitBreakerOpen(method)) {
       throw new Error(`Circuit breaker is open for ${method}`);
@@ -917,7 +1113,7 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
       // stdioルーター経由でリクエストを転送
       const request = {
         jsonrpc: '2.0',
-        id: Date.now(),
+        id: ++this.upstreamRequestSeq,
         method,
         params
       };
@@ -928,13 +1124,31 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
         paramsKeys: params ? Object.keys(params) : []
       });
       
-      // タイムアウト付きでリクエスト実行
-      const response = await Promise.race([
-        this.stdioRouter.routeRequest(request),
-        new Promise<never>((_, reject) => {
-          setTimeout(() => reject(new Error('Upstream request timeout')), TIMEOUTS.UPSTREAM_REQUEST);
-        })
-      ]);
+      // タイムアウト付きでリクエスト実行（中断されたら上流にキャンセルを通知して打ち切る）
+      let onAbort: (() => void) | undefined;
+      const aborted = new Promise<never>((_, reject) => {
+        onAbort = () => {
+          this.forwardCancelToUpstream(request.id);
+          const error = new Error('Request cancelled') as Error & { code: number };
+          error.code = -32800;
+          reject(error);
+        };
+        signal?.addEventListener('abort', onAbort, { once: true });
+      });
+      let response: UpstreamResponse;
+      try {
+        response = await Promise.race([
+          this.stdioRouter.routeRequest(request),
+          new Promise<never>((_, reject) => {
+            setTimeout(() => reject(new Error('Upstream request timeout')), TIMEOUTS.UPSTREAM_REQUEST);
+          }),
+          aborted
+        ]);
+      } finally {
+        if (onAbort) {
+          signal?.removeEventListener('abort', onAbort);
+        }
+      }
       
       this.logger.debug(`Upstream response for ${method}:`, JSON.stringify(response).substring(0, 500));
       
@@ -950,8 +1164,13 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
       
       // JSON-RPCレスポンスから結果を抽出
       if (response.error) {
-        this.recordCircuitBreakerFailure(method);
-        throw new Error(response.error.message || 'Upstream server error');
+        // 上流のエラーコード（-32601 Method not found、-32602 Invalid params等）を
+        // -32603に潰さずそのままクライアントに返す
+        this.createErrorResponse(
+          response.error.code,
+          response.error.message || 'Upstream server error',
+          response.error.data
+        );
       }
       
       // 成功時はサーキットブレーカーをリセット
@@ -961,6 +1180,12 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
       this.logger.debug(`forwardToUpstream returning:`, JSON.stringify(response).substring(0, 200));
       return response;
     } catch (error) {
+      // 上流が正常に応答したJSON-RPCエラーはコードを保持して再送出
+      // （サーバー自体は稼働しているためサーキットブレーカーの失敗には数えない）
+      if (this.isJsonRpcError(error)) {
+        throw error;
+      }
+      
       // 上流サーバーエラーも厳格に処理
       this.recordCircuitBreakerFailure(method);
       this.logger.error(`Upstream forwarding failed for ${method}`, error);
@@ -1140,7 +1365,7 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
     const anomalyReports = await this.advancedAuditSystem.detectAnomalousAccess(threshold);
     // Convert AnomalyReport[] to AnomalyAlert[]
     return anomalyReports.map(report => ({
-      alertId: `alert_${Date.now()}_${Math.random().toString(36).substr(2, 9)}`,
+      alertId: `alert_${this.sources.clock.now()}_${this.sources.rng.next().toString(36).substr(2, 9)}`,
       severity: 'MEDIUM' as const,
       pattern: {
         name: 'anomalous-access',
@@ -1457,9 +1682,13 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
   
   /**
    * claude_desktop_config.jsonの内容をロード
+   * startServersがfalseなら登録のみ行い、上流サーバーは起動しない（--check-config）
    */
-  loadDesktopConfig(config: { mcpServers: Record<string, MCPServerConfig> }): void {
+  loadDesktopConfig(config: { mcpServers: Record<string, MCPServerConfig> }, startServers: boolean = true): void {
     this.stdioRouter.loadServersFromDesktopConfig(config);
+    if (!startServers) {
+      return;
+    }
     
     // 上流サーバーをすぐに起動開始（非同期）
     this.logger.info('Starting upstream servers...');
@@ -1513,16 +1742,104 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
     this.setupNotificationHandling();
     
     // MCPサーバーを作成
-    const transport = new StdioServerTransport();
+    // 1メッセージの最大サイズを超える入力・jsonrpcが"2.0"でない入力・フィールドの型が不正な入力は
+    // トランスポートに渡さず -32600 で応答する
+    // 想定外のトップレベルのフィールドは取り除いて渡す（--strict-protocol では -32600 で応答する）
+    // フレーミング（改行区切り / Content-Length）によらず同じ検査を行う
+    const maxRequestBytes = this.config.mcpProxy?.maxRequestBytes ?? SERVER.DEFAULT_MAX_REQUEST_BYTES;
+    const strictProtocol = this.config.mcpProxy?.strictProtocol ?? false;
+    let transport: Transport;
+    const onOversize = (limit: number) => {
+      this.logger.warn(`Discarding incoming message larger than ${limit} bytes`);
+      this.sendTransportError(transport, new AegisError('Request too large', 'INVALID_REQUEST', {
+        operation: 'jsonrpc-read',
+        details: { maxRequestBytes: limit }
+      }));
+    };
+    const acceptMessage = (body: string) => {
+      const invalid = findInvalidJsonRpcVersion(body);
+      if (invalid) {
+        this.logger.warn('Rejecting message with unsupported jsonrpc version', { version: invalid.version });
+        this.sendTransportError(transport, new AegisError('Invalid Request', 'INVALID_REQUEST', {
+          operation: 'jsonrpc-read',
+          details: { expected: JSONRPC_VERSION, received: invalid.version ?? null }
+        }), invalid.id);
+        return false;
+      }
+      const malformed = findInvalidJsonRpcShape(body, strictProtocol);
+      if (malformed) {
+        this.logger.warn('Rejecting malformed JSON-RPC message', { field: malformed.field });
+        this.sendTransportError(transport, new AegisError(malformed.message, 'INVALID_REQUEST', {
+          operation: 'jsonrpc-read',
+          details: { field: malformed.field }
+        }), malformed.id);
+        return false;
+      }
+      return strictProtocol || (stripUnknownJsonRpcFields(body) ?? true);
+    };
+
+    if (this.config.mcpProxy?.framing === 'lsp') {
+      transport = new ContentLengthStdioTransport(process.stdin, process.stdout, {
+        maxMessageBytes: maxRequestBytes,
+        onOversize,
+        acceptMessage
+      });
+    } else {
+      const input = new LineSizeLimiter(maxRequestBytes, onOversize, line => acceptMessage(line.toString('utf8')))
+        .connectWhenRead(process.stdin);
+      transport = new StdioServerTransport(input);
+    }
+    this.logger.info(`stdio framing: ${this.config.mcpProxy?.framing ?? 'newline'}`);
     
     // MCPサーバーを接続（Claudeからの接続を受け付ける）
     await this.server.connect(transport);
+    this.installParseErrorResponder(transport);
+    this.installHandshakeGuard(transport);
+    this.installMetaEcho(transport);
+    this.installShutdownAcknowledgement(transport);
+    this.installResponseSizeLimit(transport);
+    this.installRequestTracing(transport);
+    this.installRequestTiming(transport);
     this.logger.info('🛡️ AEGIS MCP Proxy (stdio) started and accepting connections');
     
     // ヘルスモニタリングを開始
     this.startSystemHealthMonitoring();
   }
 
+  /**
+   * JSONパース失敗時に -32700 (Parse error) を返す
+   * SDKのトランスポートはパース失敗をonerrorに通知するだけで応答しないため、
+   * クライアントが応答待ちのままハングしないようにここで補完する
+   */
+  private installParseErrorResponder(transport: Transport): void {
+    const protocolOnError = transport.onerror;
+    
+    transport.onerror = (error: Error) => {
+      if (error instanceof SyntaxError) {
+        this.logger.warn('Failed to parse incoming JSON-RPC message', { message: error.message });
+        
+        this.sendTransportError(transport, new AegisError('Parse error', 'PARSE_ERROR', {
+          operation: 'jsonrpc-parse',
+          details: { message: error.message }
+        }));
+      }
+      
+      protocolOnError?.(error);
+    };
+  }
+
+  /**
+   * リクエストとして読み取れなかった入力に対してエラー応答を送信
+   * 壊れた・破棄した行からはidを取り出せないため、JSON-RPC仕様どおりid: nullで応答
+   * （idが読み取れた場合はそのidを返す）
+   */
+  private sendTransportError(transport: Transport, error: AegisError, requestId: string | number | null = null): void {
+    const response = { ...ErrorHandler.createMCPErrorResponse(error), id: requestId };
+    transport.send(response as unknown as JSONRPCMessage).catch(sendError => {
+      this.logger.error('Failed to send error response', sendError);
+    });
+  }
+
   /**
    * 上流サーバーからの通知処理をセットアップ
    */
@@ -1535,10 +1852,35 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
     }) => {
       this.handleUpstreamNotification(event);
     });
+
+    // 上流のツール一覧が変わったら、引数検証に使うツール名・inputSchemaを取り直す
+    this.stdioRouter.on('notification', (event: { from: string; message: { method?: string } }) => {
+      if (event.message.method === 'notifications/tools/list_changed') {
+        void this.refreshToolInputSchemas(event.from);
+      }
+    });
     
     this.logger.info('📡 Notification handling setup complete');
   }
 
+  /**
+   * ツール一覧の変更通知を受けて、引数検証に使うツール名・inputSchemaを取り直す
+   * 取り直すまでの間・取得に失敗した場合は、記録を捨てて上流に判断を委ねる
+   */
+  private async refreshToolInputSchemas(serverName: string): Promise<void> {
+    this.logger.info(`Tool list changed on ${serverName}, refreshing tool schemas`);
+    this.forgetToolInputSchemas();
+    try {
+      const result = await this.forwardToUpstream('tools/list', {});
+      const tools = (result?.result as any)?.tools ?? (result as any)?.tools;
+      if (Array.isArray(tools)) {
+        this.rememberToolInputSchemas(tools);
+      }
+    } catch (error) {
+      this.logger.warn('Failed to refresh tool schemas after tools/list_changed', error);
+    }
+  }
+
   /**
    * 上流サーバーからの通知を処理
    */
@@ -1700,6 +2042,13 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
     throw fullError;
   }
 
+  /**
+   * JSON-RPCエラーコードを持つエラーかどうかを判定
+   */
+  private isJsonRpcError(error: unknown): error is Error & { code: number; data?: any } {
+    return error instanceof Error && Number.isInteger((error as any).code);
+  }
+
   /**
    * アクセス拒否エラー
    */
@@ -1763,6 +2112,9 @@ export class MCPStdioPolicyProxy extends MCPPolicyProxyBase {
       // MCPサーバーを停止
       await this.server.close();
 
+      // 監査ログを書き切ってから停止
+      await this.flushAuditState();
+
       // キャッシュをクリア（機密情報の流出防止）
       this.intelligentCacheSystem.clear();
 
@@ -417,10 +417,35 @@ export class StdioRouter extends EventEmitter {
     });
   }
 
+  /**
+   * 応答待ちのリクエストを打ち切り、送信先の上流サーバーに notifications/cancelled を送る
+   * 応答待ちでないidは無視する
+   */
+  cancelRequest(id: string | number, reason: string): void {
+    const pending = this.pendingRequests.get(id);
+    if (!pending) {
+      return;
+    }
+
+    this.pendingRequests.delete(id);
+    this.removeAllListeners(`response-${id}`);
+    pending.reject(new Error(`Request cancelled: ${reason}`));
+
+    const server = pending.targetServer ? this.upstreamServers.get(pending.targetServer) : undefined;
+    if (server?.connected && server.process) {
+      this.logger.info(`Cancelling request ${id} on ${pending.targetServer}: ${reason}`);
+      server.process.stdin?.write(JSON.stringify({
+        jsonrpc: '2.0',
+        method: 'notifications/cancelled',
+        params: { requestId: id, reason }
+      }) + '\n');
+    }
+  }
+
   /**
    * 複数サーバーからのリスト応答を集約
    */
-  private async aggregateListResponses(method: string, params: any, id: number): Promise<any> {
+  private async aggregateListResponses(method: string, params: any, id: string | number): Promise<any> {
     // デバッグ: 接続中のサーバーを確認
     const connectedServers = Array.from(this.upstreamServers.entries())
       .filter(([_, server]) => server.connected);
@@ -433,15 +458,14 @@ export class StdioRouter extends EventEmitter {
       }
     });
     
-    // リクエストIDカウンター（標準形式）
-    const requestIdBase = typeof id === 'number' ? id : Date.now();
-    
+    // サーバーごとのリクエストID
+    // 数値の連番（id + index）は並行中の別リクエストのIDと衝突するため、元のIDとサーバー名から生成する
     const responses = await Promise.allSettled(
       connectedServers.map(([name, _], index) => 
         this.sendRequestToServer(name, { 
           method, 
           params, 
-          id: requestIdBase + index, // シンプルな数値ID
+          id: `${id}-${name}-${index}`,
           jsonrpc: '2.0' 
         })
       )
@@ -0,0 +1,106 @@
+// ============================================================================
+// AEGIS - ツール引数バリデーター
+// 上流ツールが宣言するinputSchema（JSON Schemaのサブセット）で引数を検証する
+// ============================================================================
+
+export interface ArgumentValidationIssue {
+  path: string;
+  message: string;
+}
+
+type JsonSchema = Record<string, any>;
+
+/**
+ * 値のJSON Schema上の型名を取得
+ */
+function jsonTypeOf(value: unknown): string {
+  if (value === null) return 'null';
+  if (Array.isArray(value)) return 'array';
+  if (typeof value === 'number') return Number.isInteger(value) ? 'integer' : 'number';
+  return typeof value;
+}
+
+function matchesType(value: unknown, expected: string): boolean {
+  const actual = jsonTypeOf(value);
+  if (expected === 'number') {
+    return actual === 'number' || actual === 'integer';
+  }
+  return actual === expected;
+}
+
+/**
+ * JSON Schemaのサブセットで値を検証
+ * 対応キーワード: type, required, properties, additionalProperties(false), enum,
+ * items, minimum, maximum, minLength, maxLength
+ */
+export function validateAgainstSchema(
+  value: unknown,
+  schema: JsonSchema | undefined,
+  path: string = 'arguments'
+): ArgumentValidationIssue[] {
+  if (!schema || typeof schema !== 'object') {
+    return [];
+  }
+
+  const issues: ArgumentValidationIssue[] = [];
+
+  if (schema.type) {
+    const types: string[] = Array.isArray(schema.type) ? schema.type : [schema.type];
+    if (!types.some(type => matchesType(value, type))) {
+      issues.push({ path, message: `must be ${types.join(' or ')}, got ${jsonTypeOf(value)}` });
+      // 型が違う場合は以降のキーワードを評価しても意味がない
+      return issues;
+    }
+  }
+
+  if (Array.isArray(schema.enum) && !schema.enum.some((candidate: unknown) => candidate === value)) {
+    issues.push({ path, message: `must be one of ${schema.enum.map((v: unknown) => JSON.stringify(v)).join(', ')}` });
+  }
+
+  if (typeof value === 'string') {
+    if (typeof schema.minLength === 'number' && value.length < schema.minLength) {
+      issues.push({ path, message: `must be at least ${schema.minLength} characters` });
+    }
+    if (typeof schema.maxLength === 'number' && value.length > schema.maxLength) {
+      issues.push({ path, message: `must be at most ${schema.maxLength} characters` });
+    }
+  }
+
+  if (typeof value === 'number') {
+    if (typeof schema.minimum === 'number' && value < schema.minimum) {
+      issues.push({ path, message: `must be >= ${schema.minimum}` });
+    }
+    if (typeof schema.maximum === 'number' && value > schema.maximum) {
+      issues.push({ path, message: `must be <= ${schema.maximum}` });
+    }
+  }
+
+  if (Array.isArray(value) && schema.items && typeof schema.items === 'object') {
+    value.forEach((item, index) => {
+      issues.push(...validateAgainstSchema(item, schema.items, `${path}[${index}]`));
+    });
+  }
+
+  if (jsonTypeOf(value) === 'object') {
+    const objectValue = value as Record<string, unknown>;
+    const properties: Record<string, JsonSchema> = schema.properties || {};
+
+    if (Array.isArray(schema.required)) {
+      for (const field of schema.required) {
+        if (!(field in objectValue) || objectValue[field] === undefined) {
+          issues.push({ path: `${path}.${field}`, message: 'is required' });
+        }
+      }
+    }
+
+    for (const [key, propertyValue] of Object.entries(objectValue)) {
+      if (key in properties) {
+        issues.push(...validateAgainstSchema(propertyValue, properties[key], `${path}.${key}`));
+      } else if (schema.additionalProperties === false) {
+        issues.push({ path: `${path}.${key}`, message: 'is not allowed' });
+      }
+    }
+  }
+
+  return issues;
+}
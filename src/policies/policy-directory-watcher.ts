@@ -0,0 +1,105 @@
+// ============================================================================
+// AEGIS - ポリシーディレクトリの監視（--watch）
+// .md/.txt ファイルの変更を検知してPolicyLoaderに読み直させ、結果を呼び出し元に通知する
+// 短時間に続く書き込みは1回の読み直しにまとめ、同じファイルの読み直しは順番に行う
+// 読み直しに失敗した場合（書き込み途中など）は通知だけ行い、それまでのポリシーを使い続ける
+// ============================================================================
+
+import { watch, FSWatcher } from 'fs';
+import * as path from 'path';
+import { Logger } from '../utils/logger.js';
+import { TIMEOUTS } from '../constants/index.js';
+import { PolicyLoader, PolicyDefinition, isPolicyFileName } from './policy-loader.js';
+
+const logger = new Logger('policy-watcher');
+
+export type PolicyReloadEvent =
+  | { type: 'updated'; id: string; file: string; policy: PolicyDefinition }
+  | { type: 'removed'; id: string; file: string }
+  | { type: 'failed'; file: string; error: Error };
+
+export class PolicyDirectoryWatcher {
+  private loader: PolicyLoader;
+  private onReload: (event: PolicyReloadEvent) => void;
+  private debounceMs: number;
+  private watcher?: FSWatcher;
+  private timers = new Map<string, NodeJS.Timeout>();
+  private reloads = new Map<string, Promise<void>>();
+
+  constructor(
+    loader: PolicyLoader,
+    onReload: (event: PolicyReloadEvent) => void,
+    debounceMs: number = TIMEOUTS.POLICY_WATCH_DEBOUNCE
+  ) {
+    this.loader = loader;
+    this.onReload = onReload;
+    this.debounceMs = debounceMs;
+  }
+
+  /**
+   * loadPolicyDirectoryで読み込んだディレクトリの監視を開始する
+   */
+  start(): void {
+    const directory = this.loader.getPolicyDirectory();
+    if (!directory) {
+      throw new Error('Load a policy directory before watching it');
+    }
+    if (this.watcher) {
+      return;
+    }
+
+    this.watcher = watch(directory, (_event, filename) => {
+      if (filename && isPolicyFileName(filename.toString())) {
+        this.schedule(filename.toString());
+      }
+    });
+    this.watcher.on('error', error => {
+      logger.error(`Policy directory watcher error: ${directory}`, error);
+    });
+    logger.info(`Watching policy directory: ${directory}`);
+  }
+
+  async stop(): Promise<void> {
+    this.watcher?.close();
+    this.watcher = undefined;
+    for (const timer of this.timers.values()) {
+      clearTimeout(timer);
+    }
+    this.timers.clear();
+    await Promise.all(this.reloads.values());
+  }
+
+  private schedule(file: string): void {
+    clearTimeout(this.timers.get(file));
+    this.timers.set(file, setTimeout(() => {
+      this.timers.delete(file);
+      const previous = this.reloads.get(file) ?? Promise.resolve();
+      const next = previous.then(() => this.reload(file));
+      this.reloads.set(file, next);
+      next.then(() => {
+        if (this.reloads.get(file) === next) {
+          this.reloads.delete(file);
+        }
+      });
+    }, this.debounceMs));
+  }
+
+  private async reload(file: string): Promise<void> {
+    let event: PolicyReloadEvent;
+    try {
+      const policy = await this.loader.reloadPolicyFile(file);
+      event = policy
+        ? { type: 'updated', id: policy.id, file, policy }
+        : { type: 'removed', id: path.basename(file, path.extname(file)), file };
+    } catch (error) {
+      logger.warn(`Keeping the previous policy for ${file}: ${error instanceof Error ? error.message : String(error)}`);
+      event = { type: 'failed', file, error: error instanceof Error ? error : new Error(String(error)) };
+    }
+
+    try {
+      this.onReload(event);
+    } catch (error) {
+      logger.error(`Policy reload handler failed for ${file}`, error);
+    }
+  }
+}
@@ -0,0 +1,54 @@
+// ============================================================================
+// AEGIS - ポリシーファイルのフロントマター（先頭の --- で囲んだYAMLヘッダー）
+// 対応するのは tags のみ（tags: [network, data] / tags: network, data / 「- data」のリスト）
+// それ以外のキーは無視し、ヘッダーを除いた本文と一緒に返す
+// ============================================================================
+
+export interface PolicyFrontMatter {
+  tags: string[];
+  body: string;
+}
+
+const FRONT_MATTER = /^---\r?\n([\s\S]*?)\r?\n---[ \t]*(?:\r?\n|$)/;
+
+function parseTagValue(value: string): string[] {
+  const inline = value.trim().replace(/^\[(.*)\]$/, '$1');
+  return inline.split(',').map(unquote).filter(tag => tag !== '');
+}
+
+function unquote(value: string): string {
+  return value.trim().replace(/^(['"])(.*)\1$/, '$2').trim();
+}
+
+/**
+ * 本文先頭のフロントマターからタグを取り出す（ヘッダーがなければタグなし・本文はそのまま）
+ * タグは小文字にそろえ、重複を除く
+ */
+export function parsePolicyFrontMatter(text: string): PolicyFrontMatter {
+  const match = FRONT_MATTER.exec(text);
+  if (!match) {
+    return { tags: [], body: text };
+  }
+
+  const tags: string[] = [];
+  let inTagList = false;
+  for (const line of match[1].split(/\r?\n/)) {
+    const key = /^([A-Za-z_][\w-]*)\s*:(.*)$/.exec(line);
+    if (key) {
+      inTagList = key[1] === 'tags' && key[2].trim() === '';
+      if (key[1] === 'tags' && !inTagList) {
+        tags.push(...parseTagValue(key[2]));
+      }
+      continue;
+    }
+    const item = /^\s*-\s*(.*)$/.exec(line);
+    if (inTagList && item) {
+      tags.push(unquote(item[1]));
+    }
+  }
+
+  return {
+    tags: [...new Set(tags.map(tag => tag.toLowerCase()).filter(tag => tag !== ''))],
+    body: text.slice(match[0].length)
+  };
+}
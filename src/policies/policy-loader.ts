@@ -5,6 +5,9 @@
 import * as fs from 'fs/promises';
 import * as path from 'path';
 import { Logger } from '../utils/logger.js';
+import { substituteEnvVariables } from '../utils/env-substitution.js';
+import { parsePolicyFrontMatter } from './policy-front-matter.js';
+import { BATCH } from '../constants/index.js';
 import type { IPolicyLoader } from '../types/component-interfaces.js';
 import type { LoadedPolicy } from '../types/enforcement-types.js';
 
@@ -34,9 +37,21 @@ export interface PoliciesConfig {
   policies: PolicyDefinition[];
 }
 
+// ポリシーディレクトリから読み込む自然言語ポリシーの拡張子
+const POLICY_FILE_EXTENSIONS = ['.md', '.txt'];
+
+export function isPolicyFileName(entry: string): boolean {
+  return POLICY_FILE_EXTENSIONS.includes(path.extname(entry).toLowerCase());
+}
+
 export class PolicyLoader implements IPolicyLoader {
   private policiesPath: string;
   private loadedPolicies: Map<string, PolicyDefinition> = new Map();
+  // ディレクトリから読み込んだポリシー（policies.jsonには保存しない）
+  private directoryPolicyIds = new Set<string>();
+  private policyDirectory?: string;
+  // ディレクトリのポリシーで未定義の環境変数を参照していたら読み込みを失敗させる
+  private strictEnv = false;
 
   constructor(policiesPath?: string) {
     // Ensure we use absolute path resolution
@@ -65,6 +80,7 @@ export class PolicyLoader implements IPolicyLoader {
       const config: PoliciesConfig = JSON.parse(data);
       
       this.loadedPolicies.clear();
+      this.directoryPolicyIds.clear();
       
       for (const policy of config.policies) {
         this.loadedPolicies.set(policy.id, policy);
@@ -81,6 +97,162 @@ export class PolicyLoader implements IPolicyLoader {
     }
   }
 
+  setStrictEnv(strict: boolean): void {
+    this.strictEnv = strict;
+  }
+
+  /**
+   * ディレクトリ内の .md / .txt ファイルを自然言語ポリシーとして読み込む
+   * ポリシーIDはファイル名（拡張子なし）、本文の ${VAR} は環境変数で展開する
+   * 先頭のフロントマターの tags は metadata.tags に加え、本文からは除く
+   * ファイルは並列に読み、読めないファイルは警告してスキップする。読み込んだポリシー数を返す
+   */
+  async loadPolicyDirectory(directory: string): Promise<number> {
+    const policyDir = path.isAbsolute(directory) ? directory : path.resolve(process.cwd(), directory);
+    logger.info(`Loading policies from directory: ${policyDir}`);
+
+    let entries: string[];
+    try {
+      entries = await fs.readdir(policyDir);
+    } catch (error) {
+      throw new Error(`Policy directory loading failed: ${error instanceof Error ? error.message : 'Unknown error'}`);
+    }
+
+    this.policyDirectory = policyDir;
+    const startTime = Date.now();
+    const files = entries.filter(isPolicyFileName).sort();
+    const results = await this.readPolicyFiles(policyDir, files);
+
+    // --strict-env 指定時は読めないファイルがあれば何も登録せずに失敗する（スキップしない）
+    const firstFailure = results.find((result): result is Error => result instanceof Error);
+    if (this.strictEnv && firstFailure) {
+      throw firstFailure;
+    }
+
+    // 読み込みは並列、登録はファイル名順（同じIDの上書き順を一定にする）
+    let loaded = 0;
+    const failures: Array<{ file: string; error: Error }> = [];
+    results.forEach((result, index) => {
+      const entry = files[index];
+      if (result instanceof Error) {
+        failures.push({ file: entry, error: result });
+        logger.warn(`Skipping policy file ${entry}: ${result.message}`);
+        return;
+      }
+
+      const policy = result;
+      if (this.loadedPolicies.has(policy.id) && !this.directoryPolicyIds.has(policy.id)) {
+        logger.warn(`Policy ${policy.id} from ${entry} overrides a policy defined in ${this.policiesPath}`);
+      }
+
+      this.loadedPolicies.set(policy.id, policy);
+      this.directoryPolicyIds.add(policy.id);
+      loaded++;
+      logger.info(`Loaded policy: ${policy.id} (from ${entry})`);
+    });
+
+    const elapsed = Date.now() - startTime;
+    if (failures.length > 0) {
+      logger.warn(`Loaded ${loaded} policies from ${policyDir} in ${elapsed}ms; ${failures.length} file(s) failed: ${failures.map(failure => failure.file).join(', ')}`);
+    } else {
+      logger.info(`Successfully loaded ${loaded} policies from ${policyDir} in ${elapsed}ms`);
+    }
+    return loaded;
+  }
+
+  /**
+   * ポリシーファイルを同時にconcurrency件まで並列に読む（失敗したファイルはErrorを返す）
+   */
+  private async readPolicyFiles(
+    policyDir: string,
+    files: string[],
+    concurrency: number = BATCH.POLICY_LOAD_CONCURRENCY
+  ): Promise<Array<PolicyDefinition | Error>> {
+    const results = new Array<PolicyDefinition | Error>(files.length);
+    let next = 0;
+    const worker = async (): Promise<void> => {
+      while (next < files.length) {
+        const index = next++;
+        try {
+          results[index] = await this.readPolicyFile(policyDir, files[index]);
+        } catch (error) {
+          results[index] = error instanceof Error ? error : new Error(String(error));
+        }
+      }
+    };
+
+    await Promise.all(Array.from({ length: Math.min(concurrency, files.length) }, worker));
+    return results;
+  }
+
+  /**
+   * 読み込み済みのポリシーディレクトリ内の1ファイルを読み直す（--watch用）
+   * 読み込み・環境変数の展開に失敗した場合と本文が空の場合（書き込み途中）は例外を投げ、
+   * それまでのポリシーをそのまま使い続ける。ファイルが削除されていればポリシーを取り除きnullを返す
+   */
+  async reloadPolicyFile(entry: string): Promise<PolicyDefinition | null> {
+    if (!this.policyDirectory) {
+      throw new Error('No policy directory has been loaded');
+    }
+    const id = path.basename(entry, path.extname(entry));
+
+    let policy: PolicyDefinition;
+    try {
+      policy = await this.readPolicyFile(this.policyDirectory, entry);
+    } catch (error) {
+      if ((error as NodeJS.ErrnoException).code === 'ENOENT') {
+        if (this.directoryPolicyIds.delete(id)) {
+          this.loadedPolicies.delete(id);
+          logger.info(`Removed policy: ${id} (${entry} deleted)`);
+        }
+        return null;
+      }
+      throw error;
+    }
+    if (policy.policy.content === '') {
+      throw new Error(`Policy ${entry}: file is empty`);
+    }
+
+    this.loadedPolicies.set(id, policy);
+    this.directoryPolicyIds.add(id);
+    logger.info(`Reloaded policy: ${id} (from ${entry})`);
+    return policy;
+  }
+
+  getPolicyDirectory(): string | undefined {
+    return this.policyDirectory;
+  }
+
+  /**
+   * ポリシーファイルを読み、ID＝ファイル名（拡張子なし）の自然言語ポリシーにする
+   */
+  private async readPolicyFile(policyDir: string, entry: string): Promise<PolicyDefinition> {
+    const id = path.basename(entry, path.extname(entry));
+    const raw = await fs.readFile(path.join(policyDir, entry), 'utf-8');
+    const { tags, body } = parsePolicyFrontMatter(raw);
+    let content: string;
+    try {
+      content = substituteEnvVariables(body, process.env, { strict: this.strictEnv });
+    } catch (error) {
+      throw new Error(`Policy ${entry}: ${error instanceof Error ? error.message : 'Unknown error'}`);
+    }
+
+    return {
+      id,
+      name: id,
+      version: '1.0.0',
+      status: 'active',
+      type: 'natural-language',
+      policy: { content: content.trim() },
+      metadata: {
+        createdAt: new Date().toISOString(),
+        createdBy: 'policy-directory',
+        tags: ['file', ...tags.filter(tag => tag !== 'file')],
+        priority: 100
+      }
+    };
+  }
+
   getPolicy(policyId: string): PolicyDefinition | undefined {
     return this.loadedPolicies.get(policyId);
   }
@@ -105,6 +277,23 @@ export class PolicyLoader implements IPolicyLoader {
 
   async reloadPolicies(): Promise<void> {
     await this.loadPolicies();
+    if (this.policyDirectory) {
+      await this.loadPolicyDirectory(this.policyDirectory);
+    }
+  }
+
+  /**
+   * AI判定に渡すポリシー本文を取得
+   * ディレクトリから読み込んだ自然言語ポリシーはファイル内容をそのまま返す
+   */
+  getPolicyText(policy: PolicyDefinition): string {
+    if (typeof policy.policy === 'string') {
+      return policy.policy;
+    }
+    if (this.directoryPolicyIds.has(policy.id) && typeof policy.policy.content === 'string') {
+      return policy.policy.content;
+    }
+    return JSON.stringify(policy.policy);
   }
 
   async createPolicy(policy: Omit<PolicyDefinition, 'metadata'> & { metadata?: Partial<PolicyMetadata> }): Promise<string> {
@@ -165,6 +354,7 @@ export class PolicyLoader implements IPolicyLoader {
     try {
       const config: PoliciesConfig = {
         policies: Array.from(this.loadedPolicies.values())
+          .filter(policy => !this.directoryPolicyIds.has(policy.id))
       };
 
       const data = JSON.stringify(config, null, 2);
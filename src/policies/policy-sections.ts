@@ -0,0 +1,86 @@
+// ============================================================================
+// AEGIS - ポリシー本文のセクション（Markdownの見出しで区切った範囲）
+// check_policies の scope で、大きなポリシーから判定に関係するセクションだけをプロンプトに含めるために使う
+// セクションは見出しの行から、同じかそれより上位の次の見出しの直前まで（下位の見出しを含む）
+// ============================================================================
+
+export interface PolicySectionSelection {
+  text: string;
+  // 見つからなかったセクション名
+  missing: string[];
+  // 本文にあるセクション名（出現順）
+  available: string[];
+}
+
+const HEADING = /^(#{1,6})[ \t]+(.+?)[ \t]*#*[ \t]*$/;
+const FENCE = /^[ \t]*(```|~~~)/;
+
+interface Heading {
+  line: number;
+  level: number;
+  title: string;
+}
+
+function normalizeTitle(title: string): string {
+  return title.trim().toLowerCase();
+}
+
+// コードブロック内の「#」は見出しとして扱わない
+function findHeadings(lines: string[]): Heading[] {
+  const headings: Heading[] = [];
+  let inFence = false;
+  lines.forEach((line, index) => {
+    if (FENCE.test(line)) {
+      inFence = !inFence;
+      return;
+    }
+    const match = inFence ? null : HEADING.exec(line);
+    if (match) {
+      headings.push({ line: index, level: match[1].length, title: match[2] });
+    }
+  });
+  return headings;
+}
+
+/**
+ * 指定した名前（見出しの文字列、大文字小文字は無視）のセクションだけを本文の順で取り出す
+ * 入れ子のセクションを重ねて指定しても同じ行は1回だけ含める
+ */
+export function selectPolicySections(policy: string, scope: string[]): PolicySectionSelection {
+  const lines = policy.split(/\r?\n/);
+  const headings = findHeadings(lines);
+  const included = new Array<boolean>(lines.length).fill(false);
+
+  const missing: string[] = [];
+  for (const name of scope) {
+    const matches = headings.filter(heading => normalizeTitle(heading.title) === normalizeTitle(name));
+    if (matches.length === 0) {
+      missing.push(name);
+    }
+    for (const heading of matches) {
+      const next = headings.find(other => other.line > heading.line && other.level <= heading.level);
+      included.fill(true, heading.line, next ? next.line : lines.length);
+    }
+  }
+
+  // 離れたセクションの間は空行1つで区切る
+  const parts: string[] = [];
+  let current: string[] = [];
+  lines.forEach((line, index) => {
+    if (included[index]) {
+      current.push(line);
+    } else if (current.length > 0) {
+      parts.push(current.join('\n').trim());
+      current = [];
+    }
+  });
+  if (current.length > 0) {
+    parts.push(current.join('\n').trim());
+  }
+
+  return {
+    text: parts.join('\n\n'),
+    missing,
+    available: headings.map(heading => heading.title)
+  };
+}
@@ -0,0 +1,54 @@
+// ============================================================================
+// AEGIS - 許可リスト・拒否リストによる高速判定（--allowlist / --denylist）
+// 無条件に許可・拒否するリソースはAI判定を通さずに即座に判定する
+// 1行に「action resource-glob」の形式（actionは完全一致、* は任意のaction）
+//   read file://docs/**
+//   * /prod/secrets/*
+// globは rule-policy.ts と同じ（* は / 以外、** は / を含む任意の文字列、? は / 以外の任意の1文字）
+// 空行と # で始まる行は無視する
+// ============================================================================
+
+import * as fs from 'fs';
+import type { DecisionContext } from '../types/index.js';
+import { globToRegExp } from './rule-policy.js';
+
+export interface AccessListEntry {
+  line: number;
+  action: string;
+  resource: string;
+  pattern: RegExp;
+}
+
+const ENTRY_LINE = /^(\S+)\s+(\S+)$/;
+
+/**
+ * リスト本文を解釈する。形式に合わない行は行番号付きで例外
+ */
+export function parseAccessList(text: string, source: string): AccessListEntry[] {
+  const entries: AccessListEntry[] = [];
+  for (const [index, raw] of text.split(/\r?\n/).entries()) {
+    const line = raw.trim();
+    if (line === '' || line.startsWith('#')) {
+      continue;
+    }
+    const match = line.match(ENTRY_LINE);
+    if (!match) {
+      throw new Error(`Invalid entry at line ${index + 1} (expected "action resource-glob"): ${source}`);
+    }
+    entries.push({ line: index + 1, action: match[1], resource: match[2], pattern: globToRegExp(match[2]) });
+  }
+  return entries;
+}
+
+export function loadAccessList(filePath: string): AccessListEntry[] {
+  return parseAccessList(fs.readFileSync(filePath, 'utf-8'), filePath);
+}
+
+/**
+ * リクエストに一致する最初のエントリ（なければnull）
+ */
+export function findAccessListMatch(entries: AccessListEntry[], context: DecisionContext): AccessListEntry | null {
+  return entries.find(entry =>
+    (entry.action === '*' || entry.action === context.action) && entry.pattern.test(context.resource)
+  ) ?? null;
+}
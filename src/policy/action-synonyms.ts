@@ -0,0 +1,59 @@
+// ============================================================================
+// AEGIS - actionの同義語の正規化（--action-synonyms）
+// "remove" / "rm" と "delete" のように同じ意味の動詞を1つの表記にそろえ、
+// AI判定とポリシーの適用が呼び出し側の言い回しで揺れないようにする
+// ============================================================================
+
+import * as fs from 'fs';
+
+// 正規の表記 → 同義語の一覧
+export type ActionSynonyms = Record<string, string[]>;
+
+export const DEFAULT_ACTION_SYNONYMS: ActionSynonyms = {
+  create: ['add', 'insert', 'new', 'post'],
+  read: ['get', 'view', 'fetch', 'show', 'cat'],
+  update: ['edit', 'modify', 'patch', 'put', 'change'],
+  delete: ['remove', 'rm', 'del', 'destroy', 'erase', 'unlink']
+};
+
+/**
+ * 同義語ファイル（JSON）を読み込み、既定の同義語に追加する
+ * { "delete": ["purge"], "execute": ["run", "exec"] }
+ */
+export function loadActionSynonyms(filePath: string, base: ActionSynonyms = DEFAULT_ACTION_SYNONYMS): ActionSynonyms {
+  const parsed = JSON.parse(fs.readFileSync(filePath, 'utf-8'));
+  if (parsed === null || typeof parsed !== 'object' || Array.isArray(parsed)) {
+    throw new Error(`Action synonyms must be a JSON object: ${filePath}`);
+  }
+
+  const merged: ActionSynonyms = { ...base };
+  for (const [canonical, synonyms] of Object.entries(parsed)) {
+    if (!Array.isArray(synonyms) || synonyms.some(synonym => typeof synonym !== 'string' || synonym === '')) {
+      throw new Error(`Synonyms for ${canonical} must be an array of strings: ${filePath}`);
+    }
+    merged[canonical] = [...(merged[canonical] ?? []), ...synonyms];
+  }
+  return merged;
+}
+
+/**
+ * 同義語（小文字）から正規の表記を引く表を作る
+ * 後から定義した正規の表記が優先される
+ */
+export function buildActionLookup(synonyms: ActionSynonyms): Map<string, string> {
+  const lookup = new Map<string, string>();
+  for (const [canonical, words] of Object.entries(synonyms)) {
+    lookup.set(canonical.toLowerCase(), canonical);
+    for (const word of words) {
+      lookup.set(word.toLowerCase(), canonical);
+    }
+  }
+  return lookup;
+}
+
+/**
+ * actionを正規の表記にする（大文字小文字・前後の空白は無視、未知の語はそのまま返す）
+ */
+export function canonicalizeAction(action: string, lookup: Map<string, string>): string {
+  return lookup.get(action.trim().toLowerCase()) ?? action;
+}
@@ -0,0 +1,44 @@
+// ============================================================================
+// AEGIS - エージェントごとのポリシー上書き（--agent-overrides）
+// 特定のエージェント（緊急用の管理者など）について、判定を強制（force-permit / force-deny）するか、
+// 評価するポリシーに追加のポリシーを付け加える（append-policy）
+// ============================================================================
+
+import * as fs from 'fs';
+
+export type AgentOverrideMode = 'force-permit' | 'force-deny' | 'append-policy';
+
+export const AGENT_OVERRIDE_MODES: readonly AgentOverrideMode[] = ['force-permit', 'force-deny', 'append-policy'];
+
+export interface AgentOverride {
+  mode: AgentOverrideMode;
+  // append-policy で付け加えるポリシー本文
+  policy?: string;
+}
+
+function isAgentOverrideMode(value: unknown): value is AgentOverrideMode {
+  return typeof value === 'string' && (AGENT_OVERRIDE_MODES as readonly string[]).includes(value);
+}
+
+/**
+ * 上書き設定ファイル（JSON）を読み込む。キーはエージェントID
+ * { "break-glass-admin": { "mode": "force-permit" }, "intern": { "mode": "append-policy", "policy": "..." } }
+ */
+export function loadAgentOverrides(filePath: string): Map<string, AgentOverride> {
+  const parsed = JSON.parse(fs.readFileSync(filePath, 'utf-8'));
+  if (parsed === null || typeof parsed !== 'object' || Array.isArray(parsed)) {
+    throw new Error(`Agent overrides must be a JSON object: ${filePath}`);
+  }
+
+  const overrides = new Map<string, AgentOverride>();
+  for (const [agent, value] of Object.entries(parsed as Record<string, any>)) {
+    if (!isAgentOverrideMode(value?.mode)) {
+      throw new Error(`Agent override for ${agent} must have mode ${AGENT_OVERRIDE_MODES.join(', ')}: ${filePath}`);
+    }
+    if (value.mode === 'append-policy' && (typeof value.policy !== 'string' || value.policy.trim() === '')) {
+      throw new Error(`Agent override for ${agent} needs a policy to append: ${filePath}`);
+    }
+    overrides.set(agent, value.mode === 'append-policy' ? { mode: value.mode, policy: value.policy } : { mode: value.mode });
+  }
+  return overrides;
+}
@@ -3,20 +3,78 @@
  * AI判定のみを使用するシンプルなポリシーエンジン
  */
 
+import { createHash } from 'crypto';
 import { PolicyDecision, DecisionContext } from '../types';
 import { logger } from '../utils/logger';
 import { AIJudgmentEngine } from '../ai/judgment-engine';
+import { parseRulePolicy, evaluateRules } from './rule-policy';
+import { TIMEOUTS } from '../constants/index.js';
+import { CircuitBreaker, CircuitBreakerSnapshot } from './circuit-breaker.js';
+import { RuntimeSources, systemRuntimeSources } from '../utils/rng.js';
 
 export interface AIPolicyConfig {
   aiThreshold?: number; // Confidence threshold for AI decisions
   cacheEnabled?: boolean;
   cacheTTL?: number;
+  maxCacheSize?: number; // 0でキャッシュ無効
+  minConfidence?: number; // これ未満の確信度の判定は採用しない（0で無効）
+  failClosed?: boolean; // 確信度不足の判定をINDETERMINATEではなくDENYにする
+  evalRetries?: number; // AI判定が失敗したときの再試行回数（0で再試行しない）
+  evalRetryBackoffMs?: number; // 1回目の再試行までの待ち時間（以降は倍々、ジッター付き）
+  breakerThreshold?: number; // 連続してこの回数AI判定が失敗したらバックエンドを呼ばずにDENYする（0で無効）
+  breakerCooldownMs?: number; // サーキットブレーカーが開いてから試行を再開するまでの時間
+}
+
+const DEFAULT_MAX_CACHE_SIZE = 1000;
+
+/**
+ * attempt回目の失敗の後に待つ時間（base * 2^(attempt-1) の半分〜全体の範囲でランダム）
+ */
+export function retryDelay(baseMs: number, attempt: number, random: () => number = Math.random): number {
+  const exponential = baseMs * 2 ** (attempt - 1);
+  return Math.round(exponential / 2 + random() * exponential / 2);
+}
+
+// 中断されたら待たずに戻る
+function sleep(ms: number, signal?: AbortSignal): Promise<void> {
+  return new Promise(resolve => {
+    const timer = setTimeout(done, ms);
+    function done() {
+      clearTimeout(timer);
+      signal?.removeEventListener('abort', done);
+      resolve();
+    }
+    signal?.addEventListener('abort', done, { once: true });
+  });
+}
+
+/**
+ * キーをソートしたJSON文字列（キャッシュキー用）
+ */
+function stableStringify(value: unknown): string {
+  if (value instanceof Date) {
+    return JSON.stringify(value.toISOString());
+  }
+  if (Array.isArray(value)) {
+    return `[${value.map(stableStringify).join(',')}]`;
+  }
+  if (value && typeof value === 'object') {
+    const entries = Object.keys(value as Record<string, unknown>)
+      .filter(key => (value as Record<string, unknown>)[key] !== undefined)
+      .sort()
+      .map(key => `${JSON.stringify(key)}:${stableStringify((value as Record<string, unknown>)[key])}`);
+    return `{${entries.join(',')}}`;
+  }
+  return JSON.stringify(value) ?? 'null';
 }
 
 export class AIPolicyEngine {
   private aiEngine: AIJudgmentEngine;
   private config: AIPolicyConfig;
   private decisionCache: Map<string, { decision: PolicyDecision; timestamp: number }>;
+  private circuitBreaker: CircuitBreaker;
+  // 再試行のジッター・キャッシュの有効期限・サーキットブレーカーに使う乱数と時刻
+  private sources: RuntimeSources;
 
   constructor(
     aiEngine: AIJudgmentEngine,
@@ -24,11 +82,18 @@ export class AIPolicyEngine {
       aiThreshold: 0.7,
       cacheEnabled: true,
       cacheTTL: 300000 // 5 minutes
-    }
+    },
+    sources: RuntimeSources = systemRuntimeSources()
   ) {
     this.aiEngine = aiEngine;
     this.config = config;
+    this.sources = sources;
     this.decisionCache = new Map();
+    this.circuitBreaker = new CircuitBreaker(
+      config.breakerThreshold ?? 0,
+      config.breakerCooldownMs ?? TIMEOUTS.EVAL_BREAKER_COOLDOWN,
+      sources.clock
+    );
     
     logger.info('AI Policy Engine initialized', {
       aiEnabled: true,
@@ -42,21 +107,61 @@ export class AIPolicyEngine {
    */
   async decide(
     context: DecisionContext,
-    policyText?: string
+    policyText?: string,
+    signal?: AbortSignal
   ): Promise<PolicyDecision> {
     const startTime = Date.now();
+
+    // ルール形式のポリシーはAIを使わずに評価する
+    const rules = policyText ? parseRulePolicy(policyText) : null;
+    if (rules) {
+      const ruleDecision = evaluateRules(rules, context);
+      logger.debug('Rule decision made', { decision: ruleDecision.decision, rule: ruleDecision.metadata?.ruleLine });
+      return {
+        ...ruleDecision,
+        metadata: {
+          ...ruleDecision.metadata,
+          evaluationTime: Date.now() - startTime
+        }
+      };
+    }
     
     // Check cache
-    const cacheKey = this.getCacheKey(context);
+    const cacheKey = this.getCacheKey(context, policyText);
     const cached = this.getCachedDecision(cacheKey);
     if (cached) {
       logger.debug('Returning cached decision', { cacheKey });
-      return cached;
+      return {
+        ...cached,
+        metadata: {
+          ...cached.metadata,
+          cached: true
+        }
+      };
+    }
+
+    // バックエンドの障害が続いている間は呼び出さずにフェイルクローズする
+    if (!this.circuitBreaker.tryAcquire()) {
+      logger.warn('AI policy evaluation skipped: circuit breaker is open');
+      return {
+        decision: 'DENY',
+        reason: 'Policy evaluator is unavailable (circuit breaker open); defaulting to DENY',
+        confidence: 1.0,
+        constraints: [],
+        obligations: [],
+        metadata: { circuitOpen: true, evaluationTime: Date.now() - startTime }
+      };
     }
 
     try {
       // Execute AI judgment
-      const aiDecision = await this.aiEngine.judge(context, policyText);
+      const judged = await this.judgeWithRetries(context, policyText, signal);
+      if (judged.metadata?.aiError) {
+        this.recordEvaluatorFailure();
+      } else {
+        this.circuitBreaker.recordSuccess();
+      }
+      const aiDecision = this.applyConfidenceThreshold(judged);
       
       // Add metadata for AI engine
       const enhancedDecision = {
@@ -74,11 +179,15 @@ export class AIPolicyEngine {
         evaluationTime: Date.now() - startTime
       });
       
-      this.cacheDecision(cacheKey, enhancedDecision);
+      // 呼び出し元がタイムアウトで中断した判定・AI呼び出しに失敗した判定はキャッシュしない
+      if (!signal?.aborted && !judged.metadata?.aiError) {
+        this.cacheDecision(cacheKey, enhancedDecision);
+      }
       return enhancedDecision;
       
     } catch (error) {
       logger.error('AI policy engine error', error);
+      this.recordEvaluatorFailure();
       
       // Fail safe: deny on error
       return {
@@ -95,28 +204,131 @@ export class AIPolicyEngine {
     }
   }
 
+  /**
+   * サーキットブレーカーの状態（statsツール用）
+   */
+  getCircuitBreakerState(): CircuitBreakerSnapshot {
+    return this.circuitBreaker.snapshot();
+  }
+
+  private recordEvaluatorFailure(): void {
+    this.circuitBreaker.recordFailure();
+    const { state, consecutiveFailures } = this.circuitBreaker.snapshot();
+    if (state === 'open') {
+      logger.warn(`AI policy evaluator circuit breaker opened after ${consecutiveFailures} consecutive failures`);
+    }
+  }
+
+  /**
+   * AI判定を失敗（例外、またはAI呼び出しエラーを示すmetadata.aiError）の間evalRetries回まで再試行する
+   * 再試行し尽くしたら最後の結果を返し（例外なら投げ直す）、フェイルクローズは呼び出し元で行う
+   */
+  private async judgeWithRetries(context: DecisionContext, policyText?: string, signal?: AbortSignal): Promise<PolicyDecision> {
+    const retries = this.config.evalRetries ?? 0;
+    const backoffMs = this.config.evalRetryBackoffMs ?? TIMEOUTS.EVAL_RETRY_BACKOFF;
+
+    for (let attempt = 1; ; attempt++) {
+      let decision: PolicyDecision | undefined;
+      let failure: unknown;
+      try {
+        decision = await this.aiEngine.judge(context, policyText);
+        if (!decision.metadata?.aiError) {
+          return decision;
+        }
+        failure = decision.reason;
+      } catch (error) {
+        failure = error;
+      }
+
+      if (attempt > retries || signal?.aborted) {
+        if (decision) {
+          return decision;
+        }
+        throw failure;
+      }
+
+      const delay = retryDelay(backoffMs, attempt, () => this.sources.rng.next());
+      logger.warn(`AI policy evaluation failed (attempt ${attempt}/${retries + 1}); retrying in ${delay}ms`, {
+        error: failure instanceof Error ? failure.message : String(failure)
+      });
+      await sleep(delay, signal);
+    }
+  }
+
+  /**
+   * 確信度が下限未満のPERMIT/DENYをINDETERMINATE（failClosedならDENY）に置き換え、理由に注記する
+   * 判定できなかった結果（INDETERMINATE）はそのまま返す
+   */
+  private applyConfidenceThreshold(decision: PolicyDecision): PolicyDecision {
+    const minConfidence = this.config.minConfidence ?? 0;
+    if (decision.decision === 'INDETERMINATE' || decision.confidence >= minConfidence) {
+      return decision;
+    }
+
+    const overridden = this.config.failClosed ? 'DENY' : 'INDETERMINATE';
+    logger.warn('Low-confidence decision overridden', {
+      decision: decision.decision,
+      confidence: decision.confidence,
+      minConfidence,
+      overridden
+    });
+    return {
+      ...decision,
+      decision: overridden,
+      reason: `${decision.reason} [confidence ${decision.confidence} below minimum ${minConfidence}; original decision ${decision.decision}]`,
+      metadata: {
+        ...decision.metadata,
+        originalDecision: decision.decision,
+        minConfidence,
+        ...(overridden === 'INDETERMINATE' ? { indeterminateSource: 'low-confidence' } : {})
+      }
+    };
+  }
+
   /**
    * Generate cache key for decision
+   * ポリシー本文と判定コンテキストを正規化してハッシュ化する（判定時刻は含めない）
    */
-  private getCacheKey(context: DecisionContext): string {
-    return `${context.agent}:${context.action}:${context.resource}:${context.agentType || ''}`;
+  private getCacheKey(context: DecisionContext, policyText?: string): string {
+    const normalized = stableStringify({
+      policy: policyText || '',
+      agent: context.agent,
+      action: context.action,
+      resource: context.resource,
+      agentType: context.agentType,
+      purpose: context.purpose,
+      locale: context.locale,
+      environment: context.environment
+    });
+    return createHash('sha256').update(normalized).digest('hex');
+  }
+
+  private isCacheEnabled(): boolean {
+    return !!this.config.cacheEnabled && this.getMaxCacheSize() > 0;
+  }
+
+  private getMaxCacheSize(): number {
+    return this.config.maxCacheSize ?? DEFAULT_MAX_CACHE_SIZE;
   }
 
   /**
    * Get cached decision if valid
    */
   private getCachedDecision(key: string): PolicyDecision | null {
-    if (!this.config.cacheEnabled) return null;
+    if (!this.isCacheEnabled()) return null;
     
     const cached = this.decisionCache.get(key);
     if (!cached) return null;
     
-    const age = Date.now() - cached.timestamp;
+    const age = this.sources.clock.now() - cached.timestamp;
     if (age > (this.config.cacheTTL || 300000)) {
       this.decisionCache.delete(key);
       return null;
     }
     
+    // LRU: 参照されたエントリを末尾に移動
+    this.decisionCache.delete(key);
+    this.decisionCache.set(key, cached);
     return cached.decision;
   }
 
@@ -124,23 +336,20 @@ export class AIPolicyEngine {
    * Cache a decision
    */
   private cacheDecision(key: string, decision: PolicyDecision): void {
-    if (!this.config.cacheEnabled) return;
+    if (!this.isCacheEnabled()) return;
     
+    this.decisionCache.delete(key);
     this.decisionCache.set(key, {
       decision,
-      timestamp: Date.now()
+      timestamp: this.sources.clock.now()
     });
     
-    // Cleanup old entries
-    if (this.decisionCache.size > 1000) {
-      const entries = Array.from(this.decisionCache.entries());
-      entries.sort((a, b) => a[1].timestamp - b[1].timestamp);
-      
-      // Remove oldest 20%
-      const toRemove = Math.floor(entries.length * 0.2);
-      for (let i = 0; i < toRemove; i++) {
-        this.decisionCache.delete(entries[i][0]);
-      }
+    // 最も長く参照されていないエントリから削除
+    const maxSize = this.getMaxCacheSize();
+    while (this.decisionCache.size > maxSize) {
+      const oldestKey = this.decisionCache.keys().next().value;
+      if (oldestKey === undefined) break;
+      this.decisionCache.delete(oldestKey);
     }
   }
 
@@ -149,6 +358,8 @@ export class AIPolicyEngine {
    */
   clearCache(): void {
     this.decisionCache.clear();
+    // 判定エンジン側のキャッシュも破棄する（ポリシーの追加・更新・削除時に古い判定を残さない）
+    this.aiEngine.clearCache();
     logger.info('Policy cache cleared');
   }
   
@@ -0,0 +1,90 @@
+// ============================================================================
+// AEGIS - AI判定バックエンドのサーキットブレーカー
+// 連続N回の判定失敗で開き（open）、クールダウン中はバックエンドを呼ばずにフェイルクローズする
+// クールダウン後は1件だけ試行を通し（half-open）、成功すれば閉じ、失敗すれば再び開く
+// ============================================================================
+
+import { SystemTimeProvider, TimeProvider } from '../utils/time-provider.js';
+
+export type CircuitState = 'closed' | 'open' | 'half-open';
+
+export interface CircuitBreakerSnapshot {
+  state: CircuitState;
+  consecutiveFailures: number;
+  failureThreshold: number;
+  cooldownMs: number;
+  // 最後に開いた時刻（閉じている間はnull）
+  openedAt: string | null;
+}
+
+export class CircuitBreaker {
+  private failureThreshold: number;
+  private cooldownMs: number;
+  private clock: TimeProvider;
+  private state: CircuitState = 'closed';
+  private consecutiveFailures = 0;
+  private openedAt: number | null = null;
+  // half-open中の試行が進行中か（結果が出るまで他の判定は通さない）
+  private probing = false;
+
+  /**
+   * @param failureThreshold 開くまでの連続失敗回数（0以下は無効、常に通す）
+   * @param cooldownMs 開いてからhalf-openにするまでの時間
+   * @param clock クールダウンの計測に使う時刻
+   */
+  constructor(failureThreshold: number, cooldownMs: number, clock: TimeProvider = new SystemTimeProvider()) {
+    this.failureThreshold = failureThreshold;
+    this.cooldownMs = cooldownMs;
+    this.clock = clock;
+  }
+
+  isEnabled(): boolean {
+    return this.failureThreshold > 0;
+  }
+
+  /**
+   * バックエンドを呼んでよいか（呼ぶ場合は結果を recordSuccess / recordFailure で必ず報告する）
+   */
+  tryAcquire(now: number = this.clock.now()): boolean {
+    if (!this.isEnabled() || this.state === 'closed') {
+      return true;
+    }
+    if (this.state === 'open') {
+      if (now - this.openedAt! < this.cooldownMs) {
+        return false;
+      }
+      this.state = 'half-open';
+    }
+    if (this.probing) {
+      return false;
+    }
+    this.probing = true;
+    return true;
+  }
+
+  recordSuccess(): void {
+    this.state = 'closed';
+    this.consecutiveFailures = 0;
+    this.openedAt = null;
+    this.probing = false;
+  }
+
+  recordFailure(now: number = this.clock.now()): void {
+    this.consecutiveFailures++;
+    this.probing = false;
+    if (this.isEnabled() && (this.state === 'half-open' || this.consecutiveFailures >= this.failureThreshold)) {
+      this.state = 'open';
+      this.openedAt = now;
+    }
+  }
+
+  snapshot(): CircuitBreakerSnapshot {
+    return {
+      state: this.state,
+      consecutiveFailures: this.consecutiveFailures,
+      failureThreshold: this.failureThreshold,
+      cooldownMs: this.cooldownMs,
+      openedAt: this.openedAt === null ? null : new Date(this.openedAt).toISOString()
+    };
+  }
+}
@@ -0,0 +1,67 @@
+// ============================================================================
+// AEGIS - ポリシー結合アルゴリズム（XACML準拠の簡略版）
+// 複数ポリシーの判定を1つの最終判定にまとめる
+// AI判定にはNotApplicableがないため、first_applicableではINDETERMINATEを「適用外」として扱う
+// ============================================================================
+
+import type { PolicyDecision } from '../types/index.js';
+
+export const COMBINING_ALGORITHMS = ['deny_overrides', 'permit_overrides', 'first_applicable'] as const;
+export type CombiningAlgorithm = typeof COMBINING_ALGORITHMS[number];
+
+export function isCombiningAlgorithm(value: unknown): value is CombiningAlgorithm {
+  return typeof value === 'string' && (COMBINING_ALGORITHMS as readonly string[]).includes(value);
+}
+
+export interface CombinedDecision {
+  decision: PolicyDecision['decision'];
+  reason: string;
+  confidence: number;
+  // 最終判定を決めたポリシーの位置（決まらなかった場合はnull）
+  decidingIndex: number | null;
+  // 最終判定を決めたポリシーの判定のmetadata（INDETERMINATEの由来など）
+  metadata?: PolicyDecision['metadata'];
+}
+
+type SubDecision = Pick<PolicyDecision, 'decision' | 'reason' | 'confidence' | 'metadata'>;
+
+function pick(decisions: SubDecision[], value: PolicyDecision['decision']): CombinedDecision | null {
+  const index = decisions.findIndex(decision => decision.decision === value);
+  if (index === -1) {
+    return null;
+  }
+  return {
+    decision: value,
+    reason: `policy[${index}]: ${decisions[index].reason}`,
+    confidence: decisions[index].confidence,
+    decidingIndex: index,
+    ...(decisions[index].metadata ? { metadata: decisions[index].metadata } : {})
+  };
+}
+
+const NOT_DECIDED: CombinedDecision = {
+  decision: 'INDETERMINATE',
+  reason: 'No policy produced an applicable decision',
+  confidence: 0,
+  decidingIndex: null,
+  metadata: { indeterminateSource: 'no-match' }
+};
+
+/**
+ * ポリシーごとの判定（評価順）を結合する
+ *   deny_overrides:   DENYが1つでもあればDENY、次にINDETERMINATE、次にPERMIT
+ *   permit_overrides: PERMITが1つでもあればPERMIT、次にINDETERMINATE、次にDENY
+ *   first_applicable: 最初のPERMIT/DENY
+ */
+export function combineDecisions(algorithm: CombiningAlgorithm, decisions: SubDecision[]): CombinedDecision {
+  switch (algorithm) {
+    case 'deny_overrides':
+      return pick(decisions, 'DENY') ?? pick(decisions, 'INDETERMINATE') ?? pick(decisions, 'PERMIT') ?? NOT_DECIDED;
+    case 'permit_overrides':
+      return pick(decisions, 'PERMIT') ?? pick(decisions, 'INDETERMINATE') ?? pick(decisions, 'DENY') ?? NOT_DECIDED;
+    case 'first_applicable': {
+      const index = decisions.findIndex(decision => decision.decision !== 'INDETERMINATE');
+      return index === -1 ? NOT_DECIDED : pick(decisions, decisions[index].decision)!;
+    }
+  }
+}
@@ -0,0 +1,73 @@
+// ============================================================================
+// AEGIS - INDETERMINATE判定の解決方針（--indeterminate-resolution）
+// 判定できなかった結果を呼び出し元が扱いやすいPERMIT/DENYに置き換える
+//   deny:        DENYにする（既定・安全側）
+//   permit:      PERMITにする（AIが判定できなかった場合のみ。下記の由来が記録された判定はDENYにする）
+//   passthrough: INDETERMINATEのまま返す（トランスポートごとの従来の扱い）
+// ============================================================================
+
+import type { PolicyDecision } from '../types/index.js';
+
+export const INDETERMINATE_RESOLUTIONS = ['permit', 'deny', 'passthrough'] as const;
+export type IndeterminateResolution = typeof INDETERMINATE_RESOLUTIONS[number];
+
+export const DEFAULT_INDETERMINATE_RESOLUTION: IndeterminateResolution = 'deny';
+
+// AI判定そのもの以外で生じたINDETERMINATEの由来（metadata.indeterminateSource）
+// 制約の不充足・AI呼び出しや応答解析の失敗・確信度不足・確認質問の未回答・一致するルールなしなどは、
+// permitでもPERMITにせずDENYにする
+export const INDETERMINATE_SOURCES = [
+  'constraint',
+  'ai-error',
+  'parse-error',
+  'low-confidence',
+  'clarification',
+  'no-match',
+  'evaluation-error'
+] as const;
+export type IndeterminateSource = typeof INDETERMINATE_SOURCES[number];
+
+type ResolvableDecision = Pick<PolicyDecision, 'decision' | 'reason' | 'metadata'>;
+
+/**
+ * INDETERMINATEの由来をmetadataに記録する
+ */
+export function markIndeterminateSource<T extends ResolvableDecision>(decision: T, source: IndeterminateSource): T {
+  return {
+    ...decision,
+    metadata: { ...decision.metadata, indeterminateSource: source }
+  };
+}
+
+export function isIndeterminateResolution(value: unknown): value is IndeterminateResolution {
+  return typeof value === 'string' && (INDETERMINATE_RESOLUTIONS as readonly string[]).includes(value);
+}
+
+/**
+ * INDETERMINATEを方針に従って置き換え、理由に注記する（それ以外の判定はそのまま返す）
+ * permitでも、由来（metadata.indeterminateSource）が記録された判定はDENYにする
+ */
+export function resolveIndeterminate<T extends ResolvableDecision>(
+  decision: T,
+  resolution: IndeterminateResolution
+): T {
+  if (decision.decision !== 'INDETERMINATE' || resolution === 'passthrough') {
+    return decision;
+  }
+
+  const source = decision.metadata?.indeterminateSource;
+  if (resolution === 'permit' && source !== undefined && source !== null) {
+    return {
+      ...decision,
+      decision: 'DENY',
+      reason: `${decision.reason} [INDETERMINATE (${source}) resolved to DENY; indeterminate-resolution=permit applies only to AI decisions]`
+    };
+  }
+
+  const resolved = resolution === 'permit' ? 'PERMIT' : 'DENY';
+  return {
+    ...decision,
+    decision: resolved,
+    reason: `${decision.reason} [INDETERMINATE resolved to ${resolved} by indeterminate-resolution=${resolution}]`
+  };
+}
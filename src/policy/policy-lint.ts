@@ -0,0 +1,141 @@
+// ============================================================================
+// AEGIS - 自然言語ポリシーのリント
+// AI判定を不安定にする曖昧な表現と、定義されていないロールへの言及を検出する
+// ============================================================================
+
+import * as fs from 'fs';
+
+export type LintSeverity = 'error' | 'warning' | 'info';
+
+export interface LintFinding {
+  line: number;    // 1始まり
+  column: number;  // 1始まり（文字単位）
+  message: string;
+  severity: LintSeverity;
+}
+
+export interface AmbiguityMarker {
+  term: string;
+  message?: string;
+  severity?: LintSeverity;
+}
+
+export interface LintRules {
+  ambiguityMarkers: AmbiguityMarker[];
+  // ポリシー内で参照してよいロール（これ以外のロール参照は未定義として報告）
+  roles: string[];
+}
+
+export const DEFAULT_LINT_RULES: LintRules = {
+  ambiguityMarkers: [
+    { term: 'sometimes' },
+    { term: 'as appropriate' },
+    { term: 'if necessary' },
+    { term: 'where possible' },
+    { term: 'usually' },
+    { term: 'generally' },
+    { term: 'reasonable' },
+    { term: 'etc.' },
+    { term: '適宜' },
+    { term: '必要に応じて' },
+    { term: '場合によって' },
+    { term: 'なるべく' },
+    { term: 'できるだけ' },
+    { term: '原則として' },
+    { term: '慎重に' },
+    { term: '基本的に' }
+  ],
+  roles: ['管理者', '利用者', 'エージェント', 'admin', 'administrator', 'user', 'agent']
+};
+
+// ロールへの言及: role:xxx / ロール:xxx / "xxx role" / 「〜者」
+const ROLE_PATTERNS = [
+  /(?:role|ロール)\s*[:：]\s*([\w\-ぁ-んァ-ヶー一-龠々]+)/gi,
+  /\b([A-Za-z][\w-]*)\s+role\b/gi,
+  /([一-龠々]+者)/g
+];
+
+// 英数字で始まる（終わる）語は、前（後ろ）も英数字なら語の一部として一致させない（unreasonable の reasonable）
+// 日本語の語には語の区切りがないため、そのまま部分一致で探す
+const WORD_CHAR = /[A-Za-z0-9_]/;
+
+function isWholeWord(text: string, at: number, term: string): boolean {
+  const end = at + term.length;
+  if (WORD_CHAR.test(term[0]) && at > 0 && WORD_CHAR.test(text[at - 1])) {
+    return false;
+  }
+  return !(WORD_CHAR.test(term[term.length - 1]) && end < text.length && WORD_CHAR.test(text[end]));
+}
+
+/**
+ * リントルールファイル（JSON）を読み込み、既定のルールに追加する
+ * { "ambiguityMarkers": [{ "term": "...", "message": "...", "severity": "error" }], "roles": ["..."] }
+ */
+export function loadLintRules(filePath: string, base: LintRules = DEFAULT_LINT_RULES): LintRules {
+  const parsed = JSON.parse(fs.readFileSync(filePath, 'utf-8'));
+  if (parsed === null || typeof parsed !== 'object' || Array.isArray(parsed)) {
+    throw new Error(`Lint rules must be a JSON object: ${filePath}`);
+  }
+
+  const markers = parsed.ambiguityMarkers ?? [];
+  const roles = parsed.roles ?? [];
+  if (!Array.isArray(markers) || markers.some((marker: any) => typeof marker?.term !== 'string' || marker.term === '')) {
+    throw new Error(`ambiguityMarkers must be an array of { term } objects: ${filePath}`);
+  }
+  if (!Array.isArray(roles) || roles.some((role: unknown) => typeof role !== 'string')) {
+    throw new Error(`roles must be an array of strings: ${filePath}`);
+  }
+
+  return {
+    ambiguityMarkers: [...base.ambiguityMarkers, ...markers],
+    roles: [...base.roles, ...roles]
+  };
+}
+
+/**
+ * ポリシー本文を検査し、行・列順に指摘を返す
+ */
+export function lintPolicy(text: string, rules: LintRules = DEFAULT_LINT_RULES): LintFinding[] {
+  const findings: LintFinding[] = [];
+  const knownRoles = new Set(rules.roles.map(role => role.toLowerCase()));
+
+  text.split(/\r?\n/).forEach((lineText, index) => {
+    const line = index + 1;
+    const lower = lineText.toLowerCase();
+
+    for (const marker of rules.ambiguityMarkers) {
+      const term = marker.term.toLowerCase();
+      for (let at = lower.indexOf(term); at !== -1; at = lower.indexOf(term, at + term.length)) {
+        if (!isWholeWord(lower, at, term)) {
+          continue;
+        }
+        findings.push({
+          line,
+          column: at + 1,
+          message: marker.message ?? `Ambiguous wording "${marker.term}" may make decisions inconsistent`,
+          severity: marker.severity ?? 'warning'
+        });
+      }
+    }
+
+    const reported = new Set<number>();
+    for (const pattern of ROLE_PATTERNS) {
+      for (const match of lineText.matchAll(pattern)) {
+        const role = match[1];
+        const column = (match.index ?? 0) + match[0].indexOf(role) + 1;
+        if (knownRoles.has(role.toLowerCase()) || reported.has(column)) {
+          continue;
+        }
+        reported.add(column);
+        findings.push({
+          line,
+          column,
+          message: `Role "${role}" is not defined in the lint rules`,
+          severity: 'info'
+        });
+      }
+    }
+  });
+
+  return findings.sort((a, b) => a.line - b.line || a.column - b.column);
+}
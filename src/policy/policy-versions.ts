@@ -0,0 +1,156 @@
+// ============================================================================
+// AEGIS - ポリシーのバージョン管理と差分
+// ポリシーが登録されるたびに内容のハッシュと登録時刻を記録し（内容が同じなら版を増やさない）、
+// 任意の2つの版の間のunified diffを生成する
+// ============================================================================
+
+import { createHash } from 'crypto';
+
+export interface PolicyVersion {
+  version: number;   // 1始まり
+  hash: string;      // 本文のSHA-256（hex）
+  loadedAt: string;  // ISO 8601
+  content: string;
+}
+
+// 判定に使ったポリシーの出所（登録済みでないポリシー本文はversionがnull）
+export interface PolicyProvenance {
+  policy: string;
+  version: number | null;
+  hash: string;
+}
+
+/**
+ * ポリシー本文のSHA-256（hex）
+ */
+export function hashPolicyContent(content: string): string {
+  return createHash('sha256').update(content).digest('hex');
+}
+
+// ポリシーごとに保持する版の上限（古いものから捨てる）
+export const MAX_VERSIONS_PER_POLICY = 20;
+
+export class PolicyVersionStore {
+  private versions = new Map<string, PolicyVersion[]>();
+
+  /**
+   * 版を記録して最新版を返す（直前の版と同じ内容なら新しい版は作らない）
+   */
+  record(name: string, content: string, loadedAt: Date = new Date()): PolicyVersion {
+    const history = this.versions.get(name) ?? [];
+    const hash = hashPolicyContent(content);
+    const latest = history[history.length - 1];
+    if (latest && latest.hash === hash) {
+      return latest;
+    }
+
+    const version: PolicyVersion = {
+      version: (latest?.version ?? 0) + 1,
+      hash,
+      loadedAt: loadedAt.toISOString(),
+      content
+    };
+    history.push(version);
+    this.versions.set(name, history.slice(-MAX_VERSIONS_PER_POLICY));
+    return version;
+  }
+
+  latest(name: string): PolicyVersion | undefined {
+    const history = this.list(name);
+    return history[history.length - 1];
+  }
+
+  list(name: string): PolicyVersion[] {
+    return this.versions.get(name) ?? [];
+  }
+
+  get(name: string, version: number): PolicyVersion | undefined {
+    return this.list(name).find(candidate => candidate.version === version);
+  }
+}
+
+type DiffLine = { op: ' ' | '-' | '+'; text: string };
+
+// 行単位のLCSから編集列を作る（ポリシー程度の大きさを想定したO(n*m)）
+function diffLines(before: string[], after: string[]): DiffLine[] {
+  const lcs: number[][] = Array.from({ length: before.length + 1 }, () => new Array(after.length + 1).fill(0));
+  for (let i = before.length - 1; i >= 0; i--) {
+    for (let j = after.length - 1; j >= 0; j--) {
+      lcs[i][j] = before[i] === after[j] ? lcs[i + 1][j + 1] + 1 : Math.max(lcs[i + 1][j], lcs[i][j + 1]);
+    }
+  }
+
+  const lines: DiffLine[] = [];
+  let i = 0;
+  let j = 0;
+  while (i < before.length && j < after.length) {
+    if (before[i] === after[j]) {
+      lines.push({ op: ' ', text: before[i] });
+      i++;
+      j++;
+    } else if (lcs[i + 1][j] >= lcs[i][j + 1]) {
+      lines.push({ op: '-', text: before[i++] });
+    } else {
+      lines.push({ op: '+', text: after[j++] });
+    }
+  }
+  while (i < before.length) {
+    lines.push({ op: '-', text: before[i++] });
+  }
+  while (j < after.length) {
+    lines.push({ op: '+', text: after[j++] });
+  }
+  return lines;
+}
+
+function splitLines(text: string): string[] {
+  if (text === '') {
+    return [];
+  }
+  const lines = text.split(/\r?\n/);
+  return lines[lines.length - 1] === '' ? lines.slice(0, -1) : lines;
+}
+
+function hunkRange(start: number, count: number): string {
+  // 空の範囲は直前の行番号で表す（diff -u と同じ）
+  return count === 1 ? `${start}` : `${count === 0 ? start - 1 : start},${count}`;
+}
+
+/**
+ * unified diff（前後 context 行を含むハンク）を返す。差分がなければ空文字列
+ */
+export function unifiedDiff(before: string, after: string, fromLabel: string, toLabel: string, context: number = 3): string {
+  const lines = diffLines(splitLines(before), splitLines(after));
+  const changed = lines.flatMap((line, index) => (line.op === ' ' ? [] : [index]));
+  if (changed.length === 0) {
+    return '';
+  }
+
+  // 変更行の前後context行をまとめてハンクにする（間隔が狭いハンクは結合）
+  const ranges: Array<[number, number]> = [];
+  for (const index of changed) {
+    const start = Math.max(0, index - context);
+    const end = Math.min(lines.length - 1, index + context);
+    const last = ranges[ranges.length - 1];
+    if (last && start <= last[1] + 1) {
+      last[1] = Math.max(last[1], end);
+    } else {
+      ranges.push([start, end]);
+    }
+  }
+
+  const output = [`--- ${fromLabel}`, `+++ ${toLabel}`];
+  for (const [start, end] of ranges) {
+    // ハンク開始位置までの旧・新それぞれの行数
+    const prefix = lines.slice(0, start);
+    const oldStart = prefix.filter(line => line.op !== '+').length + 1;
+    const newStart = prefix.filter(line => line.op !== '-').length + 1;
+    const hunk = lines.slice(start, end + 1);
+    const oldCount = hunk.filter(line => line.op !== '+').length;
+    const newCount = hunk.filter(line => line.op !== '-').length;
+
+    output.push(`@@ -${hunkRange(oldStart, oldCount)} +${hunkRange(newStart, newCount)} @@`);
+    output.push(...hunk.map(line => `${line.op}${line.text}`));
+  }
+  return output.join('\n') + '\n';
+}
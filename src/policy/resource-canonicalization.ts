@@ -0,0 +1,44 @@
+// ============================================================================
+// AEGIS - パス形式のresourceの正規化（--canonicalize-resources）
+// /prod/../prod/secrets や /prod/secrets/. のような表記で、/prod/secrets を拒否するポリシーを
+// すり抜けられないよう、判定の前に . と .. を畳み込み、区切り文字（\ と連続した /）をそろえる
+// file:///a/../b のようなURIはパスの部分だけを正規化し、? や # 以降はそのまま残す
+// パスの中の %2e（.）・%2f（/）・%5c（\）は正規化の前にデコードする（/prod/%2e%2e/prod/secrets の対策）
+// パスに見えない resource（/ も \ も含まないもの）は変更しない
+// ============================================================================
+
+import * as path from 'path';
+
+// scheme://authority の部分（パスの前まで）
+const URI_PREFIX = /^[a-z][a-z0-9+.-]*:\/\/[^/\\?#]*/i;
+
+// パスの区切りや . を表すパーセントエンコーディング（それ以外はデコードしない）
+const ENCODED_PATH_CHARS = /%(2e|2f|5c)/gi;
+
+function canonicalizePath(value: string): string {
+  const decoded = value.replace(ENCODED_PATH_CHARS, (_, hex: string) => String.fromCharCode(parseInt(hex, 16)));
+  const normalized = path.posix.normalize(decoded.replace(/\\/g, '/'));
+  // 末尾の / は取り除く（ルートの / は残す）
+  return normalized.length > 1 && normalized.endsWith('/') ? normalized.slice(0, -1) : normalized;
+}
+
+/**
+ * パス形式のresourceを正規化する（変更がなければ同じ文字列を返す）
+ * ルートより上への .. は / にとどめる（/../etc/passwd は /etc/passwd）
+ */
+export function canonicalizeResource(resource: string): string {
+  if (!/[/\\]|%2f|%5c/i.test(resource)) {
+    return resource;
+  }
+
+  const prefix = resource.match(URI_PREFIX)?.[0] ?? '';
+  const rest = resource.slice(prefix.length);
+  const suffixStart = prefix ? rest.search(/[?#]/) : -1;
+  const pathPart = suffixStart === -1 ? rest : rest.slice(0, suffixStart);
+  const suffix = suffixStart === -1 ? '' : rest.slice(suffixStart);
+  if (pathPart === '') {
+    return resource;
+  }
+
+  return prefix + canonicalizePath(pathPart) + suffix;
+}
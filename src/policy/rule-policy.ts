@@ -0,0 +1,116 @@
+// ============================================================================
+// AEGIS - ルール形式ポリシーの決定的評価
+// すべての行が次の形式のポリシーはAIを使わずに評価する（上から順に最初に一致した行の判定）
+//   DENY action=delete resource=/prod/*
+//   PERMIT agent=claude,gpt action=read,list resource=file://docs/**
+// 条件: agent・action は完全一致（カンマ区切りでいずれか、* は任意）、resource はglob
+//       （* は / 以外の任意の文字列、** は / を含む任意の文字列、? は / 以外の任意の1文字）
+// 空行と # で始まる行は無視する。どの行にも一致しなければINDETERMINATE
+// ============================================================================
+
+import type { DecisionContext, PolicyDecision } from '../types/index.js';
+
+type RuleField = 'agent' | 'action' | 'resource';
+
+export interface PolicyRule {
+  line: number;
+  effect: 'PERMIT' | 'DENY';
+  conditions: Partial<Record<RuleField, string[]>>;
+  text: string;
+}
+
+const RULE_LINE = /^(PERMIT|DENY)((?:\s+(?:agent|action|resource)=\S+)*)\s*$/;
+const CONDITION = /(agent|action|resource)=(\S+)/g;
+
+/**
+ * ポリシー本文をルールとして解釈する（1行でもルール形式でなければnull＝自然言語ポリシー）
+ */
+export function parseRulePolicy(text: string): PolicyRule[] | null {
+  const rules: PolicyRule[] = [];
+
+  const lines = text.split(/\r?\n/);
+  for (const [index, raw] of lines.entries()) {
+    const line = raw.trim();
+    if (line === '' || line.startsWith('#')) {
+      continue;
+    }
+
+    const match = line.match(RULE_LINE);
+    if (!match) {
+      return null;
+    }
+
+    const conditions: PolicyRule['conditions'] = {};
+    for (const [, field, values] of match[2].matchAll(CONDITION)) {
+      conditions[field as RuleField] = values.split(',').filter(value => value !== '');
+    }
+    rules.push({ line: index + 1, effect: match[1] as PolicyRule['effect'], conditions, text: line });
+  }
+
+  return rules.length > 0 ? rules : null;
+}
+
+export function globToRegExp(pattern: string): RegExp {
+  let source = '';
+  for (let i = 0; i < pattern.length; i++) {
+    const char = pattern[i];
+    if (char === '*' && pattern[i + 1] === '*') {
+      source += '.*';
+      i++;
+    } else if (char === '*') {
+      source += '[^/]*';
+    } else if (char === '?') {
+      source += '[^/]';
+    } else {
+      source += char.replace(/[.+^${}()|[\]\\]/g, '\\$&');
+    }
+  }
+  return new RegExp(`^${source}$`);
+}
+
+function matchesRule(rule: PolicyRule, context: DecisionContext): boolean {
+  const { agent, action, resource } = rule.conditions;
+  if (agent && !agent.some(value => value === '*' || value === context.agent)) {
+    return false;
+  }
+  if (action && !action.some(value => value === '*' || value === context.action)) {
+    return false;
+  }
+  if (resource && !resource.some(pattern => globToRegExp(pattern).test(context.resource))) {
+    return false;
+  }
+  return true;
+}
+
+/**
+ * 最初に一致したルール（なければundefined）
+ */
+export function findMatchingRule(rules: PolicyRule[], context: DecisionContext): PolicyRule | undefined {
+  return rules.find(candidate => matchesRule(candidate, context));
+}
+
+/**
+ * ルールを上から評価し、最初に一致したルールの判定を返す
+ */
+export function evaluateRules(rules: PolicyRule[], context: DecisionContext): PolicyDecision {
+  const rule = findMatchingRule(rules, context);
+  if (!rule) {
+    return {
+      decision: 'INDETERMINATE',
+      reason: 'No rule matched the request',
+      confidence: 1.0,
+      constraints: [],
+      obligations: [],
+      metadata: { engine: 'RULE', indeterminateSource: 'no-match' }
+    };
+  }
+
+  return {
+    decision: rule.effect,
+    reason: `Matched rule at line ${rule.line}: ${rule.text}`,
+    confidence: 1.0,
+    constraints: [],
+    obligations: [],
+    metadata: { engine: 'RULE', ruleLine: rule.line }
+  };
+}
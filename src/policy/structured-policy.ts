@@ -0,0 +1,205 @@
+// ============================================================================
+// AEGIS - 構造化ポリシーの評価（check_policies の policy_format）
+//   natural: 自然言語ポリシー（AIによる判定、既定）
+//   json:    組み込みのルールインタープリタ（上から順に最初に一致したルールの判定）
+//     { "rules": [ { "effect": "deny", "action": ["delete"], "resource": "/prod/**" },
+//                  { "effect": "permit", "agent": "claude" } ] }
+//     agent・action は完全一致（* は任意）、resource はglob（ルール形式ポリシーと同じ）。一致しなければINDETERMINATE
+//   cedar:   Cedarポリシー（任意のpeer依存 @cedar-policy/cedar-wasm がインストールされている場合のみ利用可能）
+//     principal は Agent::"<agent>"、action は Action::"<action>"、resource は Resource::"<resource>"
+// ============================================================================
+
+import type { DecisionContext, PolicyDecision } from '../types/index.js';
+import { findMatchingRule, PolicyRule } from './rule-policy.js';
+
+export const POLICY_FORMATS = ['natural', 'json', 'cedar'] as const;
+export type PolicyFormat = typeof POLICY_FORMATS[number];
+
+export const DEFAULT_POLICY_FORMAT: PolicyFormat = 'natural';
+
+export function isPolicyFormat(value: unknown): value is PolicyFormat {
+  return typeof value === 'string' && (POLICY_FORMATS as readonly string[]).includes(value);
+}
+
+const JSON_RULE_FIELDS = ['agent', 'action', 'resource'] as const;
+
+/**
+ * JSONポリシーをルールとして読み込む（{ "rules": [...] } またはルールの配列）
+ * 形式の誤りは何番目のルールかを含めたエラーにする
+ */
+export function parseJsonPolicy(text: string): PolicyRule[] {
+  let parsed: unknown;
+  try {
+    parsed = JSON.parse(text);
+  } catch (error) {
+    throw new Error(`Invalid JSON policy: ${error instanceof Error ? error.message : String(error)}`);
+  }
+  const rules = Array.isArray(parsed) ? parsed : (parsed as { rules?: unknown } | null)?.rules;
+  if (!Array.isArray(rules) || rules.length === 0) {
+    throw new Error('JSON policy must be a non-empty array of rules or an object with a non-empty "rules" array');
+  }
+
+  return rules.map((rule, index) => {
+    const fail = (message: string): never => {
+      throw new Error(`JSON policy rule ${index + 1}: ${message}`);
+    };
+    if (typeof rule !== 'object' || rule === null || Array.isArray(rule)) {
+      fail('must be an object');
+    }
+    const { effect, ...conditions } = rule as Record<string, unknown>;
+    if (typeof effect !== 'string' || !['PERMIT', 'DENY'].includes(effect.toUpperCase())) {
+      fail('effect must be "permit" or "deny"');
+    }
+
+    const parsedRule: PolicyRule = {
+      line: index + 1,
+      effect: (effect as string).toUpperCase() as PolicyRule['effect'],
+      conditions: {},
+      text: JSON.stringify(rule)
+    };
+    for (const [field, value] of Object.entries(conditions)) {
+      if (!(JSON_RULE_FIELDS as readonly string[]).includes(field)) {
+        fail(`unknown field "${field}" (use ${JSON_RULE_FIELDS.join(', ')})`);
+      }
+      const values = Array.isArray(value) ? value : [value];
+      if (values.length === 0 || !values.every(item => typeof item === 'string' && item !== '')) {
+        fail(`${field} must be a string or a non-empty array of strings`);
+      }
+      parsedRule.conditions[field as typeof JSON_RULE_FIELDS[number]] = values as string[];
+    }
+    return parsedRule;
+  });
+}
+
+/**
+ * JSONポリシーのルールを上から評価し、最初に一致したルールの判定を返す
+ */
+export function evaluateJsonPolicy(rules: PolicyRule[], context: DecisionContext): PolicyDecision {
+  const rule = findMatchingRule(rules, context);
+  if (!rule) {
+    return {
+      decision: 'INDETERMINATE',
+      reason: 'No JSON policy rule matched the request',
+      confidence: 1.0,
+      constraints: [],
+      obligations: [],
+      metadata: { engine: 'JSON', indeterminateSource: 'no-match' }
+    };
+  }
+
+  return {
+    decision: rule.effect,
+    reason: `Matched JSON policy rule ${rule.line}: ${rule.text}`,
+    confidence: 1.0,
+    constraints: [],
+    obligations: [],
+    metadata: { engine: 'JSON', ruleIndex: rule.line - 1 }
+  };
+}
+
+// @cedar-policy/cedar-wasm のうち使用する部分のみの型
+interface CedarEntityUid {
+  type: string;
+  id: string;
+}
+
+type CedarAuthorizationAnswer =
+  | {
+      type: 'success';
+      response: {
+        decision: 'allow' | 'deny';
+        diagnostics: { reason: string[]; errors: Array<{ policyId: string; error: { message: string } }> };
+      };
+    }
+  | { type: 'failure'; errors: Array<{ message: string }> };
+
+interface CedarModule {
+  isAuthorized(call: {
+    principal: CedarEntityUid;
+    action: CedarEntityUid;
+    resource: CedarEntityUid;
+    context: Record<string, unknown>;
+    policies: { staticPolicies: string };
+    entities: unknown[];
+  }): CedarAuthorizationAnswer;
+  checkParsePolicySet(policies: { staticPolicies: string }): { type: 'success' } | { type: 'failure'; errors: Array<{ message: string }> };
+}
+
+// 任意の依存関係のため、指定子を変数経由で読み込む（未インストールならcedarは使えない）
+const CEDAR_MODULE = '@cedar-policy/cedar-wasm/nodejs';
+
+let cedarModule: Promise<CedarModule | null> | undefined;
+
+function loadCedar(): Promise<CedarModule | null> {
+  if (!cedarModule) {
+    cedarModule = import(CEDAR_MODULE).then(module => module as CedarModule, () => null);
+  }
+  return cedarModule;
+}
+
+/**
+ * この環境で評価できる policy_format（cedar は @cedar-policy/cedar-wasm を読み込める場合のみ）
+ */
+export async function availablePolicyFormats(): Promise<PolicyFormat[]> {
+  const cedar = await loadCedar();
+  return POLICY_FORMATS.filter(format => format !== 'cedar' || cedar !== null);
+}
+
+/**
+ * Cedarポリシーの構文を検査し、エラーのメッセージを返す（問題がなければ空）
+ */
+export async function validateCedarPolicy(text: string): Promise<string[]> {
+  const cedar = await loadCedar();
+  if (!cedar) {
+    throw new Error(`Cedar policies require the optional ${CEDAR_MODULE} package`);
+  }
+
+  const result = cedar.checkParsePolicySet({ staticPolicies: text });
+  return result.type === 'failure' ? result.errors.map(error => error.message) : [];
+}
+
+/**
+ * Cedarポリシーで評価する（ポリシーの構文エラーなど評価自体の失敗は例外）
+ * Cedarはどのポリシーにも許可されなければDENY（既定で拒否）
+ */
+export async function evaluateCedarPolicy(text: string, context: DecisionContext): Promise<PolicyDecision> {
+  const cedar = await loadCedar();
+  if (!cedar) {
+    throw new Error(`Cedar policies require the optional ${CEDAR_MODULE} package`);
+  }
+
+  const answer = cedar.isAuthorized({
+    principal: { type: 'Agent', id: context.agent },
+    action: { type: 'Action', id: context.action },
+    resource: { type: 'Resource', id: context.resource },
+    context: context.purpose ? { purpose: context.purpose } : {},
+    policies: { staticPolicies: text },
+    entities: []
+  });
+  if (answer.type === 'failure') {
+    throw new Error(`Cedar evaluation failed: ${answer.errors.map(error => error.message).join('; ')}`);
+  }
+
+  const { decision, diagnostics } = answer.response;
+  const policyIds = diagnostics.reason.join(', ');
+  let reason: string;
+  if (decision === 'allow') {
+    reason = `Permitted by Cedar policy ${policyIds}`;
+  } else if (diagnostics.reason.length > 0) {
+    reason = `Forbidden by Cedar policy ${policyIds}`;
+  } else {
+    reason = 'No Cedar policy permitted the request (default deny)';
+  }
+  if (diagnostics.errors.length > 0) {
+    reason += ` (skipped policies with errors: ${diagnostics.errors.map(error => `${error.policyId}: ${error.error.message}`).join('; ')})`;
+  }
+
+  return {
+    decision: decision === 'allow' ? 'PERMIT' : 'DENY',
+    reason,
+    confidence: 1.0,
+    constraints: [],
+    obligations: [],
+    metadata: { engine: 'CEDAR', policies: diagnostics.reason }
+  };
+}
@@ -1,4 +1,5 @@
 import { z } from 'zod';
+import { LOG_FORMATS } from '../utils/logger.js';
 
 // ============================================================================
 // 設定関連のスキーマ定義
@@ -40,7 +41,7 @@ export const cacheConfigSchema = z.object({
  */
 export const logConfigSchema = z.object({
   level: z.enum(['debug', 'info', 'warn', 'error']).default('info'),
-  format: z.enum(['json', 'pretty', 'text']).default('json'),
+  format: z.enum(LOG_FORMATS).default('pretty'),
   filePath: z.string().optional(),
   maxFiles: z.number().min(1).default(7),
   maxSize: z.string().regex(/^\d+[kmg]b?$/i).default('10m')
@@ -92,7 +93,7 @@ export const appConfigSchema = z.object({
   }),
   log: logConfigSchema.default({
     level: 'info',
-    format: 'json',
+    format: 'pretty',
     maxFiles: 7,
     maxSize: '10m'
   }),
@@ -171,7 +172,7 @@ export const envConfigSchema = z.object({
   
   // ログ設定
   LOG_LEVEL: z.enum(['debug', 'info', 'warn', 'error']).optional(),
-  LOG_FORMAT: z.enum(['json', 'pretty', 'text']).optional(),
+  LOG_FORMAT: z.enum(LOG_FORMATS).optional(),
   LOG_FILE_PATH: z.string().optional(),
   
   // キャッシュ設定
@@ -232,7 +233,7 @@ export function buildConfigFromEnv(env: z.infer<typeof envConfigSchema>): z.infe
     
     log: {
       level: env.LOG_LEVEL || 'info',
-      format: env.LOG_FORMAT || 'json',
+      format: env.LOG_FORMAT || 'pretty',
       filePath: env.LOG_FILE_PATH,
       maxFiles: 7,
       maxSize: '10m'
@@ -85,10 +85,11 @@ export const policyExportSchema = z.object({
  */
 export const policyDecisionSchema = z.object({
   decision: z.enum(['PERMIT', 'DENY', 'INDETERMINATE']),
-  reason: z.string(),
+  reason: z.string().min(1),
   confidence: z.number().min(0).max(1),
   constraints: z.array(z.string()).optional(),
   obligations: z.array(z.string()).optional(),
+  needs_clarification: z.array(z.string()).optional(),
   metadata: z.record(z.any()).optional()
 });
 
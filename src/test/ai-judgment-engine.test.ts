@@ -256,6 +256,77 @@ describe('AIJudgmentEngine - 機能テスト', () => {
       await engine.makeDecision(policy, context);
       expect(mockLLM.complete).toHaveBeenCalledTimes(2);
     });
+
+    it('ポリシーの内容が変わるとキャッシュを使わない', async () => {
+      const context: DecisionContext = {
+        agent: 'test-agent',
+        action: 'read',
+        resource: 'test-resource',
+        time: new Date(),
+        environment: {}
+      };
+
+      mockLLM.complete
+        .mockResolvedValueOnce(JSON.stringify({ decision: 'PERMIT', reason: 'Old policy', confidence: 0.9 }))
+        .mockResolvedValueOnce(JSON.stringify({ decision: 'DENY', reason: 'Edited policy', confidence: 0.9 }));
+
+      // "Aa" と "BB" は32bitの文字列ハッシュが一致する（内容のハッシュでは別のキーになる）
+      await engine.makeDecision('Group Aa may read', context);
+      const reloaded = await engine.makeDecision('Group BB may read', context);
+
+      expect(mockLLM.complete).toHaveBeenCalledTimes(2);
+      expect(reloaded.decision).toBe('DENY');
+    });
+  });
+
+  describe('プロンプトの記録', () => {
+    const context: DecisionContext = {
+      agent: 'test-agent',
+      action: 'read',
+      resource: 'test-resource',
+      purpose: 'incident-42 の調査',
+      time: new Date(),
+      environment: { apiToken: 'sk-secret', region: 'tokyo' }
+    };
+
+    beforeEach(() => {
+      mockLLM.complete.mockResolvedValue(JSON.stringify({ decision: 'PERMIT', reason: 'OK', confidence: 0.9 }));
+    });
+
+    it('既定では送信したプロンプトを判定結果に付けない', async () => {
+      const result = await engine.makeDecision('Prompt logging policy', context);
+
+      expect(result.metadata).not.toHaveProperty('renderedPrompt');
+    });
+
+    it('送信したプロンプトを指定した項目を伏せ字にして付ける', async () => {
+      engine.enablePromptLogging(['purpose', 'environment.apiToken', 'environment.missing']);
+
+      const result = await engine.makeDecision('Prompt logging policy', context);
+
+      const sent = mockLLM.complete.mock.calls[0][0];
+      const logged = result.metadata?.renderedPrompt as string;
+      expect(sent).toContain('sk-secret');
+      expect(logged).not.toContain('sk-secret');
+      expect(logged).not.toContain('incident-42');
+      expect(logged).toContain('[REDACTED]');
+      expect(logged).toContain('tokyo');
+      expect(context.environment.apiToken).toBe('sk-secret');
+    });
+
+    it('伏せ字の項目がなければ送信したプロンプトをそのまま付ける', async () => {
+      engine.enablePromptLogging();
+
+      const result = await engine.makeDecision('Prompt logging policy', context);
+
+      expect(result.metadata?.renderedPrompt).toBe(mockLLM.complete.mock.calls[0][0]);
+    });
+
+    it('正規化したresourceとともに正規化前の表記を送信する', async () => {
+      await engine.makeDecision('Prompt logging policy', { ...context, resource: '/prod/secrets', originalResource: '/prod/../prod/secrets' });
+
+      expect(mockLLM.complete.mock.calls[0][0]).toContain('/prod/secrets (正規化前の表記: /prod/../prod/secrets)');
+    });
   });
 
   describe('エラーハンドリング', () => {
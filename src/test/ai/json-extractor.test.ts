@@ -0,0 +1,37 @@
+// ============================================================================
+// JSON Extractor Test Suite
+// ============================================================================
+
+import { extractJsonBlock } from '../../ai/json-extractor';
+
+describe('extractJsonBlock', () => {
+  const decision = '{"decision": "PERMIT", "reason": "ok", "confidence": 0.9}';
+
+  it('should extract JSON from a ```json fence', () => {
+    expect(JSON.parse(extractJsonBlock('```json\n' + decision + '\n```'))).toMatchObject({ decision: 'PERMIT' });
+  });
+
+  it('should tolerate fences without a language tag and CRLF line endings', () => {
+    expect(JSON.parse(extractJsonBlock('判定結果:\r\n```\r\n' + decision + '\r\n```\r\n'))).toMatchObject({ decision: 'PERMIT' });
+  });
+
+  it('should extract a JSON object surrounded by prose', () => {
+    const text = `Here is the decision: ${decision} Let me know if you need more.`;
+
+    expect(extractJsonBlock(text)).toBe(decision);
+  });
+
+  it('should ignore braces inside string literals', () => {
+    const text = 'Result: {"decision": "DENY", "reason": "contains } brace", "confidence": 0.7} done';
+
+    expect(JSON.parse(extractJsonBlock(text)).reason).toBe('contains } brace');
+  });
+
+  it('should extract JSON arrays for batch responses', () => {
+    expect(JSON.parse(extractJsonBlock('Results:\n[{"decision": "PERMIT"}, {"decision": "DENY"}]'))).toHaveLength(2);
+  });
+
+  it('should return the trimmed text when no JSON is present', () => {
+    expect(extractJsonBlock('  no json here  ')).toBe('no json here');
+  });
+});
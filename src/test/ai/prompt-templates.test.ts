@@ -0,0 +1,53 @@
+// ============================================================================
+// Prompt Templates Test Suite
+// ============================================================================
+
+import {
+  PromptTemplateEngine,
+  POLICY_ANALYSIS_PLACEHOLDERS,
+  PROMPT_TEMPLATES,
+  findTemplatePlaceholders,
+  normalizeLocale,
+  reasonLocaleInstruction,
+  validateTemplatePlaceholders
+} from '../../ai/prompt-templates';
+
+describe('prompt templates', () => {
+  it('should only use supported placeholders in the built-in decision template', () => {
+    expect(() => validateTemplatePlaceholders(PROMPT_TEMPLATES.POLICY_ANALYSIS, POLICY_ANALYSIS_PLACEHOLDERS))
+      .not.toThrow();
+  });
+
+  it('should list placeholders without treating JSON braces as placeholders', () => {
+    const template = '{agent} wants {action}\n{ "decision": "PERMIT" }\n{agent}';
+
+    expect(findTemplatePlaceholders(template)).toEqual(['agent', 'action']);
+  });
+
+  it('should reject unknown placeholders with the allowed list', () => {
+    expect(() => validateTemplatePlaceholders('{policy} {tenant}', POLICY_ANALYSIS_PLACEHOLDERS))
+      .toThrow('Unknown prompt template placeholder(s): {tenant}');
+  });
+
+  it('should render a custom POLICY_ANALYSIS template in place of the built-in one', () => {
+    const engine = new PromptTemplateEngine();
+    engine.addTemplate('POLICY_ANALYSIS', 'POLICY={policy} AGENT={agent}');
+
+    expect(engine.render('POLICY_ANALYSIS', { policy: 'p', agent: 'claude' })).toBe('POLICY=p AGENT=claude');
+  });
+
+  it('should normalize BCP 47 locales and fall back to en for anything else', () => {
+    expect(normalizeLocale('pt-br')).toBe('pt-BR');
+    expect(normalizeLocale('zh-hant-tw')).toBe('zh-Hant-TW');
+    expect(normalizeLocale('es-419')).toBe('es-419');
+    expect(normalizeLocale('en_US')).toBe('en');
+    expect(normalizeLocale('japanese')).toBe('en');
+  });
+
+  it('should ask for the reason in the locale while keeping the decision enum canonical', () => {
+    const instruction = reasonLocaleInstruction('fr');
+
+    expect(instruction).toContain('"reason" は言語タグ fr の言語で記述してください');
+    expect(instruction).toContain('PERMIT / DENY / INDETERMINATE');
+  });
+});
@@ -0,0 +1,29 @@
+// ============================================================================
+// Token Estimate Test Suite
+// ============================================================================
+
+import { TokenUsageTracker, estimateTokens } from '../../ai/token-estimate';
+
+describe('estimateTokens', () => {
+  it('should count CJK characters individually and other words by four characters', () => {
+    expect(estimateTokens('')).toBe(0);
+    expect(estimateTokens('read the file')).toBe(3);
+    expect(estimateTokens('営業時間内のみ許可')).toBe(9);
+    expect(estimateTokens('DENY file://docs/a.txt')).toBe(6);
+  });
+});
+
+describe('TokenUsageTracker', () => {
+  it('should accumulate prompt and completion tokens per evaluation', () => {
+    const tracker = new TokenUsageTracker();
+    tracker.record(120, 30);
+    tracker.record(80, 10);
+
+    expect(tracker.snapshot()).toEqual({
+      evaluations: 2,
+      estimatedPromptTokens: 200,
+      estimatedCompletionTokens: 40,
+      estimatedTotalTokens: 240
+    });
+  });
+});
@@ -0,0 +1,69 @@
+// ============================================================================
+// AuditRetryBuffer Test Suite
+// ============================================================================
+
+import { AuditRetryBuffer, isAuditFailureMode } from '../../audit/audit-failure-mode';
+import type { DecisionAuditRecord } from '../../audit/decision-audit-log';
+
+describe('AuditRetryBuffer', () => {
+  const record = (requestId: number) => ({ requestId } as unknown as DecisionAuditRecord);
+
+  it('should write buffered records in order once writes succeed again', async () => {
+    const written: unknown[] = [];
+    let available = false;
+    const buffer = new AuditRetryBuffer(async entry => {
+      if (!available) {
+        throw new Error('ENOSPC');
+      }
+      written.push(entry.requestId);
+    }, 10, 60_000);
+
+    buffer.push(record(1));
+    buffer.push(record(2));
+    await buffer.drain();
+    expect(buffer.size()).toBe(2);
+
+    available = true;
+    await buffer.drain();
+    buffer.stop();
+
+    expect(written).toEqual([1, 2]);
+    expect(buffer.size()).toBe(0);
+  });
+
+  it('should refuse records beyond its capacity', () => {
+    const buffer = new AuditRetryBuffer(() => Promise.reject(new Error('ENOSPC')), 2, 60_000);
+
+    expect(buffer.push(record(1))).toBe(true);
+    expect(buffer.push(record(2))).toBe(true);
+    expect(buffer.push(record(3))).toBe(false);
+    expect(buffer.size()).toBe(2);
+    buffer.stop();
+  });
+
+  it('should retry on its own after the retry interval', async () => {
+    jest.useFakeTimers();
+    try {
+      const write = jest.fn().mockResolvedValue(undefined);
+      const buffer = new AuditRetryBuffer(write, 10, 1000);
+
+      buffer.push(record(1));
+      expect(write).not.toHaveBeenCalled();
+
+      await jest.advanceTimersByTimeAsync(1000);
+
+      expect(write).toHaveBeenCalledWith(record(1));
+      expect(buffer.size()).toBe(0);
+    } finally {
+      jest.useRealTimers();
+    }
+  });
+});
+
+describe('isAuditFailureMode', () => {
+  it('should accept only the supported modes', () => {
+    expect(isAuditFailureMode('fail-open')).toBe(true);
+    expect(isAuditFailureMode('buffer')).toBe(true);
+    expect(isAuditFailureMode('fail-soft')).toBe(false);
+  });
+});
@@ -0,0 +1,138 @@
+// ============================================================================
+// DecisionAuditLog Test Suite
+// ============================================================================
+
+import * as fs from 'fs/promises';
+import * as os from 'os';
+import * as path from 'path';
+import { DecisionAuditLog, DecisionAuditRecord, GENESIS_HASH, verifyAuditLog } from '../../audit/decision-audit-log';
+
+describe('DecisionAuditLog', () => {
+  let workDir: string;
+
+  const record = (requestId: number): DecisionAuditRecord => ({
+    timestamp: new Date().toISOString(),
+    requestId,
+    agent: 'mcp-client',
+    action: 'filesystem__read_file',
+    resource: 'tool:filesystem__read_file',
+    decision: 'PERMIT',
+    confidence: 0.9
+  });
+
+  beforeEach(async () => {
+    workDir = await fs.mkdtemp(path.join(os.tmpdir(), 'aegis-audit-log-'));
+  });
+
+  afterEach(async () => {
+    await fs.rm(workDir, { recursive: true, force: true });
+  });
+
+  it('should append one JSON line per record in call order', async () => {
+    const log = new DecisionAuditLog(path.join(workDir, 'nested', 'decisions.jsonl'));
+
+    await Promise.all([log.append(record(1)), log.append(record(2)), log.append(record(3))]);
+
+    const lines = (await fs.readFile(log.getFilePath(), 'utf-8')).trim().split('\n');
+    expect(lines.map(line => JSON.parse(line).requestId)).toEqual([1, 2, 3]);
+    expect(JSON.parse(lines[0])).toMatchObject({ decision: 'PERMIT', confidence: 0.9, agent: 'mcp-client' });
+  });
+
+  it('should reject when the record cannot be written', async () => {
+    // ディレクトリをファイルパスとして指定すると書き込みに失敗する
+    const log = new DecisionAuditLog(workDir);
+
+    await expect(log.append(record(1))).rejects.toThrow();
+  });
+
+  it('should report whether the log can be written without creating it', async () => {
+    const filePath = path.join(workDir, 'nested', 'decisions.jsonl');
+
+    await expect(new DecisionAuditLog(filePath).checkWritable()).resolves.toBe(true);
+    await expect(fs.stat(filePath)).rejects.toThrow();
+    await expect(new DecisionAuditLog(workDir).checkWritable()).resolves.toBe(false);
+  });
+
+  it('should keep accepting writes after a failed write', async () => {
+    const filePath = path.join(workDir, 'decisions.jsonl');
+    const log = new DecisionAuditLog(filePath);
+    await fs.mkdir(filePath);
+
+    await expect(log.append(record(1))).rejects.toThrow();
+
+    await fs.rmdir(filePath);
+    await log.append(record(2));
+    await log.flush();
+
+    expect(JSON.parse((await fs.readFile(filePath, 'utf-8')).trim()).requestId).toBe(2);
+  });
+
+  describe('hash chain', () => {
+    it('should chain entries across log instances and verify them', async () => {
+      const filePath = path.join(workDir, 'decisions.jsonl');
+      await new DecisionAuditLog(filePath).append(record(1));
+      await new DecisionAuditLog(filePath).append(record(2));
+
+      const [first, second] = (await fs.readFile(filePath, 'utf-8')).trim().split('\n').map(line => JSON.parse(line));
+      expect(first.prevHash).toBe(GENESIS_HASH);
+      expect(second.prevHash).toBe(first.hash);
+      await expect(verifyAuditLog(filePath)).resolves.toEqual({ valid: true, entries: 2 });
+    });
+
+    it('should continue the chain from a last line longer than the tail read', async () => {
+      const filePath = path.join(workDir, 'decisions.jsonl');
+      await new DecisionAuditLog(filePath).append({ ...record(1), resource: 'x'.repeat(200 * 1024) });
+      await new DecisionAuditLog(filePath).append(record(2));
+
+      await expect(verifyAuditLog(filePath)).resolves.toEqual({ valid: true, entries: 2 });
+    });
+
+    it('should start a new segment after a corrupt or unchained tail instead of failing', async () => {
+      const filePath = path.join(workDir, 'decisions.jsonl');
+      const onChainBreak = jest.fn();
+      await fs.writeFile(filePath, '{"agent": "legacy"}\n{"agent": "trunc');
+
+      await new DecisionAuditLog(filePath, undefined, onChainBreak).append(record(1));
+
+      const lines = (await fs.readFile(filePath, 'utf-8')).trim().split('\n');
+      expect(lines).toHaveLength(3);
+      expect(JSON.parse(lines[2])).toMatchObject({
+        requestId: 1,
+        prevHash: GENESIS_HASH,
+        chainBreak: 'last line is not valid JSON'
+      });
+      expect(onChainBreak).toHaveBeenCalledWith(`last line is not valid JSON: ${filePath}`);
+
+      await fs.writeFile(filePath, '{"agent": "legacy"}\n');
+      await new DecisionAuditLog(filePath, undefined, onChainBreak).append(record(2));
+      expect(onChainBreak).toHaveBeenLastCalledWith(`last line has no hash to continue: ${filePath}`);
+    });
+
+    it('should report the first tampered line', async () => {
+      const log = new DecisionAuditLog(path.join(workDir, 'decisions.jsonl'));
+      await Promise.all([log.append(record(1)), log.append(record(2)), log.append(record(3))]);
+      const lines = (await fs.readFile(log.getFilePath(), 'utf-8')).split('\n');
+      lines[1] = lines[1].replace('"PERMIT"', '"DENY"');
+      await fs.writeFile(log.getFilePath(), lines.join('\n'));
+
+      await expect(log.verify()).resolves.toEqual({
+        valid: false,
+        entries: 1,
+        line: 2,
+        reason: 'Hash does not match the entry'
+      });
+    });
+
+    it('should authenticate entries with the HMAC key', async () => {
+      const log = new DecisionAuditLog(path.join(workDir, 'decisions.jsonl'), 'secret');
+      await log.append(record(1));
+
+      await expect(log.verify()).resolves.toEqual({ valid: true, entries: 1 });
+      await expect(verifyAuditLog(log.getFilePath(), 'other')).resolves.toMatchObject({
+        valid: false,
+        line: 1,
+        reason: 'HMAC does not match the entry'
+      });
+    });
+  });
+});
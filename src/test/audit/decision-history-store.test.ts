@@ -0,0 +1,71 @@
+// ============================================================================
+// DecisionHistoryStore Test Suite
+// ============================================================================
+
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { DecisionHistoryStore } from '../../audit/decision-history-store';
+
+// node:sqlite はNode.js 22.13以降のみ
+const sqliteAvailable = (() => {
+  try {
+    require('node:sqlite');
+    return true;
+  } catch {
+    return false;
+  }
+})();
+
+(sqliteAvailable ? describe : describe.skip)('DecisionHistoryStore', () => {
+  let tempDir: string;
+  let store: DecisionHistoryStore;
+
+  const record = (overrides: Record<string, any> = {}) => ({
+    timestamp: '2024-01-15T10:00:00.000Z',
+    requestId: 1,
+    agent: 'claude',
+    action: 'read',
+    resource: 'file://a.txt',
+    decision: 'PERMIT' as const,
+    confidence: 0.9,
+    ...overrides
+  });
+
+  beforeEach(async () => {
+    tempDir = fs.mkdtempSync(path.join(os.tmpdir(), 'aegis-history-'));
+    store = await DecisionHistoryStore.open(path.join(tempDir, 'nested', 'decisions.db'));
+  });
+
+  afterEach(() => {
+    store.close();
+    fs.rmSync(tempDir, { recursive: true, force: true });
+  });
+
+  it('should filter by exact fields and time range, newest first', () => {
+    store.insert(record());
+    store.insert(record({ timestamp: '2024-01-15T11:00:00.000Z', requestId: 'abc', decision: 'DENY' }));
+    store.insert(record({ timestamp: '2024-01-15T12:00:00.000Z', agent: 'other' }));
+
+    expect(store.query({ agent: 'claude' }).map(row => row.requestId)).toEqual(['abc', '1']);
+    expect(store.query({ decision: 'DENY' })).toEqual([
+      expect.objectContaining({ agent: 'claude', decision: 'DENY', confidence: 0.9 })
+    ]);
+    expect(store.query({ since: '2024-01-15T11:00:00.000Z', until: '2024-01-15T12:00:00.000Z' }))
+      .toHaveLength(1);
+  });
+
+  it('should persist across reopen and clamp the limit', async () => {
+    for (let i = 0; i < 3; i++) {
+      store.insert(record({ requestId: i }));
+    }
+    const filePath = store.getFilePath();
+    store.close();
+
+    store = await DecisionHistoryStore.open(filePath);
+
+    expect(store.query()).toHaveLength(3);
+    expect(store.query({ limit: 2 })).toHaveLength(2);
+    expect(store.query({ limit: 0 })).toHaveLength(1);
+  });
+});
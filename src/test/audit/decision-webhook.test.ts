@@ -0,0 +1,129 @@
+// ============================================================================
+// DecisionWebhookNotifier Test Suite
+// ============================================================================
+
+import * as fs from 'fs/promises';
+import * as os from 'os';
+import * as path from 'path';
+import { DecisionWebhookNotifier, DecisionWebhookPayload, invalidWebhookDecisions, isWebhookUrl } from '../../audit/decision-webhook';
+
+describe('DecisionWebhookNotifier', () => {
+  let workDir: string;
+
+  const payload = (decision: string): DecisionWebhookPayload => ({
+    agent: 'mcp-client',
+    action: 'filesystem__delete_file',
+    resource: 'file:///etc/passwd',
+    decision,
+    reason: 'System files are protected',
+    timestamp: '2024-01-01T00:00:00.000Z'
+  });
+
+  beforeEach(async () => {
+    workDir = await fs.mkdtemp(path.join(os.tmpdir(), 'aegis-webhook-'));
+  });
+
+  afterEach(async () => {
+    await fs.rm(workDir, { recursive: true, force: true });
+  });
+
+  it('should POST only decisions matching the filter', async () => {
+    const fetch = jest.fn().mockResolvedValue({ ok: true, status: 200 });
+    const notifier = new DecisionWebhookNotifier('https://hooks.example.com/aegis', { fetch });
+
+    notifier.notify(payload('PERMIT'));
+    notifier.notify(payload('DENY'));
+    await notifier.flush();
+
+    expect(fetch).toHaveBeenCalledTimes(1);
+    const [url, init] = fetch.mock.calls[0];
+    expect(url).toBe('https://hooks.example.com/aegis');
+    expect(init).toMatchObject({ method: 'POST', headers: { 'Content-Type': 'application/json' } });
+    expect(JSON.parse(init.body)).toEqual(payload('DENY'));
+  });
+
+  it('should not wait for the delivery before returning', () => {
+    const fetch = jest.fn(() => new Promise<never>(() => undefined));
+    const notifier = new DecisionWebhookNotifier('https://hooks.example.com/aegis', { fetch, decisions: ['permit'] });
+
+    expect(notifier.notify(payload('PERMIT'))).toBeUndefined();
+    expect(fetch).toHaveBeenCalledTimes(1);
+  });
+
+  it('should retry failures and write a dead letter once the retries are exhausted', async () => {
+    const deadLetterPath = path.join(workDir, 'webhook-dead-letter.jsonl');
+    const fetch = jest.fn()
+      .mockRejectedValueOnce(new Error('ECONNREFUSED'))
+      .mockResolvedValue({ ok: false, status: 503 });
+    const onError = jest.fn();
+    const notifier = new DecisionWebhookNotifier('https://hooks.example.com/aegis', {
+      fetch,
+      maxRetries: 2,
+      retryBackoffMs: 1,
+      deadLetterPath,
+      onError
+    });
+
+    notifier.notify(payload('DENY'));
+    await notifier.flush();
+
+    expect(fetch).toHaveBeenCalledTimes(3);
+    expect(onError).toHaveBeenCalledWith('Decision webhook failed after 3 attempts: HTTP 503');
+    const entries = (await fs.readFile(deadLetterPath, 'utf-8')).trim().split('\n').map(line => JSON.parse(line));
+    expect(entries).toEqual([expect.objectContaining({ attempts: 3, error: 'HTTP 503', payload: payload('DENY') })]);
+  });
+
+  it('should stop retrying after a successful delivery', async () => {
+    const deadLetterPath = path.join(workDir, 'webhook-dead-letter.jsonl');
+    const fetch = jest.fn()
+      .mockResolvedValueOnce({ ok: false, status: 500 })
+      .mockResolvedValue({ ok: true, status: 204 });
+    const notifier = new DecisionWebhookNotifier('https://hooks.example.com/aegis', { fetch, retryBackoffMs: 1, deadLetterPath });
+
+    notifier.notify(payload('DENY'));
+    await notifier.flush();
+
+    expect(fetch).toHaveBeenCalledTimes(2);
+    await expect(fs.access(deadLetterPath)).rejects.toThrow();
+  });
+
+  it('should dead-letter notifications immediately while the pending limit is reached', async () => {
+    const deadLetterPath = path.join(workDir, 'webhook-dead-letter.jsonl');
+    let respond: (value: { ok: boolean; status: number }) => void = () => undefined;
+    const fetch = jest.fn(() => new Promise(resolve => { respond = resolve; }));
+    const onError = jest.fn();
+    const notifier = new DecisionWebhookNotifier('https://hooks.example.com/aegis', { fetch, maxPending: 1, deadLetterPath, onError });
+
+    notifier.notify(payload('DENY'));
+    notifier.notify(payload('DENY'));
+    expect(fetch).toHaveBeenCalledTimes(1);
+    expect(onError).toHaveBeenCalledWith('Decision webhook queue is full (1 pending), notification not sent');
+
+    respond({ ok: true, status: 200 });
+    await notifier.flush();
+    const entries = (await fs.readFile(deadLetterPath, 'utf-8')).trim().split('\n').map(line => JSON.parse(line));
+    expect(entries).toEqual([expect.objectContaining({ attempts: 0, error: 'queue full', payload: payload('DENY') })]);
+
+    // 送信が終われば次の通知は送られる
+    notifier.notify(payload('DENY'));
+    expect(fetch).toHaveBeenCalledTimes(2);
+    respond({ ok: true, status: 200 });
+    await notifier.flush();
+  });
+});
+
+describe('invalidWebhookDecisions', () => {
+  it('should return values that are not decisions', () => {
+    expect(invalidWebhookDecisions(['deny', 'INDETERMINATE', 'PERMIT'])).toEqual([]);
+    expect(invalidWebhookDecisions(['DENY', 'DENIED', 'allow'])).toEqual(['DENIED', 'allow']);
+  });
+});
+
+describe('isWebhookUrl', () => {
+  it('should accept only http and https URLs', () => {
+    expect(isWebhookUrl('https://hooks.example.com/aegis')).toBe(true);
+    expect(isWebhookUrl('http://localhost:8080/hook')).toBe(true);
+    expect(isWebhookUrl('ftp://example.com')).toBe(false);
+    expect(isWebhookUrl('not a url')).toBe(false);
+  });
+});
@@ -0,0 +1,133 @@
+// ============================================================================
+// Structured Constraints Test Suite
+// ============================================================================
+
+import {
+  ConstraintEvaluator,
+  applyConstraintEvaluation,
+  parseConstraint
+} from '../../../core/constraints/structured';
+import type { DecisionContext, PolicyDecision } from '../../../types';
+
+describe('structured constraints', () => {
+  const contextAt = (hours: number, minutes: number = 0, environment: Record<string, any> = {}): DecisionContext => ({
+    agent: 'claude',
+    action: 'read',
+    resource: 'file://a.txt',
+    time: new Date(2024, 0, 15, hours, minutes),
+    environment
+  });
+
+  describe('parseConstraint', () => {
+    it('should parse the supported grammar', () => {
+      expect(parseConstraint('time_window:09:00-17:00')).toEqual({
+        type: 'time_window', raw: 'time_window:09:00-17:00', startMinutes: 540, endMinutes: 1020
+      });
+      expect(parseConstraint('rate_limit:100/min')).toEqual({
+        type: 'rate_limit', raw: 'rate_limit:100/min', maxRequests: 100, windowMs: 60000
+      });
+      expect(parseConstraint('require_approval')).toEqual({ type: 'require_approval', raw: 'require_approval' });
+      expect(parseConstraint('require_approval:manager')).toMatchObject({ approver: 'manager' });
+    });
+
+    it('should leave natural-language constraints to the constraint processors', () => {
+      expect(parseConstraint('個人情報を匿名化')).toBeNull();
+      expect(parseConstraint('rate limit 10 per minute')).toBeNull();
+    });
+
+    it('should reject malformed structured constraints', () => {
+      expect(() => parseConstraint('time_window:9-17')).toThrow('Invalid time_window');
+      expect(() => parseConstraint('rate_limit:ten/min')).toThrow('Invalid rate_limit');
+      expect(() => parseConstraint('rate_limit:10/fortnight')).toThrow('Invalid rate_limit');
+    });
+  });
+
+  describe('ConstraintEvaluator', () => {
+    let evaluator: ConstraintEvaluator;
+
+    beforeEach(() => {
+      evaluator = new ConstraintEvaluator();
+    });
+
+    it('should check time windows including overnight windows', () => {
+      const business = [parseConstraint('time_window:09:00-17:00')!];
+      const overnight = [parseConstraint('time_window:22:00-06:00')!];
+
+      expect(evaluator.evaluate(business, contextAt(10)).satisfied).toBe(true);
+      expect(evaluator.evaluate(business, contextAt(17)).satisfied).toBe(false);
+      expect(evaluator.evaluate(overnight, contextAt(23, 30)).satisfied).toBe(true);
+      expect(evaluator.evaluate(overnight, contextAt(12)).satisfied).toBe(false);
+    });
+
+    it('should count requests per agent within the rate window', () => {
+      const limit = [parseConstraint('rate_limit:2/min')!];
+      const context = contextAt(10);
+
+      expect(evaluator.evaluate(limit, context, 0).satisfied).toBe(true);
+      expect(evaluator.evaluate(limit, context, 1000).satisfied).toBe(true);
+      expect(evaluator.evaluate(limit, context, 2000).satisfied).toBe(false);
+      expect(evaluator.evaluate(limit, { ...context, agent: 'other' }, 2000).satisfied).toBe(true);
+      expect(evaluator.evaluate(limit, context, 61000).satisfied).toBe(true);
+    });
+
+    it('should evict rate limit windows that have expired', () => {
+      const limit = [parseConstraint('rate_limit:2/min')!];
+      const context = contextAt(10);
+
+      evaluator.evaluate(limit, context, 0);
+      evaluator.evaluate(limit, { ...context, agent: 'other' }, 30000);
+      expect(evaluator.trackedWindows).toBe(2);
+
+      // claude の最後の要求から1分経った時点で、other のウィンドウだけが残る
+      evaluator.evaluate([], context, 60000);
+      expect(evaluator.trackedWindows).toBe(1);
+      evaluator.evaluate([], context, 90000);
+      expect(evaluator.trackedWindows).toBe(0);
+    });
+
+    it('should require an approval recorded in the environment', () => {
+      const approval = [parseConstraint('require_approval:manager')!];
+
+      expect(evaluator.evaluate(approval, contextAt(10)).satisfied).toBe(false);
+      expect(evaluator.evaluate(approval, contextAt(10, 0, { approvedBy: 'peer' })).satisfied).toBe(false);
+      expect(evaluator.evaluate(approval, contextAt(10, 0, { approvedBy: 'manager' })).satisfied).toBe(true);
+    });
+
+    it('should treat malformed structured constraints in a decision as unmet', () => {
+      const evaluation = evaluator.evaluateDecision({ constraints: ['個人情報を匿名化', 'time_window:bad'] }, contextAt(10));
+
+      expect(evaluation.satisfied).toBe(false);
+      expect(evaluation.checks).toEqual([
+        expect.objectContaining({ constraint: 'time_window:bad', satisfied: false })
+      ]);
+    });
+  });
+
+  describe('applyConstraintEvaluation', () => {
+    const permit: PolicyDecision = {
+      decision: 'PERMIT',
+      reason: '許可',
+      confidence: 0.9,
+      constraints: ['time_window:09:00-17:00']
+    };
+
+    it('should downgrade PERMIT to INDETERMINATE when constraints are unmet', () => {
+      const evaluation = new ConstraintEvaluator().evaluateDecision(permit, contextAt(20));
+
+      const result = applyConstraintEvaluation(permit, evaluation);
+
+      expect(result.decision).toBe('INDETERMINATE');
+      expect(result.reason).toContain('time_window:09:00-17:00');
+      expect(result.metadata?.unmetConstraints).toBe('time_window:09:00-17:00');
+      expect(result.metadata?.indeterminateSource).toBe('constraint');
+    });
+
+    it('should keep satisfied PERMITs and non-PERMIT decisions unchanged', () => {
+      const evaluator = new ConstraintEvaluator();
+      const deny: PolicyDecision = { ...permit, decision: 'DENY' };
+
+      expect(applyConstraintEvaluation(permit, evaluator.evaluateDecision(permit, contextAt(10)))).toBe(permit);
+      expect(applyConstraintEvaluation(deny, evaluator.evaluateDecision(deny, contextAt(20)))).toBe(deny);
+    });
+  });
+});
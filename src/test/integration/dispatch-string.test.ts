@@ -0,0 +1,148 @@
+// ============================================================================
+// dispatchString 統合テスト
+// stdioプロキシのハンドラーをプロセス内トランスポート経由で呼び出し、
+// initialize → tools/list → tools/call の往復がJSON-RPCの文字列で完結することを検証
+// ============================================================================
+
+import { MCPStdioPolicyProxy } from '../../mcp/stdio-proxy';
+import { AIJudgmentEngine } from '../../ai/judgment-engine';
+import { Logger } from '../../utils/logger';
+import type { AEGISConfig } from '../../types';
+
+describe('dispatchString Integration', () => {
+  let proxy: MCPStdioPolicyProxy;
+  let routeRequest: jest.Mock;
+
+  const config: AEGISConfig = {
+    mcpProxy: {
+      port: 8080,
+      upstreamServers: {}
+    },
+    llm: {
+      provider: 'openai',
+      model: 'gpt-4',
+      apiKey: 'test-key',
+      temperature: 0.3
+    }
+  };
+
+  const dispatch = async (message: Record<string, unknown>) =>
+    JSON.parse((await proxy.dispatchString(JSON.stringify({ jsonrpc: '2.0', ...message })))!);
+
+  const initialize = async () => {
+    await dispatch({
+      id: 0,
+      method: 'initialize',
+      params: { protocolVersion: '2024-11-05', capabilities: {}, clientInfo: { name: 'test-client', version: '1.0.0' } }
+    });
+    await proxy.dispatchString('{"jsonrpc":"2.0","method":"notifications/initialized"}');
+  };
+
+  beforeEach(() => {
+    const logger = new Logger();
+    proxy = new MCPStdioPolicyProxy(config, logger, new AIJudgmentEngine(config.llm!));
+    proxy.addPolicy('default-policy', 'ファイルの読み取りは許可する');
+
+    jest.spyOn((proxy as any).aiPolicyEngine, 'decide').mockResolvedValue({
+      decision: 'PERMIT',
+      reason: 'Reading files is allowed',
+      confidence: 0.95
+    });
+    jest.spyOn((proxy as any).advancedAuditSystem, 'recordAuditEntry').mockResolvedValue(undefined);
+
+    // 上流サーバーの代わりに応答する
+    routeRequest = jest.fn(async (request: { id: string | number; method: string; params?: any }) => {
+      if (request.method === 'tools/list') {
+        return {
+          jsonrpc: '2.0',
+          id: request.id,
+          result: {
+            tools: [{
+              name: 'filesystem__read_file',
+              description: 'Read a file',
+              inputSchema: { type: 'object', properties: { path: { type: 'string' } }, required: ['path'] }
+            }, {
+              name: 'filesystem__list_allowed_directories',
+              description: 'List the directories the server may access',
+              inputSchema: { type: 'object', properties: {} }
+            }]
+          }
+        };
+      }
+      if (request.params.name === 'filesystem__list_allowed_directories') {
+        return { jsonrpc: '2.0', id: request.id, result: { content: [{ type: 'text', text: '/tmp' }] } };
+      }
+      return {
+        jsonrpc: '2.0',
+        id: request.id,
+        result: { content: [{ type: 'text', text: `contents of ${request.params.arguments.path}` }] }
+      };
+    });
+    (proxy as any).stdioRouter.routeRequest = routeRequest;
+  });
+
+  it('should complete initialize, tools/list and tools/call through the proxy handlers', async () => {
+    const initialize = await dispatch({
+      id: 1,
+      method: 'initialize',
+      params: { protocolVersion: '2024-11-05', capabilities: {}, clientInfo: { name: 'test-client', version: '1.0.0' } }
+    });
+    expect(initialize).toMatchObject({ id: 1, result: { serverInfo: { name: expect.any(String) } } });
+    expect(await proxy.dispatchString('{"jsonrpc":"2.0","method":"notifications/initialized"}')).toBeNull();
+
+    const list = await dispatch({ id: 2, method: 'tools/list' });
+    expect(list.result.tools.map((tool: { name: string }) => tool.name)).toContain('filesystem__read_file');
+
+    const call = await dispatch({
+      id: 3,
+      method: 'tools/call',
+      params: { name: 'filesystem__read_file', arguments: { path: '/tmp/report.txt' } }
+    });
+    expect(call).toMatchObject({ id: 3, result: { content: [{ type: 'text', text: 'contents of /tmp/report.txt' }] } });
+    expect(routeRequest).toHaveBeenCalledWith(expect.objectContaining({
+      method: 'tools/call',
+      params: { name: 'filesystem__read_file', arguments: { path: '/tmp/report.txt' } }
+    }));
+  });
+
+  it('should forward a tools/call without arguments as an empty arguments object', async () => {
+    await initialize();
+    await dispatch({ id: 1, method: 'tools/list' });
+
+    const call = await dispatch({ id: 2, method: 'tools/call', params: { name: 'filesystem__list_allowed_directories' } });
+
+    expect(call).toMatchObject({ id: 2, result: { content: [{ type: 'text', text: '/tmp' }] } });
+    expect(call.result.isError).toBeUndefined();
+    expect(routeRequest).toHaveBeenCalledWith(expect.objectContaining({
+      method: 'tools/call',
+      params: { name: 'filesystem__list_allowed_directories', arguments: {} }
+    }));
+  });
+
+  it('should run a builtin tool called without arguments', async () => {
+    await initialize();
+
+    const call = await dispatch({ id: 1, method: 'tools/call', params: { name: 'server_info' } });
+
+    expect(call.id).toBe(1);
+    expect(call.result.isError).toBeUndefined();
+    expect(call.result.content[0].type).toBe('text');
+    expect(routeRequest).not.toHaveBeenCalled();
+  });
+
+  it('should answer ping with an empty result carrying the same id', async () => {
+    expect(JSON.parse((await proxy.dispatchString('{"jsonrpc":"2.0","id":7,"method":"ping"}'))!))
+      .toEqual({ jsonrpc: '2.0', id: 7, result: {} });
+
+    await initialize();
+    expect(await dispatch({ id: 'ping-2', method: 'ping' })).toEqual({ jsonrpc: '2.0', id: 'ping-2', result: {} });
+    expect(routeRequest).not.toHaveBeenCalled();
+  });
+
+  it('should reject requests sent before initialize', async () => {
+    const list = await dispatch({ id: 1, method: 'tools/list' });
+
+    expect(list).toEqual({ jsonrpc: '2.0', id: 1, error: { code: -32002, message: 'Server not initialized' } });
+    expect(routeRequest).not.toHaveBeenCalled();
+  });
+});
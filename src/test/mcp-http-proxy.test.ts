@@ -332,7 +332,8 @@ describe('MCPHttpPolicyProxy - 機能テスト', () => {
         })
       );
 
-      expect(result.tools).toHaveLength(2);
+      expect(result.tools).toHaveLength(proxy['listBuiltinTools']().length + 2);
+      expect(result.tools.map((tool: any) => tool.name)).toContain('policy_explain');
     });
 
     it('上流サーバーエラーを適切に処理する', async () => {
@@ -382,6 +383,35 @@ describe('MCPHttpPolicyProxy - 機能テスト', () => {
         })
       ).rejects.toThrow('Network error');
     });
+
+    it('未知のツール・不正な引数は転送せずisErrorの結果として返す', async () => {
+      proxy.addUpstreamServer('test-server', 'http://upstream-server:8080');
+      mockFetch.mockResolvedValueOnce({
+        ok: true,
+        json: async () => ({
+          result: {
+            tools: [{
+              name: 'fs__read',
+              inputSchema: { type: 'object', required: ['path'], properties: { path: { type: 'string' } } }
+            }]
+          }
+        })
+      } as Response);
+
+      await proxy.start();
+      await mockServer._handlers.get('ListToolsRequest')({});
+      mockFetch.mockClear();
+      const callToolHandler = mockServer._handlers.get('CallToolRequest');
+
+      const unknown = await callToolHandler({ params: { name: 'fs__write', arguments: {} } });
+      const invalid = await callToolHandler({ params: { name: 'fs__read', arguments: {} } });
+
+      expect(unknown).toEqual({ content: [{ type: 'text', text: 'Unknown tool: fs__write' }], isError: true });
+      expect(invalid.isError).toBe(true);
+      expect(invalid.content[0].text).toContain('path');
+      expect(mockJudgmentEngine.makeDecision).not.toHaveBeenCalled();
+      expect(mockFetch).not.toHaveBeenCalled();
+    });
   });
 
   describe('ポリシー管理', () => {
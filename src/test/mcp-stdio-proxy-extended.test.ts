@@ -310,6 +310,7 @@ describe('MCPStdioPolicyProxy - 拡張機能テスト', () => {
       
       expect(result.decision).toBe('PERMIT');
       expect(result.policyUsed).toBe('cached-result');
+      expect(result.metadata?.cached).toBe(true);
       expect(mockLogger.debug).toHaveBeenCalledWith(
         'Using cached decision result',
         expect.any(Object)
@@ -114,7 +114,8 @@ describe('MCPStdioPolicyProxy - 機能テスト', () => {
       stopServers: jest.fn(),  // stopServersメソッドを追加
       listAllTools: jest.fn(),
       listAllResources: jest.fn(),
-      getAvailableServers: jest.fn().mockReturnValue([])  // getAvailableServersメソッドを追加
+      getAvailableServers: jest.fn().mockReturnValue([]),  // getAvailableServersメソッドを追加
+      on: jest.fn()
     } as any;
 
     // コンストラクタのモック実装
@@ -345,8 +346,9 @@ describe('MCPStdioPolicyProxy - 機能テスト', () => {
 
       const result = await listToolsHandler({});
 
-      expect(result.tools).toHaveLength(2);
+      expect(result.tools).toHaveLength(proxy['listBuiltinTools']().length + 2);
       expect(result.tools[0].name).toBe('tool1');
+      expect(result.tools[2].name).toBe('policy_explain');
     });
 
     it('resources/list で利用可能なリソースを返す', async () => {
@@ -459,6 +461,72 @@ describe('MCPStdioPolicyProxy - 機能テスト', () => {
         'Upstream server error'
       );
     });
+
+    it('上流のJSON-RPCエラーコードを保持する', async () => {
+      mockStdioRouter.routeRequest.mockResolvedValueOnce({
+        jsonrpc: '2.0',
+        id: 1,
+        error: { code: -32601, message: 'Method not found' }
+      });
+
+      await expect(proxy['forwardToUpstream']('prompts/get', {})).rejects.toMatchObject({
+        code: -32601,
+        message: 'Method not found'
+      });
+    });
+
+    it('並行して転送したリクエストに異なるIDを割り当てる', async () => {
+      mockStdioRouter.routeRequest.mockResolvedValue({ jsonrpc: '2.0', id: 1, result: {} });
+
+      await Promise.all([
+        proxy['forwardToUpstream']('prompts/list', {}),
+        proxy['forwardToUpstream']('prompts/list', {})
+      ]);
+
+      const ids = mockStdioRouter.routeRequest.mock.calls.map((call: any[]) => call[0].id);
+      expect(new Set(ids).size).toBe(ids.length);
+    });
+
+    it('未知のツール・不正な引数はisErrorの結果として返す', async () => {
+      mockStdioRouter.routeRequest.mockResolvedValueOnce({
+        result: {
+          tools: [{
+            name: 'fs__read',
+            inputSchema: { type: 'object', required: ['path'], properties: { path: { type: 'string' } } }
+          }]
+        }
+      });
+
+      await proxy.start();
+      await mockServer._handlers.get('ListToolsRequest')({});
+      mockStdioRouter.routeRequest.mockClear();
+      const callToolHandler = mockServer._handlers.get('CallToolRequest');
+
+      const unknown = await callToolHandler({ params: { name: 'fs__write', arguments: {} } });
+      const invalid = await callToolHandler({ params: { name: 'fs__read', arguments: {} } });
+
+      expect(unknown).toEqual({ content: [{ type: 'text', text: 'Unknown tool: fs__write' }], isError: true });
+      expect(invalid.isError).toBe(true);
+      expect(invalid.content[0].text).toContain('path');
+      expect(mockStdioRouter.routeRequest).not.toHaveBeenCalled();
+    });
+
+    it('ツール一覧の変更通知で引数検証用のツール一覧を取り直す', async () => {
+      mockStdioRouter.routeRequest
+        .mockResolvedValueOnce({ result: { tools: [{ name: 'fs__read' }] } })
+        .mockResolvedValueOnce({ result: { tools: [{ name: 'fs__read' }, { name: 'fs__write' }] } });
+
+      await proxy.start();
+      await mockServer._handlers.get('ListToolsRequest')({});
+      expect(proxy['validateToolArguments']('fs__write', {})).toMatchObject({ isError: true });
+
+      const onNotification = mockStdioRouter.on.mock.calls.find(([event]: any[]) => event === 'notification')![1];
+      onNotification({ from: 'fs', message: { jsonrpc: '2.0', method: 'notifications/tools/list_changed' } });
+      await new Promise(resolve => setImmediate(resolve));
+
+      expect(proxy['validateToolArguments']('fs__write', {})).toBeNull();
+      expect(proxy['validateToolArguments']('fs__delete', {})).toMatchObject({ isError: true });
+    });
   });
 
   describe('ポリシー管理', () => {
@@ -0,0 +1,58 @@
+// ============================================================================
+// AgentRateLimiter Test Suite
+// ============================================================================
+
+import { AgentRateLimiter } from '../../mcp/agent-rate-limiter';
+import { FixedTimeProvider } from '../../utils/time-provider';
+
+describe('AgentRateLimiter', () => {
+  it('should allow a burst up to the limit and report when the next token arrives', () => {
+    const limiter = new AgentRateLimiter(2);
+
+    expect(limiter.take('claude', 0)).toEqual({ allowed: true });
+    expect(limiter.take('claude', 0)).toEqual({ allowed: true });
+    expect(limiter.take('claude', 0)).toEqual({ allowed: false, retryAfterMs: 30000 });
+    expect(limiter.take('claude', 10000)).toEqual({ allowed: false, retryAfterMs: 20000 });
+    expect(limiter.take('claude', 30000)).toEqual({ allowed: true });
+  });
+
+  it('should keep a separate bucket per agent', () => {
+    const limiter = new AgentRateLimiter(1);
+
+    expect(limiter.take('claude', 0).allowed).toBe(true);
+    expect(limiter.take('claude', 0).allowed).toBe(false);
+    expect(limiter.take('other', 0).allowed).toBe(true);
+  });
+
+  it('should refill from the injected clock when no time is given', () => {
+    const clock = new FixedTimeProvider(0);
+    const limiter = new AgentRateLimiter(1, clock);
+
+    expect(limiter.take('claude').allowed).toBe(true);
+    expect(limiter.take('claude')).toEqual({ allowed: false, retryAfterMs: 60000 });
+    clock.advance(60000);
+    expect(limiter.take('claude').allowed).toBe(true);
+  });
+
+  it('should drop buckets that have refilled since the last request', () => {
+    const limiter = new AgentRateLimiter(1);
+    for (let i = 0; i < 100; i++) {
+      limiter.take(`agent-${i}`, 0);
+    }
+    expect(limiter.trackedAgents).toBe(100);
+
+    expect(limiter.take('claude', 59999).allowed).toBe(true);
+    expect(limiter.trackedAgents).toBe(101);
+    expect(limiter.take('claude', 60000).allowed).toBe(false);
+    expect(limiter.trackedAgents).toBe(1);
+    expect(limiter.take('agent-0', 60000).allowed).toBe(true);
+  });
+
+  it('should treat 0 as unlimited', () => {
+    const limiter = new AgentRateLimiter(0);
+
+    for (let i = 0; i < 100; i++) {
+      expect(limiter.take('claude', 0).allowed).toBe(true);
+    }
+  });
+});
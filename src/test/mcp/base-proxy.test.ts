@@ -2,7 +2,10 @@
 // MCPPolicyProxyBase Test Suite
 // ============================================================================
 
-import { MCPPolicyProxyBase } from '../../mcp/base-proxy';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { MCPPolicyProxyBase, ProgressReporter } from '../../mcp/base-proxy';
 import { Logger } from '../../utils/logger';
 import { AIJudgmentEngine } from '../../ai/judgment-engine';
 import { ContextCollector } from '../../context/collector';
@@ -11,6 +14,12 @@ import { AIPolicyEngine } from '../../policy/ai-policy-engine';
 import { AdvancedAuditSystem } from '../../audit/advanced-audit-system';
 import { AuditDashboardDataProvider } from '../../audit/audit-dashboard-data';
 import type { AEGISConfig, DecisionContext, AccessControlResult, PolicyDecision } from '../../types';
+import { Server } from '@modelcontextprotocol/sdk/server/index.js';
+import type { Transport } from '@modelcontextprotocol/sdk/shared/transport.js';
+import { getRequestTrace, runWithRequestTrace } from '../../utils/request-trace';
+import { parseAccessList } from '../../policy/access-lists';
+import { SystemRng } from '../../utils/rng';
+import { FixedTimeProvider } from '../../utils/time-provider';
 
 // Mock all dependencies
 jest.mock('../../utils/logger');
@@ -20,9 +29,14 @@ jest.mock('../../core/enforcement');
 jest.mock('../../policy/ai-policy-engine');
 jest.mock('../../audit/advanced-audit-system');
 jest.mock('../../audit/audit-dashboard-data');
+// 任意のpeer依存 @cedar-policy/cedar-wasm はインストールされていないものとして扱う
+jest.mock('@cedar-policy/cedar-wasm/nodejs', () => {
+  throw new Error('Cannot find module');
+}, { virtual: true });
 jest.mock('@modelcontextprotocol/sdk/server/index.js', () => ({
   Server: jest.fn().mockImplementation(() => ({
-    setRequestHandler: jest.fn()
+    setRequestHandler: jest.fn(),
+    _requestHandlers: new Map()
   }))
 }));
 
@@ -58,6 +72,186 @@ class TestMCPProxy extends MCPPolicyProxyBase {
     return this.executeObligations(result, context);
   }
 
+  public testListPolicyResources() {
+    return this.listPolicyResources();
+  }
+
+  public testReadPolicyResource(uri: string) {
+    return this.readPolicyResource(uri);
+  }
+
+  public testBuiltinToolPolicyTarget(name: string) {
+    return this.builtinToolPolicyTarget(name);
+  }
+
+  public testWithBuiltinTools<T extends { name: string }>(upstreamTools: T[]) {
+    return this.withBuiltinTools(upstreamTools);
+  }
+
+  public testReadDecisionResource(uri: string, requester: string = 'claude') {
+    return this.readDecisionResource(uri, requester);
+  }
+
+  public testSubscribeResource(uri: string, sessionId?: string) {
+    return this.subscribeResource(uri, sessionId);
+  }
+
+  public testUnsubscribeResource(uri: string, sessionId?: string) {
+    return this.unsubscribeResource(uri, sessionId);
+  }
+
+  public testInstallSubscriptionCleanup(transport: Transport) {
+    return this.installSubscriptionCleanup(transport);
+  }
+
+  public testSetLogLevel(level: string) {
+    return this.setLogLevel(level);
+  }
+
+  public testListBuiltinPrompts() {
+    return this.listBuiltinPrompts();
+  }
+
+  public testGetBuiltinPrompt(name: string, args?: Record<string, string>) {
+    return this.getBuiltinPrompt(name, args);
+  }
+
+  public testCompleteArgument(ref: { type: string; name?: string; uri?: string }, argument: { name: string; value: string }) {
+    return this.completeArgument(ref, argument);
+  }
+
+  public testListBuiltinTools() {
+    return this.listBuiltinTools();
+  }
+
+  public testCallBuiltinTool(name: string, args?: Record<string, unknown>, progress?: ProgressReporter, signal?: AbortSignal) {
+    return this.callBuiltinTool(name, args, progress, signal);
+  }
+
+  public testCreateProgressReporter(request: any, extra?: any) {
+    return this.createProgressReporter(request, extra);
+  }
+
+  public testAssertContextMatchesSchema(context: DecisionContext) {
+    return this.assertContextMatchesSchema(context);
+  }
+
+  public testApplyDefaultContext(context: DecisionContext) {
+    return this.applyDefaultContext(context);
+  }
+
+  public testResolveAgent(explicit: string | undefined, fallback: string) {
+    return this.resolveAgent(explicit, fallback);
+  }
+
+  public testForcedAgentDecision(context: DecisionContext) {
+    return this.forcedAgentDecision(context);
+  }
+
+  public testFastPathDecision(context: DecisionContext) {
+    return this.fastPathDecision(context);
+  }
+
+  public testWithAgentPolicy(context: DecisionContext, policy: string | null) {
+    return this.withAgentPolicy(context, policy);
+  }
+
+  public testMarkAgentOverride(context: DecisionContext, decision: PolicyDecision) {
+    return this.markAgentOverride(context, decision);
+  }
+
+  public testRecordDecisionAudit(context: DecisionContext, decision: PolicyDecision, requestId?: string | number) {
+    return this.recordDecisionAudit(context, decision, requestId);
+  }
+
+  public async flushDecisionAuditLog(): Promise<void> {
+    await this.decisionAuditLog?.flush();
+  }
+
+  public testInstallRequestTracing(transport: Transport) {
+    return this.installRequestTracing(transport);
+  }
+
+  public testNormalizeToolCallParams(params: { name: string; arguments?: Record<string, unknown> }) {
+    return this.normalizeToolCallParams(params);
+  }
+
+  public testInstallRequestTiming(transport: Transport) {
+    return this.installRequestTiming(transport);
+  }
+
+  public testRegisterRequestMethods(...methods: string[]) {
+    for (const method of methods) {
+      (this.server as unknown as { _requestHandlers: Map<string, unknown> })._requestHandlers.set(method, jest.fn());
+    }
+  }
+
+  public testWithToolTimeout<R>(handler: (request: any, extra: unknown, signal: AbortSignal) => Promise<R>) {
+    return this.withToolTimeout(handler);
+  }
+
+  public testWithDeduplication<R>(handler: (request: any, extra: any) => Promise<R>) {
+    return this.withDeduplication(handler);
+  }
+
+  public dedupAgents = new Map<string, string>();
+
+  protected deduplicationAgent(extra: { sessionId?: string } | undefined): string | null {
+    return this.dedupAgents.get(extra?.sessionId ?? '') ?? null;
+  }
+
+  public testIsCacheableDecision(decision: PolicyDecision, signal?: AbortSignal) {
+    return this.isCacheableDecision(decision, signal);
+  }
+
+  public testThrowIfCancelled(signal?: AbortSignal) {
+    return this.throwIfCancelled(signal);
+  }
+
+  public testDecideWithEvalTimeout(context: DecisionContext, policyText: string, signal?: AbortSignal) {
+    return this.decideWithEvalTimeout(context, policyText, signal);
+  }
+
+  public testRecordClientCapabilities(capabilities: any) {
+    return this.recordClientCapabilities(capabilities);
+  }
+
+  public testInstallMetaEcho(transport: Transport) {
+    return this.installMetaEcho(transport);
+  }
+
+  public testFilterEnabledTools<T extends { name: string }>(tools: T[]) {
+    return this.filterEnabledTools(tools);
+  }
+
+  public testAssertToolEnabled(name: string) {
+    return this.assertToolEnabled(name);
+  }
+
+  public testHandleShutdownRequest(requestId: string | number) {
+    return this.handleShutdownRequest(requestId);
+  }
+
+  public testInstallShutdownAcknowledgement(transport: Transport) {
+    return this.installShutdownAcknowledgement(transport);
+  }
+
+  public testInstallHandshakeGuard(transport: Transport) {
+    return this.installHandshakeGuard(transport);
+  }
+
+  public testRunObligationHandlers(result: AccessControlResult) {
+    return this.runObligationHandlers(result);
+  }
+
+  public testResolveClarification(decision: AccessControlResult, reevaluate: (clarifications: any[]) => Promise<AccessControlResult>) {
+    return this.resolveClarification(decision, reevaluate, 7);
+  }
+
+  public getServer(): any {
+    return this.server;
+  }
+
   public getPolicies(): Map<string, string> {
     return this.policies;
   }
@@ -80,10 +274,23 @@ const mockLogger = {
   info: jest.fn(),
   error: jest.fn(),
   debug: jest.fn(),
-  warn: jest.fn()
+  warn: jest.fn(),
+  critical: jest.fn(),
+  setLevel: jest.fn()
 } as unknown as Logger;
 
-const mockJudgmentEngine = {} as unknown as AIJudgmentEngine;
+const mockJudgmentEngine = {
+  renderDecisionPrompt: jest.fn((policy: string, context: DecisionContext) =>
+    `POLICY:${policy} AGENT:${context.agent} ACTION:${context.action} RESOURCE:${context.resource}`),
+  explainDecision: jest.fn().mockResolvedValue({
+    decision: 'PERMIT',
+    reason: '営業時間内のため許可',
+    confidence: 0.9,
+    clauses: [{ text: '営業時間内のみ許可', relevance: 'HIGH', effect: 'PERMIT' }]
+  }),
+  exportRego: jest.fn().mockResolvedValue('package aegis.authz\n\ndefault allow := false'),
+  getTokenUsage: jest.fn()
+} as unknown as AIJudgmentEngine;
 
 const testConfig: AEGISConfig = {
   port: 3000,
@@ -108,7 +315,9 @@ describe('MCPPolicyProxyBase', () => {
     
     // Setup mocked AIPolicyEngine
     (AIPolicyEngine as jest.MockedClass<typeof AIPolicyEngine>).mockImplementation(() => ({
-      addPolicy: jest.fn()
+      addPolicy: jest.fn(),
+      decide: jest.fn(),
+      clearCache: jest.fn()
     } as any));
     
     // Setup mocked EnforcementSystem
@@ -151,6 +360,26 @@ describe('MCPPolicyProxyBase', () => {
       expect(proxyWithoutAI.getAIPolicyEngine()).toBeDefined();
       // Should still work but with AI disabled
     });
+
+    it('should report the default server name and version', () => {
+      expect(Server).toHaveBeenLastCalledWith(
+        { name: 'aegis-proxy', version: '1.0.0' },
+        expect.any(Object)
+      );
+    });
+
+    it('should report a configured server name and version', () => {
+      new TestMCPProxy(
+        { ...testConfig, mcpProxy: { port: 3000, upstreamServers: {}, serverName: 'acme-guard', serverVersion: '2.3.4' } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+
+      expect(Server).toHaveBeenLastCalledWith(
+        { name: 'acme-guard', version: '2.3.4' },
+        expect.any(Object)
+      );
+    });
   });
 
   describe('selectApplicablePolicy', () => {
@@ -369,6 +598,1887 @@ describe('MCPPolicyProxyBase', () => {
     });
   });
 
+  describe('default context', () => {
+    it('should merge the default context under the request context', () => {
+      proxy.setDefaultContext({ clearanceLevel: 'standard', environment: { tenant: 'acme', transport: 'default' } });
+
+      const merged = proxy.testApplyDefaultContext({
+        agent: 'claude',
+        action: 'read',
+        resource: 'file://a.txt',
+        time: new Date(),
+        environment: { transport: 'stdio' }
+      });
+
+      expect(merged.clearanceLevel).toBe('standard');
+      expect(merged.environment).toEqual({ tenant: 'acme', transport: 'stdio' });
+    });
+  });
+
+  describe('default agent', () => {
+    it('should prefer the explicit agent, then --default-agent, then the transport fallback', () => {
+      const configured = new TestMCPProxy(
+        { ...testConfig, mcpProxy: { port: 3000, upstreamServers: {}, defaultAgent: 'ci-bot' } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+
+      expect(configured.testResolveAgent('claude', 'http-client')).toBe('claude');
+      expect(configured.testResolveAgent(undefined, 'http-client')).toBe('ci-bot');
+      expect(proxy.testResolveAgent(undefined, 'http-client')).toBe('http-client');
+    });
+
+    it('should reject requests without an explicit agent under --require-agent with -32602', () => {
+      const strict = new TestMCPProxy(
+        { ...testConfig, mcpProxy: { port: 3000, upstreamServers: {}, defaultAgent: 'ci-bot', requireAgent: true } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+
+      expect(strict.testResolveAgent('claude', 'http-client')).toBe('claude');
+      expect(() => strict.testResolveAgent(undefined, 'http-client')).toThrow(
+        expect.objectContaining({ code: -32602, message: 'Request has no explicit agent and --require-agent is set' })
+      );
+    });
+  });
+
+  describe('action synonyms', () => {
+    const context = (action: string): DecisionContext => ({
+      agent: 'claude',
+      action,
+      resource: 'file://a.txt',
+      time: new Date(),
+      environment: {}
+    });
+
+    it('should canonicalize synonyms and keep the original action', () => {
+      const canonical = proxy.testApplyDefaultContext(context('rm'));
+
+      expect(canonical.action).toBe('delete');
+      expect(canonical.originalAction).toBe('rm');
+      expect(proxy.testApplyDefaultContext(context('delete'))).not.toHaveProperty('originalAction');
+      expect(proxy.testApplyDefaultContext(context('execute')).action).toBe('execute');
+    });
+
+    it('should use configured synonyms', () => {
+      proxy.setActionSynonyms({ delete: ['purge'] });
+
+      expect(proxy.testApplyDefaultContext(context('purge')).action).toBe('delete');
+      expect(proxy.testApplyDefaultContext(context('rm')).action).toBe('rm');
+    });
+
+    it('should record the original action in the decision audit log', async () => {
+      const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'aegis-synonyms-'));
+      const auditLogPath = path.join(dir, 'decisions.jsonl');
+      const audited = new TestMCPProxy(
+        { ...testConfig, monitoring: { enabled: true, decisionAuditLogPath: auditLogPath } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+      const decision = { decision: 'DENY' as const, reason: 'no', confidence: 0.8 };
+
+      try {
+        await audited.testRecordDecisionAudit(audited.testApplyDefaultContext(context('rm')), decision, 1);
+        await audited.testRecordDecisionAudit(audited.testApplyDefaultContext(context('read')), decision, 2);
+        await audited.flushDecisionAuditLog();
+
+        const records = fs.readFileSync(auditLogPath, 'utf-8').trim().split('\n').map(line => JSON.parse(line));
+        expect(records[0]).toMatchObject({ action: 'delete', originalAction: 'rm' });
+        expect(records[1]).not.toHaveProperty('originalAction');
+      } finally {
+        fs.rmSync(dir, { recursive: true, force: true });
+      }
+    });
+  });
+
+  describe('resource canonicalization', () => {
+    const context = (resource: string): DecisionContext => ({
+      agent: 'claude',
+      action: 'read',
+      resource,
+      time: new Date(),
+      environment: {}
+    });
+
+    it('should leave resources unchanged unless enabled', () => {
+      expect(proxy.testApplyDefaultContext(context('/prod/../prod/secrets')).resource).toBe('/prod/../prod/secrets');
+    });
+
+    it('should canonicalize path-like resources and keep the original', () => {
+      const canonicalizing = new TestMCPProxy(
+        { ...testConfig, mcpProxy: { port: 3000, upstreamServers: {}, canonicalizeResources: true } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+
+      expect(canonicalizing.testApplyDefaultContext(context('/prod/secrets/.'))).toMatchObject({
+        resource: '/prod/secrets',
+        originalResource: '/prod/secrets/.'
+      });
+      expect(canonicalizing.testApplyDefaultContext(context('/prod/secrets'))).not.toHaveProperty('originalResource');
+    });
+  });
+
+  describe('context schema', () => {
+    const context: DecisionContext = {
+      agent: 'claude',
+      action: 'read',
+      resource: 'file://a.txt',
+      time: new Date('2024-01-15T10:00:00Z'),
+      environment: {}
+    };
+    const schema = {
+      type: 'object',
+      required: ['tenant_id', 'time'],
+      properties: { tenant_id: { type: 'string' }, time: { type: 'string' } }
+    };
+
+    it('should skip validation when no schema is configured', () => {
+      expect(() => proxy.testAssertContextMatchesSchema(context)).not.toThrow();
+    });
+
+    it('should accept contexts whose merged keys satisfy the schema', () => {
+      proxy.setContextSchema(schema);
+      proxy.setDefaultContext({ tenant_id: 'acme' });
+
+      expect(() => proxy.testAssertContextMatchesSchema(proxy.testApplyDefaultContext(context))).not.toThrow();
+    });
+
+    it('should reject non-conforming contexts with -32602 and the issues', () => {
+      proxy.setContextSchema(schema);
+
+      expect(() => proxy.testAssertContextMatchesSchema({ ...context, tenant_id: 42 } as DecisionContext)).toThrow(
+        expect.objectContaining({
+          code: -32602,
+          message: 'Context does not match schema: context.tenant_id must be string, got integer',
+          data: { issues: [{ path: 'context.tenant_id', message: 'must be string, got integer' }] }
+        })
+      );
+    });
+  });
+
+  describe('agent overrides', () => {
+    const context = (agent: string): DecisionContext => ({
+      agent,
+      action: 'execute',
+      resource: 'tool:filesystem__delete',
+      time: new Date(),
+      environment: {}
+    });
+
+    beforeEach(() => {
+      proxy.setAgentOverrides(new Map([
+        ['break-glass', { mode: 'force-permit' as const }],
+        ['quarantined', { mode: 'force-deny' as const }],
+        ['intern', { mode: 'append-policy' as const, policy: '削除操作は拒否' }]
+      ]));
+    });
+
+    it('should force decisions for overridden agents and warn when widening access', () => {
+      expect(proxy.testForcedAgentDecision(context('break-glass'))).toEqual({
+        decision: 'PERMIT',
+        reason: 'Agent override force-permit applied for break-glass',
+        confidence: 1.0,
+        metadata: { agentOverride: 'force-permit' }
+      });
+      expect(mockLogger.warn).toHaveBeenCalledWith(
+        'Agent override force-permit applied for break-glass: execute tool:filesystem__delete'
+      );
+      expect(proxy.testForcedAgentDecision(context('quarantined'))?.decision).toBe('DENY');
+      expect(proxy.testForcedAgentDecision(context('intern'))).toBeNull();
+      expect(proxy.testForcedAgentDecision(context('claude'))).toBeNull();
+    });
+
+    it('should not apply force-permit where agent ids are client-controlled', () => {
+      class ClientNamedAgentProxy extends TestMCPProxy {
+        protected forcePermitAllowed(): boolean {
+          return false;
+        }
+      }
+      const untrusted = new ClientNamedAgentProxy(testConfig, mockLogger, mockJudgmentEngine);
+      untrusted.setAgentOverrides(new Map([
+        ['break-glass', { mode: 'force-permit' as const }],
+        ['quarantined', { mode: 'force-deny' as const }]
+      ]));
+
+      expect(untrusted.testForcedAgentDecision(context('break-glass'))).toBeNull();
+      expect(untrusted.testForcedAgentDecision(context('quarantined'))?.decision).toBe('DENY');
+      expect(mockLogger.warn).toHaveBeenCalledWith(expect.stringContaining('break-glass -> force-permit is ignored'));
+    });
+
+    it('should append the extra policy and note the override in the reason', () => {
+      expect(proxy.testWithAgentPolicy(context('intern'), '営業時間内のみ許可')).toBe('営業時間内のみ許可\n\n削除操作は拒否');
+      expect(proxy.testWithAgentPolicy(context('intern'), null)).toBe('削除操作は拒否');
+      expect(proxy.testWithAgentPolicy(context('claude'), '営業時間内のみ許可')).toBe('営業時間内のみ許可');
+
+      const marked = proxy.testMarkAgentOverride(context('intern'), { decision: 'DENY', reason: '削除は不可', confidence: 0.9 });
+      expect(marked).toEqual({
+        decision: 'DENY',
+        reason: '削除は不可 (agent override append-policy applied for intern)',
+        confidence: 0.9,
+        metadata: { agentOverride: 'append-policy' }
+      });
+      expect(proxy.testMarkAgentOverride(context('intern'), marked)).toBe(marked);
+    });
+
+    it('should record the applied override in the decision audit log', async () => {
+      const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'aegis-overrides-'));
+      const auditLogPath = path.join(dir, 'decisions.jsonl');
+      const audited = new TestMCPProxy(
+        { ...testConfig, monitoring: { enabled: true, decisionAuditLogPath: auditLogPath } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+      audited.setAgentOverrides(new Map([['break-glass', { mode: 'force-permit' as const }]]));
+
+      try {
+        await audited.testRecordDecisionAudit(context('break-glass'), audited.testForcedAgentDecision(context('break-glass'))!, 1);
+        await audited.testRecordDecisionAudit(context('claude'), { decision: 'DENY', reason: 'no', confidence: 0.8 }, 2);
+        await audited.flushDecisionAuditLog();
+
+        const records = fs.readFileSync(auditLogPath, 'utf-8').trim().split('\n').map(line => JSON.parse(line));
+        expect(records[0]).toMatchObject({ agent: 'break-glass', decision: 'PERMIT', override: 'force-permit' });
+        expect(records[1]).not.toHaveProperty('override');
+      } finally {
+        fs.rmSync(dir, { recursive: true, force: true });
+      }
+    });
+  });
+
+  describe('allowlist and denylist fast path', () => {
+    const context = (action: string, resource: string): DecisionContext => ({
+      agent: 'claude',
+      action,
+      resource,
+      time: new Date(),
+      environment: {}
+    });
+
+    beforeEach(() => {
+      proxy.setAccessLists({
+        allowlist: parseAccessList('read file://docs/**\n* file://public/*\n', 'allowlist.txt'),
+        denylist: parseAccessList('* file://docs/secret/**\n', 'denylist.txt')
+      });
+    });
+
+    it('should deny on a denylist hit before checking the allowlist', () => {
+      expect(proxy.testFastPathDecision(context('read', 'file://docs/secret/key.txt'))).toEqual({
+        decision: 'DENY',
+        reason: 'Fast-path decision: denylist entry at line 1 matched (* file://docs/secret/**)',
+        confidence: 1.0,
+        metadata: { fastPath: 'denylist' }
+      });
+    });
+
+    it('should permit on an allowlist hit and fall through to AI evaluation otherwise', () => {
+      expect(proxy.testFastPathDecision(context('read', 'file://docs/guide.md'))).toMatchObject({
+        decision: 'PERMIT',
+        reason: 'Fast-path decision: allowlist entry at line 1 matched (read file://docs/**)',
+        metadata: { fastPath: 'allowlist' }
+      });
+      expect(proxy.testFastPathDecision(context('write', 'file://public/index.html'))?.decision).toBe('PERMIT');
+      expect(proxy.testFastPathDecision(context('write', 'file://docs/guide.md'))).toBeNull();
+    });
+
+    it('should record the fast path in the decision audit log', async () => {
+      const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'aegis-fast-path-'));
+      const auditLogPath = path.join(dir, 'decisions.jsonl');
+      const audited = new TestMCPProxy(
+        { ...testConfig, monitoring: { enabled: true, decisionAuditLogPath: auditLogPath } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+      audited.setAccessLists({ denylist: parseAccessList('* file://docs/secret/**\n', 'denylist.txt') });
+      const denied = context('read', 'file://docs/secret/key.txt');
+
+      try {
+        await audited.testRecordDecisionAudit(denied, audited.testFastPathDecision(denied)!, 1);
+        await audited.testRecordDecisionAudit(context('read', 'file://a.txt'), { decision: 'PERMIT', reason: 'ok', confidence: 0.9 }, 2);
+        await audited.flushDecisionAuditLog();
+
+        const records = fs.readFileSync(auditLogPath, 'utf-8').trim().split('\n').map(line => JSON.parse(line));
+        expect(records[0]).toMatchObject({ decision: 'DENY', fastPath: 'denylist' });
+        expect(records[1]).not.toHaveProperty('fastPath');
+      } finally {
+        fs.rmSync(dir, { recursive: true, force: true });
+      }
+    });
+  });
+
+  describe('builtin prompts', () => {
+    const args = { agent: 'claude', action: 'read', resource: 'file://a.txt', policy: '営業時間内のみ許可' };
+
+    it('should list the evaluate_access prompt with its arguments', () => {
+      const prompts = proxy.testListBuiltinPrompts();
+
+      expect(prompts.map(prompt => prompt.name)).toEqual(['evaluate_access']);
+      expect(prompts[0].arguments?.map(argument => argument.name)).toEqual(['agent', 'action', 'resource', 'policy']);
+    });
+
+    it('should render the decision prompt as a user message', () => {
+      const result = proxy.testGetBuiltinPrompt('evaluate_access', args);
+
+      expect(result.messages).toEqual([{
+        role: 'user',
+        content: { type: 'text', text: 'POLICY:営業時間内のみ許可 AGENT:claude ACTION:read RESOURCE:file://a.txt' }
+      }]);
+    });
+
+    it('should resolve a registered policy id to its text', () => {
+      proxy.addPolicy('office-hours', '営業時間内のみ許可');
+
+      const result = proxy.testGetBuiltinPrompt('evaluate_access', { ...args, policy: 'office-hours' });
+
+      expect((result.messages[0].content as any).text).toContain('POLICY:営業時間内のみ許可');
+    });
+
+    it('should reject missing arguments and unknown prompts with -32602', () => {
+      const codeOf = (fn: () => unknown) => {
+        try {
+          fn();
+        } catch (error: any) {
+          return { code: error.code, data: error.data };
+        }
+        return undefined;
+      };
+
+      expect(codeOf(() => proxy.testGetBuiltinPrompt('evaluate_access', { agent: 'claude' })))
+        .toEqual({ code: -32602, data: { field: 'action', reason: 'required', missing: ['action', 'resource', 'policy'] } });
+      expect(codeOf(() => proxy.testGetBuiltinPrompt('unknown', args))?.code).toBe(-32602);
+    });
+  });
+
+  describe('argument completion', () => {
+    const prompt = { type: 'ref/prompt', name: 'evaluate_access' };
+
+    it('should suggest canonical actions matching the name or a synonym', () => {
+      expect(proxy.testCompleteArgument(prompt, { name: 'action', value: 'r' }).completion)
+        .toEqual({ values: ['delete', 'read'], total: 2, hasMore: false });
+      expect(proxy.testCompleteArgument(prompt, { name: 'action', value: 'ED' }).completion.values).toEqual(['update']);
+    });
+
+    it('should suggest loaded policy names for the policy argument and resource', () => {
+      proxy.addPolicy('office-hours', '営業時間内のみ許可');
+      proxy.addPolicy('prod-readonly', '本番環境は読み取りのみ');
+
+      expect(proxy.testCompleteArgument(prompt, { name: 'policy', value: 'off' }).completion.values).toEqual(['office-hours']);
+      expect(proxy.testCompleteArgument({ type: 'ref/resource', uri: 'aegis://policy/{name}' }, { name: 'name', value: 'p' })
+        .completion.values).toEqual(['prod-readonly']);
+    });
+
+    it('should return an empty list for arguments without completions', () => {
+      expect(proxy.testCompleteArgument(prompt, { name: 'agent', value: 'c' }).completion)
+        .toEqual({ values: [], total: 0, hasMore: false });
+      expect(proxy.testCompleteArgument({ type: 'ref/prompt', name: 'unknown' }, { name: 'action', value: '' }).completion.values)
+        .toEqual([]);
+    });
+  });
+
+  describe('builtin tools', () => {
+    const args = { agent: 'claude', action: 'read', resource: 'file://a.txt', policy: 'office-hours' };
+
+    it('should list policy_explain with its input schema', () => {
+      const tools = proxy.testListBuiltinTools();
+
+      expect(tools.map(tool => tool.name)).toEqual([
+        'policy_explain', 'policy_simulate', 'stats', 'server_info', 'policy_lint', 'policy_validate', 'check_policies',
+        'policy_export_rego', 'policy_diff'
+      ]);
+      expect((tools[0].inputSchema as any).required).toEqual(['agent', 'action', 'resource']);
+      expect((tools[0].inputSchema as any).properties.policy_id.type).toBe('string');
+    });
+
+    describe('policy and policy_id', () => {
+      const explain = async (target: TestMCPProxy, policyArgs: Record<string, unknown>) => {
+        await target.testCallBuiltinTool('policy_explain', { agent: 'claude', action: 'read', resource: 'file://a.txt', ...policyArgs });
+        return (mockJudgmentEngine.explainDecision as jest.Mock).mock.calls.at(-1)![0];
+      };
+
+      beforeEach(() => {
+        proxy.addPolicy('office-hours', '営業時間内のみ許可');
+      });
+
+      it('should evaluate policy as a registered id or inline text when given alone', async () => {
+        expect(await explain(proxy, { policy: 'office-hours' })).toBe('営業時間内のみ許可');
+        expect(await explain(proxy, { policy: '機密ファイルは拒否' })).toBe('機密ファイルは拒否');
+      });
+
+      it('should evaluate policy_id alone and reject an unregistered policy_id with -32602', async () => {
+        expect(await explain(proxy, { policy_id: 'office-hours' })).toBe('営業時間内のみ許可');
+        await expect(explain(proxy, { policy_id: 'ofice-hours' })).rejects.toMatchObject({
+          code: -32602,
+          message: 'Unknown policy_id: ofice-hours',
+          data: { policy_id: 'ofice-hours', registered: ['office-hours'] }
+        });
+      });
+
+      it('should prefer policy when both are given, and reject a conflict under --strict-policy-refs', async () => {
+        const strict = new TestMCPProxy(
+          { ...testConfig, mcpProxy: { port: 3000, upstreamServers: {}, strictPolicyRefs: true } },
+          mockLogger,
+          mockJudgmentEngine
+        );
+        strict.addPolicy('office-hours', '営業時間内のみ許可');
+
+        expect(await explain(proxy, { policy: '機密ファイルは拒否', policy_id: 'office-hours' })).toBe('機密ファイルは拒否');
+        expect(await explain(strict, { policy: 'office-hours', policy_id: 'office-hours' })).toBe('営業時間内のみ許可');
+        await expect(explain(strict, { policy: '機密ファイルは拒否', policy_id: 'office-hours' })).rejects.toMatchObject({
+          code: -32602,
+          message: 'policy and policy_id refer to different policies'
+        });
+      });
+
+      it('should reject an unregistered id-like policy only under --strict-policy-refs', async () => {
+        const strict = new TestMCPProxy(
+          { ...testConfig, mcpProxy: { port: 3000, upstreamServers: {}, strictPolicyRefs: true } },
+          mockLogger,
+          mockJudgmentEngine
+        );
+        strict.addPolicy('office-hours', '営業時間内のみ許可');
+
+        expect(await explain(proxy, { policy: 'ofice-hours' })).toBe('ofice-hours');
+        await expect(explain(strict, { policy: 'ofice-hours' })).rejects.toMatchObject({ code: -32602, message: 'Unknown policy: ofice-hours' });
+        await expect(strict.testCallBuiltinTool('check_policies', {
+          agent: 'claude', action: 'read', resource: 'file://a.txt', policies: ['office-hours', 'ofice-hours'], combining_algorithm: 'deny_overrides'
+        })).rejects.toMatchObject({ code: -32602, message: 'Unknown policy: ofice-hours' });
+        expect(await explain(strict, { policy: '営業時間外は拒否する' })).toBe('営業時間外は拒否する');
+      });
+    });
+
+    it('should evaluate builtin tools before running them', () => {
+      expect(proxy.testBuiltinToolPolicyTarget('history_query')).toEqual({ action: 'read', resource: 'aegis://history' });
+      expect(proxy.testBuiltinToolPolicyTarget('stats')).toEqual({ action: 'execute', resource: 'tool:stats' });
+      expect(proxy.testBuiltinToolPolicyTarget('policy_simulate')).toEqual({ action: 'execute', resource: 'tool:policy_simulate' });
+    });
+
+    it('should hide upstream tools whose names collide with builtin tools', () => {
+      const tools = proxy.testWithBuiltinTools([{ name: 'filesystem__read_file' }, { name: 'stats' }]);
+
+      expect(tools.map(tool => tool.name).filter(name => name === 'stats')).toHaveLength(1);
+      expect(tools[0]).toEqual({ name: 'filesystem__read_file' });
+      expect(tools.find(tool => tool.name === 'stats')).toHaveProperty('inputSchema');
+      expect(mockLogger.warn).toHaveBeenCalledWith('Hiding upstream tools that collide with AEGIS builtin tools: stats');
+    });
+
+    it('should report empty and unparsable policies from policy_validate', async () => {
+      const validate = async (policyArgs: Record<string, unknown>) =>
+        JSON.parse(((await proxy.testCallBuiltinTool('policy_validate', policyArgs)).content[0] as any).text);
+      proxy.addPolicy('blank', '  \n\t');
+
+      expect(await validate({ policy: '営業時間内のみ許可' })).toEqual({ valid: true, errors: [] });
+      expect(await validate({ policy: 'blank' })).toEqual({ valid: false, errors: [{ message: 'Policy is empty' }] });
+      expect(await validate({ policy: '[{"effect": "deny", "owner": "me"}]', policy_format: 'json' })).toEqual({
+        valid: false,
+        errors: [{ message: 'JSON policy rule 1: unknown field "owner" (use agent, action, resource)' }]
+      });
+      await expect(proxy.testCallBuiltinTool('policy_validate', { policy: 'p', policy_format: 'cedar' }))
+        .rejects.toMatchObject({ code: -32602, data: { supported: ['natural', 'json'] } });
+    });
+
+    it('should include lint findings in policy_validate only when lint is requested', async () => {
+      proxy.setLintRules({ ambiguityMarkers: [{ term: 'TBD', severity: 'error' }, { term: 'usually' }], roles: [] });
+      const policy = 'Access is usually allowed\nOwner: TBD';
+
+      const plain = JSON.parse(((await proxy.testCallBuiltinTool('policy_validate', { policy })).content[0] as any).text);
+      const linted = JSON.parse(((await proxy.testCallBuiltinTool('policy_validate', { policy, lint: true })).content[0] as any).text);
+
+      expect(plain).toEqual({ valid: true, errors: [] });
+      expect(linted.valid).toBe(false);
+      expect(linted.errors).toEqual([{ message: 'Ambiguous wording "TBD" may make decisions inconsistent', line: 2, column: 8 }]);
+      expect(linted.findings.map((finding: any) => finding.severity)).toEqual(['warning', 'error']);
+    });
+
+    it('should return the clause explanation as JSON text', async () => {
+      proxy.addPolicy('office-hours', '営業時間内のみ許可');
+
+      const result = await proxy.testCallBuiltinTool('policy_explain', args);
+
+      expect(mockJudgmentEngine.explainDecision).toHaveBeenCalledWith(
+        '営業時間内のみ許可',
+        expect.objectContaining({ agent: 'claude', action: 'read', resource: 'file://a.txt' })
+      );
+      const explanation = JSON.parse((result.content[0] as any).text);
+      expect(explanation.clauses).toEqual([{ text: '営業時間内のみ許可', relevance: 'HIGH', effect: 'PERMIT' }]);
+    });
+
+    it('should explain against the enforced context and resolve versioned policy references', async () => {
+      proxy.addPolicy('office-hours', '営業時間内のみ許可');
+      proxy.addPolicy('office-hours', '平日の営業時間内のみ許可');
+      proxy.setDefaultContext({ tenant_id: 'acme' });
+
+      await proxy.testCallBuiltinTool('policy_explain', { ...args, policy: 'office-hours@1' });
+
+      expect(mockJudgmentEngine.explainDecision).toHaveBeenCalledWith(
+        '営業時間内のみ許可',
+        expect.objectContaining({ agent: 'claude', tenant_id: 'acme' })
+      );
+      proxy.setContextSchema({ type: 'object', required: ['clearance'], properties: { clearance: { type: 'string' } } });
+      await expect(proxy.testCallBuiltinTool('policy_explain', args)).rejects.toMatchObject({ code: -32602 });
+    });
+
+    it('should explain agent overrides and access list matches without asking the AI', async () => {
+      proxy.setAgentOverrides(new Map([['quarantined', { mode: 'force-deny' as const }]]));
+      proxy.setAccessLists({ allowlist: parseAccessList('read file://public/*\n', 'allowlist.txt') });
+
+      const forced = JSON.parse(((await proxy.testCallBuiltinTool('policy_explain', { ...args, agent: 'quarantined' })).content[0] as any).text);
+      const listed = JSON.parse(((await proxy.testCallBuiltinTool('policy_explain', { ...args, resource: 'file://public/a.txt' })).content[0] as any).text);
+
+      expect(forced).toMatchObject({ decision: 'DENY', reason: 'Agent override force-deny applied for quarantined', clauses: [] });
+      expect(listed).toMatchObject({ decision: 'PERMIT', clauses: [] });
+      expect(mockJudgmentEngine.explainDecision).not.toHaveBeenCalled();
+    });
+
+    it('should export a Rego stub with its language and package', async () => {
+      proxy.addPolicy('office-hours', '営業時間内のみ許可');
+
+      const result = await proxy.testCallBuiltinTool('policy_export_rego', { policy: 'office-hours' });
+
+      expect(mockJudgmentEngine.exportRego).toHaveBeenCalledWith('営業時間内のみ許可', 'aegis.authz');
+      expect(JSON.parse((result.content[0] as any).text)).toEqual({
+        language: 'rego',
+        package: 'aegis.authz',
+        source: 'package aegis.authz\n\ndefault allow := false'
+      });
+      await expect(proxy.testCallBuiltinTool('policy_export_rego', { policy: 'x', package: 'bad-name' }))
+        .resolves.toMatchObject({ isError: true });
+    });
+
+    it('should diff the previous and latest versions of a registered policy', async () => {
+      proxy.addPolicy('office-hours', '営業時間内のみ許可\n機密は拒否');
+      proxy.addPolicy('office-hours', '営業時間内のみ許可\n機密は拒否');
+      proxy.addPolicy('office-hours', '営業時間内のみ許可\n機密は管理者のみ');
+
+      const result = await proxy.testCallBuiltinTool('policy_diff', { policy: 'office-hours' });
+
+      const body = JSON.parse((result.content[0] as any).text);
+      expect(body.from.version).toBe(1);
+      expect(body.to.version).toBe(2);
+      expect(body.versions).toHaveLength(2);
+      expect(body.diff).toBe([
+        '--- office-hours@1',
+        '+++ office-hours@2',
+        '@@ -1,2 +1,2 @@',
+        ' 営業時間内のみ許可',
+        '-機密は拒否',
+        '+機密は管理者のみ',
+        ''
+      ].join('\n'));
+      await expect(proxy.testCallBuiltinTool('policy_diff', { policy: 'office-hours', from: 5 }))
+        .resolves.toMatchObject({ isError: true });
+      await expect(proxy.testCallBuiltinTool('policy_diff', { policy: 'missing' }))
+        .resolves.toMatchObject({ isError: true });
+    });
+
+    it('should reject missing required arguments with -32602 naming the field', async () => {
+      await expect(proxy.testCallBuiltinTool('policy_explain', { agent: 'claude' })).rejects.toMatchObject({
+        code: -32602,
+        message: 'Missing required tool arguments: action, resource, policy',
+        data: { field: 'action', reason: 'required', missing: ['action', 'resource', 'policy'] }
+      });
+      for (const tool of ['policy_export_rego', 'policy_lint', 'policy_validate', 'policy_diff', 'policy_simulate']) {
+        await expect(proxy.testCallBuiltinTool(tool, {})).rejects.toMatchObject({
+          code: -32602,
+          data: { field: 'policy', reason: 'required' }
+        });
+      }
+      for (const requests of [undefined, []]) {
+        await expect(proxy.testCallBuiltinTool('policy_simulate', { policy: 'p', requests })).rejects.toMatchObject({
+          code: -32602,
+          data: { field: 'requests', reason: 'required' }
+        });
+      }
+      await expect(proxy.testCallBuiltinTool('policy_simulate', {
+        policy: 'p',
+        requests: [args, { agent: 'claude', resource: 'file://a.txt' }]
+      })).rejects.toMatchObject({ data: { field: 'requests[1].action', reason: 'required', missing: ['requests[1].action'] } });
+      await expect(proxy.testCallBuiltinTool('check_policies', { ...args, policies: ['p'] })).rejects.toMatchObject({
+        data: { field: 'combining_algorithm', reason: 'required' }
+      });
+      for (const policies of [undefined, []]) {
+        await expect(proxy.testCallBuiltinTool('check_policies', {
+          ...args,
+          combining_algorithm: 'deny_overrides',
+          policies
+        })).rejects.toMatchObject({ code: -32602, data: { field: 'policies', reason: 'required' } });
+      }
+    });
+
+    it('should report unknown tools as isError results', async () => {
+      await expect(proxy.testCallBuiltinTool('unknown', args)).resolves.toMatchObject({ isError: true });
+    });
+
+    it('should simulate a batch of requests and summarize the decisions', async () => {
+      const decide = proxy.getAIPolicyEngine().decide as jest.Mock;
+      decide
+        .mockResolvedValueOnce({ decision: 'PERMIT', reason: 'ok', confidence: 0.9 })
+        .mockResolvedValueOnce({ decision: 'DENY', reason: 'after hours', confidence: 0.8 });
+
+      const result = await proxy.testCallBuiltinTool('policy_simulate', {
+        policy: '営業時間内のみ許可',
+        requests: [
+          { agent: 'claude', action: 'read', resource: 'file://a.txt' },
+          { agent: 'claude', action: 'read', resource: 'file://b.txt', time: '2024-01-01T23:00:00Z' }
+        ]
+      });
+
+      const body = JSON.parse((result.content[0] as any).text);
+      expect(body.summary).toEqual({ PERMIT: 1, DENY: 1, INDETERMINATE: 0 });
+      expect(body.decisions.map((d: any) => d.decision)).toEqual(['PERMIT', 'DENY']);
+      expect(decide.mock.calls[1][0].environment).toEqual({ simulation: true });
+      expect(decide.mock.calls[1][1]).toBe('営業時間内のみ許可');
+    });
+
+    it('should apply agent overrides and access lists to simulated requests', async () => {
+      const decide = proxy.getAIPolicyEngine().decide as jest.Mock;
+      decide.mockResolvedValue({ decision: 'PERMIT', reason: 'ok', confidence: 0.9 });
+      proxy.setAgentOverrides(new Map([['quarantined', { mode: 'force-deny' as const }]]));
+      proxy.setAccessLists({ denylist: parseAccessList('* file://secret/**\n', 'denylist.txt') });
+
+      const result = await proxy.testCallBuiltinTool('policy_simulate', {
+        policy: '営業時間内のみ許可',
+        requests: [
+          { agent: 'quarantined', action: 'read', resource: 'file://a.txt' },
+          { agent: 'claude', action: 'read', resource: 'file://secret/keys.txt' },
+          { agent: 'claude', action: 'read', resource: 'file://a.txt' }
+        ]
+      });
+
+      const body = JSON.parse((result.content[0] as any).text);
+      expect(body.decisions.map((d: any) => d.decision)).toEqual(['DENY', 'DENY', 'PERMIT']);
+      expect(decide).toHaveBeenCalledTimes(1);
+    });
+
+    it('should reject simulation batches over the configured limit', async () => {
+      const limited = new TestMCPProxy(
+        { ...testConfig, mcpProxy: { port: 3000, upstreamServers: {}, maxSimulateBatch: 1 } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+      const request = { agent: 'claude', action: 'read', resource: 'file://a.txt' };
+
+      const result = await limited.testCallBuiltinTool('policy_simulate', { policy: 'p', requests: [request, request] });
+
+      expect(result).toEqual({
+        content: [{ type: 'text', text: 'Too many requests to simulate: 2 (max 1)' }],
+        isError: true
+      });
+    });
+
+    it('should return request and decision counters from the stats tool', async () => {
+      const transport = { onmessage: jest.fn() } as unknown as Transport;
+      proxy.testInstallRequestTracing(transport);
+      transport.onmessage!({ jsonrpc: '2.0', id: 1, method: 'tools/call' } as any);
+      transport.onmessage!({ jsonrpc: '2.0', id: 2, method: 'tools/call' } as any);
+      transport.onmessage!({ jsonrpc: '2.0', id: 3, method: 'tools/list' } as any);
+
+      const result = await proxy.testCallBuiltinTool('stats', {});
+
+      const stats = JSON.parse((result.content[0] as any).text);
+      expect(stats.totalRequests).toBe(3);
+      expect(stats.requestsByMethod).toEqual({ 'tools/call': 2, 'tools/list': 1 });
+      expect(stats.decisions).toEqual({ PERMIT: 0, DENY: 0, INDETERMINATE: 0 });
+      expect(stats.uptimeSeconds).toBeGreaterThanOrEqual(0);
+    });
+
+    it('should include the estimated token usage in the stats tool', async () => {
+      const usage = { evaluations: 2, estimatedPromptTokens: 300, estimatedCompletionTokens: 40, estimatedTotalTokens: 340 };
+      (mockJudgmentEngine.getTokenUsage as jest.Mock).mockReturnValue(usage);
+
+      const result = await proxy.testCallBuiltinTool('stats', {});
+
+      expect(JSON.parse((result.content[0] as any).text).tokenUsage).toEqual(usage);
+    });
+
+    it('should record estimated tokens per evaluated request but not for cached decisions', async () => {
+      const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'aegis-tokens-'));
+      const auditLogPath = path.join(dir, 'decisions.jsonl');
+      const audited = new TestMCPProxy(
+        { ...testConfig, monitoring: { enabled: true, decisionAuditLogPath: auditLogPath } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+      const context: DecisionContext = { agent: 'claude', action: 'read', resource: 'file://a.txt', time: new Date(), environment: {} };
+      const metadata = { estimatedPromptTokens: 310, estimatedCompletionTokens: 25 };
+
+      try {
+        await audited.testRecordDecisionAudit(context, { decision: 'PERMIT', reason: 'ok', confidence: 0.9, metadata }, 1);
+        await audited.testRecordDecisionAudit(context, { decision: 'PERMIT', reason: 'ok', confidence: 0.9, metadata: { ...metadata, cached: true } }, 2);
+        await audited.flushDecisionAuditLog();
+
+        const records = fs.readFileSync(auditLogPath, 'utf-8').trim().split('\n').map(line => JSON.parse(line));
+        expect(records[0].estimatedTokens).toEqual({ prompt: 310, completion: 25 });
+        expect(records[1]).not.toHaveProperty('estimatedTokens');
+      } finally {
+        fs.rmSync(dir, { recursive: true, force: true });
+      }
+    });
+
+    it('should record the rendered prompt of evaluated requests but not of cached decisions', async () => {
+      const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'aegis-prompts-'));
+      const auditLogPath = path.join(dir, 'decisions.jsonl');
+      const audited = new TestMCPProxy(
+        { ...testConfig, monitoring: { enabled: true, decisionAuditLogPath: auditLogPath } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+      const context: DecisionContext = { agent: 'claude', action: 'read', resource: 'file://a.txt', time: new Date(), environment: {} };
+      const metadata = { renderedPrompt: 'ポリシー: 営業時間内のみ許可\n業務目的: [REDACTED]' };
+
+      try {
+        await audited.testRecordDecisionAudit(context, { decision: 'PERMIT', reason: 'ok', confidence: 0.9, metadata }, 1);
+        await audited.testRecordDecisionAudit(context, { decision: 'PERMIT', reason: 'ok', confidence: 0.9, metadata: { ...metadata, cached: true } }, 2);
+        await audited.testRecordDecisionAudit(context, { decision: 'PERMIT', reason: 'ok', confidence: 0.9 }, 3);
+        await audited.flushDecisionAuditLog();
+
+        const records = fs.readFileSync(auditLogPath, 'utf-8').trim().split('\n').map(line => JSON.parse(line));
+        expect(records[0].prompt).toBe(metadata.renderedPrompt);
+        expect(records[1]).not.toHaveProperty('prompt');
+        expect(records[2]).not.toHaveProperty('prompt');
+      } finally {
+        fs.rmSync(dir, { recursive: true, force: true });
+      }
+    });
+
+    describe('audit failure mode', () => {
+      let dir: string;
+      let auditLogPath: string;
+      const context: DecisionContext = { agent: 'claude', action: 'read', resource: 'file://a.txt', time: new Date(), environment: {} };
+      const decision: PolicyDecision = { decision: 'PERMIT', reason: 'ok', confidence: 0.9 };
+
+      // 監査ログのパスをディレクトリにして書き込みを失敗させる
+      const unwritableProxy = (mode: 'fail-closed' | 'fail-open' | 'buffer') => {
+        fs.mkdirSync(auditLogPath);
+        return new TestMCPProxy(
+          { ...testConfig, monitoring: { enabled: true, decisionAuditLogPath: auditLogPath, decisionAuditFailureMode: mode } },
+          mockLogger,
+          mockJudgmentEngine
+        );
+      };
+      const auditStats = async (target: TestMCPProxy) =>
+        JSON.parse(((await target.testCallBuiltinTool('stats', {})).content[0] as any).text).auditLog;
+
+      beforeEach(() => {
+        dir = fs.mkdtempSync(path.join(os.tmpdir(), 'aegis-audit-failure-'));
+        auditLogPath = path.join(dir, 'decisions.jsonl');
+      });
+
+      afterEach(() => {
+        fs.rmSync(dir, { recursive: true, force: true });
+      });
+
+      it('should deny the request by default when the audit log cannot be written', async () => {
+        const audited = unwritableProxy('fail-closed');
+
+        await expect(audited.testRecordDecisionAudit(context, decision, 1)).rejects.toThrow('Audit log write failed');
+      });
+
+      it('should allow the decision and count it as unaudited with fail-open', async () => {
+        const audited = unwritableProxy('fail-open');
+
+        await expect(audited.testRecordDecisionAudit(context, decision, 1)).resolves.toBeUndefined();
+
+        expect(await auditStats(audited)).toEqual({ failureMode: 'fail-open', unauditedDecisions: 1, bufferedRecords: 0 });
+        expect(mockLogger.critical).toHaveBeenCalledWith(
+          expect.stringContaining('allowing the decision UNAUDITED'),
+          expect.objectContaining({ requestId: 1 })
+        );
+      });
+
+      it('should buffer records in order and write them once the audit log is writable again', async () => {
+        const audited = unwritableProxy('buffer');
+
+        await audited.testRecordDecisionAudit(context, decision, 1);
+        await audited.testRecordDecisionAudit(context, decision, 2);
+        expect(await auditStats(audited)).toEqual({ failureMode: 'buffer', unauditedDecisions: 0, bufferedRecords: 2 });
+
+        fs.rmdirSync(auditLogPath);
+        await (audited as any).auditRetryBuffer.drain();
+        await audited.flushDecisionAuditLog();
+
+        const records = fs.readFileSync(auditLogPath, 'utf-8').trim().split('\n').map(line => JSON.parse(line));
+        expect(records.map(record => record.requestId)).toEqual([1, 2]);
+        expect((await auditStats(audited)).bufferedRecords).toBe(0);
+      });
+    });
+
+    it('should send audited decisions to the decision webhook with their reason', async () => {
+      const notifying = new TestMCPProxy(
+        { ...testConfig, monitoring: { enabled: true, decisionWebhookUrl: 'https://hooks.example.com/aegis' } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+      const notify = jest.spyOn((notifying as any).decisionWebhook, 'notify').mockImplementation(() => undefined);
+      const context: DecisionContext = { agent: 'claude', action: 'delete', resource: 'file://a.txt', time: new Date(), environment: {} };
+
+      await notifying.testRecordDecisionAudit(context, { decision: 'DENY', reason: 'protected', confidence: 0.9 }, 1);
+
+      expect(notify).toHaveBeenCalledWith({
+        agent: 'claude',
+        action: 'delete',
+        resource: 'file://a.txt',
+        decision: 'DENY',
+        reason: 'protected',
+        timestamp: expect.any(String)
+      });
+    });
+
+    it('should record the HTTP request id of the traced request', async () => {
+      const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'aegis-request-id-'));
+      const auditLogPath = path.join(dir, 'decisions.jsonl');
+      const audited = new TestMCPProxy(
+        { ...testConfig, monitoring: { enabled: true, decisionAuditLogPath: auditLogPath } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+      const context: DecisionContext = { agent: 'claude', action: 'read', resource: 'file://a.txt', time: new Date(), environment: {} };
+
+      try {
+        await runWithRequestTrace({ requestId: 1, httpRequestId: 'gw-123' }, () =>
+          audited.testRecordDecisionAudit(context, { decision: 'PERMIT', reason: 'ok', confidence: 0.9 }, 1)
+        );
+        await audited.testRecordDecisionAudit(context, { decision: 'PERMIT', reason: 'ok', confidence: 0.9 }, 2);
+        await audited.flushDecisionAuditLog();
+
+        const records = fs.readFileSync(auditLogPath, 'utf-8').trim().split('\n').map(line => JSON.parse(line));
+        expect(records[0].httpRequestId).toBe('gw-123');
+        expect(records[1]).not.toHaveProperty('httpRequestId');
+      } finally {
+        fs.rmSync(dir, { recursive: true, force: true });
+      }
+    });
+
+    it('should summarize the effective configuration without secrets from the server_info tool', async () => {
+      const configured = new TestMCPProxy({
+        ...testConfig,
+        llm: { provider: 'openai', apiKey: 'sk-secret', model: 'gpt-4o' },
+        security: { secretKey: 'secret-key' },
+        monitoring: { enabled: true, decisionAuditLogKey: 'hmac-secret' },
+        mcpProxy: { port: 3000, upstreamServers: {}, disabledTools: ['policy_diff'], dedupWindow: 5 }
+      } as any, mockLogger, mockJudgmentEngine);
+      configured.addPolicy('office-hours', '営業時間内のみ許可');
+
+      const result = await configured.testCallBuiltinTool('server_info', {});
+
+      const text = (result.content[0] as any).text as string;
+      const info = JSON.parse(text);
+      expect(info.policies).toEqual({ count: 1, names: ['office-hours'] });
+      expect(info.tools.builtin).toContain('server_info');
+      expect(info.tools.builtin).not.toContain('policy_diff');
+      expect(info.llm).toEqual({ provider: 'openai', model: 'gpt-4o' });
+      expect(info.audit).toEqual({ decisionAuditLog: false, signed: false, decisionHistory: false });
+      expect(info.limits.dedupWindow).toBe(5);
+      expect(text).not.toMatch(/sk-secret|secret-key|hmac-secret/);
+    });
+
+    it('should combine per-policy decisions with the requested algorithm', async () => {
+      const decide = proxy.getAIPolicyEngine().decide as jest.Mock;
+      decide
+        .mockResolvedValueOnce({ decision: 'PERMIT', reason: 'office hours', confidence: 0.9 })
+        .mockResolvedValueOnce({ decision: 'DENY', reason: 'confidential', confidence: 0.7 });
+      proxy.addPolicy('office-hours', '営業時間内のみ許可');
+
+      const result = await proxy.testCallBuiltinTool('check_policies', {
+        ...args,
+        policies: ['office-hours', '機密ファイルは拒否'],
+        combining_algorithm: 'deny_overrides'
+      });
+
+      const body = JSON.parse((result.content[0] as any).text);
+      expect(body).toMatchObject({ decision: 'DENY', decidingIndex: 1, combiningAlgorithm: 'deny_overrides' });
+      expect(body.decisions.map((d: any) => [d.policy, d.decision])).toEqual([
+        ['office-hours', 'PERMIT'],
+        ['機密ファイルは拒否', 'DENY']
+      ]);
+      expect(decide.mock.calls[0][1]).toBe('営業時間内のみ許可');
+      expect(result.structuredContent).toEqual(body);
+    });
+
+    it('should report the evaluated policy versions and the controlling policy in the result and audit log', async () => {
+      const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'aegis-provenance-'));
+      const auditLogPath = path.join(dir, 'decisions.jsonl');
+      const audited = new TestMCPProxy(
+        { ...testConfig, monitoring: { enabled: true, decisionAuditLogPath: auditLogPath } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+      const decide = audited.getAIPolicyEngine().decide as jest.Mock;
+      decide
+        .mockResolvedValueOnce({ decision: 'PERMIT', reason: 'office hours', confidence: 0.9 })
+        .mockResolvedValueOnce({ decision: 'DENY', reason: 'confidential', confidence: 0.7 });
+      audited.addPolicy('office-hours', '営業時間内のみ許可');
+      audited.addPolicy('office-hours', '平日の営業時間内のみ許可');
+
+      try {
+        const result = await audited.testCallBuiltinTool('check_policies', {
+          ...args,
+          policies: ['office-hours', '機密ファイルは拒否'],
+          combining_algorithm: 'deny_overrides'
+        });
+        await audited.flushDecisionAuditLog();
+
+        const provenance = (result.structuredContent as any).provenance;
+        expect(provenance.evaluated).toEqual([
+          { policy: 'office-hours', version: 2, hash: expect.stringMatching(/^[0-9a-f]{64}$/) },
+          { policy: '機密ファイルは拒否', version: null, hash: expect.stringMatching(/^[0-9a-f]{64}$/) }
+        ]);
+        expect(provenance.controlling).toEqual(provenance.evaluated[1]);
+
+        const [record] = fs.readFileSync(auditLogPath, 'utf-8').trim().split('\n').map(line => JSON.parse(line));
+        expect(record).toMatchObject({ decision: 'DENY', provenance: { combiningAlgorithm: 'deny_overrides', ...provenance } });
+      } finally {
+        fs.rmSync(dir, { recursive: true, force: true });
+      }
+    });
+
+    it('should apply agent overrides and access lists before evaluating each policy', async () => {
+      const decide = proxy.getAIPolicyEngine().decide as jest.Mock;
+      decide.mockResolvedValue({ decision: 'PERMIT', reason: 'office hours', confidence: 0.9 });
+      proxy.setAgentOverrides(new Map([['quarantined', { mode: 'force-deny' as const }]]));
+      proxy.setAccessLists({ denylist: parseAccessList('* file://secret/**\n', 'denylist.txt') });
+      const check = async (overrides: Record<string, unknown>) => (await proxy.testCallBuiltinTool('check_policies', {
+        ...args,
+        policies: ['営業時間内のみ許可'],
+        combining_algorithm: 'permit_overrides',
+        ...overrides
+      })).structuredContent as any;
+
+      const forced = await check({ agent: 'quarantined' });
+      expect(forced).toMatchObject({ decision: 'DENY', decidingIndex: null, metadata: { agentOverride: 'force-deny' }, decisions: [] });
+      const listed = await check({ resource: 'file://secret/keys.txt' });
+      expect(listed).toMatchObject({ decision: 'DENY', metadata: { fastPath: 'denylist' }, decisions: [] });
+      expect(decide).not.toHaveBeenCalled();
+    });
+
+    it('should downgrade a policy PERMIT with unmet constraints before combining', async () => {
+      const decide = proxy.getAIPolicyEngine().decide as jest.Mock;
+      decide.mockResolvedValue({ decision: 'PERMIT', reason: 'ok', confidence: 0.9, constraints: ['require_approval'] });
+
+      const result = await proxy.testCallBuiltinTool('check_policies', {
+        ...args,
+        policies: ['営業時間内のみ許可'],
+        combining_algorithm: 'permit_overrides'
+      });
+
+      expect((result.structuredContent as any).decisions[0].decision).toBe('INDETERMINATE');
+    });
+
+    it('should declare an outputSchema covering the structured check_policies result', () => {
+      const tool = proxy.testListBuiltinTools().find(t => t.name === 'check_policies')!;
+
+      expect(tool.outputSchema).toMatchObject({ type: 'object' });
+      expect(Object.keys((tool.outputSchema as any).properties)).toEqual(
+        expect.arrayContaining(['decision', 'reason', 'confidence', 'decisions'])
+      );
+      // format: minimal の応答も満たすよう、必須は decision のみ
+      expect((tool.outputSchema as any).required).toEqual(['decision']);
+    });
+
+    it('should return only the decision with format minimal', async () => {
+      const decide = proxy.getAIPolicyEngine().decide as jest.Mock;
+      decide.mockResolvedValue({ decision: 'DENY', reason: 'confidential', confidence: 0.7, obligations: ['log'] });
+
+      const result = await proxy.testCallBuiltinTool('check_policies', {
+        ...args,
+        policies: ['p'],
+        combining_algorithm: 'deny_overrides',
+        format: 'minimal'
+      });
+
+      expect(result.structuredContent).toEqual({ decision: 'DENY' });
+      expect((result.content[0] as any).text).toBe('{"decision":"DENY"}');
+    });
+
+    it('should reject an unknown format with -32602 before evaluating', async () => {
+      const decide = proxy.getAIPolicyEngine().decide as jest.Mock;
+
+      await expect(proxy.testCallBuiltinTool('check_policies', {
+        ...args,
+        policies: ['p'],
+        combining_algorithm: 'deny_overrides',
+        format: 'compact'
+      })).rejects.toMatchObject({ code: -32602, message: 'Unknown format: compact' });
+      expect(decide).not.toHaveBeenCalled();
+    });
+
+    it('should evaluate JSON policies with the rule interpreter instead of the AI engine', async () => {
+      const decide = proxy.getAIPolicyEngine().decide as jest.Mock;
+      proxy.addPolicy('read-only', JSON.stringify({ rules: [{ effect: 'deny', action: ['delete'] }, { effect: 'permit', resource: 'file://**' }] }));
+
+      const result = await proxy.testCallBuiltinTool('check_policies', {
+        ...args,
+        policies: ['read-only', '[{"effect": "deny", "agent": "gpt"}]'],
+        combining_algorithm: 'permit_overrides',
+        policy_format: 'json'
+      });
+
+      expect(result.structuredContent).toMatchObject({
+        decision: 'PERMIT',
+        decidingIndex: 0,
+        decisions: [
+          { policy: 'read-only', decision: 'PERMIT', confidence: 1, reason: expect.stringContaining('Matched JSON policy rule 2') },
+          { decision: 'INDETERMINATE', reason: 'No JSON policy rule matched the request' }
+        ]
+      });
+      expect(decide).not.toHaveBeenCalled();
+    });
+
+    it('should reject malformed JSON policies and unsupported policy formats with -32602', async () => {
+      const checkArgs = { ...args, combining_algorithm: 'deny_overrides' };
+
+      await expect(proxy.testCallBuiltinTool('check_policies', {
+        ...checkArgs,
+        policies: ['[{"effect": "allow"}]'],
+        policy_format: 'json'
+      })).rejects.toMatchObject({ code: -32602, message: 'JSON policy rule 1: effect must be "permit" or "deny"' });
+      // @cedar-policy/cedar-wasm が読み込めないため cedar は使えない
+      await expect(proxy.testCallBuiltinTool('check_policies', { ...checkArgs, policies: ['p'], policy_format: 'cedar' }))
+        .rejects.toMatchObject({ code: -32602, message: 'Unsupported policy_format: cedar', data: { supported: ['natural', 'json'] } });
+      await expect(proxy.testCallBuiltinTool('check_policies', { ...checkArgs, policies: ['p'], policy_format: 'rego' }))
+        .rejects.toMatchObject({ code: -32602, message: 'Unsupported policy_format: rego' });
+      expect(proxy.getAIPolicyEngine().decide).not.toHaveBeenCalled();
+    });
+
+    it('should pass a normalized locale to the evaluation and fall back to en for invalid tags', async () => {
+      const decide = proxy.getAIPolicyEngine().decide as jest.Mock;
+      decide.mockResolvedValue({ decision: 'PERMIT', reason: 'autorisé', confidence: 0.9 });
+
+      await proxy.testCallBuiltinTool('check_policies', { ...args, policies: ['p'], combining_algorithm: 'deny_overrides', locale: 'fr-ca' });
+      await proxy.testCallBuiltinTool('check_policies', { ...args, policies: ['p'], combining_algorithm: 'deny_overrides', locale: 'french!' });
+      await proxy.testCallBuiltinTool('check_policies', { ...args, policies: ['p'], combining_algorithm: 'deny_overrides' });
+
+      expect(decide.mock.calls.map(call => call[0].locale)).toEqual(['fr-CA', 'en', undefined]);
+      expect(mockLogger.warn).toHaveBeenCalledWith('Unsupported locale "french!"; returning reasons in en');
+    });
+
+    it('should evaluate every registered policy carrying one of the requested tags', async () => {
+      const decide = proxy.getAIPolicyEngine().decide as jest.Mock;
+      decide.mockResolvedValue({ decision: 'PERMIT', reason: 'ok', confidence: 0.9 });
+      proxy.addPolicy('egress', '外部通信は拒否', ['network']);
+      proxy.addPolicy('pii', '個人情報は拒否', ['Data']);
+      proxy.addPolicy('office-hours', '営業時間内のみ許可');
+
+      const result = await proxy.testCallBuiltinTool('check_policies', {
+        ...args,
+        tags: ['data', 'network'],
+        combining_algorithm: 'deny_overrides'
+      });
+
+      expect((result.structuredContent as any).decisions.map((d: any) => d.policy)).toEqual(['egress', 'pii']);
+      expect(proxy.testListPolicyResources().find(resource => resource.name === 'pii')?._meta).toEqual({ tags: ['data'] });
+    });
+
+    it('should return an empty result when no policy matches the tags', async () => {
+      const passthrough = new TestMCPProxy({ ...testConfig, indeterminateResolution: 'passthrough' }, mockLogger, mockJudgmentEngine);
+
+      const result = await passthrough.testCallBuiltinTool('check_policies', {
+        ...args,
+        tags: ['finance'],
+        combining_algorithm: 'deny_overrides'
+      });
+
+      expect(result.isError).toBeUndefined();
+      expect(result.structuredContent).toMatchObject({
+        decision: 'INDETERMINATE',
+        reason: 'No registered policies are tagged: finance',
+        decidingIndex: null,
+        decisions: []
+      });
+      expect(passthrough.getAIPolicyEngine().decide).not.toHaveBeenCalled();
+    });
+
+    it('should resolve an INDETERMINATE combined decision according to indeterminateResolution', async () => {
+      const indeterminate = { decision: 'INDETERMINATE', reason: 'unclear', confidence: 0.3 };
+      (proxy.getAIPolicyEngine().decide as jest.Mock).mockResolvedValue(indeterminate);
+      const checkArgs = { ...args, policies: ['p'], combining_algorithm: 'first_applicable' };
+
+      const denied = JSON.parse(((await proxy.testCallBuiltinTool('check_policies', checkArgs)).content[0] as any).text);
+      expect(denied.decision).toBe('DENY');
+      expect(denied.reason).toContain('resolved to DENY');
+
+      const passthrough = new TestMCPProxy({ ...testConfig, indeterminateResolution: 'passthrough' }, mockLogger, mockJudgmentEngine);
+      (passthrough.getAIPolicyEngine().decide as jest.Mock).mockResolvedValue(indeterminate);
+      const unchanged = JSON.parse(((await passthrough.testCallBuiltinTool('check_policies', checkArgs)).content[0] as any).text);
+      expect(unchanged.decision).toBe('INDETERMINATE');
+    });
+
+    it('should evaluate only the sections named in scope', async () => {
+      const decide = proxy.getAIPolicyEngine().decide as jest.Mock;
+      decide.mockResolvedValue({ decision: 'PERMIT', reason: 'ok', confidence: 0.9 });
+      proxy.addPolicy('handbook', '# 社内ポリシー\n## ファイルアクセス\n営業時間内のみ許可\n## 外部通信\n外部通信は拒否');
+      const checkArgs = { ...args, policies: ['handbook'], combining_algorithm: 'deny_overrides' };
+
+      await proxy.testCallBuiltinTool('check_policies', { ...checkArgs, scope: ['ファイルアクセス'] });
+      await proxy.testCallBuiltinTool('check_policies', checkArgs);
+
+      expect(decide.mock.calls[0][1]).toBe('## ファイルアクセス\n営業時間内のみ許可');
+      expect(decide.mock.calls[1][1]).toContain('外部通信は拒否');
+    });
+
+    it('should reject a scope naming a nonexistent section with -32602', async () => {
+      proxy.addPolicy('handbook', '## ファイルアクセス\n営業時間内のみ許可');
+
+      await expect(proxy.testCallBuiltinTool('check_policies', {
+        ...args,
+        policies: ['handbook'],
+        scope: ['ファイルアクセス', '外部通信'],
+        combining_algorithm: 'deny_overrides'
+      })).rejects.toMatchObject({
+        code: -32602,
+        message: 'Unknown policy section in scope: 外部通信',
+        data: { policy: 'handbook', missing: ['外部通信'], available: ['ファイルアクセス'] }
+      });
+      expect(proxy.getAIPolicyEngine().decide).not.toHaveBeenCalled();
+    });
+
+    it('should reject unknown combining algorithms with -32602', async () => {
+      await expect(proxy.testCallBuiltinTool('check_policies', {
+        ...args,
+        policies: ['p'],
+        combining_algorithm: 'majority'
+      })).rejects.toMatchObject({ code: -32602, message: 'Unknown combining_algorithm: majority' });
+    });
+
+    it('should report explanation failures as isError results', async () => {
+      (mockJudgmentEngine.explainDecision as jest.Mock).mockRejectedValueOnce(new Error('LLM unavailable'));
+
+      const result = await proxy.testCallBuiltinTool('policy_explain', args);
+
+      expect(result.isError).toBe(true);
+      expect((result.content[0] as any).text).toContain('LLM unavailable');
+    });
+
+    it('should send progress notifications only when a progress token is given', async () => {
+      const sendNotification = jest.fn().mockResolvedValue(undefined);
+
+      expect(proxy.testCreateProgressReporter({ params: {} }, { sendNotification })).toBeUndefined();
+
+      const progress = proxy.testCreateProgressReporter(
+        { params: { _meta: { progressToken: 'tok-1' } } },
+        { sendNotification }
+      );
+      await proxy.testCallBuiltinTool('policy_explain', args, progress);
+
+      expect(sendNotification.mock.calls.map(call => call[0].params.message))
+        .toEqual(['parsing policy', 'evaluating clauses', 'done']);
+      expect(sendNotification).toHaveBeenLastCalledWith({
+        method: 'notifications/progress',
+        params: { progressToken: 'tok-1', progress: 2, total: 2, message: 'done' }
+      });
+    });
+  });
+
+  describe('request tracing', () => {
+    it('should run the protocol handler within the message trace', () => {
+      const seen: unknown[] = [];
+      const transport = {
+        onmessage: jest.fn(() => seen.push(getRequestTrace()))
+      } as unknown as Transport;
+
+      proxy.testInstallRequestTracing(transport);
+      transport.onmessage!({ jsonrpc: '2.0', id: 3, method: 'tools/list' } as any);
+      transport.onmessage!({ jsonrpc: '2.0', method: 'notifications/initialized' } as any);
+
+      expect(seen[0]).toEqual({ requestId: 3, method: 'tools/list' });
+      expect(seen[1]).toEqual({ traceId: expect.any(String), method: 'notifications/initialized' });
+    });
+
+    it('should keep the HTTP request id set around the transport', () => {
+      const seen: unknown[] = [];
+      const transport = {
+        onmessage: jest.fn(() => seen.push(getRequestTrace()))
+      } as unknown as Transport;
+
+      proxy.testInstallRequestTracing(transport);
+      runWithRequestTrace({ httpRequestId: 'gw-123' }, () => {
+        transport.onmessage!({ jsonrpc: '2.0', id: 3, method: 'tools/list' } as any);
+      });
+
+      expect(seen[0]).toEqual({ requestId: 3, method: 'tools/list', httpRequestId: 'gw-123' });
+    });
+  });
+
+  describe('tool call arguments', () => {
+    it('should treat missing arguments as an empty object', () => {
+      expect(proxy.testNormalizeToolCallParams({ name: 'server_info' })).toEqual({ name: 'server_info', arguments: {} });
+    });
+
+    it('should keep the given arguments', () => {
+      expect(proxy.testNormalizeToolCallParams({ name: 'echo', arguments: { text: 'hi' } }).arguments).toEqual({ text: 'hi' });
+    });
+  });
+
+  describe('request timing', () => {
+    it('should record per-method latency and warn about slow requests', async () => {
+      const clock = new FixedTimeProvider(0);
+      const timed = new TestMCPProxy(
+        { ...testConfig, mcpProxy: { port: 3000, upstreamServers: {}, slowRequestMs: 500 } },
+        mockLogger,
+        mockJudgmentEngine,
+        { rng: new SystemRng(), clock }
+      );
+      timed.testRegisterRequestMethods('tools/list', 'tools/call');
+      const transport = { onmessage: jest.fn(), send: jest.fn().mockResolvedValue(undefined) } as unknown as Transport;
+      timed.testInstallRequestTiming(transport);
+
+      transport.onmessage!({ jsonrpc: '2.0', id: 1, method: 'tools/list' } as any);
+      transport.onmessage!({ jsonrpc: '2.0', id: 2, method: 'tools/call' } as any);
+      clock.advance(20);
+      await transport.send({ jsonrpc: '2.0', id: 1, result: {} } as any);
+      clock.advance(1480);
+      await transport.send({ jsonrpc: '2.0', id: 2, result: {} } as any);
+
+      const stats = JSON.parse(((await timed.testCallBuiltinTool('stats', {})).content[0] as any).text);
+      expect(stats.requestLatency['tools/list']).toMatchObject({ count: 1, sumSeconds: 0.02 });
+      expect(stats.requestLatency['tools/call']).toMatchObject({ count: 1, sumSeconds: 1.5 });
+      expect(mockLogger.warn).not.toHaveBeenCalledWith('Slow request: tools/list took 20ms', expect.anything());
+      expect(mockLogger.warn).toHaveBeenCalledWith('Slow request: tools/call took 1500ms', {
+        method: 'tools/call',
+        id: 2,
+        durationMs: 1500,
+        slowRequestMs: 500
+      });
+    });
+
+    it('should bucket methods without a handler as other', async () => {
+      proxy.testRegisterRequestMethods('tools/list');
+      const transport = { onmessage: jest.fn(), send: jest.fn().mockResolvedValue(undefined) } as unknown as Transport;
+      proxy.testInstallRequestTiming(transport);
+
+      transport.onmessage!({ jsonrpc: '2.0', id: 1, method: 'tools/list' } as any);
+      transport.onmessage!({ jsonrpc: '2.0', id: 2, method: 'x-random/abc123' } as any);
+      transport.onmessage!({ jsonrpc: '2.0', id: 3, method: 'x-random/def456' } as any);
+      for (const id of [1, 2, 3]) {
+        await transport.send({ jsonrpc: '2.0', id, result: {} } as any);
+      }
+
+      const stats = JSON.parse(((await proxy.testCallBuiltinTool('stats', {})).content[0] as any).text);
+      expect(Object.keys(stats.requestLatency).sort()).toEqual(['other', 'tools/list']);
+      expect(stats.requestLatency.other).toMatchObject({ count: 2 });
+    });
+
+    it('should not time cancelled requests', async () => {
+      proxy.testRegisterRequestMethods('tools/call');
+      const transport = { onmessage: jest.fn(), send: jest.fn().mockResolvedValue(undefined) } as unknown as Transport;
+      proxy.testInstallRequestTiming(transport);
+
+      transport.onmessage!({ jsonrpc: '2.0', id: 3, method: 'tools/call' } as any);
+      transport.onmessage!({ jsonrpc: '2.0', method: 'notifications/cancelled', params: { requestId: 3 } } as any);
+      await transport.send({ jsonrpc: '2.0', id: 3, result: {} } as any);
+
+      const stats = JSON.parse(((await proxy.testCallBuiltinTool('stats', {})).content[0] as any).text);
+      expect(stats.requestLatency).toEqual({});
+    });
+  });
+
+  describe('tool timeout', () => {
+    const request = { params: { name: 'filesystem__read_file' } };
+
+    it('should fail a stuck tool call with -32603 and abort its signal', async () => {
+      const limited = new TestMCPProxy(
+        { ...testConfig, mcpProxy: { port: 3000, upstreamServers: {}, toolTimeoutMs: 10 } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+      let seenSignal: AbortSignal | undefined;
+      const handler = limited.testWithToolTimeout((_request, _extra, signal) => {
+        seenSignal = signal;
+        return new Promise(() => undefined);
+      });
+
+      await expect(handler(request, undefined)).rejects.toMatchObject({
+        code: -32603,
+        message: 'Tool timed out: filesystem__read_file (after 10ms)'
+      });
+      expect(seenSignal?.aborted).toBe(true);
+    });
+
+    it('should not forward a call whose evaluation finishes after the timeout', async () => {
+      const limited = new TestMCPProxy(
+        { ...testConfig, mcpProxy: { port: 3000, upstreamServers: {}, toolTimeoutMs: 10 } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+      const forward = jest.fn();
+      let finished: Promise<void> | undefined;
+      const handler = limited.testWithToolTimeout((_request, _extra, signal) => {
+        // ハンドラーと同じく、判定の後・転送の前に中断を確認する
+        finished = new Promise<void>(resolve => setTimeout(resolve, 30)).then(() => {
+          limited.testThrowIfCancelled(signal);
+          forward();
+        });
+        return finished;
+      });
+
+      await expect(handler(request, undefined)).rejects.toMatchObject({ code: -32603 });
+      await expect(finished).rejects.toMatchObject({ code: -32800 });
+      expect(forward).not.toHaveBeenCalled();
+    });
+
+    it('should return the result of calls that finish in time', async () => {
+      const handler = proxy.testWithToolTimeout(async (_request, _extra, signal) => ({ aborted: signal.aborted }));
+
+      await expect(handler(request, undefined)).resolves.toEqual({ aborted: false });
+    });
+
+    it('should abort the handler signal when the client cancels the request', async () => {
+      const cancellation = new AbortController();
+      let seenSignal: AbortSignal | undefined;
+      const handler = proxy.testWithToolTimeout((_request, _extra, signal) => {
+        seenSignal = signal;
+        return new Promise(() => undefined);
+      });
+
+      const pending = handler(request, { signal: cancellation.signal });
+      cancellation.abort();
+
+      await expect(pending).rejects.toMatchObject({ code: -32800 });
+      expect(seenSignal?.aborted).toBe(true);
+    });
+
+    it('should not forward a call cancelled during policy evaluation', async () => {
+      const cancellation = new AbortController();
+      const forward = jest.fn();
+      let finished: Promise<void> | undefined;
+      const handler = proxy.testWithToolTimeout((_request, _extra, signal) => {
+        finished = Promise.resolve().then(() => {
+          cancellation.abort();
+          proxy.testThrowIfCancelled(signal);
+          forward();
+        });
+        return finished;
+      });
+
+      await expect(handler(request, { signal: cancellation.signal })).rejects.toMatchObject({ code: -32800 });
+      await expect(finished).rejects.toMatchObject({ code: -32800 });
+      expect(forward).not.toHaveBeenCalled();
+    });
+
+    it('should stop evaluating remaining policies once cancelled', async () => {
+      const cancellation = new AbortController();
+      const decide = proxy.getAIPolicyEngine().decide as jest.Mock;
+      decide.mockImplementation(async () => {
+        cancellation.abort();
+        return { decision: 'PERMIT', reason: 'ok', confidence: 0.9 };
+      });
+
+      await expect(proxy.testCallBuiltinTool('check_policies', {
+        agent: 'claude',
+        action: 'read',
+        resource: 'file://a.txt',
+        policies: ['p1', 'p2', 'p3'],
+        combining_algorithm: 'deny_overrides'
+      }, undefined, cancellation.signal)).rejects.toMatchObject({ code: -32800 });
+      expect(decide).toHaveBeenCalledTimes(1);
+    });
+  });
+
+  describe('evaluation timeout', () => {
+    const context: DecisionContext = {
+      agent: 'claude',
+      action: 'read',
+      resource: 'file://a.txt',
+      time: new Date(),
+      environment: {}
+    };
+
+    it('should fail closed with DENY and abort the evaluation when it exceeds evalTimeoutMs', async () => {
+      const limited = new TestMCPProxy(
+        { ...testConfig, mcpProxy: { port: 3000, upstreamServers: {}, evalTimeoutMs: 10 } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+      let seenSignal: AbortSignal | undefined;
+      (limited.getAIPolicyEngine().decide as jest.Mock).mockImplementation((_context, _policy, signal) => {
+        seenSignal = signal;
+        return new Promise(() => undefined);
+      });
+
+      const decision = await limited.testDecideWithEvalTimeout(context, 'policy');
+
+      expect(decision).toMatchObject({
+        decision: 'DENY',
+        reason: 'Policy evaluation timed out after 10ms; defaulting to DENY',
+        metadata: { evaluationTimedOut: true }
+      });
+      expect(seenSignal?.aborted).toBe(true);
+    });
+
+    it('should propagate an aborted tool signal to the evaluation', async () => {
+      const controller = new AbortController();
+      let seenSignal: AbortSignal | undefined;
+      (proxy.getAIPolicyEngine().decide as jest.Mock).mockImplementation(async (_context, _policy, signal) => {
+        seenSignal = signal;
+        controller.abort();
+        return { decision: 'PERMIT', reason: 'ok', confidence: 0.9 };
+      });
+
+      await expect(proxy.testDecideWithEvalTimeout(context, 'policy', controller.signal)).resolves.toMatchObject({ decision: 'PERMIT' });
+      expect(seenSignal?.aborted).toBe(true);
+    });
+  });
+
+  describe('tool call deduplication', () => {
+    it('should replay a repeated id only for the same session and agent', async () => {
+      const handler = jest.fn(async (request: any) => request.params.name);
+      const deduplicated = proxy.testWithDeduplication(handler);
+      const request = { params: { name: 'filesystem__delete', arguments: {} } };
+
+      proxy.dedupAgents.set('s1', 'alice');
+      await deduplicated(request, { requestId: 1, sessionId: 's1' });
+      await deduplicated(request, { requestId: 1, sessionId: 's1' });
+      expect(handler).toHaveBeenCalledTimes(1);
+
+      proxy.dedupAgents.set('s1', 'mallory');
+      await deduplicated(request, { requestId: 1, sessionId: 's1' });
+      expect(handler).toHaveBeenCalledTimes(2);
+    });
+  });
+
+  describe('decision caching', () => {
+    const deny = (metadata: Record<string, boolean>): PolicyDecision => ({ decision: 'DENY', reason: 'no', confidence: 1, metadata });
+
+    it('should not cache decisions caused by evaluator failures or cancelled calls', () => {
+      const aborted = new AbortController();
+      aborted.abort();
+
+      expect(proxy.testIsCacheableDecision({ decision: 'PERMIT', reason: 'ok', confidence: 0.9 })).toBe(true);
+      expect(proxy.testIsCacheableDecision(deny({ evaluationTimedOut: true }))).toBe(false);
+      expect(proxy.testIsCacheableDecision(deny({ circuitOpen: true }))).toBe(false);
+      expect(proxy.testIsCacheableDecision(deny({ aiError: true }))).toBe(false);
+      expect(proxy.testIsCacheableDecision(deny({}), aborted.signal)).toBe(false);
+    });
+  });
+
+  describe('tool enable/disable', () => {
+    const tools = [{ name: 'check_policy' }, { name: 'hello_world' }, { name: 'policy_lint' }];
+    const withTools = (mcpProxy: { enabledTools?: string[]; disabledTools?: string[] }) => new TestMCPProxy(
+      { ...testConfig, mcpProxy: { port: 3000, upstreamServers: {}, ...mcpProxy } },
+      mockLogger,
+      mockJudgmentEngine
+    );
+
+    it('should keep every tool enabled by default', () => {
+      expect(proxy.testFilterEnabledTools(tools)).toEqual(tools);
+      expect(() => proxy.testAssertToolEnabled('hello_world')).not.toThrow();
+    });
+
+    it('should apply the allowlist and then the denylist', () => {
+      const limited = withTools({ enabledTools: ['check_policy', 'policy_lint'], disabledTools: ['policy_lint'] });
+
+      expect(limited.testFilterEnabledTools(tools)).toEqual([{ name: 'check_policy' }]);
+    });
+
+    it('should reject calls to disabled tools with -32601', () => {
+      const limited = withTools({ disabledTools: ['hello_world'] });
+
+      expect(() => limited.testAssertToolEnabled('hello_world')).toThrow(expect.objectContaining({
+        code: -32601,
+        message: 'Tool not found: hello_world'
+      }));
+      expect(() => limited.testAssertToolEnabled('check_policy')).not.toThrow();
+    });
+  });
+
+  describe('client capabilities', () => {
+    it('should report the features declared at initialize', () => {
+      expect(proxy.clientSupportsElicitation()).toBe(false);
+
+      proxy.testRecordClientCapabilities({ sampling: {}, roots: { listChanged: true }, elicitation: {} } as any);
+
+      expect(proxy.clientSupportsElicitation()).toBe(true);
+      expect(mockLogger.info).toHaveBeenCalledWith('Client capabilities: sampling, roots, elicitation');
+    });
+
+    it('should replace the capabilities when initialize is sent again', () => {
+      proxy.testRecordClientCapabilities({ elicitation: {} } as any);
+      proxy.testRecordClientCapabilities(undefined);
+
+      expect(proxy.clientSupportsElicitation()).toBe(false);
+      expect(mockLogger.info).toHaveBeenLastCalledWith('Client capabilities: none');
+    });
+  });
+
+  describe('shutdown', () => {
+    it('should run the shutdown handler only after the acknowledgment has been sent', async () => {
+      const order: string[] = [];
+      const originalSend = jest.fn(async (message: any) => {
+        order.push(`send:${message.id ?? message.method}`);
+      });
+      const transport = { send: originalSend } as unknown as Transport;
+      proxy.setShutdownHandler(reason => order.push(`shutdown:${reason}`));
+      proxy.testInstallShutdownAcknowledgement(transport);
+
+      await transport.send({ jsonrpc: '2.0', id: 1, result: {} });
+      await expect(proxy.testHandleShutdownRequest(2)).resolves.toEqual({});
+      await transport.send({ jsonrpc: '2.0', method: 'notifications/message', params: {} } as any);
+      await transport.send({ jsonrpc: '2.0', id: 2, result: {} });
+
+      expect(order).toEqual(['send:1', 'send:notifications/message', 'send:2', 'shutdown:shutdown request']);
+    });
+  });
+
+  describe('_meta echo', () => {
+    it('should copy request params._meta into the matching result', async () => {
+      const originalSend = jest.fn().mockResolvedValue(undefined);
+      const transport = { onmessage: jest.fn(), send: originalSend } as unknown as Transport;
+      proxy.testInstallMetaEcho(transport);
+
+      transport.onmessage!({
+        jsonrpc: '2.0', id: 1, method: 'tools/call', params: { _meta: { progressToken: 'tok', traceId: 'abc' } }
+      } as any);
+      await transport.send({ jsonrpc: '2.0', id: 1, result: { content: [], _meta: { traceId: 'server' } } } as any);
+      await transport.send({ jsonrpc: '2.0', id: 1, result: { content: [] } } as any);
+
+      expect(originalSend.mock.calls[0][0]).toEqual({
+        jsonrpc: '2.0',
+        id: 1,
+        result: { content: [], _meta: { progressToken: 'tok', traceId: 'server' } }
+      });
+      expect(originalSend.mock.calls[1][0].result._meta).toBeUndefined();
+    });
+
+    it('should leave error responses and notifications unchanged', async () => {
+      const originalSend = jest.fn().mockResolvedValue(undefined);
+      const transport = { onmessage: jest.fn(), send: originalSend } as unknown as Transport;
+      proxy.testInstallMetaEcho(transport);
+      const error = { jsonrpc: '2.0', id: 2, error: { code: -32603, message: 'failed' } };
+      const notification = { jsonrpc: '2.0', method: 'notifications/progress', params: { progressToken: 'tok' } };
+
+      transport.onmessage!({ jsonrpc: '2.0', id: 2, method: 'tools/call', params: { _meta: { progressToken: 'tok' } } } as any);
+      await transport.send(notification as any);
+      await transport.send(error as any);
+
+      expect(originalSend.mock.calls.map(call => call[0])).toEqual([notification, error]);
+    });
+  });
+
+  describe('initialization handshake', () => {
+    let protocolOnMessage: jest.Mock;
+    let transport: Transport;
+
+    beforeEach(() => {
+      protocolOnMessage = jest.fn();
+      transport = {
+        onmessage: protocolOnMessage,
+        send: jest.fn().mockResolvedValue(undefined)
+      } as unknown as Transport;
+      proxy.testInstallHandshakeGuard(transport);
+    });
+
+    const deliver = (message: Record<string, unknown>) =>
+      transport.onmessage!({ jsonrpc: '2.0', ...message } as any);
+
+    it('should reject requests other than initialize and ping before notifications/initialized', () => {
+      deliver({ id: 1, method: 'tools/call' });
+      deliver({ id: 2, method: 'ping' });
+      deliver({ id: 3, method: 'initialize' });
+      deliver({ id: 4, method: 'tools/list' });
+
+      expect(transport.send).toHaveBeenCalledTimes(2);
+      expect(transport.send).toHaveBeenCalledWith({
+        jsonrpc: '2.0',
+        id: 1,
+        error: { code: -32002, message: 'Server not initialized' }
+      });
+      expect(protocolOnMessage.mock.calls.map(call => call[0].method)).toEqual(['ping', 'initialize']);
+    });
+
+    it('should accept requests after the handshake and require it again after a repeated initialize', () => {
+      deliver({ id: 1, method: 'initialize' });
+      deliver({ method: 'notifications/initialized' });
+      deliver({ id: 2, method: 'tools/list' });
+      deliver({ id: 3, method: 'initialize' });
+      deliver({ id: 4, method: 'tools/list' });
+
+      expect(protocolOnMessage.mock.calls.map(call => call[0].id)).toEqual([1, undefined, 2, 3]);
+      expect(transport.send).toHaveBeenCalledWith(expect.objectContaining({ id: 4 }));
+    });
+  });
+
+  describe('logging/setLevel', () => {
+    it('should map MCP log levels onto logger levels', () => {
+      expect(proxy.testSetLogLevel('warning')).toEqual({});
+      expect(mockLogger.setLevel).toHaveBeenLastCalledWith('warn');
+
+      proxy.testSetLogLevel('debug');
+      expect(mockLogger.setLevel).toHaveBeenLastCalledWith('debug');
+
+      proxy.testSetLogLevel('critical');
+      expect(mockLogger.setLevel).toHaveBeenLastCalledWith('error');
+    });
+
+    it('should reject unknown levels with -32602', () => {
+      let thrown: any;
+      try {
+        proxy.testSetLogLevel('verbose');
+      } catch (error) {
+        thrown = error;
+      }
+
+      expect(thrown?.code).toBe(-32602);
+      expect(mockLogger.setLevel).not.toHaveBeenCalledWith('verbose');
+    });
+  });
+
+  describe('policy resources', () => {
+    it('should list each policy as an aegis://policy resource', () => {
+      proxy.addPolicy('default-policy', 'Default policy content');
+
+      const resources = proxy.testListPolicyResources();
+
+      expect(resources).toEqual([{
+        uri: 'aegis://policy/default-policy',
+        name: 'default-policy',
+        mimeType: 'text/plain',
+        description: 'AEGIS policy "default-policy"'
+      }]);
+    });
+
+    it('should read policy text by resource uri', () => {
+      proxy.addPolicy('default-policy', 'Default policy content');
+
+      const result = proxy.testReadPolicyResource('aegis://policy/default-policy');
+
+      expect(result).toEqual({
+        contents: [{
+          uri: 'aegis://policy/default-policy',
+          mimeType: 'text/plain',
+          text: 'Default policy content'
+        }]
+      });
+    });
+
+    it('should return null for unknown or foreign uris', () => {
+      expect(proxy.testReadPolicyResource('aegis://policy/missing')).toBeNull();
+      expect(proxy.testReadPolicyResource('file:///etc/passwd')).toBeNull();
+    });
+
+    it('should evaluate a decision resource against the default policy', async () => {
+      const decide = proxy.getAIPolicyEngine().decide as jest.Mock;
+      decide.mockResolvedValueOnce({ decision: 'PERMIT', reason: 'office hours', confidence: 0.9 });
+      proxy.addPolicy('default-policy', '営業時間内のみ許可');
+      const uri = `aegis://decision/claude/${encodeURIComponent('file:///docs/a.txt')}`;
+
+      const result = await proxy.testReadDecisionResource(uri);
+
+      expect(result.contents[0]).toMatchObject({ uri, mimeType: 'application/json' });
+      expect(JSON.parse(result.contents[0].text!)).toMatchObject({
+        agent: 'claude',
+        action: 'read',
+        resource: 'file:///docs/a.txt',
+        policy: 'default-policy',
+        decision: 'PERMIT'
+      });
+      expect(decide.mock.calls[0]).toEqual([
+        expect.objectContaining({ agent: 'claude', resource: 'file:///docs/a.txt' }),
+        '営業時間内のみ許可',
+        expect.any(AbortSignal)
+      ]);
+    });
+
+    it('should apply overrides, access lists and unmet constraints to decision resources', async () => {
+      const decide = proxy.getAIPolicyEngine().decide as jest.Mock;
+      proxy.addPolicy('default-policy', '営業時間内のみ許可');
+      proxy.setAgentOverrides(new Map([['quarantined', { mode: 'force-deny' as const }]]));
+      proxy.setAccessLists({ allowlist: parseAccessList('read file:///public/**', 'allowlist.txt') });
+      decide.mockResolvedValueOnce({
+        decision: 'PERMIT',
+        reason: 'ok',
+        confidence: 0.9,
+        constraints: ['require_approval']
+      });
+      const read = async (agent: string, resource: string) => JSON.parse(
+        (await proxy.testReadDecisionResource(`aegis://decision/${agent}/${encodeURIComponent(resource)}`)).contents[0].text!
+      );
+
+      expect((await read('quarantined', 'file:///docs/a.txt')).decision).toBe('DENY');
+      expect((await read('claude', 'file:///public/a.txt')).decision).toBe('PERMIT');
+      expect((await read('claude', 'file:///docs/a.txt')).decision).toBe('DENY');
+      expect(decide).toHaveBeenCalledTimes(1);
+    });
+
+    it('should rate limit decision resource reads per requesting agent with -32000', async () => {
+      const limited = new TestMCPProxy(
+        { ...testConfig, mcpProxy: { port: 3000, upstreamServers: {}, agentRateLimitPerMinute: 1 } },
+        mockLogger,
+        mockJudgmentEngine
+      );
+      (limited.getAIPolicyEngine().decide as jest.Mock).mockResolvedValue({ decision: 'PERMIT', reason: 'ok', confidence: 0.9 });
+      limited.addPolicy('default-policy', '営業時間内のみ許可');
+      const uri = `aegis://decision/claude/${encodeURIComponent('file:///docs/a.txt')}`;
+
+      await limited.testReadDecisionResource(uri, 'reader');
+      await expect(limited.testReadDecisionResource(uri, 'reader')).rejects.toMatchObject({
+        code: -32000,
+        data: { agent: 'reader' }
+      });
+    });
+
+    it('should reject aegis uris that match no resource template with -32602', async () => {
+      proxy.addPolicy('default-policy', 'Default policy content');
+
+      for (const uri of ['aegis://decision/claude', 'aegis://decision/claude/a/b', 'aegis://unknown/x']) {
+        await expect(proxy.testReadDecisionResource(uri)).rejects.toMatchObject({
+          code: -32602,
+          message: `Resource URI does not match any template: ${uri}`
+        });
+      }
+    });
+
+    describe('subscriptions', () => {
+      let sendResourceUpdated: jest.Mock;
+
+      beforeEach(() => {
+        sendResourceUpdated = jest.fn().mockResolvedValue(undefined);
+        proxy.getServer().sendResourceUpdated = sendResourceUpdated;
+        proxy.addPolicy('office hours', '営業時間内のみ許可');
+      });
+
+      it('should notify subscribers when a policy changes or is removed', () => {
+        expect(proxy.testSubscribeResource('aegis://policy/office%20hours', 'session-1')).toEqual({});
+
+        proxy.addPolicy('office hours', '営業時間内のみ許可');
+        proxy.addPolicy('office hours', '平日の営業時間内のみ許可');
+        proxy.addPolicy('other', '拒否');
+        proxy.removePolicy('office hours');
+
+        expect(sendResourceUpdated.mock.calls).toEqual([
+          [{ uri: 'aegis://policy/office%20hours' }],
+          [{ uri: 'aegis://policy/office%20hours' }]
+        ]);
+      });
+
+      it('should stop notifying after unsubscribe or when the connection closes', () => {
+        const transport = { onclose: jest.fn() } as unknown as Transport & { sessionId?: string };
+        const protocolOnClose = transport.onclose;
+        proxy.testSubscribeResource('aegis://policy/office%20hours', 'session-1');
+        proxy.testSubscribeResource('aegis://policy/office%20hours', 'session-2');
+        proxy.testUnsubscribeResource('aegis://policy/office%20hours', 'session-1');
+        proxy.testInstallSubscriptionCleanup(transport);
+
+        transport.sessionId = 'session-2';
+        transport.onclose!();
+        proxy.addPolicy('office hours', '変更後');
+
+        expect(protocolOnClose).toHaveBeenCalled();
+        expect(sendResourceUpdated).not.toHaveBeenCalled();
+      });
+
+      it('should reject subscriptions to non-policy resources with -32602', () => {
+        expect(() => proxy.testSubscribeResource('file:///docs/a.txt')).toThrow(
+          expect.objectContaining({ code: -32602, message: 'Only policy resources can be subscribed: file:///docs/a.txt' })
+        );
+      });
+    });
+  });
+
+  describe('obligation handlers', () => {
+    const permit = (obligations: string[]): AccessControlResult => ({
+      decision: 'PERMIT',
+      reason: '許可',
+      confidence: 0.9,
+      obligations,
+      processingTime: 1,
+      policyUsed: 'default-policy',
+      context: { agent: 'agent-1', action: 'read', resource: 'file:///a', time: new Date() }
+    });
+
+    it('should run the built-in log handler and leave unmatched obligations for the enforcement system', async () => {
+      const { result, outcomes } = await proxy.testRunObligationHandlers(permit(['log: 機密ファイル', 'アクセスログ記録']));
+
+      expect(result.decision).toBe('PERMIT');
+      expect(result.obligations).toEqual(['アクセスログ記録']);
+      expect(outcomes).toEqual([{ obligation: 'log: 機密ファイル', handler: 'log', mandatory: true, success: true }]);
+      expect(mockLogger.info).toHaveBeenCalledWith('Obligation log: 機密ファイル', expect.objectContaining({ agent: 'agent-1' }));
+    });
+
+    it('should downgrade to DENY when a mandatory obligation fails', async () => {
+      proxy.registerObligationHandler({
+        name: 'siem',
+        mandatory: true,
+        execute: jest.fn().mockRejectedValue(new Error('SIEM unreachable'))
+      });
+
+      const { result, outcomes } = await proxy.testRunObligationHandlers(permit(['siem: forward']));
+
+      expect(result.decision).toBe('DENY');
+      expect(result.reason).toBe('Mandatory obligation failed: siem: forward (SIEM unreachable)');
+      expect(outcomes[0]).toMatchObject({ success: false, error: 'SIEM unreachable' });
+    });
+
+    it('should keep PERMIT when an optional obligation fails', async () => {
+      proxy.registerObligationHandler({
+        name: 'notify',
+        execute: jest.fn().mockRejectedValue(new Error('mail down'))
+      });
+
+      const { result, outcomes } = await proxy.testRunObligationHandlers(permit(['notify: owner']));
+
+      expect(result.decision).toBe('PERMIT');
+      expect(outcomes[0]).toMatchObject({ handler: 'notify', mandatory: false, success: false });
+    });
+  });
+
+  describe('clarification', () => {
+    const needsInfo: AccessControlResult = {
+      decision: 'INDETERMINATE',
+      reason: '目的が不明',
+      confidence: 0.5,
+      needsClarification: ['What is the purpose?', 'Which project?'],
+      processingTime: 1,
+      policyUsed: 'default-policy'
+    };
+    const permitted: AccessControlResult = { decision: 'PERMIT', reason: '許可', confidence: 0.9, processingTime: 1, policyUsed: 'default-policy' };
+
+    it('should return INDETERMINATE listing the questions when the client lacks elicitation', async () => {
+      const reevaluate = jest.fn();
+
+      const result = await proxy.testResolveClarification(needsInfo, reevaluate);
+
+      expect(result.decision).toBe('INDETERMINATE');
+      expect(result.reason).toBe('目的が不明 [needs clarification: What is the purpose? / Which project?]');
+      expect(reevaluate).not.toHaveBeenCalled();
+    });
+
+    it('should elicit answers and re-evaluate with them', async () => {
+      proxy.testRecordClientCapabilities({ elicitation: {} } as any);
+      proxy.getServer().request = jest.fn().mockResolvedValue({
+        action: 'accept',
+        content: { answer_1: 'monthly report', answer_2: 'finance' }
+      });
+      const reevaluate = jest.fn().mockResolvedValue(permitted);
+
+      const result = await proxy.testResolveClarification(needsInfo, reevaluate);
+
+      expect(result).toBe(permitted);
+      expect(proxy.getServer().request).toHaveBeenCalledWith(
+        expect.objectContaining({
+          method: 'elicitation/create',
+          params: expect.objectContaining({
+            requestedSchema: expect.objectContaining({ required: ['answer_1', 'answer_2'] })
+          })
+        }),
+        expect.anything(),
+        expect.objectContaining({ relatedRequestId: 7 })
+      );
+      expect(reevaluate).toHaveBeenCalledWith([
+        { question: 'What is the purpose?', answer: 'monthly report' },
+        { question: 'Which project?', answer: 'finance' }
+      ]);
+    });
+
+    it('should not re-evaluate when the requester declines', async () => {
+      proxy.testRecordClientCapabilities({ elicitation: {} } as any);
+      proxy.getServer().request = jest.fn().mockResolvedValue({ action: 'decline' });
+      const reevaluate = jest.fn();
+
+      const result = await proxy.testResolveClarification(needsInfo, reevaluate);
+
+      expect(result.reason).toContain('[clarification declined: ');
+      expect(reevaluate).not.toHaveBeenCalled();
+    });
+  });
+
   describe('getSystemPerformanceStats', () => {
     it('should return performance statistics', () => {
       const stats = proxy.getSystemPerformanceStats();
@@ -0,0 +1,53 @@
+// ============================================================================
+// ContentLengthStdioTransport Test Suite
+// ============================================================================
+
+import { PassThrough } from 'stream';
+import { ContentLengthReader, ContentLengthStdioTransport } from '../../mcp/content-length-transport';
+
+const frame = (body: string) => `Content-Length: ${Buffer.byteLength(body)}\r\n\r\n${body}`;
+
+describe('ContentLengthReader', () => {
+  it('should read concatenated frames and wait for incomplete ones', () => {
+    const reader = new ContentLengthReader(1024);
+    const data = frame('{"a":1}') + frame('{"b":"日本"}');
+    reader.append(Buffer.from(data.slice(0, data.length - 3)));
+
+    expect(reader.read()).toEqual({ kind: 'message', body: '{"a":1}' });
+    expect(reader.read()).toBeNull();
+
+    reader.append(Buffer.from(Buffer.from(data).subarray(Buffer.byteLength(data.slice(0, data.length - 3)))));
+    expect(reader.read()).toEqual({ kind: 'message', body: '{"b":"日本"}' });
+  });
+
+  it('should skip oversized bodies and report frames without Content-Length', () => {
+    const reader = new ContentLengthReader(8);
+    reader.append(Buffer.from(frame('{"big":"0123456789"}') + 'Content-Type: x\r\n\r\n' + frame('{"c":3}')));
+
+    expect(reader.read()).toEqual({ kind: 'oversize', length: 20 });
+    expect(reader.read()).toEqual({ kind: 'invalid', reason: 'Missing Content-Length header' });
+    expect(reader.read()).toEqual({ kind: 'message', body: '{"c":3}' });
+  });
+});
+
+describe('ContentLengthStdioTransport', () => {
+  it('should deliver parsed messages and write framed responses', async () => {
+    const stdin = new PassThrough();
+    const stdout = new PassThrough();
+    const transport = new ContentLengthStdioTransport(stdin, stdout, { maxMessageBytes: 1024 });
+    const messages: unknown[] = [];
+    const errors: Error[] = [];
+    transport.onmessage = message => messages.push(message);
+    transport.onerror = error => errors.push(error);
+    await transport.start();
+
+    stdin.write(frame('{"jsonrpc":"2.0","id":1,"method":"ping"}') + frame('{not json}'));
+    await new Promise(resolve => setImmediate(resolve));
+    await transport.send({ jsonrpc: '2.0', id: 1, result: {} });
+
+    expect(messages).toEqual([{ jsonrpc: '2.0', id: 1, method: 'ping' }]);
+    expect(errors[0]).toBeInstanceOf(SyntaxError);
+    expect(stdout.read().toString()).toBe(frame('{"jsonrpc":"2.0","id":1,"result":{}}'));
+    await transport.close();
+  });
+});
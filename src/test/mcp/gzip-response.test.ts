@@ -0,0 +1,63 @@
+// ============================================================================
+// gzipResponses Test Suite
+// ============================================================================
+
+import express from 'express';
+import request from 'supertest';
+import { gzipResponses } from '../../mcp/gzip-response';
+
+describe('gzipResponses', () => {
+  const large = { text: 'x'.repeat(2048) };
+
+  const createApp = () => {
+    const app = express();
+    app.use(gzipResponses(1024));
+    app.get('/large', (req, res) => res.json(large));
+    app.get('/small', (req, res) => res.json({ ok: true }));
+    app.get('/raw', (req, res) => {
+      res.writeHead(200, { 'Content-Type': 'application/json' }).end(JSON.stringify(large));
+    });
+    app.get('/sse', (req, res) => {
+      res.writeHead(200, { 'Content-Type': 'text/event-stream' }).flushHeaders();
+      res.write('event: message\ndata: {"id":1}\n\n');
+      setTimeout(() => res.end('event: message\ndata: {"id":2}\n\n'), 10);
+    });
+    app.get('/text', (req, res) => {
+      res.type('text/plain').send('x'.repeat(2048));
+    });
+    return app;
+  };
+
+  it('should compress JSON responses above the threshold when gzip is accepted', async () => {
+    const response = await request(createApp()).get('/large').set('Accept-Encoding', 'gzip');
+
+    expect(response.headers['content-encoding']).toBe('gzip');
+    expect(response.headers.vary).toBe('Accept-Encoding');
+    expect(response.body).toEqual(large);
+  });
+
+  it('should compress bodies written with writeHead and end', async () => {
+    const response = await request(createApp()).get('/raw').set('Accept-Encoding', 'deflate, gzip;q=0.5');
+
+    expect(response.headers['content-encoding']).toBe('gzip');
+    expect(response.body).toEqual(large);
+  });
+
+  it('should compress SSE streams without buffering the events', async () => {
+    const response = await request(createApp()).get('/sse').set('Accept-Encoding', 'gzip').buffer(true);
+
+    expect(response.headers['content-encoding']).toBe('gzip');
+    expect(response.headers['content-length']).toBeUndefined();
+    expect(response.text).toBe('event: message\ndata: {"id":1}\n\nevent: message\ndata: {"id":2}\n\n');
+  });
+
+  it('should leave responses uncompressed without the header, below the threshold or for other content types', async () => {
+    const app = createApp();
+
+    expect((await request(app).get('/large').set('Accept-Encoding', 'identity')).headers['content-encoding']).toBeUndefined();
+    expect((await request(app).get('/large').set('Accept-Encoding', 'gzip;q=0')).headers['content-encoding']).toBeUndefined();
+    expect((await request(app).get('/small').set('Accept-Encoding', 'gzip')).headers['content-encoding']).toBeUndefined();
+    expect((await request(app).get('/text').set('Accept-Encoding', 'gzip')).headers['content-encoding']).toBeUndefined();
+    expect((await request(app).get('/sse').set('Accept-Encoding', 'identity')).headers['content-encoding']).toBeUndefined();
+  });
+});
@@ -0,0 +1,141 @@
+// ============================================================================
+// InMemoryDispatchTransport Test Suite
+// ============================================================================
+
+import { Server } from '@modelcontextprotocol/sdk/server/index.js';
+import { CallToolRequestSchema, ListToolsRequestSchema } from '@modelcontextprotocol/sdk/types.js';
+import { InMemoryDispatchTransport } from '../../mcp/in-memory-dispatcher';
+
+describe('InMemoryDispatchTransport', () => {
+  let transport: InMemoryDispatchTransport;
+
+  beforeEach(async () => {
+    const server = new Server({ name: 'test-server', version: '1.0.0' }, { capabilities: { tools: {} } });
+    server.setRequestHandler(ListToolsRequestSchema, async () => ({
+      tools: [{ name: 'echo', description: 'Echo text', inputSchema: { type: 'object' } }]
+    }));
+    server.setRequestHandler(CallToolRequestSchema, async (request) => ({
+      content: [{ type: 'text', text: String(request.params.arguments?.text ?? '') }]
+    }));
+
+    transport = new InMemoryDispatchTransport();
+    await server.connect(transport);
+  });
+
+  it('should handle initialize, tools/list and tools/call in order', async () => {
+    const initialize = JSON.parse((await transport.dispatch(JSON.stringify({
+      jsonrpc: '2.0',
+      id: 1,
+      method: 'initialize',
+      params: { protocolVersion: '2024-11-05', capabilities: {}, clientInfo: { name: 'test', version: '1.0.0' } }
+    })))!);
+    expect(initialize).toMatchObject({ jsonrpc: '2.0', id: 1, result: { serverInfo: { name: 'test-server' } } });
+
+    expect(await transport.dispatch('{"jsonrpc":"2.0","method":"notifications/initialized"}')).toBeNull();
+
+    const list = JSON.parse((await transport.dispatch('{"jsonrpc":"2.0","id":2,"method":"tools/list"}'))!);
+    expect(list.result.tools.map((tool: { name: string }) => tool.name)).toEqual(['echo']);
+
+    const call = JSON.parse((await transport.dispatch(JSON.stringify({
+      jsonrpc: '2.0',
+      id: 'call-3',
+      method: 'tools/call',
+      params: { name: 'echo', arguments: { text: 'hello' } }
+    })))!);
+    expect(call).toEqual({ jsonrpc: '2.0', id: 'call-3', result: { content: [{ type: 'text', text: 'hello' }] } });
+  });
+
+  it('should return a JSON-RPC error for unknown methods and unparsable input', async () => {
+    const unknown = JSON.parse((await transport.dispatch('{"jsonrpc":"2.0","id":4,"method":"nope/nope"}'))!);
+    expect(unknown).toMatchObject({ id: 4, error: { code: -32601 } });
+
+    const parseError = JSON.parse((await transport.dispatch('{not json'))!);
+    expect(parseError).toMatchObject({ id: null, error: { code: -32700, message: 'Parse error' } });
+  });
+
+  it('should answer wrongly typed fields with -32600 naming the field', async () => {
+    const badMethod = JSON.parse((await transport.dispatch('{"jsonrpc":"2.0","id":5,"method":7}'))!);
+    expect(badMethod).toEqual({
+      jsonrpc: '2.0',
+      id: 5,
+      error: { code: -32600, message: '`method` must be a string', data: { field: 'method' } }
+    });
+
+    const badVersion = JSON.parse((await transport.dispatch('{"jsonrpc":"1.0","id":6,"method":"tools/list"}'))!);
+    expect(badVersion).toMatchObject({ id: 6, error: { code: -32600, data: { expected: '2.0', received: '1.0' } } });
+  });
+
+  it('should drop unknown top-level fields unless the protocol is strict', async () => {
+    const input = '{"jsonrpc":"2.0","id":7,"method":"tools/list","x-trace":"abc"}';
+
+    const lenient = JSON.parse((await transport.dispatch(input))!);
+    expect(lenient.result.tools.map((tool: { name: string }) => tool.name)).toEqual(['echo']);
+
+    const server = new Server({ name: 'test-server', version: '1.0.0' }, { capabilities: { tools: {} } });
+    const strict = new InMemoryDispatchTransport(true);
+    await server.connect(strict);
+    expect(JSON.parse((await strict.dispatch(input))!)).toEqual({
+      jsonrpc: '2.0',
+      id: 7,
+      error: { code: -32600, message: 'unexpected field `x-trace`', data: { field: 'x-trace' } }
+    });
+  });
+
+  it('should answer every mutated or random input without hanging', async () => {
+    // 乱数は固定シード（再現できるように）
+    let seed = 0x5eed;
+    const random = () => {
+      seed = (seed * 1103515245 + 12345) % 2147483648;
+      return seed / 2147483648;
+    };
+    const pick = <T>(values: T[]): T => values[Math.floor(random() * values.length)];
+    const values = [undefined, null, true, 0, 1.5, '', 'tools/list', 'nope', [], [1], {}, { text: 'x' }];
+
+    for (let i = 0; i < 300; i++) {
+      let input: string;
+      if (i % 3 === 0) {
+        input = Buffer.from(Array.from({ length: 1 + Math.floor(random() * 24) }, () => Math.floor(random() * 256))).toString('latin1');
+      } else {
+        const message: Record<string, unknown> = { jsonrpc: pick(['2.0', '2.0', '1.0', 2, undefined]), id: i, method: 'tools/list' };
+        for (const field of ['id', 'method', 'params', 'result']) {
+          if (random() < 0.4) {
+            message[field] = pick(values);
+          }
+        }
+        input = JSON.stringify(message);
+      }
+
+      const response = await transport.dispatch(input);
+      if (response !== null) {
+        expect(JSON.parse(response)).toMatchObject({ jsonrpc: '2.0' });
+      }
+    }
+  });
+
+  it('should reject a request whose id is still awaiting a response', async () => {
+    const server = new Server({ name: 'test-server', version: '1.0.0' }, { capabilities: { tools: {} } });
+    let release: () => void = () => undefined;
+    const released = new Promise<void>(resolve => { release = resolve; });
+    server.setRequestHandler(CallToolRequestSchema, async () => {
+      await released;
+      return { content: [{ type: 'text', text: 'first' }] };
+    });
+    const slow = new InMemoryDispatchTransport();
+    await server.connect(slow);
+
+    const input = '{"jsonrpc":"2.0","id":8,"method":"tools/call","params":{"name":"echo"}}';
+    const first = slow.dispatch(input);
+    expect(JSON.parse((await slow.dispatch(input))!)).toEqual({
+      jsonrpc: '2.0',
+      id: 8,
+      error: { code: -32600, message: 'Duplicate request id', data: { field: 'id' } }
+    });
+
+    release();
+    expect(JSON.parse((await first)!)).toMatchObject({ id: 8, result: { content: [{ type: 'text', text: 'first' }] } });
+  });
+
+  it('should reject dispatch before the transport is connected', async () => {
+    await expect(new InMemoryDispatchTransport().dispatch('{}')).rejects.toThrow('not connected');
+  });
+});
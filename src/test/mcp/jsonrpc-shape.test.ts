@@ -0,0 +1,53 @@
+// ============================================================================
+// JSON-RPC Shape Check Test Suite
+// ============================================================================
+
+import { checkJsonRpcShape, findInvalidJsonRpcShape, stripUnknownJsonRpcFields } from '../../mcp/jsonrpc-shape';
+
+describe('checkJsonRpcShape', () => {
+  it('should accept well-formed requests, notifications and responses', () => {
+    expect(checkJsonRpcShape({ jsonrpc: '2.0', id: 1, method: 'tools/list' })).toBeNull();
+    expect(checkJsonRpcShape({ jsonrpc: '2.0', method: 'notifications/initialized', params: {} })).toBeNull();
+    expect(checkJsonRpcShape({ jsonrpc: '2.0', id: 'a', result: {} })).toBeNull();
+  });
+
+  it('should name the offending field and keep a readable id', () => {
+    expect(checkJsonRpcShape({ jsonrpc: '2.0', id: 3, method: 42 }))
+      .toEqual({ id: 3, field: 'method', message: '`method` must be a string' });
+    expect(checkJsonRpcShape({ jsonrpc: '2.0', id: 'x', method: 'tools/call', params: 'name=echo' }))
+      .toEqual({ id: 'x', field: 'params', message: '`params` must be an object' });
+    expect(checkJsonRpcShape({ jsonrpc: '2.0', id: true, method: 'tools/list' }))
+      .toEqual({ id: null, field: 'id', message: '`id` must be a string or number' });
+    expect(checkJsonRpcShape({ jsonrpc: '2.0', id: 5 })).toMatchObject({ id: 5, field: 'method' });
+    expect(checkJsonRpcShape('tools/list')).toMatchObject({ id: null, field: 'message' });
+    expect(checkJsonRpcShape({ jsonrpc: '2.0', id: 6, method: 'tools/list', result: {} })).toMatchObject({ id: 6, field: 'method' });
+  });
+
+  it('should reject unknown top-level fields only in strict mode', () => {
+    const message = { jsonrpc: '2.0', id: 7, method: 'tools/list', trace: 'x' };
+
+    expect(checkJsonRpcShape(message)).toBeNull();
+    expect(checkJsonRpcShape(message, true)).toEqual({ id: 7, field: 'trace', message: 'unexpected field `trace`' });
+    expect(findInvalidJsonRpcShape(JSON.stringify(message), true)).toMatchObject({ field: 'trace' });
+  });
+
+  it('should leave unparseable lines and batches to the other checks', () => {
+    expect(findInvalidJsonRpcShape('{"method":')).toBeNull();
+    expect(findInvalidJsonRpcShape('[{"jsonrpc":"2.0","id":1,"method":"tools/list"}]')).toBeNull();
+    expect(findInvalidJsonRpcShape('{"jsonrpc":"2.0","id":1,"method":["tools/list"]}')).toMatchObject({ field: 'method' });
+  });
+});
+
+describe('stripUnknownJsonRpcFields', () => {
+  it('should drop unknown fields from messages and batch entries', () => {
+    expect(JSON.parse(stripUnknownJsonRpcFields('{"jsonrpc":"2.0","id":1,"method":"tools/list","trace":"x"}')!))
+      .toEqual({ jsonrpc: '2.0', id: 1, method: 'tools/list' });
+    expect(JSON.parse(stripUnknownJsonRpcFields('[{"jsonrpc":"2.0","id":1,"method":"ping"},{"jsonrpc":"2.0","id":2,"method":"ping","v":1}]')!))
+      .toEqual([{ jsonrpc: '2.0', id: 1, method: 'ping' }, { jsonrpc: '2.0', id: 2, method: 'ping' }]);
+  });
+
+  it('should leave clean and unparseable lines unchanged', () => {
+    expect(stripUnknownJsonRpcFields('{"jsonrpc":"2.0","id":1,"method":"tools/list"}')).toBeNull();
+    expect(stripUnknownJsonRpcFields('{"method":')).toBeNull();
+  });
+});
@@ -0,0 +1,24 @@
+// ============================================================================
+// JSON-RPC Version Check Test Suite
+// ============================================================================
+
+import { findInvalidJsonRpcVersion } from '../../mcp/jsonrpc-version';
+
+describe('findInvalidJsonRpcVersion', () => {
+  it('should accept jsonrpc "2.0" messages', () => {
+    expect(findInvalidJsonRpcVersion('{"jsonrpc":"2.0","id":1,"method":"tools/list"}')).toBeNull();
+  });
+
+  it('should report a wrong or missing version with the request id', () => {
+    expect(findInvalidJsonRpcVersion('{"jsonrpc":"1.0","id":7,"method":"tools/list"}'))
+      .toEqual({ id: 7, version: '1.0' });
+    expect(findInvalidJsonRpcVersion('{"id":"abc","method":"tools/list"}'))
+      .toEqual({ id: 'abc', version: undefined });
+    expect(findInvalidJsonRpcVersion('{"jsonrpc":2.0,"method":"notify"}'))
+      .toEqual({ id: null, version: 2 });
+  });
+
+  it('should leave unparseable input to the parse error handling', () => {
+    expect(findInvalidJsonRpcVersion('{"jsonrpc":')).toBeNull();
+  });
+});
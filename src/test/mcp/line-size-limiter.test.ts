@@ -0,0 +1,86 @@
+// ============================================================================
+// LineSizeLimiter Test Suite
+// ============================================================================
+
+import { PassThrough } from 'stream';
+import { LineSizeLimiter } from '../../mcp/line-size-limiter';
+
+describe('LineSizeLimiter', () => {
+  const run = async (limiter: LineSizeLimiter, chunks: string[]): Promise<string> => {
+    const output: Buffer[] = [];
+    limiter.on('data', (chunk: Buffer) => output.push(chunk));
+    const done = new Promise(resolve => limiter.on('end', resolve));
+    for (const chunk of chunks) {
+      limiter.write(chunk);
+    }
+    limiter.end();
+    await done;
+    return Buffer.concat(output).toString();
+  };
+
+  it('should pass through lines within the limit', async () => {
+    const onOversize = jest.fn();
+    const output = await run(new LineSizeLimiter(16, onOversize), ['{"a":1}\n{"b":2}\n']);
+
+    expect(output).toBe('{"a":1}\n{"b":2}\n');
+    expect(onOversize).not.toHaveBeenCalled();
+  });
+
+  it('should drop an oversized line and report it once', async () => {
+    const onOversize = jest.fn();
+    const output = await run(new LineSizeLimiter(8, onOversize), [
+      '{"a":1}\n',
+      '{"big":"0123', '456789abcdef"}\n',
+      '{"b":2}\n'
+    ]);
+
+    expect(output).toBe('{"a":1}\n{"b":2}\n');
+    expect(onOversize).toHaveBeenCalledTimes(1);
+    expect(onOversize).toHaveBeenCalledWith(8);
+  });
+
+  it('should reassemble lines split across chunks', async () => {
+    const output = await run(new LineSizeLimiter(16, jest.fn()), ['{"a"', ':1}', '\n']);
+
+    expect(output).toBe('{"a":1}\n');
+  });
+
+  it('should drop lines rejected by acceptLine', async () => {
+    const acceptLine = jest.fn((line: Buffer) => !line.toString().includes('"1.0"'));
+    const output = await run(new LineSizeLimiter(64, jest.fn(), acceptLine), [
+      '{"jsonrpc":"1.0","id":1}\n{"jsonrpc":"2.0","id":2}\n'
+    ]);
+
+    expect(output).toBe('{"jsonrpc":"2.0","id":2}\n');
+    expect(acceptLine.mock.calls.map(call => call[0].toString()))
+      .toEqual(['{"jsonrpc":"1.0","id":1}', '{"jsonrpc":"2.0","id":2}']);
+  });
+
+  it('should pass the line returned by acceptLine in place of the original', async () => {
+    const output = await run(new LineSizeLimiter(64, jest.fn(), () => '{"id":1}'), ['{"id":1,"x":2}\n']);
+
+    expect(output).toBe('{"id":1}\n');
+  });
+
+  it('should normalize CRLF line endings and skip blank lines', async () => {
+    const acceptLine = jest.fn(() => true);
+    const output = await run(new LineSizeLimiter(64, jest.fn(), acceptLine), [
+      '{"jsonrpc":"2.0","id":1,"method":"ping"}\r\n\r\n',
+      '{"jsonrpc":"2.0","id":2,"method":"ping"}\r', '\n'
+    ]);
+
+    expect(output).toBe('{"jsonrpc":"2.0","id":1,"method":"ping"}\n{"jsonrpc":"2.0","id":2,"method":"ping"}\n');
+    expect(output.split('\n').filter(line => line !== '').map(line => JSON.parse(line).id)).toEqual([1, 2]);
+    expect(acceptLine).toHaveBeenCalledTimes(2);
+  });
+
+  it('should connect the source only once a reader is attached', () => {
+    const source = new PassThrough();
+    const pipe = jest.spyOn(source, 'pipe');
+    const limiter = new LineSizeLimiter(16, jest.fn()).connectWhenRead(source);
+
+    expect(pipe).not.toHaveBeenCalled();
+    limiter.on('data', () => undefined);
+    expect(pipe).toHaveBeenCalledTimes(1);
+  });
+});
@@ -0,0 +1,45 @@
+// ============================================================================
+// ObligationHandlerRegistry Test Suite
+// ============================================================================
+
+import { ObligationHandlerRegistry, parseObligation } from '../../mcp/obligation-handlers';
+import type { DecisionContext, PolicyDecision } from '../../types';
+
+const context: DecisionContext = { agent: 'agent-1', action: 'read', resource: 'file:///a', time: new Date() };
+const decision: PolicyDecision = { decision: 'PERMIT', reason: '許可', confidence: 0.9 };
+
+describe('parseObligation', () => {
+  it('should split the name before the first colon and lowercase it', () => {
+    expect(parseObligation('Notify: owner: now')).toEqual({ name: 'notify', argument: 'owner: now' });
+    expect(parseObligation(' log ')).toEqual({ name: 'log', argument: '' });
+  });
+});
+
+describe('ObligationHandlerRegistry', () => {
+  it('should run matching handlers in order and report unhandled obligations', async () => {
+    const registry = new ObligationHandlerRegistry();
+    const calls: string[] = [];
+    registry.register({ name: 'Notify', execute: async argument => { calls.push(`notify:${argument}`); } });
+    registry.register({ name: 'siem', mandatory: true, execute: async () => { throw new Error('down'); } });
+
+    const { outcomes, unhandled } = await registry.run(['notify: owner', 'unknown', 'SIEM'], context, decision);
+
+    expect(calls).toEqual(['notify:owner']);
+    expect(unhandled).toEqual(['unknown']);
+    expect(outcomes).toEqual([
+      { obligation: 'notify: owner', handler: 'Notify', mandatory: false, success: true },
+      { obligation: 'SIEM', handler: 'siem', mandatory: true, success: false, error: 'down' }
+    ]);
+  });
+
+  it('should replace and unregister handlers by name', () => {
+    const registry = new ObligationHandlerRegistry();
+    registry.register({ name: 'log', execute: async () => {} });
+    registry.register({ name: 'LOG', mandatory: true, execute: async () => {} });
+
+    expect(registry.names()).toEqual(['log']);
+    expect(registry.get('log')?.mandatory).toBe(true);
+    expect(registry.unregister('Log')).toBe(true);
+    expect(registry.names()).toEqual([]);
+  });
+});
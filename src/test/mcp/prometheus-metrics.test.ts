@@ -0,0 +1,59 @@
+// ============================================================================
+// Prometheus Metrics Test Suite
+// ============================================================================
+
+import { ServerStats } from '../../mcp/server-stats';
+import { formatPrometheusMetrics } from '../../mcp/prometheus-metrics';
+
+describe('formatPrometheusMetrics', () => {
+  it('should export the stats counters in the text exposition format', () => {
+    const stats = new ServerStats(0);
+    stats.recordRequest('tools/call');
+    stats.recordRequest('tools/call');
+    stats.recordDecision('DENY');
+    stats.recordCacheLookup(true);
+    stats.recordCacheLookup(false);
+    stats.recordCacheLookup(false);
+    stats.recordCacheLookup(false);
+
+    const text = formatPrometheusMetrics(stats, 42_000);
+
+    expect(text).toContain('# TYPE aegis_requests_total counter\naegis_requests_total{method="tools/call"} 2\n');
+    expect(text).toContain('aegis_decisions_total{decision="PERMIT"} 0\naegis_decisions_total{decision="DENY"} 1\n');
+    expect(text).toContain('aegis_cache_hits_total 1\n');
+    expect(text).toContain('aegis_cache_hit_ratio 0.25\n');
+    expect(text).toContain('aegis_uptime_seconds 42\n');
+    expect(text.endsWith('\n')).toBe(true);
+  });
+
+  it('should export cumulative evaluation latency buckets', () => {
+    const stats = new ServerStats(0);
+    stats.recordEvaluation(30);
+    stats.recordEvaluation(700);
+    stats.recordEvaluation(25_000);
+
+    const text = formatPrometheusMetrics(stats, 0);
+
+    expect(text).toContain('# TYPE aegis_policy_evaluation_duration_seconds histogram\n');
+    expect(text).toContain('aegis_policy_evaluation_duration_seconds_bucket{le="0.05"} 1\n');
+    expect(text).toContain('aegis_policy_evaluation_duration_seconds_bucket{le="1"} 2\n');
+    expect(text).toContain('aegis_policy_evaluation_duration_seconds_bucket{le="20"} 2\n');
+    expect(text).toContain('aegis_policy_evaluation_duration_seconds_bucket{le="+Inf"} 3\n');
+    expect(text).toContain('aegis_policy_evaluation_duration_seconds_sum 25.73\n');
+    expect(text).toContain('aegis_policy_evaluation_duration_seconds_count 3\n');
+  });
+
+  it('should export request latency histograms labelled by method', () => {
+    const stats = new ServerStats(0);
+    stats.recordRequestDuration('tools/list', 40);
+
+    const text = formatPrometheusMetrics(stats, 0);
+
+    expect(text).toContain('# TYPE aegis_request_duration_seconds histogram\n');
+    expect(text).toContain('aegis_request_duration_seconds_bucket{method="tools/list",le="0.025"} 0\n');
+    expect(text).toContain('aegis_request_duration_seconds_bucket{method="tools/list",le="0.05"} 1\n');
+    expect(text).toContain('aegis_request_duration_seconds_bucket{method="tools/list",le="+Inf"} 1\n');
+    expect(text).toContain('aegis_request_duration_seconds_sum{method="tools/list"} 0.04\n');
+    expect(text).toContain('aegis_request_duration_seconds_count{method="tools/list"} 1\n');
+  });
+});
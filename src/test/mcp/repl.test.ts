@@ -0,0 +1,94 @@
+// ============================================================================
+// REPL Test Suite
+// ============================================================================
+
+import { PassThrough } from 'stream';
+import { formatCheckResponse, parseReplCommand, runRepl } from '../../mcp/repl';
+
+describe('parseReplCommand', () => {
+  it('should read check commands and keep spaces in the resource', () => {
+    expect(parseReplCommand('check alice read file:/tmp/a b.txt')).toEqual({
+      kind: 'check',
+      agent: 'alice',
+      action: 'read',
+      resource: 'file:/tmp/a b.txt'
+    });
+    expect(parseReplCommand('  ')).toEqual({ kind: 'empty' });
+    expect(parseReplCommand('quit')).toEqual({ kind: 'exit' });
+  });
+
+  it('should report usage errors and unknown commands', () => {
+    expect(parseReplCommand('check alice read').kind).toBe('invalid');
+    expect(parseReplCommand('list')).toEqual({ kind: 'invalid', message: 'Unknown command: list (type "help" for commands)' });
+  });
+});
+
+describe('formatCheckResponse', () => {
+  it('should format the combined decision with one line per policy', () => {
+    const structuredContent = {
+      decision: 'DENY',
+      reason: 'Deletion is not allowed',
+      confidence: 0.9,
+      combiningAlgorithm: 'deny_overrides',
+      decisions: [
+        { policy: 'files', decision: 'DENY', reason: 'Deletion is not allowed', confidence: 0.9 },
+        { policy: 'hours', decision: 'PERMIT', reason: 'Within business hours', confidence: 0.8 }
+      ]
+    };
+    expect(formatCheckResponse(JSON.stringify({ jsonrpc: '2.0', id: 1, result: { content: [], structuredContent } }))).toBe([
+      'DENY (confidence 0.9, deny_overrides)',
+      '  reason: Deletion is not allowed',
+      '  - files: DENY (0.9) Deletion is not allowed',
+      '  - hours: PERMIT (0.8) Within business hours'
+    ].join('\n'));
+  });
+
+  it('should show JSON-RPC errors and tool errors', () => {
+    expect(formatCheckResponse(JSON.stringify({ jsonrpc: '2.0', id: 1, error: { code: -32602, message: 'Bad' } }))).toBe('Error -32602: Bad');
+    expect(formatCheckResponse(JSON.stringify({
+      jsonrpc: '2.0', id: 1, result: { isError: true, content: [{ type: 'text', text: 'tags must be an array of strings' }] }
+    }))).toBe('Error: tags must be an array of strings');
+  });
+});
+
+describe('runRepl', () => {
+  it('should dispatch check_policies with every registered policy and print the decision', async () => {
+    const requests: any[] = [];
+    const dispatcher = {
+      dispatchString: jest.fn(async (input: string) => {
+        const message = JSON.parse(input);
+        requests.push(message);
+        if (message.method === 'resources/list') {
+          return JSON.stringify({ jsonrpc: '2.0', id: message.id, result: { resources: [
+            { uri: 'aegis://policy/files', name: 'files' },
+            { uri: 'file:///upstream.txt', name: 'upstream' }
+          ] } });
+        }
+        if (message.method === 'tools/call') {
+          return JSON.stringify({ jsonrpc: '2.0', id: message.id, result: { content: [], structuredContent: {
+            decision: 'PERMIT', reason: 'ok', confidence: 1, combiningAlgorithm: 'deny_overrides',
+            decisions: [{ policy: 'files', decision: 'PERMIT', reason: 'ok', confidence: 1 }]
+          } } });
+        }
+        return message.id === undefined ? null : JSON.stringify({ jsonrpc: '2.0', id: message.id, result: {} });
+      })
+    };
+    const input = new PassThrough();
+    const output = new PassThrough();
+    let printed = '';
+    output.on('data', chunk => { printed += chunk; });
+
+    const done = runRepl(dispatcher, input, output);
+    input.end('check alice read file:/tmp/a.txt\nexit\ncheck bob read never\n');
+    await done;
+
+    expect(requests.map(request => request.method)).toEqual([
+      'initialize', 'notifications/initialized', 'resources/list', 'tools/call'
+    ]);
+    expect(requests[3].params).toEqual({
+      name: 'check_policies',
+      arguments: { agent: 'alice', action: 'read', resource: 'file:/tmp/a.txt', policies: ['files'], combining_algorithm: 'deny_overrides' }
+    });
+    expect(printed).toContain('PERMIT (confidence 1, deny_overrides)\n  reason: ok\n  - files: PERMIT (1) ok\naegis> ');
+  });
+});
@@ -0,0 +1,52 @@
+// ============================================================================
+// RequestDeduplicator Test Suite
+// ============================================================================
+
+import { RequestDeduplicator } from '../../mcp/request-deduplicator';
+
+describe('RequestDeduplicator', () => {
+  it('should replay the recorded response for a repeated id without re-executing', async () => {
+    const deduplicator = new RequestDeduplicator<string>(10);
+    const execute = jest.fn().mockResolvedValueOnce('first').mockResolvedValueOnce('second');
+
+    await expect(deduplicator.run('s:1', { name: 'write' }, execute)).resolves.toBe('first');
+    await expect(deduplicator.run('s:1', { name: 'write' }, execute)).resolves.toBe('first');
+    expect(execute).toHaveBeenCalledTimes(1);
+  });
+
+  it('should reject a duplicate that arrives while the first call is in flight', async () => {
+    const deduplicator = new RequestDeduplicator<string>(10);
+    let finish!: (value: string) => void;
+    const first = deduplicator.run('s:1', {}, () => new Promise<string>(resolve => { finish = resolve; }));
+
+    await expect(deduplicator.run('s:1', {}, jest.fn())).rejects.toMatchObject({ code: -32600 });
+    finish('done');
+    await expect(first).resolves.toBe('done');
+  });
+
+  it('should replay failures and treat a reused id with different params as a new request', async () => {
+    const deduplicator = new RequestDeduplicator<string>(10);
+    const failure = new Error('upstream failed');
+
+    await expect(deduplicator.run('s:1', { a: 1 }, () => Promise.reject(failure))).rejects.toBe(failure);
+    await expect(deduplicator.run('s:1', { a: 1 }, jest.fn())).rejects.toBe(failure);
+    await expect(deduplicator.run('s:1', { a: 2 }, async () => 'fresh')).resolves.toBe('fresh');
+  });
+
+  it('should forget the oldest ids beyond the window and skip when disabled', async () => {
+    const deduplicator = new RequestDeduplicator<number>(2);
+    for (const id of ['1', '2', '3']) {
+      await deduplicator.run(id, {}, async () => Number(id));
+    }
+    const execute = jest.fn().mockResolvedValue(0);
+
+    expect(deduplicator.size()).toBe(2);
+    await expect(deduplicator.run('1', {}, execute)).resolves.toBe(0);
+    expect(execute).toHaveBeenCalledTimes(1);
+
+    const disabled = new RequestDeduplicator<number>(0);
+    await disabled.run('1', {}, execute);
+    await disabled.run('1', {}, execute);
+    expect(execute).toHaveBeenCalledTimes(3);
+  });
+});
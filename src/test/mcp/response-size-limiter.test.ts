@@ -0,0 +1,57 @@
+// ============================================================================
+// Response Size Limiter Test Suite
+// ============================================================================
+
+import type { JSONRPCMessage } from '@modelcontextprotocol/sdk/types.js';
+import { limitResponseSize } from '../../mcp/response-size-limiter';
+
+const size = (message: unknown) => Buffer.byteLength(JSON.stringify(message), 'utf8');
+
+describe('limitResponseSize', () => {
+  it('should leave responses within the limit and non-result messages untouched', () => {
+    const small = { jsonrpc: '2.0', id: 1, result: { content: [{ type: 'text', text: 'ok' }] } } as JSONRPCMessage;
+    const error = { jsonrpc: '2.0', id: 2, error: { code: -32603, message: 'x'.repeat(500) } } as JSONRPCMessage;
+
+    expect(limitResponseSize(small, 1000)).toBe(small);
+    expect(limitResponseSize(error, 100)).toBe(error);
+    expect(limitResponseSize(small, 0)).toBe(small);
+  });
+
+  it('should truncate the largest text with a marker and keep structured fields intact', () => {
+    const message = {
+      jsonrpc: '2.0',
+      id: 1,
+      result: {
+        content: [{ type: 'text', text: 'short' }, { type: 'text', text: 'あ'.repeat(2000) }],
+        structuredContent: { decision: 'PERMIT' },
+        _meta: { traceId: 't-1' }
+      }
+    } as JSONRPCMessage;
+
+    const limited = limitResponseSize(message, 1200) as any;
+
+    expect(size(limited)).toBeLessThanOrEqual(1200);
+    expect(limited.result.content[0].text).toBe('short');
+    expect(limited.result.content[1].text).toMatch(/^あ+\.\.\. \[truncated \d+ bytes\]$/);
+    const kept = limited.result.content[1].text.replace(/\.\.\. \[truncated \d+ bytes\]$/, '');
+    const dropped = Number(limited.result.content[1].text.match(/truncated (\d+) bytes/)[1]);
+    expect(Buffer.byteLength(kept, 'utf8') + dropped).toBe(6000);
+    expect(limited.result.structuredContent).toEqual({ decision: 'PERMIT' });
+    expect(limited.result._meta).toEqual({ traceId: 't-1' });
+    expect((message as any).result.content[1].text).toHaveLength(2000);
+  });
+
+  it('should truncate resource contents as well', () => {
+    const message = {
+      jsonrpc: '2.0',
+      id: 1,
+      result: { contents: [{ uri: 'aegis://policy/p', mimeType: 'text/plain', text: 'x'.repeat(5000) }] }
+    } as JSONRPCMessage;
+
+    const limited = limitResponseSize(message, 300) as any;
+
+    expect(size(limited)).toBeLessThanOrEqual(300);
+    expect(limited.result.contents[0].uri).toBe('aegis://policy/p');
+    expect(limited.result.contents[0].text).toMatch(/\[truncated \d+ bytes\]$/);
+  });
+});
@@ -0,0 +1,67 @@
+// ============================================================================
+// ServerStats Test Suite
+// ============================================================================
+
+import { ServerStats } from '../../mcp/server-stats';
+
+describe('ServerStats', () => {
+  it('should count requests per method, decisions and cache lookups', () => {
+    const stats = new ServerStats(1_000_000);
+
+    stats.recordRequest('tools/call');
+    stats.recordRequest('tools/call');
+    stats.recordRequest('resources/read');
+    stats.recordDecision('PERMIT');
+    stats.recordDecision('DENY');
+    stats.recordDecision('PERMIT');
+    stats.recordCacheLookup(true);
+    stats.recordCacheLookup(false);
+    stats.recordCacheLookup(false);
+
+    expect(stats.snapshot(1_065_500)).toEqual({
+      uptimeSeconds: 65,
+      totalRequests: 3,
+      requestsByMethod: { 'tools/call': 2, 'resources/read': 1 },
+      decisions: { PERMIT: 2, DENY: 1, INDETERMINATE: 0 },
+      cache: { hits: 1, misses: 2 }
+    });
+  });
+
+  it('should keep cumulative evaluation latency buckets in seconds', () => {
+    const stats = new ServerStats();
+
+    stats.recordEvaluation(80);
+    stats.recordEvaluation(3000);
+
+    const latency = stats.evaluationLatency();
+    expect(latency.count).toBe(2);
+    expect(latency.sumSeconds).toBeCloseTo(3.08);
+    expect(latency.buckets.find(bucket => bucket.le === 0.1)?.count).toBe(1);
+    expect(latency.buckets.find(bucket => bucket.le === 5)?.count).toBe(2);
+  });
+
+  it('should keep request latency buckets per method', () => {
+    const stats = new ServerStats();
+
+    stats.recordRequestDuration('ping', 2);
+    stats.recordRequestDuration('tools/call', 800);
+    stats.recordRequestDuration('tools/call', 12_000);
+
+    const latency = stats.requestLatency();
+    expect(Object.keys(latency)).toEqual(['ping', 'tools/call']);
+    expect(latency.ping.buckets[0]).toEqual({ le: 0.005, count: 1 });
+    expect(latency['tools/call'].count).toBe(2);
+    expect(latency['tools/call'].buckets.find(bucket => bucket.le === 1)?.count).toBe(1);
+    expect(latency['tools/call'].buckets.find(bucket => bucket.le === 30)?.count).toBe(2);
+  });
+
+  it('should return snapshots that are not affected by later updates', () => {
+    const stats = new ServerStats();
+    const before = stats.snapshot();
+
+    stats.recordDecision('INDETERMINATE');
+
+    expect(before.decisions.INDETERMINATE).toBe(0);
+    expect(stats.snapshot().decisions.INDETERMINATE).toBe(1);
+  });
+});
@@ -347,6 +347,29 @@ describe('StdioRouter', () => {
       jest.useRealTimers();
     });
 
+    it('should send notifications/cancelled to the server handling a cancelled request', async () => {
+      stdin.write.mockClear();
+      const routePromise = router.routeRequest({
+        jsonrpc: '2.0',
+        method: 'tools/call',
+        params: { name: 'test-server__some-tool', arguments: {} },
+        id: 5
+      });
+
+      router.cancelRequest(5, 'client cancelled');
+
+      await expect(routePromise).rejects.toThrow('Request cancelled: client cancelled');
+      expect(JSON.parse(stdin.write.mock.calls[1][0])).toEqual({
+        jsonrpc: '2.0',
+        method: 'notifications/cancelled',
+        params: { requestId: 5, reason: 'client cancelled' }
+      });
+
+      // 応答待ちでないidは無視する
+      router.cancelRequest(5, 'again');
+      expect(stdin.write).toHaveBeenCalledTimes(2);
+    });
+
     it('should aggregate tools/list from multiple servers', async () => {
       // Add second server
       const { process: mockProcess2, stdin: stdin2, stdout: stdout2, stderr: stderr2 } = createMockProcess();
@@ -0,0 +1,63 @@
+// ============================================================================
+// Tool Argument Validator Test Suite
+// ============================================================================
+
+import { validateAgainstSchema } from '../../mcp/tool-argument-validator';
+
+describe('validateAgainstSchema', () => {
+  const readFileSchema = {
+    type: 'object',
+    properties: {
+      path: { type: 'string', minLength: 1 },
+      encoding: { type: 'string', enum: ['utf-8', 'base64'] },
+      limit: { type: 'integer', minimum: 1 },
+      tags: { type: 'array', items: { type: 'string' } }
+    },
+    required: ['path'],
+    additionalProperties: false
+  };
+
+  it('should accept arguments that satisfy the schema', () => {
+    const issues = validateAgainstSchema(
+      { path: '/tmp/a.txt', encoding: 'utf-8', limit: 10, tags: ['x'] },
+      readFileSchema
+    );
+
+    expect(issues).toEqual([]);
+  });
+
+  it('should report missing required fields with their path', () => {
+    const issues = validateAgainstSchema({}, readFileSchema);
+
+    expect(issues).toEqual([{ path: 'arguments.path', message: 'is required' }]);
+  });
+
+  it('should report type mismatches', () => {
+    const issues = validateAgainstSchema({ path: 42 }, readFileSchema);
+
+    expect(issues).toEqual([{ path: 'arguments.path', message: 'must be string, got integer' }]);
+  });
+
+  it('should report enum, range and nested array violations', () => {
+    const issues = validateAgainstSchema(
+      { path: 'a', encoding: 'latin1', limit: 0, tags: ['ok', 3] },
+      readFileSchema
+    );
+
+    expect(issues.map(issue => issue.path)).toEqual([
+      'arguments.encoding',
+      'arguments.limit',
+      'arguments.tags[1]'
+    ]);
+  });
+
+  it('should reject unknown properties when additionalProperties is false', () => {
+    const issues = validateAgainstSchema({ path: 'a', mode: 'w' }, readFileSchema);
+
+    expect(issues).toEqual([{ path: 'arguments.mode', message: 'is not allowed' }]);
+  });
+
+  it('should skip validation when no schema is given', () => {
+    expect(validateAgainstSchema({ anything: true }, undefined)).toEqual([]);
+  });
+});
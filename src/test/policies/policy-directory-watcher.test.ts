@@ -0,0 +1,56 @@
+// ============================================================================
+// PolicyDirectoryWatcher Test Suite
+// ============================================================================
+
+import * as fs from 'fs/promises';
+import * as os from 'os';
+import * as path from 'path';
+import { PolicyLoader } from '../../policies/policy-loader';
+import { PolicyDirectoryWatcher, PolicyReloadEvent } from '../../policies/policy-directory-watcher';
+
+jest.mock('../../utils/logger');
+
+describe('PolicyDirectoryWatcher', () => {
+  let workDir: string;
+  let loader: PolicyLoader;
+  let watcher: PolicyDirectoryWatcher | undefined;
+
+  beforeEach(async () => {
+    workDir = await fs.mkdtemp(path.join(os.tmpdir(), 'aegis-policy-watcher-'));
+    loader = new PolicyLoader(path.join(workDir, 'policies.json'));
+  });
+
+  afterEach(async () => {
+    await watcher?.stop();
+    watcher = undefined;
+    await fs.rm(workDir, { recursive: true, force: true });
+  });
+
+  it('should debounce successive writes into one reload of the final content', async () => {
+    await fs.writeFile(path.join(workDir, 'file-access.txt'), 'v1');
+    await loader.loadPolicyDirectory(workDir);
+
+    const events: PolicyReloadEvent[] = [];
+    const reloaded = new Promise<void>(resolve => {
+      watcher = new PolicyDirectoryWatcher(loader, event => {
+        events.push(event);
+        resolve();
+      }, 100);
+    });
+    watcher!.start();
+
+    await fs.writeFile(path.join(workDir, 'file-access.txt'), 'v2');
+    await fs.writeFile(path.join(workDir, 'file-access.txt'), 'v3');
+    await fs.writeFile(path.join(workDir, 'notes.json'), '{}');
+    await reloaded;
+    await new Promise(resolve => setTimeout(resolve, 200));
+
+    expect(events).toHaveLength(1);
+    expect(events[0]).toMatchObject({ type: 'updated', id: 'file-access', file: 'file-access.txt' });
+    expect(loader.getPolicyText(loader.getPolicy('file-access')!)).toBe('v3');
+  });
+
+  it('should refuse to start before a policy directory is loaded', () => {
+    expect(() => new PolicyDirectoryWatcher(loader, jest.fn()).start()).toThrow('Load a policy directory');
+  });
+});
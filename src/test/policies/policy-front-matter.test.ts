@@ -0,0 +1,20 @@
+// ============================================================================
+// Policy Front Matter Test Suite
+// ============================================================================
+
+import { parsePolicyFrontMatter } from '../../policies/policy-front-matter';
+
+describe('parsePolicyFrontMatter', () => {
+  it('should read inline and block tag lists and strip the header from the body', () => {
+    expect(parsePolicyFrontMatter('---\ntags: [network, "Data"]\nowner: sec\n---\n外部通信は拒否')).toEqual({
+      tags: ['network', 'data'],
+      body: '外部通信は拒否'
+    });
+    expect(parsePolicyFrontMatter('---\ntags:\n  - finance\n  - data\n  - finance\n---\n本文').tags).toEqual(['finance', 'data']);
+    expect(parsePolicyFrontMatter('---\ntags: data, network\n---\n').tags).toEqual(['data', 'network']);
+  });
+
+  it('should leave text without a front matter header unchanged', () => {
+    expect(parsePolicyFrontMatter('営業時間内のみ許可\n---\n')).toEqual({ tags: [], body: '営業時間内のみ許可\n---\n' });
+  });
+});
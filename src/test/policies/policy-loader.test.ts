@@ -0,0 +1,124 @@
+// ============================================================================
+// PolicyLoader Test Suite
+// ============================================================================
+
+import * as fs from 'fs/promises';
+import * as os from 'os';
+import * as path from 'path';
+import { PolicyLoader } from '../../policies/policy-loader';
+
+jest.mock('../../utils/logger');
+
+describe('PolicyLoader', () => {
+  let workDir: string;
+  let policyDir: string;
+  let loader: PolicyLoader;
+
+  beforeEach(async () => {
+    workDir = await fs.mkdtemp(path.join(os.tmpdir(), 'aegis-policy-loader-'));
+    policyDir = path.join(workDir, 'policies.d');
+    await fs.mkdir(policyDir);
+    loader = new PolicyLoader(path.join(workDir, 'policies.json'));
+  });
+
+  afterEach(async () => {
+    await fs.rm(workDir, { recursive: true, force: true });
+  });
+
+  describe('loadPolicyDirectory', () => {
+    it('should load .md and .txt files keyed by file name', async () => {
+      await fs.writeFile(path.join(policyDir, 'customer-data.md'), '# 顧客データ\n営業時間内のみ許可\n');
+      await fs.writeFile(path.join(policyDir, 'file-access.txt'), '機密ファイルへのアクセスは拒否');
+      await fs.writeFile(path.join(policyDir, 'notes.json'), '{}');
+
+      const loaded = await loader.loadPolicyDirectory(policyDir);
+
+      expect(loaded).toBe(2);
+      expect(loader.getPolicy('customer-data')?.status).toBe('active');
+      expect(loader.getPolicy('notes')).toBeUndefined();
+      expect(loader.getPolicyText(loader.getPolicy('file-access')!)).toBe('機密ファイルへのアクセスは拒否');
+    });
+
+    it('should not persist directory policies into policies.json', async () => {
+      await loader.loadPolicies(); // policies.jsonがないためデフォルトポリシーを作成
+      await fs.writeFile(path.join(policyDir, 'file-access.txt'), '機密ファイルへのアクセスは拒否');
+      await loader.loadPolicyDirectory(policyDir);
+
+      await loader.deletePolicy('default-policy');
+
+      const saved = JSON.parse(await fs.readFile(path.join(workDir, 'policies.json'), 'utf-8'));
+      expect(saved.policies).toEqual([]);
+      expect(loader.getPolicy('file-access')).toBeDefined();
+    });
+
+    it('should reload directory policies on reloadPolicies', async () => {
+      await loader.loadPolicies();
+      await fs.writeFile(path.join(policyDir, 'file-access.txt'), 'v1');
+      await loader.loadPolicyDirectory(policyDir);
+
+      await fs.writeFile(path.join(policyDir, 'file-access.txt'), 'v2');
+      await loader.reloadPolicies();
+
+      expect(loader.getPolicyText(loader.getPolicy('file-access')!)).toBe('v2');
+    });
+
+    it('should reload a single file, keep the old text for empty files and drop deleted files', async () => {
+      await fs.writeFile(path.join(policyDir, 'file-access.txt'), 'v1');
+      await loader.loadPolicyDirectory(policyDir);
+
+      await fs.writeFile(path.join(policyDir, 'file-access.txt'), 'v2');
+      await expect(loader.reloadPolicyFile('file-access.txt')).resolves.toMatchObject({ id: 'file-access' });
+      expect(loader.getPolicyText(loader.getPolicy('file-access')!)).toBe('v2');
+
+      await fs.writeFile(path.join(policyDir, 'file-access.txt'), '');
+      await expect(loader.reloadPolicyFile('file-access.txt')).rejects.toThrow('file is empty');
+      expect(loader.getPolicyText(loader.getPolicy('file-access')!)).toBe('v2');
+
+      await fs.rm(path.join(policyDir, 'file-access.txt'));
+      await expect(loader.reloadPolicyFile('file-access.txt')).resolves.toBeNull();
+      expect(loader.getPolicy('file-access')).toBeUndefined();
+    });
+
+    it('should expand environment variables in policy text', async () => {
+      process.env.AEGIS_TEST_ALLOWED_HOST = 'api.example.com';
+      await fs.writeFile(path.join(policyDir, 'hosts.md'), '${AEGIS_TEST_ALLOWED_HOST} のみ許可、$${LITERAL}、${AEGIS_TEST_UNDEFINED}');
+
+      try {
+        await loader.loadPolicyDirectory(policyDir);
+        expect(loader.getPolicyText(loader.getPolicy('hosts')!))
+          .toBe('api.example.com のみ許可、${LITERAL}、${AEGIS_TEST_UNDEFINED}');
+
+        loader.setStrictEnv(true);
+        await expect(loader.loadPolicyDirectory(policyDir))
+          .rejects.toThrow('Policy hosts.md: Undefined environment variable(s): AEGIS_TEST_UNDEFINED');
+      } finally {
+        delete process.env.AEGIS_TEST_ALLOWED_HOST;
+      }
+    });
+
+    it('should read front matter tags into metadata and drop the header from the text', async () => {
+      await fs.writeFile(path.join(policyDir, 'egress.md'), '---\ntags: [network, data]\n---\n外部通信は拒否\n');
+
+      await loader.loadPolicyDirectory(policyDir);
+
+      expect(loader.getPolicy('egress')?.metadata.tags).toEqual(['file', 'network', 'data']);
+      expect(loader.getPolicyText(loader.getPolicy('egress')!)).toBe('外部通信は拒否');
+    });
+
+    it('should skip unreadable files and keep loading the rest', async () => {
+      await fs.mkdir(path.join(policyDir, 'broken.md'));
+      for (let i = 0; i < 20; i++) {
+        await fs.writeFile(path.join(policyDir, `policy-${String(i).padStart(2, '0')}.txt`), `ポリシー${i}`);
+      }
+
+      await expect(loader.loadPolicyDirectory(policyDir)).resolves.toBe(20);
+      expect(loader.getPolicy('broken')).toBeUndefined();
+      expect(loader.getPolicyText(loader.getPolicy('policy-19')!)).toBe('ポリシー19');
+    });
+
+    it('should fail when the directory does not exist', async () => {
+      await expect(loader.loadPolicyDirectory(path.join(workDir, 'missing')))
+        .rejects.toThrow('Policy directory loading failed');
+    });
+  });
+});
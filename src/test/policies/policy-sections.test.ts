@@ -0,0 +1,41 @@
+// ============================================================================
+// Policy Sections Test Suite
+// ============================================================================
+
+import { selectPolicySections } from '../../policies/policy-sections';
+
+const POLICY = [
+  '# 社内ポリシー',
+  '前文',
+  '',
+  '## ファイルアクセス',
+  '営業時間内のみ許可',
+  '### 例外',
+  '管理者は常に許可',
+  '',
+  '## 外部通信',
+  '```',
+  '# コメント（見出しではない）',
+  '```',
+  '外部通信は拒否',
+  '',
+  '## 個人情報 ##',
+  '個人情報は拒否'
+].join('\n');
+
+describe('selectPolicySections', () => {
+  it('should keep only the named sections, including nested headings, in document order', () => {
+    expect(selectPolicySections(POLICY, ['個人情報', 'ファイルアクセス', '例外'])).toEqual({
+      text: '## ファイルアクセス\n営業時間内のみ許可\n### 例外\n管理者は常に許可\n\n## 個人情報 ##\n個人情報は拒否',
+      missing: [],
+      available: ['社内ポリシー', 'ファイルアクセス', '例外', '外部通信', '個人情報']
+    });
+  });
+
+  it('should ignore headings inside code blocks and report unknown sections', () => {
+    const selection = selectPolicySections(POLICY, ['外部通信', 'コメント（見出しではない）']);
+
+    expect(selection.text).toBe('## 外部通信\n```\n# コメント（見出しではない）\n```\n外部通信は拒否');
+    expect(selection.missing).toEqual(['コメント（見出しではない）']);
+  });
+});
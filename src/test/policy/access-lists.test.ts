@@ -0,0 +1,48 @@
+// ============================================================================
+// Access Lists Test Suite
+// ============================================================================
+
+import type { DecisionContext } from '../../types';
+import { findAccessListMatch, parseAccessList } from '../../policy/access-lists';
+
+const context = (action: string, resource: string): DecisionContext => ({
+  agent: 'claude',
+  action,
+  resource,
+  time: new Date(),
+  environment: {}
+});
+
+describe('parseAccessList', () => {
+  it('should read action and resource-glob entries, skipping blank lines and comments', () => {
+    const entries = parseAccessList('# 公開ドキュメント\nread file://docs/**\n\n* /prod/secrets/*\n', 'allowlist.txt');
+
+    expect(entries.map(entry => [entry.line, entry.action, entry.resource])).toEqual([
+      [2, 'read', 'file://docs/**'],
+      [4, '*', '/prod/secrets/*']
+    ]);
+  });
+
+  it('should reject lines that are not "action resource-glob"', () => {
+    expect(() => parseAccessList('read\n', 'denylist.txt')).toThrow('Invalid entry at line 1 (expected "action resource-glob"): denylist.txt');
+    expect(() => parseAccessList('read file://a extra\n', 'denylist.txt')).toThrow('Invalid entry at line 1');
+  });
+});
+
+describe('findAccessListMatch', () => {
+  const entries = parseAccessList('read file://docs/**\n* /prod/secrets/*\n', 'list.txt');
+
+  it('should match the action exactly or any action for *', () => {
+    expect(findAccessListMatch(entries, context('read', 'file://docs/guide/intro.md'))?.line).toBe(1);
+    expect(findAccessListMatch(entries, context('delete', '/prod/secrets/key'))?.line).toBe(2);
+    expect(findAccessListMatch(entries, context('write', 'file://docs/guide/intro.md'))).toBeNull();
+    expect(findAccessListMatch(entries, context('read', '/prod/secrets/nested/key'))).toBeNull();
+  });
+
+  it('should not let ? match a slash', () => {
+    const singleChar = parseAccessList('read /tmp/?/report\n', 'list.txt');
+
+    expect(findAccessListMatch(singleChar, context('read', '/tmp/a/report'))?.line).toBe(1);
+    expect(findAccessListMatch(singleChar, context('read', '/tmp///report'))).toBeNull();
+  });
+});
@@ -0,0 +1,63 @@
+// ============================================================================
+// Action Synonyms Test Suite
+// ============================================================================
+
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  DEFAULT_ACTION_SYNONYMS,
+  buildActionLookup,
+  canonicalizeAction,
+  loadActionSynonyms
+} from '../../policy/action-synonyms';
+
+describe('canonicalizeAction', () => {
+  const lookup = buildActionLookup(DEFAULT_ACTION_SYNONYMS);
+
+  it('should map built-in CRUD synonyms to their canonical action', () => {
+    expect(canonicalizeAction('rm', lookup)).toBe('delete');
+    expect(canonicalizeAction(' Remove ', lookup)).toBe('delete');
+    expect(canonicalizeAction('fetch', lookup)).toBe('read');
+    expect(canonicalizeAction('PATCH', lookup)).toBe('update');
+    expect(canonicalizeAction('Create', lookup)).toBe('create');
+  });
+
+  it('should leave unknown actions unchanged', () => {
+    expect(canonicalizeAction('execute', lookup)).toBe('execute');
+    expect(canonicalizeAction('resources/read', lookup)).toBe('resources/read');
+  });
+});
+
+describe('loadActionSynonyms', () => {
+  let dir: string;
+  const write = (content: unknown) => {
+    const filePath = path.join(dir, 'synonyms.json');
+    fs.writeFileSync(filePath, JSON.stringify(content));
+    return filePath;
+  };
+
+  beforeEach(() => {
+    dir = fs.mkdtempSync(path.join(os.tmpdir(), 'aegis-synonyms-'));
+  });
+
+  afterEach(() => {
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('should add synonyms on top of the defaults', () => {
+    const synonyms = loadActionSynonyms(write({ delete: ['purge'], execute: ['run', 'exec'] }));
+    const lookup = buildActionLookup(synonyms);
+
+    expect(synonyms.delete).toEqual([...DEFAULT_ACTION_SYNONYMS.delete, 'purge']);
+    expect(canonicalizeAction('purge', lookup)).toBe('delete');
+    expect(canonicalizeAction('rm', lookup)).toBe('delete');
+    expect(canonicalizeAction('exec', lookup)).toBe('execute');
+  });
+
+  it('should reject files that are not a map of string arrays', () => {
+    expect(() => loadActionSynonyms(write(['delete']))).toThrow('Action synonyms must be a JSON object');
+    expect(() => loadActionSynonyms(write({ delete: 'purge' }))).toThrow('Synonyms for delete must be an array of strings');
+    expect(() => loadActionSynonyms(write({ delete: [''] }))).toThrow('Synonyms for delete must be an array of strings');
+  });
+});
@@ -0,0 +1,45 @@
+// ============================================================================
+// Agent Overrides Test Suite
+// ============================================================================
+
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { loadAgentOverrides } from '../../policy/agent-overrides';
+
+describe('loadAgentOverrides', () => {
+  let dir: string;
+  const write = (content: unknown) => {
+    const filePath = path.join(dir, 'overrides.json');
+    fs.writeFileSync(filePath, JSON.stringify(content));
+    return filePath;
+  };
+
+  beforeEach(() => {
+    dir = fs.mkdtempSync(path.join(os.tmpdir(), 'aegis-overrides-'));
+  });
+
+  afterEach(() => {
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('should map agent ids to their override', () => {
+    const overrides = loadAgentOverrides(write({
+      'break-glass': { mode: 'force-permit' },
+      quarantined: { mode: 'force-deny', policy: 'ignored' },
+      intern: { mode: 'append-policy', policy: '削除操作は拒否' }
+    }));
+
+    expect([...overrides]).toEqual([
+      ['break-glass', { mode: 'force-permit' }],
+      ['quarantined', { mode: 'force-deny' }],
+      ['intern', { mode: 'append-policy', policy: '削除操作は拒否' }]
+    ]);
+  });
+
+  it('should reject unknown modes and append-policy without a policy', () => {
+    expect(() => loadAgentOverrides(write({ admin: { mode: 'allow-all' } }))).toThrow('Agent override for admin must have mode');
+    expect(() => loadAgentOverrides(write({ intern: { mode: 'append-policy' } }))).toThrow('needs a policy to append');
+    expect(() => loadAgentOverrides(write(['break-glass']))).toThrow('must be a JSON object');
+  });
+});
@@ -0,0 +1,256 @@
+// ============================================================================
+// AIPolicyEngine Test Suite
+// ============================================================================
+
+import { AIPolicyEngine, retryDelay } from '../../policy/ai-policy-engine';
+import { AIJudgmentEngine } from '../../ai/judgment-engine';
+import type { DecisionContext, PolicyDecision } from '../../types';
+import { FixedTimeProvider } from '../../utils/time-provider';
+import { SeededRng } from '../../utils/rng';
+
+jest.mock('../../utils/logger');
+
+describe('AIPolicyEngine', () => {
+  const permit: PolicyDecision = {
+    decision: 'PERMIT',
+    reason: 'allowed',
+    confidence: 0.9,
+    constraints: [],
+    obligations: []
+  };
+
+  const context: DecisionContext = {
+    agent: 'agent-1',
+    action: 'read',
+    resource: 'file://docs/a.txt',
+    time: new Date(),
+    environment: { transport: 'stdio' }
+  };
+
+  let judge: jest.Mock;
+  let judgmentEngine: AIJudgmentEngine;
+
+  beforeEach(() => {
+    judge = jest.fn().mockResolvedValue(permit);
+    judgmentEngine = { judge } as unknown as AIJudgmentEngine;
+  });
+
+  describe('decision cache', () => {
+    it('should return a cached decision marked as cached for identical requests', async () => {
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: true, cacheTTL: 60000 });
+
+      const first = await engine.decide(context, 'policy A');
+      const second = await engine.decide({ ...context, time: new Date(Date.now() + 1000) }, 'policy A');
+
+      expect(judge).toHaveBeenCalledTimes(1);
+      expect(first.metadata?.cached).toBeUndefined();
+      expect(second.metadata?.cached).toBe(true);
+      expect(second.decision).toBe('PERMIT');
+    });
+
+    it('should not share cached decisions across different policies or environments', async () => {
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: true, cacheTTL: 60000 });
+
+      await engine.decide(context, 'policy A');
+      await engine.decide(context, 'policy B');
+      await engine.decide({ ...context, environment: { transport: 'http' } }, 'policy A');
+
+      expect(judge).toHaveBeenCalledTimes(3);
+    });
+
+    it('should not cache decisions whose caller has already timed out', async () => {
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: true, cacheTTL: 60000 });
+      const controller = new AbortController();
+      controller.abort();
+
+      await engine.decide(context, 'policy A', controller.signal);
+      const next = await engine.decide(context, 'policy A');
+
+      expect(judge).toHaveBeenCalledTimes(2);
+      expect(next.metadata?.cached).toBeUndefined();
+    });
+
+    it('should disable caching when maxCacheSize is 0', async () => {
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: true, cacheTTL: 60000, maxCacheSize: 0 });
+
+      await engine.decide(context, 'policy A');
+      await engine.decide(context, 'policy A');
+
+      expect(judge).toHaveBeenCalledTimes(2);
+    });
+
+    it('should evict the least recently used entry when full', async () => {
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: true, cacheTTL: 60000, maxCacheSize: 2 });
+
+      await engine.decide(context, 'policy A');
+      await engine.decide(context, 'policy B');
+      await engine.decide(context, 'policy A'); // Aを最近参照済みにする
+      await engine.decide(context, 'policy C'); // Bが追い出される
+      judge.mockClear();
+
+      await engine.decide(context, 'policy A');
+      await engine.decide(context, 'policy B');
+
+      expect(judge).toHaveBeenCalledTimes(1);
+    });
+
+    it('should also clear the judgment engine cache', () => {
+      const clearCache = jest.fn();
+      const engine = new AIPolicyEngine({ judge, clearCache } as unknown as AIJudgmentEngine, { cacheEnabled: true });
+
+      engine.clearCache();
+
+      expect(clearCache).toHaveBeenCalledTimes(1);
+    });
+
+    it('should expire entries after cacheTTL', async () => {
+      const clock = new FixedTimeProvider(1_000_000);
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: true, cacheTTL: 1000 }, { rng: new SeededRng(1), clock });
+
+      await engine.decide(context, 'policy A');
+      clock.advance(2000);
+      await engine.decide(context, 'policy A');
+
+      expect(judge).toHaveBeenCalledTimes(2);
+    });
+  });
+
+  describe('rule fast path', () => {
+    it('should evaluate rule-format policies without calling the AI', async () => {
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: true, cacheTTL: 60000 });
+
+      const decision = await engine.decide(context, 'DENY action=read resource=file://docs/*');
+
+      expect(judge).not.toHaveBeenCalled();
+      expect(decision.decision).toBe('DENY');
+      expect(decision.metadata).toEqual(expect.objectContaining({ engine: 'RULE', ruleLine: 1 }));
+    });
+
+    it('should fall back to the AI for free-form policies', async () => {
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: true, cacheTTL: 60000 });
+
+      await engine.decide(context, 'DENY action=read resource=file://docs/*\n営業時間外は拒否する');
+
+      expect(judge).toHaveBeenCalledTimes(1);
+    });
+  });
+
+  describe('confidence threshold', () => {
+    const lowConfidence: PolicyDecision = { ...permit, confidence: 0.4 };
+
+    it('should override low-confidence decisions to INDETERMINATE and annotate the reason', async () => {
+      judge.mockResolvedValue(lowConfidence);
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: false, minConfidence: 0.6 });
+
+      const decision = await engine.decide(context, 'policy A');
+
+      expect(decision.decision).toBe('INDETERMINATE');
+      expect(decision.reason).toBe('allowed [confidence 0.4 below minimum 0.6; original decision PERMIT]');
+      expect(decision.metadata).toEqual(expect.objectContaining({ originalDecision: 'PERMIT', minConfidence: 0.6 }));
+    });
+
+    it('should deny low-confidence decisions when failing closed', async () => {
+      judge.mockResolvedValue(lowConfidence);
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: false, minConfidence: 0.6, failClosed: true });
+
+      expect((await engine.decide(context, 'policy A')).decision).toBe('DENY');
+    });
+
+    it('should keep decisions at or above the threshold', async () => {
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: false, minConfidence: 0.9 });
+
+      expect(await engine.decide(context, 'policy A')).toEqual(expect.objectContaining({ decision: 'PERMIT', reason: 'allowed' }));
+    });
+  });
+
+  describe('evaluation retries', () => {
+    const aiError: PolicyDecision = {
+      decision: 'INDETERMINATE',
+      reason: 'AI判定エラー: 503',
+      confidence: 0,
+      metadata: { aiError: true }
+    };
+
+    it('should retry transient failures and return the first successful decision', async () => {
+      judge.mockRejectedValueOnce(new Error('ECONNRESET')).mockResolvedValueOnce(aiError).mockResolvedValueOnce(permit);
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: false, evalRetries: 2, evalRetryBackoffMs: 1 });
+
+      const decision = await engine.decide(context, 'policy A');
+
+      expect(judge).toHaveBeenCalledTimes(3);
+      expect(decision.decision).toBe('PERMIT');
+    });
+
+    it('should fail closed once the retries are exhausted', async () => {
+      judge.mockRejectedValue(new Error('ECONNRESET'));
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: false, evalRetries: 1, evalRetryBackoffMs: 1 });
+
+      const decision = await engine.decide(context, 'policy A');
+
+      expect(judge).toHaveBeenCalledTimes(2);
+      expect(decision).toEqual(expect.objectContaining({ decision: 'DENY', reason: 'Policy evaluation failed' }));
+    });
+
+    it('should not retry by default', async () => {
+      judge.mockResolvedValue(aiError);
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: false });
+
+      expect((await engine.decide(context, 'policy A')).decision).toBe('INDETERMINATE');
+      expect(judge).toHaveBeenCalledTimes(1);
+    });
+
+    it('should not cache decisions from failed AI calls', async () => {
+      judge.mockResolvedValueOnce(aiError).mockResolvedValueOnce(permit);
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: true, cacheTTL: 60000 });
+
+      await engine.decide(context, 'policy A');
+
+      expect((await engine.decide(context, 'policy A')).decision).toBe('PERMIT');
+      expect(judge).toHaveBeenCalledTimes(2);
+    });
+
+    it('should double the backoff per attempt with jitter between half and the full delay', () => {
+      expect(retryDelay(100, 1, () => 0)).toBe(50);
+      expect(retryDelay(100, 1, () => 1)).toBe(100);
+      expect(retryDelay(100, 3, () => 0.5)).toBe(300);
+    });
+  });
+
+  describe('circuit breaker', () => {
+    it('should stop calling the backend after consecutive failures and probe again after the cooldown', async () => {
+      judge.mockRejectedValue(new Error('ECONNREFUSED'));
+      const clock = new FixedTimeProvider();
+      const engine = new AIPolicyEngine(
+        judgmentEngine,
+        { cacheEnabled: false, breakerThreshold: 2, breakerCooldownMs: 20 },
+        { rng: new SeededRng(1), clock }
+      );
+
+      await engine.decide(context, 'policy A');
+      await engine.decide(context, 'policy A');
+      const shortCircuited = await engine.decide(context, 'policy A');
+
+      expect(judge).toHaveBeenCalledTimes(2);
+      expect(shortCircuited).toMatchObject({ decision: 'DENY', metadata: { circuitOpen: true } });
+      expect(engine.getCircuitBreakerState()).toMatchObject({ state: 'open', consecutiveFailures: 2, failureThreshold: 2 });
+
+      clock.advance(30);
+      judge.mockResolvedValue(permit);
+
+      expect((await engine.decide(context, 'policy A')).decision).toBe('PERMIT');
+      expect(engine.getCircuitBreakerState()).toMatchObject({ state: 'closed', consecutiveFailures: 0, openedAt: null });
+    });
+
+    it('should stay closed when disabled', async () => {
+      judge.mockRejectedValue(new Error('ECONNREFUSED'));
+      const engine = new AIPolicyEngine(judgmentEngine, { cacheEnabled: false });
+
+      for (let i = 0; i < 5; i++) {
+        await engine.decide(context, 'policy A');
+      }
+
+      expect(judge).toHaveBeenCalledTimes(5);
+      expect(engine.getCircuitBreakerState().state).toBe('closed');
+    });
+  });
+});
@@ -0,0 +1,57 @@
+// ============================================================================
+// CircuitBreaker Test Suite
+// ============================================================================
+
+import { CircuitBreaker } from '../../policy/circuit-breaker';
+
+describe('CircuitBreaker', () => {
+  it('should open after the threshold and reject until the cooldown passes', () => {
+    const breaker = new CircuitBreaker(3, 1000);
+
+    breaker.recordFailure(0);
+    breaker.recordFailure(0);
+    expect(breaker.tryAcquire(0)).toBe(true);
+    breaker.recordFailure(100);
+
+    expect(breaker.snapshot()).toEqual({
+      state: 'open',
+      consecutiveFailures: 3,
+      failureThreshold: 3,
+      cooldownMs: 1000,
+      openedAt: new Date(100).toISOString()
+    });
+    expect(breaker.tryAcquire(1099)).toBe(false);
+  });
+
+  it('should let a single probe through when half-open', () => {
+    const breaker = new CircuitBreaker(1, 1000);
+    breaker.recordFailure(0);
+
+    expect(breaker.tryAcquire(1000)).toBe(true);
+    expect(breaker.snapshot().state).toBe('half-open');
+    expect(breaker.tryAcquire(1001)).toBe(false);
+
+    breaker.recordFailure(1500);
+    expect(breaker.snapshot()).toMatchObject({ state: 'open', openedAt: new Date(1500).toISOString() });
+
+    expect(breaker.tryAcquire(2500)).toBe(true);
+    breaker.recordSuccess();
+    expect(breaker.snapshot()).toMatchObject({ state: 'closed', consecutiveFailures: 0 });
+    expect(breaker.tryAcquire(2500)).toBe(true);
+  });
+
+  it('should reset the failure count on success and never open when disabled', () => {
+    const breaker = new CircuitBreaker(2, 1000);
+    breaker.recordFailure();
+    breaker.recordSuccess();
+    breaker.recordFailure();
+    expect(breaker.snapshot().state).toBe('closed');
+
+    const disabled = new CircuitBreaker(0, 1000);
+    for (let i = 0; i < 10; i++) {
+      disabled.recordFailure();
+    }
+    expect(disabled.tryAcquire()).toBe(true);
+    expect(disabled.snapshot().state).toBe('closed');
+  });
+});
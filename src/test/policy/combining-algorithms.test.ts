@@ -0,0 +1,39 @@
+// ============================================================================
+// Policy Combining Algorithms Test Suite
+// ============================================================================
+
+import { combineDecisions, isCombiningAlgorithm } from '../../policy/combining-algorithms';
+
+describe('combineDecisions', () => {
+  const permit = { decision: 'PERMIT' as const, reason: 'ok', confidence: 0.9 };
+  const deny = { decision: 'DENY' as const, reason: 'no', confidence: 0.8 };
+  const unknown = { decision: 'INDETERMINATE' as const, reason: 'unclear', confidence: 0.1 };
+
+  it('should let any DENY win under deny_overrides', () => {
+    expect(combineDecisions('deny_overrides', [permit, unknown, deny])).toEqual({
+      decision: 'DENY', reason: 'policy[2]: no', confidence: 0.8, decidingIndex: 2
+    });
+    expect(combineDecisions('deny_overrides', [permit, unknown]).decision).toBe('INDETERMINATE');
+    expect(combineDecisions('deny_overrides', [permit, permit]).decision).toBe('PERMIT');
+  });
+
+  it('should let any PERMIT win under permit_overrides', () => {
+    expect(combineDecisions('permit_overrides', [deny, unknown, permit]).decidingIndex).toBe(2);
+    expect(combineDecisions('permit_overrides', [deny, unknown]).decision).toBe('INDETERMINATE');
+    expect(combineDecisions('permit_overrides', [deny]).decision).toBe('DENY');
+  });
+
+  it('should take the first PERMIT or DENY under first_applicable', () => {
+    expect(combineDecisions('first_applicable', [unknown, deny, permit])).toMatchObject({
+      decision: 'DENY', decidingIndex: 1
+    });
+    expect(combineDecisions('first_applicable', [unknown])).toMatchObject({
+      decision: 'INDETERMINATE', decidingIndex: null
+    });
+  });
+
+  it('should recognize only the supported algorithm names', () => {
+    expect(isCombiningAlgorithm('deny_overrides')).toBe(true);
+    expect(isCombiningAlgorithm('only_one_applicable')).toBe(false);
+  });
+});
@@ -0,0 +1,73 @@
+// ============================================================================
+// INDETERMINATE Resolution Test Suite
+// ============================================================================
+
+import {
+  isIndeterminateResolution,
+  markIndeterminateSource,
+  resolveIndeterminate
+} from '../../policy/indeterminate-resolution';
+import { ConstraintEvaluator, applyConstraintEvaluation } from '../../core/constraints/structured';
+
+describe('resolveIndeterminate', () => {
+  const unknown = { decision: 'INDETERMINATE' as const, reason: 'unclear', confidence: 0.4 };
+  const permit = { decision: 'PERMIT' as const, reason: 'ok', confidence: 0.9 };
+
+  it('should turn INDETERMINATE into DENY under deny', () => {
+    expect(resolveIndeterminate(unknown, 'deny')).toEqual({
+      decision: 'DENY',
+      reason: 'unclear [INDETERMINATE resolved to DENY by indeterminate-resolution=deny]',
+      confidence: 0.4
+    });
+  });
+
+  it('should turn INDETERMINATE into PERMIT under permit', () => {
+    expect(resolveIndeterminate(unknown, 'permit')).toMatchObject({
+      decision: 'PERMIT',
+      reason: 'unclear [INDETERMINATE resolved to PERMIT by indeterminate-resolution=permit]'
+    });
+  });
+
+  it('should keep an unmet-constraint downgrade denied under permit', () => {
+    const constrained = { ...permit, constraints: ['time_window:09:00-17:00'] };
+    const context = {
+      agent: 'claude',
+      action: 'read',
+      resource: 'file://a.txt',
+      time: new Date(2024, 0, 15, 20),
+      environment: {}
+    };
+    const downgraded = applyConstraintEvaluation(
+      constrained,
+      new ConstraintEvaluator().evaluateDecision(constrained, context)
+    );
+
+    const result = resolveIndeterminate(downgraded, 'permit');
+
+    expect(downgraded.decision).toBe('INDETERMINATE');
+    expect(result.decision).toBe('DENY');
+    expect(result.reason).toContain('INDETERMINATE (constraint) resolved to DENY');
+  });
+
+  it('should deny every tagged INDETERMINATE under permit', () => {
+    for (const source of ['parse-error', 'low-confidence', 'clarification', 'no-match'] as const) {
+      expect(resolveIndeterminate(markIndeterminateSource(unknown, source), 'permit').decision).toBe('DENY');
+    }
+  });
+
+  it('should return the decision unchanged under passthrough', () => {
+    expect(resolveIndeterminate(unknown, 'passthrough')).toBe(unknown);
+  });
+
+  it('should never change concrete decisions', () => {
+    expect(resolveIndeterminate(permit, 'deny')).toBe(permit);
+  });
+});
+
+describe('isIndeterminateResolution', () => {
+  it('should accept only the supported modes', () => {
+    expect(['permit', 'deny', 'passthrough'].every(isIndeterminateResolution)).toBe(true);
+    expect(isIndeterminateResolution('allow')).toBe(false);
+    expect(isIndeterminateResolution(undefined)).toBe(false);
+  });
+});
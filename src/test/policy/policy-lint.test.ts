@@ -0,0 +1,67 @@
+// ============================================================================
+// Policy Lint Test Suite
+// ============================================================================
+
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { DEFAULT_LINT_RULES, lintPolicy, loadLintRules } from '../../policy/policy-lint';
+
+describe('policy lint', () => {
+  it('should report ambiguity markers with line and column', () => {
+    const findings = lintPolicy('営業時間内のみ許可\n必要に応じて管理者に通知\nAccess is SOMETIMES allowed');
+
+    expect(findings).toEqual([
+      { line: 2, column: 1, message: 'Ambiguous wording "必要に応じて" may make decisions inconsistent', severity: 'warning' },
+      { line: 3, column: 11, message: 'Ambiguous wording "sometimes" may make decisions inconsistent', severity: 'warning' }
+    ]);
+  });
+
+  it('should match English markers only as whole words', () => {
+    const findings = lintPolicy('Unreasonable requests are denied\nUsually, reasonable limits apply\nreasonableness is not a marker');
+
+    expect(findings.map(finding => [finding.line, finding.column])).toEqual([[2, 1], [2, 10]]);
+  });
+
+  it('should report roles that are not defined in the rules', () => {
+    const findings = lintPolicy('承認者の確認後に許可\nrole:auditor のみ閲覧可\n管理者は常に許可');
+
+    expect(findings.map(finding => [finding.line, finding.column, finding.message])).toEqual([
+      [1, 1, 'Role "承認者" is not defined in the lint rules'],
+      [2, 6, 'Role "auditor" is not defined in the lint rules']
+    ]);
+    expect(findings.every(finding => finding.severity === 'info')).toBe(true);
+  });
+
+  it('should extend the default rules from a rules file', () => {
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'aegis-lint-'));
+    const rulesPath = path.join(dir, 'rules.json');
+    fs.writeFileSync(rulesPath, JSON.stringify({
+      ambiguityMarkers: [{ term: 'ASAP', message: 'Specify a deadline', severity: 'error' }],
+      roles: ['承認者']
+    }));
+
+    try {
+      const rules = loadLintRules(rulesPath);
+
+      expect(rules.ambiguityMarkers).toHaveLength(DEFAULT_LINT_RULES.ambiguityMarkers.length + 1);
+      expect(lintPolicy('承認者が asap で確認', rules)).toEqual([
+        { line: 1, column: 6, message: 'Specify a deadline', severity: 'error' }
+      ]);
+    } finally {
+      fs.rmSync(dir, { recursive: true, force: true });
+    }
+  });
+
+  it('should reject malformed rules files', () => {
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'aegis-lint-'));
+    const rulesPath = path.join(dir, 'rules.json');
+    fs.writeFileSync(rulesPath, JSON.stringify({ ambiguityMarkers: ['適宜'] }));
+
+    try {
+      expect(() => loadLintRules(rulesPath)).toThrow('ambiguityMarkers must be an array of { term } objects');
+    } finally {
+      fs.rmSync(dir, { recursive: true, force: true });
+    }
+  });
+});
@@ -0,0 +1,50 @@
+// ============================================================================
+// Policy Versions Test Suite
+// ============================================================================
+
+import { PolicyVersionStore, unifiedDiff } from '../../policy/policy-versions';
+
+describe('PolicyVersionStore', () => {
+  it('should add a version only when the content hash changes', () => {
+    const store = new PolicyVersionStore();
+    const first = store.record('p', 'a', new Date('2026-01-01T00:00:00Z'));
+    const same = store.record('p', 'a');
+    const second = store.record('p', 'b');
+
+    expect(same).toBe(first);
+    expect(first).toMatchObject({ version: 1, loadedAt: '2026-01-01T00:00:00.000Z' });
+    expect(first.hash).toMatch(/^[0-9a-f]{64}$/);
+    expect(second.version).toBe(2);
+    expect(store.get('p', 2)?.content).toBe('b');
+    expect(store.list('other')).toEqual([]);
+  });
+});
+
+describe('unifiedDiff', () => {
+  it('should return an empty string for identical texts', () => {
+    expect(unifiedDiff('a\nb\n', 'a\nb\n', 'old', 'new')).toBe('');
+  });
+
+  it('should emit separate hunks with context lines', () => {
+    const before = ['1', '2', '3', '4', '5', '6', '7', '8', '9', '10'].join('\n');
+    const after = ['1', 'two', '3', '4', '5', '6', '7', '8', '9', '10', '11'].join('\n');
+
+    expect(unifiedDiff(before, after, 'p@1', 'p@2', 1).split('\n')).toEqual([
+      '--- p@1',
+      '+++ p@2',
+      '@@ -1,3 +1,3 @@',
+      ' 1',
+      '-2',
+      '+two',
+      ' 3',
+      '@@ -10 +10,2 @@',
+      ' 10',
+      '+11',
+      ''
+    ]);
+  });
+
+  it('should describe an empty side with a zero-length range', () => {
+    expect(unifiedDiff('', 'new line', 'p@1', 'p@2')).toBe('--- p@1\n+++ p@2\n@@ -0,0 +1 @@\n+new line\n');
+  });
+});
@@ -0,0 +1,37 @@
+// ============================================================================
+// Resource Canonicalization Test Suite
+// ============================================================================
+
+import { canonicalizeResource } from '../../policy/resource-canonicalization';
+
+describe('canonicalizeResource', () => {
+  it('should collapse dot segments, repeated slashes and trailing slashes', () => {
+    expect(canonicalizeResource('/prod/../prod/secrets')).toBe('/prod/secrets');
+    expect(canonicalizeResource('/prod/secrets/.')).toBe('/prod/secrets');
+    expect(canonicalizeResource('/prod//secrets/')).toBe('/prod/secrets');
+    expect(canonicalizeResource('/../etc/passwd')).toBe('/etc/passwd');
+  });
+
+  it('should normalize backslash separators', () => {
+    expect(canonicalizeResource('\\prod\\logs\\..\\secrets')).toBe('/prod/secrets');
+  });
+
+  it('should canonicalize only the path of URIs', () => {
+    expect(canonicalizeResource('file:///prod/../prod/secrets')).toBe('file:///prod/secrets');
+    expect(canonicalizeResource('https://api.example.com/v1/./users/?next=../admin')).toBe('https://api.example.com/v1/users?next=../admin');
+    expect(canonicalizeResource('https://api.example.com')).toBe('https://api.example.com');
+  });
+
+  it('should decode percent-encoded dots and separators in the path before normalizing', () => {
+    expect(canonicalizeResource('file:///prod/%2e%2e/prod/secrets')).toBe('file:///prod/secrets');
+    expect(canonicalizeResource('/prod/%2E%2E%2Fprod%5Csecrets/%2e')).toBe('/prod/secrets');
+    expect(canonicalizeResource('..%2fprod%2fsecrets')).toBe('../prod/secrets');
+    expect(canonicalizeResource('https://api.example.com/v1/%2e%2e/admin?next=%2e%2e')).toBe('https://api.example.com/admin?next=%2e%2e');
+    expect(canonicalizeResource('/files/report%20final.pdf')).toBe('/files/report%20final.pdf');
+  });
+
+  it('should leave resources that are not paths unchanged', () => {
+    expect(canonicalizeResource('read_file')).toBe('read_file');
+    expect(canonicalizeResource('customer-db')).toBe('customer-db');
+  });
+});
@@ -0,0 +1,50 @@
+// ============================================================================
+// Rule Policy Test Suite
+// ============================================================================
+
+import { parseRulePolicy, evaluateRules } from '../../policy/rule-policy';
+import type { DecisionContext } from '../../types';
+
+describe('rule policy', () => {
+  const context = (overrides: Partial<DecisionContext> = {}): DecisionContext => ({
+    agent: 'claude',
+    action: 'delete',
+    resource: '/prod/db/users',
+    time: new Date(),
+    environment: {},
+    ...overrides
+  });
+
+  it('should parse rules and ignore blank lines and comments', () => {
+    const rules = parseRulePolicy('# 本番保護\n\nDENY action=delete,drop resource=/prod/**\nPERMIT agent=*');
+
+    expect(rules).toEqual([
+      { line: 3, effect: 'DENY', conditions: { action: ['delete', 'drop'], resource: ['/prod/**'] }, text: 'DENY action=delete,drop resource=/prod/**' },
+      { line: 4, effect: 'PERMIT', conditions: { agent: ['*'] }, text: 'PERMIT agent=*' }
+    ]);
+  });
+
+  it('should return null for free-form or empty policies', () => {
+    expect(parseRulePolicy('DENY action=delete\n本番環境の削除は禁止')).toBeNull();
+    expect(parseRulePolicy('DENY owner=me')).toBeNull();
+    expect(parseRulePolicy('# comment only\n')).toBeNull();
+  });
+
+  it('should apply the first matching rule', () => {
+    const rules = parseRulePolicy('DENY action=delete resource=/prod/**\nPERMIT agent=claude,gpt')!;
+
+    expect(evaluateRules(rules, context())).toEqual(expect.objectContaining({ decision: 'DENY', confidence: 1.0 }));
+    expect(evaluateRules(rules, context({ action: 'read' })).decision).toBe('PERMIT');
+    expect(evaluateRules(rules, context({ action: 'read', agent: 'other' })).decision).toBe('INDETERMINATE');
+  });
+
+  it('should match single-segment wildcards without crossing slashes', () => {
+    const rules = parseRulePolicy('DENY resource=/prod/*.txt\nDENY resource=file://docs/?.md')!;
+
+    expect(evaluateRules(rules, context({ resource: '/prod/a.txt' })).decision).toBe('DENY');
+    expect(evaluateRules(rules, context({ resource: '/prod/nested/a.txt' })).decision).toBe('INDETERMINATE');
+    expect(evaluateRules(rules, context({ resource: '/prod/aXtxt' })).decision).toBe('INDETERMINATE');
+    expect(evaluateRules(rules, context({ resource: 'file://docs/a.md' })).decision).toBe('DENY');
+    expect(evaluateRules(rules, context({ resource: 'file://docs//.md' })).decision).toBe('INDETERMINATE');
+  });
+});
@@ -0,0 +1,99 @@
+// ============================================================================
+// Structured Policy Test Suite
+// ============================================================================
+
+import {
+  availablePolicyFormats,
+  evaluateCedarPolicy,
+  evaluateJsonPolicy,
+  parseJsonPolicy
+} from '../../policy/structured-policy';
+import type { DecisionContext } from '../../types';
+
+const mockIsAuthorized = jest.fn();
+jest.mock('@cedar-policy/cedar-wasm/nodejs', () => ({ isAuthorized: mockIsAuthorized }), { virtual: true });
+
+const context = (overrides: Partial<DecisionContext> = {}): DecisionContext => ({
+  agent: 'claude',
+  action: 'delete',
+  resource: '/prod/db/users',
+  time: new Date(),
+  environment: {},
+  ...overrides
+});
+
+describe('JSON policy', () => {
+  it('should read rules from an object or a bare array', () => {
+    const rules = parseJsonPolicy('{"rules": [{"effect": "deny", "action": ["delete", "drop"], "resource": "/prod/**"}]}');
+
+    expect(rules).toEqual([{
+      line: 1,
+      effect: 'DENY',
+      conditions: { action: ['delete', 'drop'], resource: ['/prod/**'] },
+      text: '{"effect":"deny","action":["delete","drop"],"resource":"/prod/**"}'
+    }]);
+    expect(parseJsonPolicy('[{"effect": "PERMIT", "agent": "*"}]')[0].conditions).toEqual({ agent: ['*'] });
+  });
+
+  it('should apply the first matching rule', () => {
+    const rules = parseJsonPolicy('[{"effect": "deny", "action": "delete", "resource": "/prod/**"}, {"effect": "permit", "agent": ["claude"]}]');
+
+    expect(evaluateJsonPolicy(rules, context())).toEqual(expect.objectContaining({
+      decision: 'DENY',
+      confidence: 1.0,
+      metadata: { engine: 'JSON', ruleIndex: 0 }
+    }));
+    expect(evaluateJsonPolicy(rules, context({ action: 'read' })).decision).toBe('PERMIT');
+    expect(evaluateJsonPolicy(rules, context({ action: 'read', agent: 'other' })).decision).toBe('INDETERMINATE');
+  });
+
+  it('should reject malformed policies with the rule number', () => {
+    expect(() => parseJsonPolicy('本番環境の削除は禁止')).toThrow('Invalid JSON policy');
+    expect(() => parseJsonPolicy('{"rules": []}')).toThrow('JSON policy must be a non-empty array of rules');
+    expect(() => parseJsonPolicy('[{"effect": "permit"}, {"effect": "deny", "owner": "me"}]'))
+      .toThrow('JSON policy rule 2: unknown field "owner" (use agent, action, resource)');
+    expect(() => parseJsonPolicy('[{"effect": "deny", "action": []}]'))
+      .toThrow('JSON policy rule 1: action must be a string or a non-empty array of strings');
+  });
+});
+
+describe('Cedar policy', () => {
+  beforeEach(() => {
+    mockIsAuthorized.mockReset();
+  });
+
+  it('should be available when the Cedar package can be loaded', async () => {
+    expect(await availablePolicyFormats()).toEqual(['natural', 'json', 'cedar']);
+  });
+
+  it('should map the request to Cedar entities and the answer to a decision', async () => {
+    mockIsAuthorized.mockReturnValue({
+      type: 'success',
+      response: { decision: 'deny', diagnostics: { reason: ['policy1'], errors: [] } }
+    });
+    const policy = 'forbid(principal, action == Action::"delete", resource);';
+
+    const decision = await evaluateCedarPolicy(policy, context({ purpose: 'cleanup' }));
+
+    expect(mockIsAuthorized).toHaveBeenCalledWith({
+      principal: { type: 'Agent', id: 'claude' },
+      action: { type: 'Action', id: 'delete' },
+      resource: { type: 'Resource', id: '/prod/db/users' },
+      context: { purpose: 'cleanup' },
+      policies: { staticPolicies: policy },
+      entities: []
+    });
+    expect(decision).toEqual(expect.objectContaining({ decision: 'DENY', reason: 'Forbidden by Cedar policy policy1', confidence: 1.0 }));
+  });
+
+  it('should deny by default and fail on Cedar errors', async () => {
+    mockIsAuthorized.mockReturnValueOnce({
+      type: 'success',
+      response: { decision: 'deny', diagnostics: { reason: [], errors: [] } }
+    });
+    mockIsAuthorized.mockReturnValueOnce({ type: 'failure', errors: [{ message: 'unexpected token' }] });
+
+    expect((await evaluateCedarPolicy('', context())).reason).toBe('No Cedar policy permitted the request (default deny)');
+    await expect(evaluateCedarPolicy('permit(', context())).rejects.toThrow('Cedar evaluation failed: unexpected token');
+  });
+});
@@ -1,5 +1,6 @@
 import { Config } from '../utils/config';
 import dotenv from 'dotenv';
+import packageJson from '../../package.json';
 
 // dotenvをモック
 jest.mock('dotenv');
@@ -151,13 +152,38 @@ describe('Config', () => {
   });
 
   describe('MCPプロキシ設定', () => {
+    it('serverInfoの既定のバージョンはpackage.jsonのバージョンになる', () => {
+      expect(new Config().mcpProxy.serverVersion).toBe(packageJson.version);
+    });
+
     it('デフォルトのMCPプロキシ設定を使用する', () => {
       const config = new Config();
 
       expect(config.mcpProxy).toEqual({
         port: 3000,
         upstreamServers: {},
-        corsOrigins: ['http://localhost:3000']
+        corsOrigins: ['http://localhost:3000'],
+        serverName: 'aegis-proxy',
+        serverVersion: '1.0.0',
+        maxRequestBytes: 1048576,
+        maxSimulateBatch: 50,
+        agentRateLimitPerMinute: 0,
+        gzipMinBytes: 1024,
+        framing: 'newline',
+        dedupWindow: 100,
+        evalTimeoutMs: 20000,
+        evalRetries: 0,
+        evalRetryBackoffMs: 200,
+        maxResponseBytes: 0,
+        allowHttpForcePermit: false,
+        evalBreakerThreshold: 0,
+        evalBreakerCooldownMs: 30000,
+        requireAgent: false,
+        strictProtocol: false,
+        canonicalizeResources: false,
+        strictPolicyRefs: false,
+        slowRequestMs: 0,
+        toolTimeoutMs: 30000
       });
     });
 
@@ -174,10 +200,41 @@ describe('Config', () => {
           server1: 'http://server1.com',
           server2: 'http://server2.com:8080'
         },
-        corsOrigins: ['http://localhost:3000', 'http://localhost:3001']
+        corsOrigins: ['http://localhost:3000', 'http://localhost:3001'],
+        serverName: 'aegis-proxy',
+        serverVersion: '1.0.0',
+        maxRequestBytes: 1048576,
+        maxSimulateBatch: 50,
+        agentRateLimitPerMinute: 0,
+        gzipMinBytes: 1024,
+        framing: 'newline',
+        dedupWindow: 100,
+        evalTimeoutMs: 20000,
+        evalRetries: 0,
+        evalRetryBackoffMs: 200,
+        maxResponseBytes: 0,
+        allowHttpForcePermit: false,
+        evalBreakerThreshold: 0,
+        evalBreakerCooldownMs: 30000,
+        requireAgent: false,
+        strictProtocol: false,
+        canonicalizeResources: false,
+        strictPolicyRefs: false,
+        slowRequestMs: 0,
+        toolTimeoutMs: 30000
       });
     });
 
+    it('環境変数からサーバー名とバージョンを読み込む', () => {
+      process.env.AEGIS_SERVER_NAME = 'acme-guard';
+      process.env.AEGIS_SERVER_VERSION = '2.3.4';
+
+      const config = new Config();
+
+      expect(config.mcpProxy.serverName).toBe('acme-guard');
+      expect(config.mcpProxy.serverVersion).toBe('2.3.4');
+    });
+
     it('MCP_PROXY_PORTも読み込む', () => {
       process.env.MCP_PROXY_PORT = '5000';
 
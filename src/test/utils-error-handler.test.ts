@@ -606,6 +606,7 @@ describe('ErrorHandler', () => {
 
     it('エラーコードを適切にマッピングする', () => {
       const testCases = [
+        { code: 'PARSE_ERROR', expectedCode: -32700 },
         { code: 'INVALID_REQUEST', expectedCode: -32600 },
         { code: 'METHOD_NOT_FOUND', expectedCode: -32601 },
         { code: 'INVALID_PARAMS', expectedCode: -32602 },
@@ -0,0 +1,119 @@
+// ============================================================================
+// Config File Test Suite
+// ============================================================================
+
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { configFileFormat, loadConfigFile, parseConfigFile } from '../../utils/config-file';
+
+const allowed = new Set(['transport', 'policy-dir', 'cache-size', 'cache-ttl', 'audit-log', 'require-agent', 'watch', 'enable-tool', 'min-confidence']);
+
+describe('parseConfigFile', () => {
+  it('should read TOML keys, tables and arrays as CLI option values', () => {
+    const text = [
+      '# AEGIS',
+      'transport = "stdio"',
+      "policy-dir = './policies'  # literal string",
+      'require-agent = true',
+      'watch = false',
+      'enable-tool = ["stats", "server_info"]',
+      '',
+      '[cache]',
+      'size = 100',
+      'ttl = 60',
+      '',
+      '[audit]',
+      'log = "logs/decisions#1.jsonl"'
+    ].join('\n');
+
+    expect(parseConfigFile(text, 'toml', allowed)).toEqual({
+      transport: 'stdio',
+      'policy-dir': './policies',
+      'require-agent': 'true',
+      'enable-tool': 'stats,server_info',
+      'cache-size': '100',
+      'cache-ttl': '60',
+      'audit-log': 'logs/decisions#1.jsonl'
+    });
+  });
+
+  it('should read YAML keys, nested mappings and block lists', () => {
+    const text = [
+      'transport: stdio',
+      'cache:',
+      '  size: 100',
+      'enable-tool:',
+      '  - stats',
+      '  - "server_info"',
+      'min-confidence: 0.8'
+    ].join('\n');
+
+    expect(parseConfigFile(text, 'yaml', allowed)).toEqual({
+      transport: 'stdio',
+      'cache-size': '100',
+      'enable-tool': 'stats,server_info',
+      'min-confidence': '0.8'
+    });
+  });
+
+  it('should read YAML flow sequences, quoted strings with # and multi-line TOML arrays', () => {
+    expect(parseConfigFile('enable-tool: [stats, "server#info"]\naudit-log: \'logs/a #1.jsonl\'', 'yaml', allowed)).toEqual({
+      'enable-tool': 'stats,server#info',
+      'audit-log': 'logs/a #1.jsonl'
+    });
+    expect(parseConfigFile('enable-tool = [\n  "stats",\n  "server_info",\n]', 'toml', allowed)).toEqual({
+      'enable-tool': 'stats,server_info'
+    });
+  });
+
+  it('should reject unknown keys with a suggestion', () => {
+    expect(() => parseConfigFile('transport = "http"\npolcy-dir = "./policies"', 'toml', allowed, 'aegis.toml'))
+      .toThrow('aegis.toml: Unknown option "polcy-dir" (did you mean "policy-dir"?)');
+    expect(() => parseConfigFile('cache:\n  sise: 10', 'yaml', allowed, 'aegis.yaml'))
+      .toThrow('aegis.yaml: Unknown option "cache-sise" (did you mean "cache-size"?)');
+  });
+
+  it('should report syntax errors with the line number', () => {
+    expect(() => parseConfigFile('transport = "stdio"\npolicy-dir = ./policies', 'toml', allowed)).toThrow(/^config:2: /);
+    expect(() => parseConfigFile('transport = "stdio"\ntransport = "http"', 'toml', allowed)).toThrow(/^config:2: /);
+    expect(() => parseConfigFile('transport: stdio\ntransport: http', 'yaml', allowed)).toThrow(/^config:2: /);
+  });
+
+  it('should reject duplicate options after flattening and unsupported values', () => {
+    expect(() => parseConfigFile('cache-size = 10\n[cache]\nsize = 20', 'toml', allowed)).toThrow('config: Duplicate option "cache-size"');
+    expect(() => parseConfigFile('enable-tool = [true]', 'toml', allowed)).toThrow('config: Arrays may only contain strings and numbers: enable-tool');
+    expect(() => parseConfigFile('watch:', 'yaml', allowed)).toThrow('config: Value must be a string, number, boolean or array: watch');
+  });
+});
+
+describe('loadConfigFile', () => {
+  let dir: string;
+
+  beforeEach(() => {
+    dir = fs.mkdtempSync(path.join(os.tmpdir(), 'aegis-config-'));
+  });
+
+  afterEach(() => {
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('should resolve relative path options against the config file directory', () => {
+    const file = path.join(dir, 'aegis.yaml');
+    fs.writeFileSync(file, 'policy-dir: ./policies\naudit-log: /var/log/aegis.jsonl\ntransport: stdio\n');
+
+    expect(loadConfigFile(file, allowed, new Set(['policy-dir', 'audit-log']))).toEqual({
+      'policy-dir': path.join(dir, 'policies'),
+      'audit-log': '/var/log/aegis.jsonl',
+      transport: 'stdio'
+    });
+  });
+});
+
+describe('configFileFormat', () => {
+  it('should pick the format from the extension', () => {
+    expect(configFileFormat('aegis.toml')).toBe('toml');
+    expect(configFileFormat('/etc/aegis/config.YML')).toBe('yaml');
+    expect(() => configFileFormat('aegis.json')).toThrow('Config file must be .toml, .yaml or .yml: aegis.json');
+  });
+});
@@ -0,0 +1,34 @@
+// ============================================================================
+// Config Summary Test Suite
+// ============================================================================
+
+import { formatConfigSummary } from '../../utils/config-summary';
+
+describe('formatConfigSummary', () => {
+  it('should list what the server would run', () => {
+    expect(formatConfigSummary({
+      transport: 'http',
+      port: 3000,
+      ai: { provider: 'anthropic', model: 'claude-opus-4-20250514' },
+      policies: ['default-policy', 'prod-readonly'],
+      upstreamServers: ['filesystem'],
+      files: [{ label: 'Policy directory', path: './policies' }, { label: 'Audit log', path: '/var/log/aegis.jsonl' }]
+    })).toBe([
+      'Configuration OK (http transport, port 3000)',
+      '  AI judgment: anthropic claude-opus-4-20250514',
+      '  Policies (2): default-policy, prod-readonly',
+      '  Upstream servers (1): filesystem',
+      '  Policy directory: ./policies',
+      '  Audit log: /var/log/aegis.jsonl',
+      ''
+    ].join('\n'));
+  });
+
+  it('should show a disabled AI engine and empty lists', () => {
+    const summary = formatConfigSummary({ transport: 'stdio', ai: null, policies: [], upstreamServers: [], files: [] });
+
+    expect(summary).toContain('Configuration OK (stdio transport)');
+    expect(summary).toContain('AI judgment: disabled (no API key)');
+    expect(summary).toContain('Upstream servers (0): none');
+  });
+});
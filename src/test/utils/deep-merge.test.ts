@@ -0,0 +1,47 @@
+// ============================================================================
+// deepMerge Test Suite
+// ============================================================================
+
+import { deepMerge } from '../../utils/deep-merge';
+
+describe('deepMerge', () => {
+  it('should merge nested objects with override values winning on conflict', () => {
+    const defaults = {
+      environment: { tenant: 'acme', region: 'ap-northeast-1', network: { zone: 'internal', vpn: false } },
+      clearanceLevel: 'standard'
+    };
+    const request = {
+      agent: 'claude',
+      environment: { transport: 'stdio', network: { vpn: true } }
+    };
+
+    expect(deepMerge(defaults, request)).toEqual({
+      agent: 'claude',
+      clearanceLevel: 'standard',
+      environment: {
+        tenant: 'acme',
+        region: 'ap-northeast-1',
+        transport: 'stdio',
+        network: { zone: 'internal', vpn: true }
+      }
+    });
+  });
+
+  it('should replace arrays and non-plain objects instead of merging them', () => {
+    const time = new Date('2024-01-01T00:00:00Z');
+
+    const merged = deepMerge({ tags: ['a', 'b'], time: { hour: 9 } }, { tags: ['c'], time });
+
+    expect(merged.tags).toEqual(['c']);
+    expect(merged.time).toBe(time);
+  });
+
+  it('should keep default values for undefined overrides and not mutate inputs', () => {
+    const defaults = { environment: { tenant: 'acme' } };
+
+    const merged = deepMerge(defaults, { purpose: undefined, environment: { tenant: undefined } });
+
+    expect(merged).toEqual({ environment: { tenant: 'acme' } });
+    expect(defaults).toEqual({ environment: { tenant: 'acme' } });
+  });
+});
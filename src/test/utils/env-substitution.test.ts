@@ -0,0 +1,25 @@
+// ============================================================================
+// Environment Variable Substitution Test Suite
+// ============================================================================
+
+import { substituteEnvVariables } from '../../utils/env-substitution';
+
+describe('substituteEnvVariables', () => {
+  const env = { ALLOWED_HOST: 'api.example.com', EMPTY: '' };
+
+  it('should replace defined variables', () => {
+    expect(substituteEnvVariables('${ALLOWED_HOST} へのアクセスのみ許可。${EMPTY}末尾', env))
+      .toBe('api.example.com へのアクセスのみ許可。末尾');
+  });
+
+  it('should leave undefined variables in place unless strict', () => {
+    expect(substituteEnvVariables('host: ${MISSING_HOST}', env)).toBe('host: ${MISSING_HOST}');
+    expect(() => substituteEnvVariables('${MISSING_HOST} ${MISSING_PORT} ${ALLOWED_HOST}', env, { strict: true }))
+      .toThrow('Undefined environment variable(s): MISSING_HOST, MISSING_PORT');
+  });
+
+  it('should keep escaped placeholders literal even in strict mode', () => {
+    expect(substituteEnvVariables('$${ALLOWED_HOST} と $${MISSING_HOST}', env, { strict: true }))
+      .toBe('${ALLOWED_HOST} と ${MISSING_HOST}');
+  });
+});
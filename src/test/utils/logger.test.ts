@@ -0,0 +1,32 @@
+// ============================================================================
+// Logger Test Suite
+// ============================================================================
+
+import { formatConsoleLine, isLogFormat } from '../../utils/logger';
+
+describe('logger console formats', () => {
+  const info = {
+    level: 'info',
+    message: 'Policy loaded',
+    timestamp: '2024-01-15 10:00:00',
+    service: 'aegis',
+    requestId: 7
+  };
+
+  it('should emit one JSON object per line for json', () => {
+    const line = formatConsoleLine({ ...info }, 'json');
+
+    expect(line).not.toContain('\n');
+    expect(JSON.parse(line)).toEqual(info);
+  });
+
+  it('should emit an uncolored single line for compact', () => {
+    expect(formatConsoleLine({ ...info }, 'compact'))
+      .toBe('2024-01-15 10:00:00 [aegis] info: Policy loaded {"requestId":7}');
+  });
+
+  it('should only accept the supported format names', () => {
+    expect(['pretty', 'compact', 'json'].every(isLogFormat)).toBe(true);
+    expect(isLogFormat('text')).toBe(false);
+  });
+});
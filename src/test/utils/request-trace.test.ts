@@ -0,0 +1,46 @@
+// ============================================================================
+// Request Trace Test Suite
+// ============================================================================
+
+import { createRequestTrace, getRequestTrace, resolveHttpRequestId, runWithRequestTrace } from '../../utils/request-trace';
+
+describe('request trace', () => {
+  it('should use the JSON-RPC id and method of requests', () => {
+    expect(createRequestTrace({ id: 7, method: 'tools/call' })).toEqual({ requestId: 7, method: 'tools/call' });
+  });
+
+  it('should assign a synthetic trace id to notifications', () => {
+    const first = createRequestTrace({ method: 'notifications/initialized' });
+    const second = createRequestTrace({ method: 'notifications/initialized' });
+
+    expect(first.requestId).toBeUndefined();
+    expect(first.traceId).toMatch(/^[0-9a-f-]{36}$/);
+    expect(first.traceId).not.toBe(second.traceId);
+  });
+
+  it('should carry the HTTP request id when given', () => {
+    expect(createRequestTrace({ id: 7, method: 'tools/call' }, 'gw-123')).toEqual({
+      requestId: 7,
+      method: 'tools/call',
+      httpRequestId: 'gw-123'
+    });
+  });
+
+  it('should keep a well-formed X-Request-Id and generate one otherwise', () => {
+    expect(resolveHttpRequestId('gw-123')).toBe('gw-123');
+    expect(resolveHttpRequestId(['gw-1', 'gw-2'])).toBe('gw-1');
+    expect(resolveHttpRequestId(undefined)).toMatch(/^[0-9a-f-]{36}$/);
+    expect(resolveHttpRequestId('has space')).toMatch(/^[0-9a-f-]{36}$/);
+    expect(resolveHttpRequestId('x'.repeat(129))).toMatch(/^[0-9a-f-]{36}$/);
+  });
+
+  it('should expose the trace across awaits and keep concurrent traces apart', async () => {
+    const observe = (id: number) => runWithRequestTrace({ requestId: id, method: 'ping' }, async () => {
+      await new Promise(resolve => setTimeout(resolve, 5 - id));
+      return getRequestTrace()?.requestId;
+    });
+
+    await expect(Promise.all([observe(1), observe(2)])).resolves.toEqual([1, 2]);
+    expect(getRequestTrace()).toBeUndefined();
+  });
+});
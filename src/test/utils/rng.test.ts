@@ -0,0 +1,47 @@
+// ============================================================================
+// Rng Test Suite
+// ============================================================================
+
+import { SeededRng, SystemRng } from '../../utils/rng';
+import { createRequestTrace, resolveHttpRequestId } from '../../utils/request-trace';
+
+const UUID_V4 = /^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$/;
+
+describe('SeededRng', () => {
+  it('should repeat the same sequence for the same seed', () => {
+    const a = new SeededRng(42);
+    const b = new SeededRng(42);
+    const sequence = [a.next(), a.next(), a.next()];
+
+    expect([b.next(), b.next(), b.next()]).toEqual(sequence);
+    expect(new SeededRng(7).next()).not.toBe(sequence[0]);
+    for (const value of sequence) {
+      expect(value).toBeGreaterThanOrEqual(0);
+      expect(value).toBeLessThan(1);
+    }
+  });
+
+  it('should generate reproducible UUID v4 ids', () => {
+    const id = new SeededRng(42).uuid();
+
+    expect(id).toMatch(UUID_V4);
+    expect(new SeededRng(42).uuid()).toBe(id);
+  });
+
+  it('should make synthetic trace and HTTP request ids deterministic', () => {
+    const rng = new SeededRng(1);
+    const expected = new SeededRng(1);
+
+    expect(createRequestTrace({ method: 'notifications/initialized' }, undefined, () => rng.uuid()).traceId).toBe(expected.uuid());
+    expect(resolveHttpRequestId(undefined, () => rng.uuid())).toBe(expected.uuid());
+  });
+});
+
+describe('SystemRng', () => {
+  it('should generate random UUID v4 ids', () => {
+    const rng = new SystemRng();
+
+    expect(rng.uuid()).toMatch(UUID_V4);
+    expect(rng.uuid()).not.toBe(rng.uuid());
+  });
+});
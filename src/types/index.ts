@@ -43,6 +43,15 @@ export interface DecisionContext {
   ipAddress?: string;
   sessionId?: string;
   
+  // 同義語を正規化する前のaction（--action-synonyms、表記を変えた場合のみ）
+  originalAction?: string;
+
+  // 正規化する前のresource（--canonicalize-resources、表記を変えた場合のみ）
+  originalResource?: string;
+
+  // 判定理由（reason）を返す言語（BCP 47、未指定ならプロンプトの言語のまま）
+  locale?: string;
+
   // その他
   emergency?: boolean;
   delegationChain?: string[];
@@ -61,9 +70,25 @@ export interface PolicyDecision {
   obligations?: string[];
   monitoringRequirements?: string[];
   validityPeriod?: string;
+  // 判定に追加情報が必要な場合に要求者へ尋ねる質問（needs_clarification）
+  needsClarification?: string[];
   metadata?: Record<string, string | number | boolean | null>;
 }
 
+// ポリシー条項ごとの判定根拠
+export interface PolicyClauseExplanation {
+  text: string;
+  relevance: "HIGH" | "MEDIUM" | "LOW" | "NONE";  // NONEは適用されなかった条項
+  effect: "PERMIT" | "DENY" | "NEUTRAL";
+}
+
+export interface PolicyExplanation {
+  decision: PolicyDecision["decision"];
+  reason: string;
+  confidence: number;
+  clauses: PolicyClauseExplanation[];
+}
+
 // ============================================================================
 // アクセス制御結果
 // ============================================================================
@@ -215,6 +240,48 @@ export interface MCPProxyConfig {
   port: number;
   upstreamServers: Record<string, string>;
   corsOrigins?: string[];
+  // initializeで通知するserverInfo（埋め込み時のリブランド用）
+  serverName?: string;
+  serverVersion?: string;
+  maxRequestBytes?: number;
+  // 応答の最大バイト数（超えた応答はテキストを切り詰める、0は無制限）
+  maxResponseBytes?: number;
+  // stdioのメッセージ区切り（newline: 改行区切り、lsp: Content-Lengthヘッダー）
+  framing?: 'newline' | 'lsp';
+  // 想定外のトップレベルのフィールドを持つJSON-RPCメッセージを -32600 で拒否する（既定では取り除いて処理する）
+  strictProtocol?: boolean;
+  // パス形式のresourceの . と .. を畳み込み、区切り文字をそろえてから判定する
+  canonicalizeResources?: boolean;
+  // IDに見える未登録のポリシー参照と、policy と policy_id の食い違いを -32602 で拒否する（既定では本文として扱う）
+  strictPolicyRefs?: boolean;
+  // HTTPレスポンスをgzip圧縮する最小バイト数
+  gzipMinBytes?: number;
+  maxSimulateBatch?: number;
+  // tools/callのエージェントごとの上限（1分あたり、0は無制限）
+  agentRateLimitPerMinute?: number;
+  // 受信から応答までこの時間（ミリ秒）を超えたリクエストを警告する（0は無効）
+  slowRequestMs?: number;
+  // tools/call 1件あたりのタイムアウト（ミリ秒）
+  toolTimeoutMs?: number;
+  // ポリシー判定（AI呼び出し）1回のタイムアウト（ミリ秒）。超えた場合はDENYとして扱う
+  evalTimeoutMs?: number;
+  // AI判定が失敗したときの再試行回数と、1回目の再試行までの待ち時間（以降は指数バックオフ）
+  evalRetries?: number;
+  evalRetryBackoffMs?: number;
+  // 連続してこの回数AI判定が失敗したら、クールダウンの間はAIを呼ばずにDENYする（0は無効）
+  evalBreakerThreshold?: number;
+  evalBreakerCooldownMs?: number;
+  // 同じJSON-RPC idのtools/callを再実行しないよう覚えておくidの数（0は無効）
+  dedupWindow?: number;
+  // 公開するツール名の許可リスト（未設定なら全ツール）と拒否リスト
+  enabledTools?: string[];
+  disabledTools?: string[];
+  // エージェントが明示されないリクエストに使うエージェント（stdioでは接続元のエージェント）
+  defaultAgent?: string;
+  // エージェントが明示されないリクエストを -32602 で拒否する（代替値で判定しない）
+  requireAgent?: boolean;
+  // HTTPでも --agent-overrides の force-permit を適用する（X-Agent-IDを認証済みのプロキシが付ける場合のみ）
+  allowHttpForcePermit?: boolean;
   rateLimit?: {
     windowMs: number;
     max: number;
@@ -229,6 +296,20 @@ export interface MonitoringConfig {
   metricsPort?: number;
   healthCheckPath?: string;
   auditLogEnabled?: boolean;
+  // 判定ごとのJSON Lines監査ログの出力先（未設定なら出力しない）
+  decisionAuditLogPath?: string;
+  // 監査ログのハッシュチェーンをHMAC-SHA256で認証する鍵（未設定ならSHA-256）
+  decisionAuditLogKey?: string;
+  // 監査ログに書き込めないときの扱い（未設定ならfail-closed）
+  decisionAuditFailureMode?: 'fail-closed' | 'fail-open' | 'buffer';
+  // 判定履歴DB（SQLite）のパス（未設定なら保存しない）
+  decisionHistoryDbPath?: string;
+  // 判定を通知するWebhookのURL（未設定なら通知しない）
+  decisionWebhookUrl?: string;
+  // Webhookで通知する判定（未設定ならDENYのみ）
+  decisionWebhookDecisions?: string[];
+  // 再送しても届かなかった通知の出力先（JSON Lines）
+  decisionWebhookDeadLetterPath?: string;
 }
 
 // ============================================================================
@@ -245,6 +326,11 @@ export interface AEGISConfig {
   monitoring?: MonitoringConfig;
 
   defaultPolicyStrictness?: 'low' | 'medium' | 'high' | 'strict';
+  // AI判定の確信度の下限（0で無効）。下回った判定はINDETERMINATE（failClosedならDENY）に置き換える
+  minConfidence?: number;
+  failClosed?: boolean;
+  // INDETERMINATE判定の扱い（permit / deny / passthrough、既定はdeny）
+  indeterminateResolution?: 'permit' | 'deny' | 'passthrough';
   policyValidationEnabled?: boolean;
 
   secretKey?: string;
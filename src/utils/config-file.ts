@@ -0,0 +1,162 @@
+// ============================================================================
+// AEGIS - 設定ファイル（--config、TOML / YAML）
+// CLIオプションと同じ名前のキーで実行時の設定をまとめて指定する（CLIオプションが優先）
+// 1段のテーブル・マッピングはキーをハイフンでつなぐ（[cache] size = 100 → cache-size）
+// 対応するのはフラットな値（文字列・数値・真偽値・文字列の配列）のみ。未知のキーはエラーにする
+// パスを取るオプションの相対パスは、作業ディレクトリではなく設定ファイルのディレクトリから解決する
+// ============================================================================
+
+import * as fs from 'fs';
+import * as path from 'path';
+import { parse as parseToml, TomlError } from 'smol-toml';
+import { parse as parseYaml, YAMLError } from 'yaml';
+
+export type ConfigFileFormat = 'toml' | 'yaml';
+
+type ConfigValue = string | number | boolean | Array<string | number>;
+
+/**
+ * 拡張子から形式を決める（.toml / .yaml / .yml）
+ */
+export function configFileFormat(filePath: string): ConfigFileFormat {
+  const extension = path.extname(filePath).toLowerCase();
+  if (extension === '.toml') {
+    return 'toml';
+  }
+  if (extension === '.yaml' || extension === '.yml') {
+    return 'yaml';
+  }
+  throw new Error(`Config file must be .toml, .yaml or .yml: ${filePath}`);
+}
+
+function editDistance(a: string, b: string): number {
+  const row = Array.from({ length: b.length + 1 }, (_, index) => index);
+  for (let i = 1; i <= a.length; i++) {
+    let previous = row[0];
+    row[0] = i;
+    for (let j = 1; j <= b.length; j++) {
+      const current = row[j];
+      row[j] = Math.min(row[j] + 1, row[j - 1] + 1, previous + (a[i - 1] === b[j - 1] ? 0 : 1));
+      previous = current;
+    }
+  }
+  return row[b.length];
+}
+
+function unknownOptionMessage(key: string, allowed: ReadonlySet<string>): string {
+  const suggestion = [...allowed]
+    .map(option => ({ option, distance: editDistance(key, option) }))
+    .filter(candidate => candidate.distance <= 2)
+    .sort((a, b) => a.distance - b.distance)[0];
+  return `Unknown option "${key}"${suggestion ? ` (did you mean "${suggestion.option}"?)` : ''}`;
+}
+
+// CLIオプションと同じ文字列にする（false はオプションを指定しないのと同じ、配列はカンマ区切り）
+function toOptionValue(value: ConfigValue): string | undefined {
+  if (Array.isArray(value)) {
+    return value.join(',');
+  }
+  if (typeof value === 'boolean') {
+    return value ? 'true' : undefined;
+  }
+  return String(value);
+}
+
+// 構文の誤りを「ファイル:行: 内容」にする（メッセージの1行目のみ）
+function syntaxError(error: unknown, source: string): Error {
+  const message = (error instanceof Error ? error.message : String(error)).split('\n')[0];
+  const line = error instanceof TomlError ? error.line
+    : error instanceof YAMLError ? error.linePos?.[0].line
+      : undefined;
+  return new Error(`${source}${line !== undefined ? `:${line}` : ''}: ${message}`);
+}
+
+function toConfigValue(key: string, value: unknown, source: string): ConfigValue {
+  if (typeof value === 'string' || typeof value === 'boolean' || (typeof value === 'number' && Number.isFinite(value))) {
+    return value;
+  }
+  if (Array.isArray(value) && value.every(item => typeof item === 'string' || typeof item === 'number')) {
+    return value;
+  }
+  if (Array.isArray(value)) {
+    throw new Error(`${source}: Arrays may only contain strings and numbers: ${key}`);
+  }
+  throw new Error(`${source}: Value must be a string, number, boolean or array: ${key}`);
+}
+
+function isTable(value: unknown): value is Record<string, unknown> {
+  return typeof value === 'object' && value !== null && !Array.isArray(value) && !(value instanceof Date);
+}
+
+/**
+ * 設定ファイルの本文を読み、CLIオプションと同じ形（キー → 文字列）にする
+ * 構文の誤りは行番号付き、未知のキー・重複したキー・対応しない値はキー名付きでエラーにする
+ */
+export function parseConfigFile(
+  text: string,
+  format: ConfigFileFormat,
+  allowed: ReadonlySet<string>,
+  source = 'config'
+): Record<string, string> {
+  let document: unknown;
+  try {
+    document = format === 'toml' ? parseToml(text) : parseYaml(text);
+  } catch (error) {
+    throw syntaxError(error, source);
+  }
+  // 空のYAMLは null になる
+  if (document === null || document === undefined) {
+    return {};
+  }
+  if (!isTable(document)) {
+    throw new Error(`${source}: Expected a table of options`);
+  }
+
+  const entries: Array<{ key: string; value: unknown }> = [];
+  for (const [key, value] of Object.entries(document)) {
+    if (isTable(value)) {
+      for (const [nestedKey, nestedValue] of Object.entries(value)) {
+        entries.push({ key: `${key}-${nestedKey}`, value: nestedValue });
+      }
+    } else {
+      entries.push({ key, value });
+    }
+  }
+
+  const options: Record<string, string> = {};
+  const seen = new Set<string>();
+  for (const entry of entries) {
+    if (!allowed.has(entry.key)) {
+      throw new Error(`${source}: ${unknownOptionMessage(entry.key, allowed)}`);
+    }
+    // cache-size と [cache] size のように、別の書き方で同じオプションを指定した場合
+    if (seen.has(entry.key)) {
+      throw new Error(`${source}: Duplicate option "${entry.key}"`);
+    }
+    seen.add(entry.key);
+    const value = toOptionValue(toConfigValue(entry.key, entry.value, source));
+    if (value !== undefined) {
+      options[entry.key] = value;
+    }
+  }
+  return options;
+}
+
+/**
+ * 設定ファイルを読み込む（形式は拡張子で決める）
+ * pathOptions のオプションの相対パスは設定ファイルのディレクトリを基準に絶対パスにする
+ */
+export function loadConfigFile(
+  filePath: string,
+  allowed: ReadonlySet<string>,
+  pathOptions: ReadonlySet<string> = new Set()
+): Record<string, string> {
+  const options = parseConfigFile(fs.readFileSync(filePath, 'utf-8'), configFileFormat(filePath), allowed, filePath);
+  const baseDir = path.dirname(path.resolve(filePath));
+  for (const key of Object.keys(options)) {
+    if (pathOptions.has(key)) {
+      options[key] = path.resolve(baseDir, options[key]);
+    }
+  }
+  return options;
+}
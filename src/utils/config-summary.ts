@@ -0,0 +1,37 @@
+// ============================================================================
+// AEGIS - 設定の検査結果の要約（--check-config）
+// サーバーと同じ手順で設定・ポリシー・テンプレート・スキーマを読み込んだ後、
+// 起動した場合に使われる内容を表示する（サーバーは起動しない）
+// ============================================================================
+
+export interface ConfigSummary {
+  transport: 'stdio' | 'http';
+  port?: number;
+  // AI判定エンジン（APIキーがなければnull）
+  ai: { provider: string; model: string } | null;
+  policies: string[];
+  upstreamServers: string[];
+  // 読み込んだファイルの種類とパス（指定されたもののみ）
+  files: Array<{ label: string; path: string }>;
+}
+
+function formatList(label: string, names: string[]): string {
+  return `  ${label} (${names.length}): ${names.length > 0 ? names.join(', ') : 'none'}`;
+}
+
+/**
+ * 検査結果を表示用の複数行の文字列にする（末尾に改行を含む）
+ */
+export function formatConfigSummary(summary: ConfigSummary): string {
+  const transport = summary.transport === 'http' && summary.port !== undefined
+    ? `http transport, port ${summary.port}`
+    : `${summary.transport} transport`;
+  const lines = [
+    `Configuration OK (${transport})`,
+    `  AI judgment: ${summary.ai ? `${summary.ai.provider} ${summary.ai.model}` : 'disabled (no API key)'}`,
+    formatList('Policies', summary.policies),
+    formatList('Upstream servers', summary.upstreamServers),
+    ...summary.files.map(file => `  ${file.label}: ${file.path}`)
+  ];
+  return lines.join('\n') + '\n';
+}
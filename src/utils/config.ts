@@ -4,7 +4,9 @@
 
 import dotenv from 'dotenv';
 import type { AEGISConfig, LLMConfig, CacheConfig, MCPProxyConfig, MonitoringConfig } from '../types/index.js';
-import { SERVER } from '../constants/index.js';
+import { BATCH, SERVER, TIMEOUTS } from '../constants/index.js';
+import { DEFAULT_INDETERMINATE_RESOLUTION, isIndeterminateResolution } from '../policy/indeterminate-resolution.js';
+import { DEFAULT_AUDIT_FAILURE_MODE, isAuditFailureMode } from '../audit/audit-failure-mode.js';
 
 const DEFAULT_SECRET_KEY = 'default-secret-key-change-in-production';
 const MIN_SECRET_KEY_LENGTH = 32;
@@ -44,17 +46,53 @@ export class Config {
     const mcpProxyConfig: MCPProxyConfig = {
       port: this.parseInteger(overrides?.mcpProxy?.port ?? env.AEGIS_MANAGEMENT_PORT ?? env.MCP_PROXY_PORT ?? env.MANAGEMENT_PORT, SERVER.DEFAULT_PORT.HTTP),
       upstreamServers: overrides?.mcpProxy?.upstreamServers ?? this.parseUpstreamServers(env.AEGIS_MCP_UPSTREAM_SERVERS ?? env.MCP_UPSTREAM_SERVERS ?? ''),
-      corsOrigins: overrides?.mcpProxy?.corsOrigins ?? this.parseStringList(env.AEGIS_CORS_ORIGINS ?? env.CORS_ORIGINS) ?? [`http://localhost:${SERVER.DEFAULT_PORT.HTTP}`]
+      corsOrigins: overrides?.mcpProxy?.corsOrigins ?? this.parseStringList(env.AEGIS_CORS_ORIGINS ?? env.CORS_ORIGINS) ?? [`http://localhost:${SERVER.DEFAULT_PORT.HTTP}`],
+      serverName: overrides?.mcpProxy?.serverName ?? env.AEGIS_SERVER_NAME ?? SERVER.DEFAULT_NAME,
+      serverVersion: overrides?.mcpProxy?.serverVersion ?? env.AEGIS_SERVER_VERSION ?? SERVER.DEFAULT_VERSION,
+      maxRequestBytes: this.parseInteger(overrides?.mcpProxy?.maxRequestBytes ?? env.AEGIS_MAX_REQUEST_BYTES, SERVER.DEFAULT_MAX_REQUEST_BYTES),
+      maxResponseBytes: this.parseInteger(overrides?.mcpProxy?.maxResponseBytes ?? env.AEGIS_MAX_RESPONSE_BYTES, SERVER.DEFAULT_MAX_RESPONSE_BYTES),
+      framing: overrides?.mcpProxy?.framing ?? (env.AEGIS_FRAMING === 'lsp' ? 'lsp' : 'newline'),
+      strictProtocol: overrides?.mcpProxy?.strictProtocol ?? this.parseBoolean(env.AEGIS_STRICT_PROTOCOL, false),
+      canonicalizeResources: overrides?.mcpProxy?.canonicalizeResources ?? this.parseBoolean(env.AEGIS_CANONICALIZE_RESOURCES, false),
+      strictPolicyRefs: overrides?.mcpProxy?.strictPolicyRefs ?? this.parseBoolean(env.AEGIS_STRICT_POLICY_REFS, false),
+      gzipMinBytes: this.parseInteger(overrides?.mcpProxy?.gzipMinBytes ?? env.AEGIS_GZIP_MIN_BYTES, SERVER.DEFAULT_GZIP_MIN_BYTES),
+      maxSimulateBatch: this.parseInteger(overrides?.mcpProxy?.maxSimulateBatch ?? env.AEGIS_MAX_SIMULATE_BATCH, BATCH.MAX_SIMULATE_SIZE),
+      agentRateLimitPerMinute: this.parseInteger(overrides?.mcpProxy?.agentRateLimitPerMinute ?? env.AEGIS_RATE_LIMIT, 0),
+      slowRequestMs: this.parseInteger(overrides?.mcpProxy?.slowRequestMs ?? env.AEGIS_SLOW_REQUEST_MS, 0),
+      toolTimeoutMs: this.parseInteger(overrides?.mcpProxy?.toolTimeoutMs ?? env.AEGIS_TOOL_TIMEOUT_MS, TIMEOUTS.TOOL_CALL),
+      evalTimeoutMs: this.parseInteger(overrides?.mcpProxy?.evalTimeoutMs ?? env.AEGIS_EVAL_TIMEOUT_MS, TIMEOUTS.POLICY_EVALUATION),
+      evalRetries: this.parseInteger(overrides?.mcpProxy?.evalRetries ?? env.AEGIS_EVAL_RETRIES, 0),
+      evalRetryBackoffMs: this.parseInteger(overrides?.mcpProxy?.evalRetryBackoffMs ?? env.AEGIS_EVAL_RETRY_BACKOFF_MS, TIMEOUTS.EVAL_RETRY_BACKOFF),
+      evalBreakerThreshold: this.parseInteger(overrides?.mcpProxy?.evalBreakerThreshold ?? env.AEGIS_EVAL_BREAKER_THRESHOLD, 0),
+      evalBreakerCooldownMs: this.parseInteger(overrides?.mcpProxy?.evalBreakerCooldownMs ?? env.AEGIS_EVAL_BREAKER_COOLDOWN_MS, TIMEOUTS.EVAL_BREAKER_COOLDOWN),
+      dedupWindow: this.parseInteger(overrides?.mcpProxy?.dedupWindow ?? env.AEGIS_DEDUP_WINDOW, SERVER.DEFAULT_DEDUP_WINDOW),
+      enabledTools: overrides?.mcpProxy?.enabledTools ?? this.parseStringList(env.AEGIS_ENABLED_TOOLS),
+      disabledTools: overrides?.mcpProxy?.disabledTools ?? this.parseStringList(env.AEGIS_DISABLED_TOOLS),
+      defaultAgent: overrides?.mcpProxy?.defaultAgent ?? env.AEGIS_DEFAULT_AGENT,
+      requireAgent: overrides?.mcpProxy?.requireAgent ?? this.parseBoolean(env.AEGIS_REQUIRE_AGENT, false),
+      allowHttpForcePermit: overrides?.mcpProxy?.allowHttpForcePermit ?? this.parseBoolean(env.AEGIS_ALLOW_HTTP_FORCE_PERMIT, false)
     };
 
     const monitoringConfig: MonitoringConfig = {
       enabled: overrides?.monitoring?.enabled ?? this.parseBoolean(env.AEGIS_MONITORING_ENABLED ?? env.MONITORING_ENABLED, true),
       metricsPort: this.parseInteger(overrides?.monitoring?.metricsPort ?? env.AEGIS_METRICS_PORT ?? env.METRICS_PORT, 9090),
       healthCheckPath: overrides?.monitoring?.healthCheckPath ?? env.AEGIS_HEALTH_CHECK_ENDPOINT ?? env.HEALTH_CHECK_ENDPOINT ?? '/health',
-      auditLogEnabled: overrides?.monitoring?.auditLogEnabled ?? this.parseBoolean(env.AEGIS_AUDIT_LOG_ENABLED ?? env.AUDIT_LOG_ENABLED, true)
+      auditLogEnabled: overrides?.monitoring?.auditLogEnabled ?? this.parseBoolean(env.AEGIS_AUDIT_LOG_ENABLED ?? env.AUDIT_LOG_ENABLED, true),
+      decisionAuditLogPath: overrides?.monitoring?.decisionAuditLogPath ?? env.AEGIS_AUDIT_LOG,
+      decisionAuditLogKey: overrides?.monitoring?.decisionAuditLogKey ?? env.AEGIS_AUDIT_KEY,
+      decisionAuditFailureMode: overrides?.monitoring?.decisionAuditFailureMode ??
+        (env.AEGIS_AUDIT_FAILURE_MODE as MonitoringConfig['decisionAuditFailureMode']),
+      decisionHistoryDbPath: overrides?.monitoring?.decisionHistoryDbPath ?? env.AEGIS_HISTORY_DB,
+      decisionWebhookUrl: overrides?.monitoring?.decisionWebhookUrl ?? env.AEGIS_WEBHOOK_URL,
+      decisionWebhookDecisions: overrides?.monitoring?.decisionWebhookDecisions ?? this.parseStringList(env.AEGIS_WEBHOOK_DECISIONS),
+      decisionWebhookDeadLetterPath: overrides?.monitoring?.decisionWebhookDeadLetterPath ?? env.AEGIS_WEBHOOK_DEAD_LETTER
     };
 
     const defaultPolicyStrictness = (overrides?.defaultPolicyStrictness as any) ?? (env.AEGIS_DEFAULT_POLICY_STRICTNESS as any) ?? (env.DEFAULT_POLICY_STRICTNESS as any) ?? 'medium';
+    const minConfidence = this.parseFloat(overrides?.minConfidence ?? env.AEGIS_MIN_CONFIDENCE, 0);
+    const failClosed = overrides?.failClosed ?? this.parseBoolean(env.AEGIS_FAIL_CLOSED, false);
+    const indeterminateResolution = overrides?.indeterminateResolution ??
+      (env.AEGIS_INDETERMINATE_RESOLUTION as AEGISConfig['indeterminateResolution']) ?? DEFAULT_INDETERMINATE_RESOLUTION;
     const policyValidationEnabled = overrides?.policyValidationEnabled ?? this.parseBoolean(env.AEGIS_POLICY_VALIDATION_ENABLED ?? env.POLICY_VALIDATION_ENABLED, true);
 
     const securitySecretKey = overrides?.security?.secretKey ?? overrides?.secretKey ?? env.AEGIS_SECRET_KEY ?? env.SECRET_KEY ?? DEFAULT_SECRET_KEY;
@@ -71,6 +109,9 @@ export class Config {
       mcpProxy: { ...mcpProxyConfig, ...overrides?.mcpProxy },
       monitoring: { ...monitoringConfig, ...overrides?.monitoring },
       defaultPolicyStrictness,
+      minConfidence,
+      failClosed,
+      indeterminateResolution,
       policyValidationEnabled,
       secretKey: securitySecretKey,
       jwtSecret: securityJwtSecret,
@@ -218,6 +259,39 @@ export class Config {
       }
     }
 
+    // 確信度の下限検証
+    if (this.config.minConfidence !== undefined && (this.config.minConfidence < 0 || this.config.minConfidence > 1)) {
+      this.config.minConfidence = 0;
+      if (!isStdioMode && process.env.LOG_SILENT !== 'true') {
+        console.warn('[Config] minConfidence must be between 0 and 1. Disabling the threshold.');
+      }
+    }
+
+    // 判定のタイムアウトがtools/call全体より長いと、判定の打ち切り（DENY）より先にツールのタイムアウトになる
+    const { evalTimeoutMs, toolTimeoutMs } = this.config.mcpProxy;
+    if (evalTimeoutMs !== undefined && toolTimeoutMs !== undefined && evalTimeoutMs >= toolTimeoutMs) {
+      if (!isStdioMode && process.env.LOG_SILENT !== 'true') {
+        console.warn(`[Config] evalTimeoutMs (${evalTimeoutMs}) should be shorter than toolTimeoutMs (${toolTimeoutMs}); slow evaluations will surface as tool timeouts.`);
+      }
+    }
+
+    // INDETERMINATEの解決方針の検証（不明な値は安全側のdenyにする）
+    if (!isIndeterminateResolution(this.config.indeterminateResolution)) {
+      if (!isStdioMode && process.env.LOG_SILENT !== 'true') {
+        console.warn(`[Config] Unknown indeterminateResolution: ${this.config.indeterminateResolution}. Using ${DEFAULT_INDETERMINATE_RESOLUTION}.`);
+      }
+      this.config.indeterminateResolution = DEFAULT_INDETERMINATE_RESOLUTION;
+    }
+
+    // 監査ログ書き込み失敗時の扱いの検証（不明な値は安全側のfail-closedにする）
+    const auditFailureMode = this.config.monitoring?.decisionAuditFailureMode;
+    if (auditFailureMode !== undefined && !isAuditFailureMode(auditFailureMode)) {
+      if (!isStdioMode && process.env.LOG_SILENT !== 'true') {
+        console.warn(`[Config] Unknown decisionAuditFailureMode: ${auditFailureMode}. Using ${DEFAULT_AUDIT_FAILURE_MODE}.`);
+      }
+      this.config.monitoring!.decisionAuditFailureMode = DEFAULT_AUDIT_FAILURE_MODE;
+    }
+
     if (!isStdioMode) {
       if (process.env.LOG_SILENT !== 'true') {
         console.info('[Config] Configuration loaded successfully');
@@ -0,0 +1,34 @@
+// ============================================================================
+// AEGIS - オブジェクトの再帰マージ
+// デフォルトコンテキストの上にリクエストのコンテキストを重ねるために使う
+// ============================================================================
+
+export function isPlainObject(value: unknown): value is Record<string, unknown> {
+  if (value === null || typeof value !== 'object' || Array.isArray(value)) {
+    return false;
+  }
+  const proto = Object.getPrototypeOf(value);
+  return proto === Object.prototype || proto === null;
+}
+
+/**
+ * baseの上にoverrideを再帰的にマージした新しいオブジェクトを返す
+ * - 両方がプレーンオブジェクトのキーは再帰的にマージする
+ * - それ以外（配列・Date・プリミティブ）はoverrideの値が優先される
+ * - overrideの値がundefinedのキーはbaseの値を残す
+ */
+export function deepMerge<T extends object>(base: Record<string, unknown>, override: T): T {
+  const result: Record<string, unknown> = { ...base };
+
+  for (const [key, value] of Object.entries(override)) {
+    if (value === undefined) {
+      continue;
+    }
+    const current = result[key];
+    result[key] = isPlainObject(current) && isPlainObject(value)
+      ? deepMerge(current, value)
+      : value;
+  }
+
+  return result as T;
+}
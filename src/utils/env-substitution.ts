@@ -0,0 +1,39 @@
+// ============================================================================
+// AEGIS - 環境変数の展開
+// ポリシー本文の ${VAR} を読み込み時にプロセスの環境変数で置き換える
+//   ${VAR}   環境変数VARの値（未定義ならそのまま残す。strict時はエラー）
+//   $${VAR}  エスケープ（${VAR} という文字列のまま残す）
+// ============================================================================
+
+const PLACEHOLDER_PATTERN = /\$(\$?)\{([A-Za-z_][A-Za-z0-9_]*)\}/g;
+
+export interface EnvSubstitutionOptions {
+  // 未定義の変数を参照していたらエラーにする
+  strict?: boolean;
+}
+
+export function substituteEnvVariables(
+  text: string,
+  env: NodeJS.ProcessEnv = process.env,
+  options: EnvSubstitutionOptions = {}
+): string {
+  const undefinedNames = new Set<string>();
+
+  const result = text.replace(PLACEHOLDER_PATTERN, (placeholder: string, escape: string, name: string) => {
+    if (escape) {
+      return placeholder.substring(1);
+    }
+    const value = env[name];
+    if (value === undefined) {
+      undefinedNames.add(name);
+      return placeholder;
+    }
+    return value;
+  });
+
+  if (options.strict && undefinedNames.size > 0) {
+    throw new Error(`Undefined environment variable(s): ${Array.from(undefinedNames).join(', ')}`);
+  }
+
+  return result;
+}
@@ -270,10 +270,27 @@ export class ErrorHandler {
   } {
     const aegisError = error instanceof AegisError ? error : this.createAegisError(error, 'mcp-response');
     
-    // Map error codes to JSON-RPC error codes
+    return {
+      jsonrpc: '2.0',
+      id: requestId || null,
+      error: {
+        code: this.toJsonRpcErrorCode(aegisError.code),
+        message: aegisError.message,
+        data: aegisError.context
+      }
+    };
+  }
+
+  /**
+   * Map AEGIS error codes to JSON-RPC error codes
+   */
+  static toJsonRpcErrorCode(errorCode: string): number {
     let code = -32603; // Internal error
     
-    switch (aegisError.code) {
+    switch (errorCode) {
+      case 'PARSE_ERROR':
+        code = -32700;
+        break;
       case 'INVALID_REQUEST':
         code = -32600;
         break;
@@ -295,14 +312,6 @@ export class ErrorHandler {
         break;
     }
     
-    return {
-      jsonrpc: '2.0',
-      id: requestId || null,
-      error: {
-        code,
-        message: aegisError.message,
-        data: aegisError.context
-      }
-    };
+    return code;
   }
 }
\ No newline at end of file
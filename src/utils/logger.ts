@@ -3,6 +3,60 @@
 // ============================================================================
 
 import winston from 'winston';
+import { getRequestTrace } from './request-trace.js';
+
+// コンソール出力の形式（--log-format / LOG_FORMAT）
+export const LOG_FORMATS = ['pretty', 'compact', 'json'] as const;
+export type LogFormat = typeof LOG_FORMATS[number];
+
+export function isLogFormat(value: unknown): value is LogFormat {
+  return typeof value === 'string' && (LOG_FORMATS as readonly string[]).includes(value);
+}
+
+// モジュール読み込み時に作られるロガーもCLIの指定に従うよう、出力のたびに参照する
+function currentLogFormat(): LogFormat {
+  const format = process.env.LOG_FORMAT;
+  return isLogFormat(format) ? format : 'pretty';
+}
+
+const MESSAGE = Symbol.for('message');
+const LEVEL = Symbol.for('level');
+const levelColorizer = winston.format.colorize();
+
+/**
+ * コンソールに出力する1行を組み立てる
+ * pretty: 色付きの1行 / compact: 色なしの1行 / json: NDJSON
+ */
+export function formatConsoleLine(info: winston.Logform.TransformableInfo, format: LogFormat): string {
+  const { timestamp, level, message, service, ...metadata } = info;
+
+  if (format === 'json') {
+    return JSON.stringify({ timestamp, level, message, service, ...metadata });
+  }
+
+  const shownLevel = format === 'pretty'
+    ? levelColorizer.colorize((info as any)[LEVEL] ?? level, level)
+    : level;
+  let msg = `${timestamp} [${service}] ${shownLevel}: ${message}`;
+  if (Object.keys(metadata).length > 0) {
+    msg += ` ${JSON.stringify(metadata)}`;
+  }
+  return msg;
+}
+
+const consoleFormat = winston.format(info => {
+  (info as any)[MESSAGE] = formatConsoleLine(info, currentLogFormat());
+  return info;
+});
+
+// 処理中のリクエストのid・メソッドをログに付与する
+const requestTraceFormat = winston.format(info => {
+  const trace = getRequestTrace();
+  if (trace) {
+    Object.assign(info, trace);
+  }
+  return info;
+});
 
 export class Logger {
   private logger: winston.Logger;
@@ -17,6 +71,7 @@ export class Logger {
     this.logger = winston.createLogger({
       level: level,
       format: winston.format.combine(
+        requestTraceFormat(),
         winston.format.timestamp({
           format: 'YYYY-MM-DD HH:mm:ss'
         }),
@@ -29,17 +84,7 @@ export class Logger {
         new winston.transports.Console({
           silent: isSilent,
           stderrLevels: isStdioMode ? ['error', 'warn', 'info', 'debug'] : ['error'],
-          format: winston.format.combine(
-            winston.format.colorize(),
-            winston.format.simple(),
-            winston.format.printf(({ timestamp, level, message, service, ...metadata }) => {
-              let msg = `${timestamp} [${service}] ${level}: ${message}`;
-              if (Object.keys(metadata).length > 0) {
-                msg += ` ${JSON.stringify(metadata)}`;
-              }
-              return msg;
-            })
-          )
+          format: consoleFormat()
         })
       ]
     });
@@ -51,6 +96,18 @@ export class Logger {
     }
   }
 
+  /**
+   * 実行中にログレベルを変更（logging/setLevel用）
+   */
+  setLevel(level: string): void {
+    // レベル未指定のトランスポート（コンソール等）はロガーのレベルに従う
+    this.logger.level = level;
+  }
+
+  getLevel(): string {
+    return this.logger.level;
+  }
+
   private shouldLog(): boolean {
     return process.env.LOG_SILENT !== 'true';
   }
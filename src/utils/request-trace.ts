@@ -0,0 +1,66 @@
+// ============================================================================
+// AEGIS - リクエストトレース（相関ID）
+// 受信したJSON-RPCメッセージの処理中に出力されるログへ、
+// リクエストIDとメソッド名を自動的に付与するための非同期コンテキスト
+// ============================================================================
+
+import { AsyncLocalStorage } from 'async_hooks';
+import { v4 as uuidv4 } from 'uuid';
+
+export interface RequestTrace {
+  requestId?: string | number;  // JSON-RPCのid（通知にはない）
+  traceId?: string;             // idのない通知に割り当てる合成ID
+  method?: string;
+  httpRequestId?: string;       // HTTPリクエストのX-Request-Id（リバースプロキシのログとの相関用）
+}
+
+// 受け取るX-Request-Idの形（表示可能なASCIIで128文字まで、それ以外は生成し直す）
+const HTTP_REQUEST_ID_PATTERN = /^[\x21-\x7e]{1,128}$/;
+
+const traceStorage = new AsyncLocalStorage<RequestTrace>();
+
+/**
+ * 受信したX-Request-Idヘッダーを使う（なければ、または不正な形なら新しく生成する）
+ */
+export function resolveHttpRequestId(header: string | string[] | undefined, newId: () => string = uuidv4): string {
+  const value = Array.isArray(header) ? header[0] : header;
+  return value !== undefined && HTTP_REQUEST_ID_PATTERN.test(value) ? value : newId();
+}
+
+/**
+ * JSON-RPCメッセージからトレース情報を作る
+ * idのない通知には合成のtraceId（newIdで生成）を割り当てる
+ */
+export function createRequestTrace(
+  message: { id?: string | number; method?: string },
+  httpRequestId?: string,
+  newId: () => string = uuidv4
+): RequestTrace {
+  const trace: RequestTrace = {};
+  if (message.id !== undefined && message.id !== null) {
+    trace.requestId = message.id;
+  } else {
+    trace.traceId = newId();
+  }
+  if (message.method) {
+    trace.method = message.method;
+  }
+  if (httpRequestId !== undefined) {
+    trace.httpRequestId = httpRequestId;
+  }
+  return trace;
+}
+
+/**
+ * トレース情報のもとで処理を実行（処理中に作られたPromiseにも引き継がれる）
+ */
+export function runWithRequestTrace<T>(trace: RequestTrace, fn: () => T): T {
+  return traceStorage.run(trace, fn);
+}
+
+/**
+ * 現在処理中のリクエストのトレース情報（リクエスト処理外ではundefined）
+ */
+export function getRequestTrace(): RequestTrace | undefined {
+  return traceStorage.getStore();
+}
@@ -0,0 +1,73 @@
+// ============================================================================
+// AEGIS - 乱数・合成IDの生成元
+// 再試行のジッターや合成IDなどの乱数をここに集め、テストではシード付きの実装に差し替える
+// 時刻（TimeProvider）と合わせてRuntimeSourcesとしてプロキシに渡す
+// ============================================================================
+
+import { randomUUID } from 'crypto';
+import { SystemTimeProvider, TimeProvider } from './time-provider.js';
+
+export interface Rng {
+  /**
+   * 0以上1未満の乱数
+   */
+  next(): number;
+
+  /**
+   * UUID v4形式のID
+   */
+  uuid(): string;
+}
+
+/**
+ * Math.random と crypto.randomUUID を使う実装（本番用）
+ */
+export class SystemRng implements Rng {
+  next(): number {
+    return Math.random();
+  }
+
+  uuid(): string {
+    return randomUUID();
+  }
+}
+
+/**
+ * シードが同じなら同じ列を返す実装（テスト用、mulberry32）
+ */
+export class SeededRng implements Rng {
+  private state: number;
+
+  constructor(seed: number) {
+    this.state = seed >>> 0;
+  }
+
+  next(): number {
+    this.state = (this.state + 0x6d2b79f5) >>> 0;
+    let t = this.state;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  }
+
+  uuid(): string {
+    const hex = Array.from({ length: 32 }, () => Math.floor(this.next() * 16).toString(16));
+    // バージョン（4）とバリアント（8〜b）
+    hex[12] = '4';
+    hex[16] = (8 + (parseInt(hex[16], 16) & 0x3)).toString(16);
+    const digits = hex.join('');
+    return `${digits.slice(0, 8)}-${digits.slice(8, 12)}-${digits.slice(12, 16)}-${digits.slice(16, 20)}-${digits.slice(20)}`;
+  }
+}
+
+/**
+ * プロキシが使う乱数と時刻（テストでは決定的な実装を渡す）
+ */
+export interface RuntimeSources {
+  rng: Rng;
+  clock: TimeProvider;
+}
+
+export function systemRuntimeSources(): RuntimeSources {
+  return { rng: new SystemRng(), clock: new SystemTimeProvider() };
+}